@@ -9,35 +9,110 @@ use tracing_subscriber::{
     fmt::{format::FmtSpan, time::UtcTime},
 };
 
-/// Command-line for converting wmf to svg.
+/// Command-line for converting wmf to svg, pdf, emf, eps, canvas commands,
+/// dxf, a TikZ picture, a PNG thumbnail, a JSON dump of the records, the
+/// text it draws, or report the records and features it uses with the
+/// `stats` subcommand.
 ///
 /// # Example
 ///
 /// ```sh
 /// wmf-cli --input sample.wmf --output out.svg --verbose
 /// wmf-cli -i sample.wmf -o out.svg --quiet
+/// wmf-cli -i sample.wmf -o out.pdf --format pdf
+/// wmf-cli -i sample.wmf -o out.emf --format emf
+/// wmf-cli -i sample.wmf -o out.eps --format eps
+/// wmf-cli -i sample.wmf -o out.js --format canvas
+/// wmf-cli -i sample.wmf -o out.dxf --format dxf
+/// wmf-cli -i sample.wmf -o out.tex --format tikz
+/// wmf-cli -i sample.wmf -o out.png --format thumbnail --max-width 128
+/// wmf-cli -i sample.wmf -o out.json --format json --bitmap-data
+/// wmf-cli -i sample.wmf -o out.txt --format text
+/// wmf-cli stats -i sample.wmf
+/// wmf-cli stats -i sample.wmf -o report.txt --svg-diagnostics
 /// ```
 #[derive(Clone, Debug, Parser)]
-#[clap(author, version, about, long_about = None)]
+#[allow(clippy::struct_excessive_bools)]
+#[clap(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+)]
 struct Cli {
-    /// The WMF file path to convert to SVG
-    #[arg(short, long)]
-    input: String,
-    /// The destination file path to save converted SVG
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// The WMF file path to convert
+    #[arg(short, long, required = true)]
+    input: Option<String>,
+    /// The destination file path to save converted output
     #[arg(short, long, default_value = "output.svg")]
     output: String,
+    /// The output format
+    #[arg(short, long, value_enum, default_value_t = Format::Svg)]
+    format: Format,
     /// Omit logs except error log
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     quiet: bool,
     /// Print debug logs
-    #[arg(long)]
+    #[arg(long, global = true)]
     verbose: bool,
+    /// Write logical coordinates and express the window/viewport mapping
+    /// as SVG transforms
+    #[arg(long)]
+    logical_coordinates: bool,
+    /// Write bitmaps in JSON output as base64 instead of their length
+    #[arg(long)]
+    bitmap_data: bool,
+    /// Largest width of thumbnails in pixels
+    #[arg(long, default_value_t = 256)]
+    max_width: u32,
+    /// Largest height of thumbnails in pixels
+    #[arg(long, default_value_t = 256)]
+    max_height: u32,
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum Format {
+    Svg,
+    Pdf,
+    Emf,
+    Eps,
+    Canvas,
+    Dxf,
+    Tikz,
+    Thumbnail,
+    Json,
+    Text,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+enum Command {
+    /// Report the records and GDI features a metafile uses instead of
+    /// converting it
+    Stats {
+        /// The WMF file path to analyze
+        #[arg(short, long)]
+        input: String,
+        /// The destination file path to save the report, or the standard
+        /// output when omitted
+        #[arg(short, long)]
+        output: Option<String>,
+        /// List the records the SVG conversion approximates or skips,
+        /// which converts the whole metafile
+        #[arg(long)]
+        svg_diagnostics: bool,
+    },
 }
 
 /// Main entry point for wmf-cli.
 ///
-/// Reads a WMF file, converts it to SVG, and writes the result to the output
-/// file. Handles errors and logs them appropriately.
+/// Reads a WMF file, converts it to SVG, PDF, EMF, EPS, HTML canvas
+/// commands, DXF, a TikZ picture, a PNG thumbnail, JSON or text, and writes
+/// the result to the output file, or writes a statistics report for the
+/// `stats` subcommand. Handles errors and logs them appropriately.
 fn main() {
     let cli = Cli::parse();
 
@@ -74,18 +149,33 @@ fn main() {
         )
         .init();
 
-    let _span = tracing::info_span!("main", input = %cli.input).entered();
+    let (input_path, output_path) = match &cli.command {
+        Some(Command::Stats { input, output, .. }) => {
+            (input.clone(), output.clone())
+        }
+        None => {
+            (cli.input.clone().unwrap_or_default(), Some(cli.output.clone()))
+        }
+    };
+
+    let _span = tracing::info_span!("main", input = %input_path).entered();
 
-    let Ok(mut input) = File::open(cli.input.clone()).inspect_err(|err| {
+    let Ok(mut input) = File::open(&input_path).inspect_err(|err| {
         tracing::error!(%err);
     }) else {
         std::process::exit(1);
     };
 
-    let Ok(mut output) = File::create(cli.output.clone()).inspect_err(|err| {
-        tracing::error!(%err);
-    }) else {
-        std::process::exit(1);
+    // Without an output path, the report goes to the standard output.
+    let mut output: Box<dyn Write> = match &output_path {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(file),
+            Err(err) => {
+                tracing::error!(%err);
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(std::io::stdout()),
     };
 
     let mut buffer = vec![];
@@ -105,32 +195,99 @@ fn main() {
     //     .join("\n");
     // println!("{bytes}");
 
-    let player = wmf_core::converter::SVGPlayer::new();
-    let converter =
-        wmf_core::converter::WMFConverter::new(buffer.as_slice(), player);
+    let result = match &cli.command {
+        Some(Command::Stats { svg_diagnostics, .. }) => {
+            let options = wmf_core::converter::AnalyzeOptions {
+                svg_diagnostics: *svg_diagnostics,
+            };
 
-    match converter.run() {
-        Ok(bytes) => {
-            if let Err(err) = output.write_all(&bytes) {
-                tracing::error!(%err);
-
-                // ignore error.
-                let _ = std::fs::remove_file(cli.output)
-                    .inspect_err(|err| tracing::error!(%err));
-
-                std::process::exit(1);
-            }
+            wmf_core::converter::analyze(&buffer, options)
+                .map(|statistics| statistics.to_string().into_bytes())
         }
-        Err(err) => {
-            tracing::error!(%err);
+        None => convert(&cli, &buffer),
+    };
+    let result = result.map_err(|err| err.to_string()).and_then(|bytes| {
+        output.write_all(&bytes).map_err(|err| err.to_string())
+    });
 
+    if let Err(err) = result {
+        tracing::error!(%err);
+
+        if let Some(path) = output_path {
             // ignore error.
-            let _ = std::fs::remove_file(cli.output)
+            let _ = std::fs::remove_file(path)
                 .inspect_err(|err| tracing::error!(%err));
-
-            std::process::exit(1);
         }
+
+        std::process::exit(1);
     }
 
     tracing::info!("Converted successfully.");
 }
+
+/// Converts the WMF data in `buffer` to the format chosen on the command
+/// line.
+fn convert(
+    cli: &Cli,
+    buffer: &[u8],
+) -> Result<Vec<u8>, wmf_core::converter::ConvertError> {
+    match cli.format {
+        Format::Svg => {
+            let mut player = wmf_core::converter::SVGPlayer::new();
+            if cli.logical_coordinates {
+                player = player.logical_coordinates();
+            }
+
+            wmf_core::converter::WMFConverter::new(buffer, player).run()
+        }
+        Format::Pdf => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::PdfPlayer::new(),
+        )
+        .run(),
+        Format::Emf => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::EmfPlayer::new(),
+        )
+        .run(),
+        Format::Eps => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::EpsPlayer::new(),
+        )
+        .run(),
+        Format::Canvas => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::CanvasPlayer::new(),
+        )
+        .run(),
+        Format::Dxf => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::DxfPlayer::new(),
+        )
+        .run(),
+        Format::Tikz => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::TikzPlayer::new(),
+        )
+        .run(),
+        Format::Thumbnail => wmf_core::converter::render_thumbnail(
+            buffer,
+            cli.max_width,
+            cli.max_height,
+        )
+        .map(|thumbnail| thumbnail.png()),
+        Format::Json => {
+            let mut player = wmf_core::converter::JsonPlayer::new();
+            if cli.bitmap_data {
+                player = player.bitmap_data();
+            }
+
+            wmf_core::converter::WMFConverter::new(buffer, player).run()
+        }
+        Format::Text => wmf_core::converter::WMFConverter::new(
+            buffer,
+            wmf_core::converter::TextExtractor::new(),
+        )
+        .run(),
+    }
+}
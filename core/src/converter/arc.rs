@@ -0,0 +1,107 @@
+/// Elliptical arc between two radial points, as GDI draws it for META_ARC,
+/// META_CHORD and META_PIE, in output units.
+///
+/// The arc starts where the first radial line, running from the centre of
+/// the bounding rectangle through the first radial point, crosses the
+/// ellipse and ends where the second radial line does. Radial lines in the
+/// same direction give the whole ellipse. See
+/// [`Window::arcs_clockwise`](crate::converter::Window::arcs_clockwise)
+/// for the direction.
+#[derive(Clone, Debug)]
+pub struct EllipticalArc {
+    center: (f32, f32),
+    radii: (f32, f32),
+    /// Parametric angle of the start point, with the y-axis pointing down.
+    start: f32,
+    /// Signed angle from the start to the end point, positive when the
+    /// arc runs clockwise on the page.
+    sweep: f32,
+    /// Start and end points, where the radial lines cross the ellipse.
+    ends: ((f32, f32), (f32, f32)),
+}
+
+impl EllipticalArc {
+    /// Build the arc from the corners of the bounding rectangle and the
+    /// radial points, with the radii reduced by `inset`. Returns `None`
+    /// when the ellipse is empty.
+    pub fn new(
+        ((x1, y1), (x2, y2)): ((f32, f32), (f32, f32)),
+        radial1: (f32, f32),
+        radial2: (f32, f32),
+        inset: f32,
+        clockwise: bool,
+    ) -> Option<Self> {
+        use core::f32::consts::TAU;
+
+        let radii =
+            ((x2 - x1).abs() / 2.0 - inset, (y2 - y1).abs() / 2.0 - inset);
+
+        if radii.0 <= 0.0 || radii.1 <= 0.0 {
+            return None;
+        }
+
+        let center = (f32::midpoint(x1, x2), f32::midpoint(y1, y2));
+        // A radial point on the centre is taken as pointing right.
+        let project = |(x, y): (f32, f32)| {
+            let (dx, dy) = (x - center.0, y - center.1);
+            let length =
+                ((dx / radii.0).powi(2) + (dy / radii.1).powi(2)).sqrt();
+
+            if length <= f32::EPSILON {
+                ((center.0 + radii.0, center.1), 0.0)
+            } else {
+                (
+                    (center.0 + dx / length, center.1 + dy / length),
+                    (dy / radii.1).atan2(dx / radii.0),
+                )
+            }
+        };
+        let ((from, start), (to, end)) = (project(radial1), project(radial2));
+        let span =
+            if clockwise { end - start } else { start - end }.rem_euclid(TAU);
+        let span = if span < 1e-4 || TAU - span < 1e-4 { TAU } else { span };
+
+        Some(Self {
+            center,
+            radii,
+            start,
+            sweep: if clockwise { span } else { -span },
+            ends: (from, to),
+        })
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        self.center
+    }
+
+    pub fn start(&self) -> (f32, f32) {
+        self.ends.0
+    }
+
+    pub fn end(&self) -> (f32, f32) {
+        self.ends.1
+    }
+
+    pub fn radii(&self) -> (f32, f32) {
+        self.radii
+    }
+
+    /// Parametric angle of the start point in radians.
+    pub fn start_angle(&self) -> f32 {
+        self.start
+    }
+
+    /// Signed angle in radians from the start to the end point, positive
+    /// when the arc runs clockwise on the page.
+    pub fn sweep(&self) -> f32 {
+        self.sweep
+    }
+
+    /// Point of the ellipse at the parametric angle `angle` in radians.
+    pub fn point(&self, angle: f32) -> (f32, f32) {
+        (
+            self.center.0 + self.radii.0 * angle.cos(),
+            self.center.1 + self.radii.1 * angle.sin(),
+        )
+    }
+}
@@ -21,10 +21,134 @@ impl From<DeviceIndependentBitmap> for Bitmap {
     fn from(dib: DeviceIndependentBitmap) -> Self {
         let dib = dib.expand_color_palette();
 
+        let info_header = dib.dib_header_info.to_bytes();
+
+        // write pixel data
+        let data = dib.bitmap_buffer.a_data;
+
+        // BMP file header (14 bytes) + info header + pixel data
+        let bmp_file_header_size: u32 = 14;
+        let info_header_len =
+            u32::try_from(info_header.len()).expect("should be as u32");
+        let data_len = u32::try_from(data.len()).expect("should be as u32");
+        let file_size = bmp_file_header_size + info_header_len + data_len;
+        let data_offset = bmp_file_header_size + info_header_len;
+
+        let mut file_header = vec![];
+        file_header.extend(b"BM");
+        file_header.extend(file_size.to_le_bytes());
+        file_header.extend(0u32.to_le_bytes());
+        file_header.extend(data_offset.to_le_bytes());
+
+        let data = {
+            file_header.extend(info_header);
+            file_header.extend(data);
+            file_header
+        };
+
+        Self(data)
+    }
+}
+
+impl From<(ColorRef, HatchStyle)> for Bitmap {
+    fn from((color_ref, brush_hatch): (ColorRef, HatchStyle)) -> Self {
+        const WIDTH: usize = 10;
+        const HEIGHT: usize = 10;
+        // BMP rows must be aligned to 4-byte boundaries.
+        // 10 pixels * 8 bits = 80 bits → ceil(80/32)*4 = 12 bytes
+        const ROW_BYTES: usize = 12;
+        const ROW_PADDING: usize = ROW_BYTES - WIDTH;
+
+        let mut a_data = Vec::with_capacity(ROW_BYTES * HEIGHT);
+
+        // Generate 8-bit indexed pixel data (palette index per pixel)
+        match brush_hatch {
+            HatchStyle::HS_HORIZONTAL => {
+                for i in 0..HEIGHT {
+                    let v = u8::from(i == 0);
+                    a_data.extend([v; WIDTH]);
+                    a_data.extend([0u8; ROW_PADDING]);
+                }
+            }
+            HatchStyle::HS_VERTICAL => {
+                for _ in 0..HEIGHT {
+                    a_data.push(1);
+                    a_data.extend([0u8; WIDTH - 1]);
+                    a_data.extend([0u8; ROW_PADDING]);
+                }
+            }
+            HatchStyle::HS_FDIAGONAL => {
+                for i in 0..HEIGHT {
+                    for j in 0..WIDTH {
+                        a_data.push(u8::from(i + j == 9));
+                    }
+                    a_data.extend([0u8; ROW_PADDING]);
+                }
+            }
+            HatchStyle::HS_BDIAGONAL => {
+                for i in 0..HEIGHT {
+                    for j in 0..WIDTH {
+                        a_data.push(u8::from(i == j));
+                    }
+                    a_data.extend([0u8; ROW_PADDING]);
+                }
+            }
+            HatchStyle::HS_CROSS => {
+                for i in 0..HEIGHT {
+                    if i == 0 {
+                        a_data.extend([1u8; WIDTH]);
+                    } else {
+                        a_data.push(1);
+                        a_data.extend([0u8; WIDTH - 1]);
+                    }
+                    a_data.extend([0u8; ROW_PADDING]);
+                }
+            }
+            HatchStyle::HS_DIAGCROSS => {
+                for i in 0..HEIGHT {
+                    for j in 0..WIDTH {
+                        a_data.push(u8::from(i == j || i + j == 9));
+                    }
+                    a_data.extend([0u8; ROW_PADDING]);
+                }
+            }
+        }
+
+        DeviceIndependentBitmap {
+            dib_header_info: BitmapInfoHeader::Info(BitmapInfoHeaderInfo {
+                header_size: 40,
+                width: 10,
+                height: 10,
+                planes: 1,
+                // 8-bit indexed: one palette index per byte
+                bit_count: BitCount::BI_BITCOUNT_3,
+                compression: Compression::BI_RGB,
+                image_size: 0,
+                x_pels_per_meter: 0,
+                y_pels_per_meter: 0,
+                color_used: 2,
+                color_important: 2,
+            }),
+            colors: Colors::RGBTriple(vec![
+                RGBTriple { red: 0, green: 0, blue: 0 },
+                RGBTriple {
+                    red: color_ref.red,
+                    green: color_ref.green,
+                    blue: color_ref.blue,
+                },
+            ]),
+            bitmap_buffer: BitmapBuffer { a_data },
+        }
+        .into()
+    }
+}
+
+impl BitmapInfoHeader {
+    /// Serialize the header as it is stored in a DIB.
+    pub(in crate::converter) fn to_bytes(&self) -> Vec<u8> {
         let mut info_header = vec![];
 
-        // write info header
-        match dib.dib_header_info {
+        match self.clone() {
             BitmapInfoHeader::Core(BitmapInfoHeaderCore {
                 header_size,
                 width,
@@ -175,123 +299,7 @@ impl From<DeviceIndependentBitmap> for Bitmap {
             }
         }
 
-        // write pixel data
-        let data = dib.bitmap_buffer.a_data;
-
-        // BMP file header (14 bytes) + info header + pixel data
-        let bmp_file_header_size: u32 = 14;
-        let info_header_len =
-            u32::try_from(info_header.len()).expect("should be as u32");
-        let data_len = u32::try_from(data.len()).expect("should be as u32");
-        let file_size = bmp_file_header_size + info_header_len + data_len;
-        let data_offset = bmp_file_header_size + info_header_len;
-
-        let mut file_header = vec![];
-        file_header.extend(b"BM");
-        file_header.extend(file_size.to_le_bytes());
-        file_header.extend(0u32.to_le_bytes());
-        file_header.extend(data_offset.to_le_bytes());
-
-        let data = {
-            file_header.extend(info_header);
-            file_header.extend(data);
-            file_header
-        };
-
-        Self(data)
-    }
-}
-
-impl From<(ColorRef, HatchStyle)> for Bitmap {
-    fn from((color_ref, brush_hatch): (ColorRef, HatchStyle)) -> Self {
-        const WIDTH: usize = 10;
-        const HEIGHT: usize = 10;
-        // BMP rows must be aligned to 4-byte boundaries.
-        // 10 pixels * 8 bits = 80 bits → ceil(80/32)*4 = 12 bytes
-        const ROW_BYTES: usize = 12;
-        const ROW_PADDING: usize = ROW_BYTES - WIDTH;
-
-        let mut a_data = Vec::with_capacity(ROW_BYTES * HEIGHT);
-
-        // Generate 8-bit indexed pixel data (palette index per pixel)
-        match brush_hatch {
-            HatchStyle::HS_HORIZONTAL => {
-                for i in 0..HEIGHT {
-                    let v = u8::from(i == 0);
-                    a_data.extend([v; WIDTH]);
-                    a_data.extend([0u8; ROW_PADDING]);
-                }
-            }
-            HatchStyle::HS_VERTICAL => {
-                for _ in 0..HEIGHT {
-                    a_data.push(1);
-                    a_data.extend([0u8; WIDTH - 1]);
-                    a_data.extend([0u8; ROW_PADDING]);
-                }
-            }
-            HatchStyle::HS_FDIAGONAL => {
-                for i in 0..HEIGHT {
-                    for j in 0..WIDTH {
-                        a_data.push(u8::from(i + j == 9));
-                    }
-                    a_data.extend([0u8; ROW_PADDING]);
-                }
-            }
-            HatchStyle::HS_BDIAGONAL => {
-                for i in 0..HEIGHT {
-                    for j in 0..WIDTH {
-                        a_data.push(u8::from(i == j));
-                    }
-                    a_data.extend([0u8; ROW_PADDING]);
-                }
-            }
-            HatchStyle::HS_CROSS => {
-                for i in 0..HEIGHT {
-                    if i == 0 {
-                        a_data.extend([1u8; WIDTH]);
-                    } else {
-                        a_data.push(1);
-                        a_data.extend([0u8; WIDTH - 1]);
-                    }
-                    a_data.extend([0u8; ROW_PADDING]);
-                }
-            }
-            HatchStyle::HS_DIAGCROSS => {
-                for i in 0..HEIGHT {
-                    for j in 0..WIDTH {
-                        a_data.push(u8::from(i == j || i + j == 9));
-                    }
-                    a_data.extend([0u8; ROW_PADDING]);
-                }
-            }
-        }
-
-        DeviceIndependentBitmap {
-            dib_header_info: BitmapInfoHeader::Info(BitmapInfoHeaderInfo {
-                header_size: 40,
-                width: 10,
-                height: 10,
-                planes: 1,
-                // 8-bit indexed: one palette index per byte
-                bit_count: BitCount::BI_BITCOUNT_3,
-                compression: Compression::BI_RGB,
-                image_size: 0,
-                x_pels_per_meter: 0,
-                y_pels_per_meter: 0,
-                color_used: 2,
-                color_important: 2,
-            }),
-            colors: Colors::RGBTriple(vec![
-                RGBTriple { red: 0, green: 0, blue: 0 },
-                RGBTriple {
-                    red: color_ref.red,
-                    green: color_ref.green,
-                    blue: color_ref.blue,
-                },
-            ]),
-            bitmap_buffer: BitmapBuffer { a_data },
-        }
-        .into()
+        info_header
     }
 }
 
@@ -0,0 +1,618 @@
+use crate::{
+    converter::{
+        Clip, DiagnosticKind, GdiState, LineCap, LineJoin, LineStyle,
+        PlayError,
+        canvas::script::{Script, css_color, string},
+        drawing::{Context, Device, Paint, TextRun},
+        page::{Image, Path, Placeable, number},
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Drawing of a [`CanvasPlayer`](crate::converter::CanvasPlayer): the
+/// statements painting on the canvas.
+#[derive(Default)]
+pub struct Canvas {
+    script: Script,
+    // META_PLACEABLE bounding box, which becomes the size of the canvas.
+    placeable: Placeable,
+}
+
+/// Functions defined at the start of the body.
+///
+/// `image` makes a canvas of base64 RGBA pixels and `pattern` repeats one
+/// with a tile pixel per device pixel. `text` draws glyphs along the
+/// x-axis from a start `align` of the run width left of the origin; each
+/// glyph advances by its entry of `advances`, plus its width when
+/// `measured` is set.
+const PRELUDE: &str = r#"const image = (width, height, data) => {
+  const canvas = new OffscreenCanvas(width, height);
+  const pixels = Uint8ClampedArray.from(atob(data), (c) => c.charCodeAt(0));
+  canvas.getContext("2d").putImageData(new ImageData(pixels, width, height), 0, 0);
+  return canvas;
+};
+const pattern = (tile, scale) => {
+  const pattern = ctx.createPattern(tile, "repeat");
+  pattern.setTransform(new DOMMatrix([scale, 0, 0, scale, 0, 0]));
+  return pattern;
+};
+const text = (glyphs, advances, measured, align, size, background, lines) => {
+  const widths = glyphs.map((glyph, i) =>
+    (advances[i] ?? 0) + (measured ? ctx.measureText(glyph).width : 0));
+  const width = widths.reduce((sum, v) => sum + v, 0);
+  const start = -align * width;
+  if (background) {
+    const fill = ctx.fillStyle;
+    ctx.fillStyle = background;
+    ctx.fillRect(start, -0.8 * size, width, size);
+    ctx.fillStyle = fill;
+  }
+  let x = start;
+  glyphs.forEach((glyph, i) => {
+    ctx.fillText(glyph, x, 0);
+    x += widths[i];
+  });
+  for (const y of lines) {
+    ctx.fillRect(start, y - 0.025 * size, width, 0.05 * size);
+  }
+};
+"#;
+
+/// Colour change and compositing standing in for a raster operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Operation {
+    tint: Tint,
+    /// Value of `globalCompositeOperation`.
+    composite: &'static str,
+}
+
+/// Colour painted in place of the pen or brush colour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tint {
+    Keep,
+    Invert,
+    Black,
+    White,
+}
+
+impl Operation {
+    const COPY: Self = Self::new(Tint::Keep, "source-over");
+
+    const fn new(tint: Tint, composite: &'static str) -> Self {
+        Self { tint, composite }
+    }
+
+    /// Operation standing in for the ROP2 mode `operation`.
+    fn from_rop2(
+        context: &mut Context<'_>,
+        operation: BinaryRasterOperation,
+    ) -> Self {
+        use BinaryRasterOperation as Rop2;
+
+        let approximated = match operation {
+            Rop2::R2_NOP | Rop2::R2_COPYPEN => return Self::COPY,
+            Rop2::R2_NOTCOPYPEN => {
+                return Self::new(Tint::Invert, "source-over");
+            }
+            Rop2::R2_BLACK => return Self::new(Tint::Black, "source-over"),
+            Rop2::R2_WHITE => return Self::new(Tint::White, "source-over"),
+            // ~D == |1 - D|
+            Rop2::R2_NOT => return Self::new(Tint::White, "difference"),
+            Rop2::R2_XORPEN => Self::new(Tint::Keep, "difference"),
+            // ~(D ^ P) == D ^ ~P
+            Rop2::R2_NOTXORPEN => Self::new(Tint::Invert, "difference"),
+            Rop2::R2_MASKPEN => Self::new(Tint::Keep, "multiply"),
+            Rop2::R2_MERGEPEN => Self::new(Tint::Keep, "screen"),
+            Rop2::R2_MASKNOTPEN => Self::new(Tint::Invert, "multiply"),
+            Rop2::R2_MERGENOTPEN => Self::new(Tint::Invert, "screen"),
+            Rop2::R2_MASKPENNOT
+            | Rop2::R2_MERGEPENNOT
+            | Rop2::R2_NOTMASKPEN
+            | Rop2::R2_NOTMERGEPEN => Self::COPY,
+        };
+
+        let message = if approximated == Self::COPY {
+            format!(
+                "{operation:?}: canvas cannot invert the drawing before or \
+                 after combining, drawn as R2_COPYPEN"
+            )
+        } else {
+            format!(
+                "{operation:?}: drawn with the {} composite operation{} \
+                 (exact for black and white only)",
+                approximated.composite,
+                if approximated.tint == Tint::Invert {
+                    " of the inverted pen"
+                } else {
+                    ""
+                },
+            )
+        };
+
+        context.report(DiagnosticKind::Approximated, message);
+        approximated
+    }
+
+    fn color(self, color: &ColorRef) -> ColorRef {
+        match self.tint {
+            Tint::Keep => color.clone(),
+            Tint::Invert => ColorRef {
+                red: !color.red,
+                green: !color.green,
+                blue: !color.blue,
+                ..color.clone()
+            },
+            Tint::Black => ColorRef::black(),
+            Tint::White => ColorRef::white(),
+        }
+    }
+}
+
+impl Canvas {
+    /// Append `body` in a graphics state of its own, clipped to the
+    /// clipping region.
+    fn paint(&mut self, state: &GdiState, body: &Script) {
+        let block = clip(state.clips(), Script::new().call("save", &[]))
+            .append(body)
+            .call("restore", &[]);
+
+        self.script = core::mem::take(&mut self.script).append(&block);
+    }
+}
+
+/// Statements selecting `brush` as the fill style, or `None` when it paints
+/// nothing.
+fn fill_paint(
+    context: &mut Context<'_>,
+    brush: &Brush,
+    operation: Operation,
+) -> Option<Script> {
+    let state = context.state;
+    let pixel_size = state.window().device_pixel_size();
+    let tile = match brush {
+        Brush::Null => return None,
+        _ if matches!(operation.tint, Tint::Black | Tint::White) => {
+            let color = operation.color(&ColorRef::black());
+
+            return Some(Script::new().fill_style(&color));
+        }
+        Brush::Solid { color_ref } => {
+            return Some(Script::new().fill_style(&operation.color(color_ref)));
+        }
+        Brush::Hatched { color_ref, brush_hatch } => {
+            let background = (state.bk_mode() == MixMode::OPAQUE)
+                .then(|| operation.color(state.bk_color()));
+
+            image_source(
+                8,
+                8,
+                &hatch(
+                    *brush_hatch,
+                    &operation.color(color_ref),
+                    background.as_ref(),
+                ),
+            )
+        }
+        Brush::DIBPatternPT { brush_hatch, .. } => {
+            image_tile(context, Image::from_dib(brush_hatch), operation)?
+        }
+        Brush::Pattern { brush_hatch } => {
+            image_tile(context, Image::from_bitmap16(brush_hatch), operation)?
+        }
+    };
+
+    // A tile pixel is a device pixel of the drawing.
+    Some(
+        Script::new().set(
+            "fillStyle",
+            format!("pattern({tile},{})", number(pixel_size)),
+        ),
+    )
+}
+
+/// Expression of the canvas showing `image` for a pattern brush.
+fn image_tile(
+    context: &mut Context<'_>,
+    image: Result<Image, String>,
+    operation: Operation,
+) -> Option<String> {
+    let image = image.and_then(|image| {
+        let image =
+            if operation.tint == Tint::Invert { image.invert() } else { image };
+        let pixels = image.rgba()?;
+
+        Ok(image_source(image.width(), image.height(), &pixels))
+    });
+
+    match image {
+        Ok(image) => Some(image),
+        Err(message) => {
+            context.report(
+                DiagnosticKind::Skipped,
+                format!("pattern brush is not painted: {message}"),
+            );
+
+            None
+        }
+    }
+}
+
+/// Statements setting up the selected pen, or `None` for PS_NULL.
+fn stroke_paint(state: &GdiState, operation: Operation) -> Option<Script> {
+    let line = LineStyle::selected(state)?;
+    let cap = match line.cap {
+        LineCap::Flat => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    };
+    let join = match line.join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    };
+
+    Some(
+        Script::new()
+            .stroke_style(&operation.color(&state.pen().color_ref))
+            .set("lineWidth", number(line.width))
+            .set("lineCap", string(cap))
+            .set("lineJoin", string(join))
+            .dash(&line.dashes),
+    )
+}
+
+/// Append statements intersecting the clipping region with each of
+/// `clips`.
+fn clip(clips: &[Clip], mut script: Script) -> Script {
+    for clip in clips {
+        script = script.call("beginPath", &[]);
+        // An empty path clips everything away.
+        script = match clip {
+            Clip::Rects(rects) => {
+                for (left, top, right, bottom) in rects {
+                    script = script.rect((*left, *top), (*right, *bottom));
+                }
+
+                script.call("clip", &[])
+            }
+            Clip::Exclude((left, top, right, bottom)) => script
+                .rect((-32768.0, -32768.0), (32767.0, 32767.0))
+                .rect((*left, *top), (*right, *bottom))
+                .raw(format!("ctx.clip({});", string("evenodd"))),
+        };
+    }
+
+    script
+}
+
+/// Expression of a canvas holding `pixels`, `width` by `height` RGBA
+/// pixels.
+fn image_source(width: usize, height: usize, pixels: &[u8]) -> String {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    format!("image({width},{height},{})", string(&STANDARD.encode(pixels)))
+}
+
+/// RGBA pixels of the 8 by 8 tile of a hatched brush, transparent between
+/// the lines unless `background` is given.
+fn hatch(
+    style: HatchStyle,
+    color: &ColorRef,
+    background: Option<&ColorRef>,
+) -> Vec<u8> {
+    let rgba = |color: &ColorRef| [color.red, color.green, color.blue, 0xFF];
+    let line = |x: usize, y: usize| match style {
+        HatchStyle::HS_HORIZONTAL => y == 0,
+        HatchStyle::HS_VERTICAL => x == 0,
+        HatchStyle::HS_FDIAGONAL => x == y,
+        HatchStyle::HS_BDIAGONAL => x + y == 7,
+        HatchStyle::HS_CROSS => x == 0 || y == 0,
+        HatchStyle::HS_DIAGCROSS => x == y || x + y == 7,
+    };
+
+    (0..64)
+        .flat_map(|i| {
+            if line(i % 8, i / 8) {
+                rgba(color)
+            } else {
+                background.map_or([0; 4], rgba)
+            }
+        })
+        .collect()
+}
+
+/// CSS font shorthand selecting `font` at `size` device units, falling
+/// back to the generic family suggested by its pitch and family.
+fn css_font(font: &Font, size: f32) -> String {
+    let style = if font.italic { "italic " } else { "" };
+    let weight = match font.weight {
+        0 => 400,
+        weight => ((i32::from(weight) + 50) / 100 * 100).clamp(100, 900),
+    };
+    let generic =
+        match (font.pitch_and_family.family, font.pitch_and_family.pitch) {
+            (_, PitchFont::FIXED_PITCH) | (FamilyFont::FF_MODERN, _) => {
+                "monospace"
+            }
+            (FamilyFont::FF_ROMAN, _) => "serif",
+            (FamilyFont::FF_SCRIPT, _) => "cursive",
+            (FamilyFont::FF_DECORATIVE, _) => "fantasy",
+            _ => "sans-serif",
+        };
+    // The '@' prefix only selects the vertical variant of the font, and
+    // quotes would end the family name.
+    let families: Vec<_> = core::iter::once(&font.facename)
+        .chain(&font.fallback_facename)
+        .map(|name| {
+            name.trim_start_matches('@')
+                .chars()
+                .filter(|c| !matches!(c, '\'' | '"' | '\\'))
+                .collect::<String>()
+        })
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| format!("'{name}'"))
+        .chain([generic.to_owned()])
+        .collect();
+
+    format!("{style}{weight} {}px {}", number(size), families.join(","))
+}
+
+impl Device for Canvas {
+    type Path = Script;
+
+    fn path(&self, _: &GdiState) -> Script {
+        Script::new()
+    }
+
+    fn header(&mut self, header: &MetafileHeader) {
+        self.placeable = Placeable::new(header);
+    }
+
+    fn draw(&mut self, context: &mut Context<'_>, path: Script, paint: &Paint) {
+        let operation = Operation::from_rop2(context, paint.operation);
+        let fill = paint
+            .brush
+            .as_ref()
+            .and_then(|brush| fill_paint(context, brush, operation));
+        let stroke = if paint.stroke {
+            stroke_paint(context.state, operation)
+        } else {
+            None
+        };
+
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        let mut body = Script::new()
+            .composite(operation.composite)
+            .call("beginPath", &[])
+            .append(&path);
+
+        // Filling leaves the path in place for the stroke.
+        if let Some(fill) = fill {
+            body = body.append(&fill);
+            body = if context.state.fill_even_odd() {
+                body.raw(format!("ctx.fill({});", string("evenodd")))
+            } else {
+                body.call("fill", &[])
+            };
+        }
+
+        if let Some(stroke) = stroke {
+            body = body.append(&stroke).call("stroke", &[]);
+        }
+
+        self.paint(context.state, &body);
+    }
+
+    /// Operations combining the source with the drawing use the composite
+    /// operation closest to them.
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Image,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        (x, y, source_width, source_height): (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    ) {
+        use TernaryRasterOperation as Rop3;
+
+        let (invert, composite) = match operation {
+            Rop3::SRCCOPY | Rop3::SRCERASE => (false, "source-over"),
+            Rop3::NOTSRCCOPY | Rop3::NOTSRCERASE => (true, "source-over"),
+            Rop3::SRCAND => (false, "multiply"),
+            Rop3::SRCPAINT => (false, "screen"),
+            Rop3::MERGEPAINT => (true, "screen"),
+            Rop3::SRCINVERT => (false, "difference"),
+            _ => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("{operation:?}: raster operation is not supported"),
+                );
+
+                return;
+            }
+        };
+        let image = if invert { image.invert() } else { image };
+        let pixels = match image.rgba() {
+            Ok(pixels) => pixels,
+            Err(message) => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("bitmap is not drawn: {message}"),
+                );
+
+                return;
+            }
+        };
+
+        if matches!(operation, Rop3::SRCERASE | Rop3::NOTSRCERASE) {
+            context.report(
+                DiagnosticKind::Approximated,
+                format!(
+                    "{operation:?}: drawn as {}",
+                    if invert { "NOTSRCCOPY" } else { "SRCCOPY" },
+                ),
+            );
+        } else if composite != "source-over" {
+            context.report(
+                DiagnosticKind::Approximated,
+                format!(
+                    "{operation:?}: drawn with the {composite} composite \
+                     operation (exact for black and white only)"
+                ),
+            );
+        }
+
+        // Device units per source pixel, negative when mirrored.
+        let kx = (p2.0 - p1.0) / source_width;
+        let ky = (p2.1 - p1.1) / source_height;
+        // The clip shows the source rectangle alone, and the transform
+        // mirrors the image where `drawImage` would not.
+        let mut body = Script::new()
+            .call("beginPath", &[])
+            .rect(p1, p2)
+            .call("clip", &[])
+            .composite(composite);
+
+        if context.state.stretch_mode() != StretchMode::HALFTONE {
+            body = body.set("imageSmoothingEnabled", false);
+        }
+
+        body = body
+            .call("transform", &[
+                kx,
+                0.0,
+                0.0,
+                ky,
+                p1.0 - x * kx,
+                p1.1 - y * ky,
+            ])
+            .raw(format!(
+                "ctx.drawImage({},0,0);",
+                image_source(image.width(), image.height(), &pixels),
+            ));
+
+        self.paint(context.state, &body);
+    }
+
+    /// Without a Dx array, the browser measures the glyphs in the font it
+    /// picks, so they advance by the extra spacing alone on top.
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let state = context.state;
+        let size = run.size;
+        let measured = !run.spaced;
+        let background = if state.bk_mode() == MixMode::OPAQUE {
+            string(&css_color(state.bk_color()))
+        } else {
+            "null".to_owned()
+        };
+
+        // ETO_OPAQUE fills the rectangle before the text is drawn.
+        if let Some((left, top, right, bottom)) = run.opaque {
+            let body = Script::new()
+                .fill_style(state.bk_color())
+                .call("fillRect", &[left, top, right - left, bottom - top]);
+
+            self.paint(state, &body);
+        }
+
+        let mut body = Script::new();
+
+        if let Some((left, top, right, bottom)) = run.clip {
+            body = body
+                .call("beginPath", &[])
+                .rect((left, top), (right, bottom))
+                .call("clip", &[]);
+        }
+
+        body = body
+            .set("font", string(&css_font(&run.font, size)))
+            .fill_style(state.text_color())
+            .call("translate", &[run.origin.0, run.origin.1]);
+
+        if run.angle != 0.0 {
+            body = body.call("rotate", &[-run.angle]);
+        }
+
+        let (glyphs, advances): (Vec<_>, Vec<_>) = if run.custom {
+            run.glyphs
+                .iter()
+                .map(|glyph| {
+                    let advance =
+                        if measured { glyph.extra } else { glyph.advance };
+
+                    (string(&glyph.text), number(advance))
+                })
+                .unzip()
+        } else {
+            let text: String =
+                run.glyphs.iter().map(|glyph| glyph.text.as_str()).collect();
+
+            (vec![string(&text)], vec![])
+        };
+        let lines: Vec<_> = run.lines().into_iter().map(number).collect();
+
+        body = body.raw(format!(
+            "text([{}],[{}],{measured},{},{},{background},[{}]);",
+            glyphs.join(","),
+            advances.join(","),
+            number(run.align),
+            number(size),
+            lines.join(","),
+        ));
+
+        self.paint(state, &body);
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+        context.report(
+            DiagnosticKind::Skipped,
+            "flood fill needs the pixels of the drawing, which the canvas \
+             commands do not read back"
+                .to_owned(),
+        );
+    }
+
+    fn escape(&mut self, _: &mut Context<'_>, _: &META_ESCAPE) {
+        info!("META_ESCAPE: skipped (not applicable to canvas)");
+    }
+
+    fn generate(self, state: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let Self { script, placeable } = self;
+        let window = state.window();
+        let (x, y, width, height) = placeable.area(window);
+
+        if width <= 0.0 || height <= 0.0 {
+            return Err(PlayError::FailedGenerate {
+                cause: format!("canvas size is empty: {width}x{height}"),
+            });
+        }
+
+        let scale = placeable.scale(window, 96.0);
+        // Whole canvas pixels covering the drawing, ignoring the rounding
+        // error below the precision of the coordinates.
+        let pixels = |size: f32| {
+            ((size * scale * 1000.0).round() / 1000.0).ceil().max(1.0)
+        };
+
+        // Resizing the canvas clears it and resets the state of the
+        // context.
+        Ok(Script::new()
+            .set("canvas.width", pixels(width))
+            .set("canvas.height", pixels(height))
+            .raw(PRELUDE.trim_end())
+            .call("save", &[])
+            // Unrounded, as the scale multiplies the coordinates.
+            .raw(format!(
+                "ctx.transform({scale},0,0,{scale},{},{});",
+                -x * scale + 0.0,
+                -y * scale + 0.0,
+            ))
+            .append(&script)
+            .call("restore", &[])
+            .into_bytes())
+    }
+}
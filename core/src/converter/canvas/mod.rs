@@ -0,0 +1,30 @@
+mod device;
+mod script;
+
+pub use self::device::Canvas;
+use crate::converter::drawing::Drawing;
+
+/// Player writing the metafile as the body of a JavaScript function which
+/// paints it on an HTML canvas.
+///
+/// The body takes the `CanvasRenderingContext2D` as `ctx`, sizes its
+/// canvas to the drawing and calls the methods of the context for the
+/// records, which browsers run much faster than they build the DOM of a
+/// large SVG document:
+///
+/// ```js
+/// new Function("ctx", body)(canvas.getContext("2d"));
+/// ```
+///
+/// The canvas covers the META_PLACEABLE bounding box at its resolution, or
+/// the area of the drawing, at 96 DPI. Raster operations combining with the
+/// drawing become `globalCompositeOperation` modes, bitmaps are drawn from
+/// their pixels and text is set in the fonts named by the metafile, which
+/// the browser substitutes when they are missing.
+pub type CanvasPlayer = Drawing<Canvas>;
+
+impl CanvasPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{
+    converter::page::{Path, number},
+    imports::*,
+    parser::*,
+};
+
+/// Statements of a JavaScript function body drawing on the
+/// `CanvasRenderingContext2D` named `ctx`.
+///
+/// Coordinates are device units with the y-axis pointing down; the
+/// transform written by [`crate::converter::CanvasPlayer`] maps them to
+/// canvas pixels.
+#[derive(Clone, Debug, Default)]
+pub struct Script(String);
+
+impl Script {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a call of the context method `method` with `arguments`.
+    pub fn call(self, method: &str, arguments: &[f32]) -> Self {
+        let arguments: Vec<_> = arguments.iter().map(|v| number(*v)).collect();
+
+        self.raw(format!("ctx.{method}({});", arguments.join(",")))
+    }
+
+    /// Append an assignment of `value`, an expression, to the context
+    /// property `property`.
+    pub fn set(self, property: &str, value: impl core::fmt::Display) -> Self {
+        self.raw(format!("ctx.{property}={value};"))
+    }
+
+    /// Append statements written by another builder.
+    pub fn append(mut self, script: &Script) -> Self {
+        self.0.push_str(&script.0);
+        self
+    }
+
+    /// Append a statement as it is.
+    pub fn raw(mut self, line: impl core::fmt::Display) -> Self {
+        use core::fmt::Write;
+
+        let _ = writeln!(self.0, "{line}");
+        self
+    }
+
+    pub fn fill_style(self, color: &ColorRef) -> Self {
+        self.set("fillStyle", string(&css_color(color)))
+    }
+
+    pub fn stroke_style(self, color: &ColorRef) -> Self {
+        self.set("strokeStyle", string(&css_color(color)))
+    }
+
+    /// Set how the following drawing combines with the canvas, unless it
+    /// is the default `source-over`.
+    pub fn composite(self, operation: &str) -> Self {
+        if operation == "source-over" {
+            return self;
+        }
+
+        self.set("globalCompositeOperation", string(operation))
+    }
+
+    /// Set the dash pattern of strokes, solid when `dashes` is empty.
+    pub fn dash(self, dashes: &[f32]) -> Self {
+        let dashes: Vec<_> = dashes.iter().map(|v| number(*v)).collect();
+
+        self.raw(format!("ctx.setLineDash([{}]);", dashes.join(",")))
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0.into_bytes()
+    }
+}
+
+impl Path for Script {
+    fn move_to(self, (x, y): (f32, f32)) -> Self {
+        self.call("moveTo", &[x, y])
+    }
+
+    fn line_to(self, (x, y): (f32, f32)) -> Self {
+        self.call("lineTo", &[x, y])
+    }
+
+    fn curve_to(
+        self,
+        (x1, y1): (f32, f32),
+        (x2, y2): (f32, f32),
+        (x3, y3): (f32, f32),
+    ) -> Self {
+        self.call("bezierCurveTo", &[x1, y1, x2, y2, x3, y3])
+    }
+
+    fn close(self) -> Self {
+        self.call("closePath", &[])
+    }
+
+    fn rect(self, (x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> Self {
+        self.call("rect", &[x1, y1, x2 - x1, y2 - y1])
+    }
+
+    /// Canvas draws elliptical arcs itself, with the same parametric
+    /// angles in the y-down space of the drawing.
+    fn arc_to(
+        self,
+        (cx, cy): (f32, f32),
+        (rx, ry): (f32, f32),
+        start: f32,
+        sweep: f32,
+    ) -> Self {
+        // `ellipse` throws on negative radii.
+        let arguments = [cx, cy, rx.max(0.0), ry.max(0.0), 0.0, start]
+            .into_iter()
+            .chain([start + sweep])
+            .map(number)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        self.raw(format!("ctx.ellipse({arguments},{});", sweep < 0.0))
+    }
+}
+
+impl core::fmt::Display for Script {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// JavaScript string literal holding `value`.
+pub fn string(value: &str) -> String {
+    use core::fmt::Write;
+
+    let mut literal = String::with_capacity(value.len() + 2);
+
+    literal.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            // Line terminators and control characters are not allowed in
+            // string literals of older engines.
+            '\u{0}'..='\u{1F}' | '\u{7F}' | '\u{2028}' | '\u{2029}' => {
+                let _ = write!(literal, "\\u{:04X}", u32::from(c));
+            }
+            _ => literal.push(c),
+        }
+    }
+
+    literal.push('"');
+    literal
+}
+
+/// Colour as a CSS hexadecimal colour.
+pub fn css_color(color: &ColorRef) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.red, color.green, color.blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ellipse_runs_anticlockwise_for_negative_sweep() {
+        let script = Script::new().arc_to((10.0, 20.0), (5.0, 2.5), 0.0, -1.5);
+
+        assert_eq!(
+            script.to_string(),
+            "ctx.ellipse(10,20,5,2.5,0,0,-1.5,true);\n"
+        );
+    }
+
+    #[test]
+    fn string_escapes_quotes_and_line_terminators() {
+        assert_eq!(
+            string("a\"b\\c\n\u{2028}\u{1}"),
+            r#""a\"b\\c\n\u2028\u0001""#
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{imports::*, parser::*};
+
+/// How a player deviated from the exact GDI rendering of a record.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum DiagnosticKind {
+    /// The record was rendered, but the output only approximates GDI.
+    Approximated,
+    /// The record was not rendered at all.
+    Skipped,
+}
+
+/// A fidelity note emitted by a player while converting a record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub record_number: usize,
+    pub record_type: RecordType,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+/// Receiver for [`Diagnostic`]s, e.g. a closure pushing into a shared
+/// list or forwarding to a logger.
+pub type DiagnosticSink = Box<dyn FnMut(Diagnostic) + Send>;
+
+/// Optional [`DiagnosticSink`] of a player, which logs every diagnostic it
+/// is given before passing it on.
+#[cfg(any(feature = "drawing", feature = "emf", feature = "svg"))]
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+    sink: Option<DiagnosticSink>,
+}
+
+#[cfg(any(feature = "drawing", feature = "emf", feature = "svg"))]
+impl Diagnostics {
+    pub fn set_sink(&mut self, sink: impl FnMut(Diagnostic) + Send + 'static) {
+        self.sink = Some(Box::new(sink));
+    }
+
+    pub fn report(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        kind: DiagnosticKind,
+        message: String,
+    ) {
+        info!(%record_number, ?record_type, ?kind, "{message}");
+
+        if let Some(sink) = self.sink.as_mut() {
+            sink(Diagnostic { record_number, record_type, kind, message });
+        }
+    }
+}
@@ -0,0 +1,548 @@
+//! Drawing layer shared by the players that paint the metafile on a page:
+//! PDF, PostScript, HTML canvas, TikZ, the thumbnail and the extent.
+//!
+//! [`Drawing`] plays the records against the graphics state. It resolves
+//! figures to paths in device units, moves the edges of closed figures
+//! inside for PS_INSIDEFRAME pens, lays out text and reports diagnostics,
+//! so that a [`Device`] only writes paths, paints, bitmaps and text runs in
+//! its own language.
+//!
+//! Implement [`Device`] to draw the metafile in another format, and play
+//! the records with a [`Drawing`] of it:
+//!
+//! ```
+//! use wmf_core::{
+//!     converter::{
+//!         DiagnosticKind, GdiState, PlayError, WMFConverter,
+//!         drawing::{Context, Device, Drawing, Image, Paint, Path, TextRun},
+//!     },
+//!     parser::TernaryRasterOperation,
+//! };
+//!
+//! /// Counts of the figures and text runs of the metafile.
+//! #[derive(Default)]
+//! struct Census {
+//!     figures: usize,
+//!     runs: usize,
+//! }
+//!
+//! /// Path only counting its subpaths.
+//! struct Subpaths(usize);
+//!
+//! impl Path for Subpaths {
+//!     fn move_to(self, _: (f32, f32)) -> Self {
+//!         Self(self.0 + 1)
+//!     }
+//!
+//!     fn line_to(self, _: (f32, f32)) -> Self {
+//!         self
+//!     }
+//!
+//!     fn curve_to(self, _: (f32, f32), _: (f32, f32), _: (f32, f32)) -> Self {
+//!         self
+//!     }
+//!
+//!     fn close(self) -> Self {
+//!         self
+//!     }
+//! }
+//!
+//! impl Device for Census {
+//!     type Path = Subpaths;
+//!
+//!     fn path(&self, _: &GdiState) -> Subpaths {
+//!         Subpaths(0)
+//!     }
+//!
+//!     fn draw(&mut self, _: &mut Context<'_>, path: Subpaths, _: &Paint) {
+//!         self.figures += path.0;
+//!     }
+//!
+//!     fn image(
+//!         &mut self,
+//!         context: &mut Context<'_>,
+//!         _: Image,
+//!         _: ((f32, f32), (f32, f32)),
+//!         _: (f32, f32, f32, f32),
+//!         _: TernaryRasterOperation,
+//!     ) {
+//!         context.report(DiagnosticKind::Skipped, "bitmap".to_owned());
+//!     }
+//!
+//!     fn text(&mut self, _: &mut Context<'_>, _: &TextRun) {
+//!         self.runs += 1;
+//!     }
+//!
+//!     fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+//!         context.report(DiagnosticKind::Skipped, "flood fill".to_owned());
+//!     }
+//!
+//!     fn generate(self, _: &GdiState) -> Result<Vec<u8>, PlayError> {
+//!         Ok(format!("{} figures, {} runs\n", self.figures, self.runs)
+//!             .into_bytes())
+//!     }
+//! }
+//!
+//! # fn count(buffer: &[u8]) -> Result<(), wmf_core::converter::ConvertError> {
+//! let census = Drawing::with_device(Census::default());
+//! let output = WMFConverter::new(buffer, census).run()?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod player;
+mod text;
+
+pub use self::text::{Glyph, TextRun};
+pub use crate::converter::{
+    EllipticalArc,
+    page::{Image, Path},
+};
+use crate::{
+    converter::{DiagnosticKind, Diagnostics, GdiState, PlayError},
+    imports::*,
+    parser::*,
+};
+
+/// Output of a [`Drawing`], which receives the figures of the metafile in
+/// device units with the graphics state they are drawn in.
+pub trait Device {
+    /// Path builder of the output.
+    type Path: Path;
+
+    /// Empty path to build a figure with.
+    fn path(&self, state: &GdiState) -> Self::Path;
+
+    /// Take what the header tells about the page, such as the
+    /// META_PLACEABLE bounding box.
+    fn header(&mut self, _: &MetafileHeader) {}
+
+    /// Paint `path` as `paint` tells, clipped to the clipping region.
+    fn draw(
+        &mut self,
+        context: &mut Context<'_>,
+        path: Self::Path,
+        paint: &Paint,
+    );
+
+    /// Paint the rectangle from `p1` to `p2` as the ternary raster
+    /// operation `operation` does, which does not take a source bitmap.
+    ///
+    /// The operations that combine the selected brush or black and white
+    /// with the page like a ROP2 mode are drawn; the others are skipped.
+    fn pattern_blt(
+        &mut self,
+        context: &mut Context<'_>,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        operation: TernaryRasterOperation,
+    ) {
+        use BinaryRasterOperation as Rop2;
+        use TernaryRasterOperation as Rop3;
+
+        let solid = |color_ref| Some(Brush::Solid { color_ref });
+        let (brush, mode) = match operation {
+            Rop3::BLACKNESS => (solid(ColorRef::black()), Rop2::R2_COPYPEN),
+            Rop3::WHITENESS => (solid(ColorRef::white()), Rop2::R2_COPYPEN),
+            Rop3::DSTINVERT => (solid(ColorRef::white()), Rop2::R2_NOT),
+            Rop3::PATCOPY => {
+                (Some(context.state.brush().clone()), Rop2::R2_COPYPEN)
+            }
+            Rop3::PATINVERT => {
+                (Some(context.state.brush().clone()), Rop2::R2_XORPEN)
+            }
+            _ => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("{operation:?}: raster operation is not supported"),
+                );
+
+                return;
+            }
+        };
+        let path = self.path(context.state).rect(p1, p2);
+
+        self.draw(context, path, &Paint {
+            brush,
+            stroke: false,
+            operation: mode,
+        });
+    }
+
+    /// Draw the part `source` of `image`, as `(x, y, width, height)` in
+    /// pixels from its top left, stretched from `p1` to `p2` and combined
+    /// with the page as the ternary raster operation `operation` does.
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Image,
+        corners: ((f32, f32), (f32, f32)),
+        source: (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    );
+
+    /// Draw a run of text in the selected font and colours.
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun);
+
+    /// Fill the area around `point` bounded by or of one colour, which
+    /// needs the pixels of the drawing.
+    fn flood_fill(&mut self, context: &mut Context<'_>, point: (f32, f32));
+
+    /// Take the data of an escape record.
+    fn escape(&mut self, _: &mut Context<'_>, _: &META_ESCAPE) {}
+
+    /// Write the output once every record is drawn.
+    fn generate(self, state: &GdiState) -> Result<Vec<u8>, PlayError>;
+}
+
+/// How a figure is painted: the fill, the stroke with the selected pen and
+/// the ROP2 mode combining them with the page, which is never R2_NOP.
+#[derive(Clone, Debug)]
+pub struct Paint {
+    /// Brush filling the figure, or `None` for a figure that is only
+    /// stroked.
+    pub brush: Option<Brush>,
+    /// Whether the outline is stroked with the selected pen.
+    pub stroke: bool,
+    pub operation: BinaryRasterOperation,
+}
+
+/// Graphics state a record is drawn in, and the record diagnostics are
+/// reported for.
+pub struct Context<'a> {
+    pub state: &'a GdiState,
+    diagnostics: &'a mut Diagnostics,
+    record_number: usize,
+    record_type: RecordType,
+}
+
+impl Context<'_> {
+    /// Number of the record being drawn.
+    pub fn record_number(&self) -> usize {
+        self.record_number
+    }
+
+    /// Report that the record cannot be drawn exactly.
+    pub fn report(&mut self, kind: DiagnosticKind, message: String) {
+        self.diagnostics.report(
+            self.record_number,
+            self.record_type,
+            kind,
+            message,
+        );
+    }
+}
+
+/// Player drawing the metafile on the device `D`.
+#[derive(Default)]
+pub struct Drawing<D> {
+    state: GdiState,
+    diagnostics: Diagnostics,
+    device: D,
+}
+
+impl<D> Drawing<D> {
+    /// Player drawing on `device`.
+    pub fn with_device(device: D) -> Self {
+        Self {
+            state: GdiState::new(),
+            diagnostics: Diagnostics::default(),
+            device,
+        }
+    }
+
+    /// The device drawn on so far.
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+
+    /// Receive a [`Diagnostic`](crate::converter::Diagnostic) for every
+    /// record that cannot be rendered exactly.
+    #[must_use]
+    pub fn diagnostic_sink(
+        mut self,
+        sink: impl FnMut(crate::converter::Diagnostic) + Send + 'static,
+    ) -> Self {
+        self.diagnostics.set_sink(sink);
+        self
+    }
+}
+
+impl<D: Device> Drawing<D> {
+    /// The device with the context of the record to draw on it.
+    fn context(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+    ) -> (&mut D, Context<'_>) {
+        let Self { state, diagnostics, device } = self;
+
+        (device, Context { state, diagnostics, record_number, record_type })
+    }
+
+    fn report(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        kind: DiagnosticKind,
+        message: String,
+    ) {
+        self.diagnostics.report(record_number, record_type, kind, message);
+    }
+
+    /// Empty path of the device.
+    fn path(&self) -> D::Path {
+        self.device.path(&self.state)
+    }
+
+    /// Convert a logical point to device units and include it in the
+    /// drawing bounds.
+    fn point(&mut self, x: i16, y: i16) -> (f32, f32) {
+        let point = self.state.point_to_device(&PointS { x, y });
+
+        self.state.extend_bounds(point);
+        point
+    }
+
+    /// Points of `a_points` in device units, failing on missing entries.
+    fn points(
+        &mut self,
+        a_points: &[PointS],
+        count: usize,
+    ) -> Result<Vec<(f32, f32)>, PlayError> {
+        (0..count)
+            .map(|i| {
+                let Some(point) = a_points.get(i) else {
+                    return Err(PlayError::InvalidRecord {
+                        cause: format!("aPoints[{i}] is not defined"),
+                    });
+                };
+
+                Ok(self.point(point.x, point.y))
+            })
+            .collect()
+    }
+
+    /// Top left and bottom right corners in device units of the rectangle
+    /// spanned by two logical corners, with the edges moved inwards by
+    /// `inset`.
+    fn rect(
+        &mut self,
+        (x1, y1): (i16, i16),
+        (x2, y2): (i16, i16),
+        inset: f32,
+    ) -> ((f32, f32), (f32, f32)) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (left, right) = (x1.min(x2) + inset, x1.max(x2) - inset);
+        let (top, bottom) = (y1.min(y2) + inset, y1.max(y2) - inset);
+
+        ((left, top), (right.max(left), bottom.max(top)))
+    }
+
+    /// Build the arc GDI draws for META_ARC, META_CHORD and META_PIE from the
+    /// corners of the bounding rectangle and the radial points in logical
+    /// units, with the radii reduced by `inset`.
+    fn elliptical_arc(
+        &mut self,
+        (left, top): (i16, i16),
+        (right, bottom): (i16, i16),
+        (x1, y1): (i16, i16),
+        (x2, y2): (i16, i16),
+        inset: f32,
+    ) -> Option<EllipticalArc> {
+        let corners = (self.point(left, top), self.point(right, bottom));
+        let radial1 = self.state.point_to_device(&PointS { x: x1, y: y1 });
+        let radial2 = self.state.point_to_device(&PointS { x: x2, y: y2 });
+        let clockwise = self.state.window().arcs_clockwise();
+
+        EllipticalArc::new(corners, radial1, radial2, inset, clockwise)
+    }
+
+    /// Path of the rectangles of `region` in device units, included in the
+    /// drawing bounds.
+    fn region_path(&mut self, region: &Region) -> D::Path {
+        let rects = self.state.region_to_device(region);
+
+        for (left, top, right, bottom) in &rects {
+            self.state.extend_bounds((*left, *top));
+            self.state.extend_bounds((*right, *bottom));
+        }
+
+        self.rects_path(&rects)
+    }
+
+    /// Path of the rectangles `(left, top, right, bottom)`.
+    fn rects_path(&self, rects: &[(f32, f32, f32, f32)]) -> D::Path {
+        rects.iter().fold(self.path(), |path, (left, top, right, bottom)| {
+            path.rect((*left, *top), (*right, *bottom))
+        })
+    }
+
+    /// Paint `path` with `brush` and, when `stroke` is set, the selected
+    /// pen, in the ROP2 drawing mode.
+    fn draw(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        path: D::Path,
+        brush: Option<Brush>,
+        stroke: bool,
+    ) {
+        let operation =
+            self.state.draw_mode().unwrap_or(BinaryRasterOperation::R2_COPYPEN);
+
+        // R2_NOP leaves the page unchanged.
+        if operation == BinaryRasterOperation::R2_NOP {
+            return;
+        }
+
+        self.paint(record_number, record_type, path, &Paint {
+            brush,
+            stroke,
+            operation,
+        });
+    }
+
+    fn paint(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        path: D::Path,
+        paint: &Paint,
+    ) {
+        let (device, mut context) = self.context(record_number, record_type);
+
+        device.draw(&mut context, path, paint);
+    }
+
+    /// Combine the destination rectangle `(x, y, width, height)` in
+    /// logical units with the selected brush and `source`, an image and
+    /// the part of it to show, as the ternary raster operation does.
+    #[allow(clippy::type_complexity)]
+    fn blit(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        raster_operation: TernaryRasterOperation,
+        (x, y, width, height): (i16, i16, i16, i16),
+        source: Option<(Result<Image, String>, Option<(i16, i16, i16, i16)>)>,
+    ) -> Result<(), PlayError> {
+        let p1 = self.point(x, y);
+        let p2 = self.point(x.saturating_add(width), y.saturating_add(height));
+
+        if !raster_operation.use_source() {
+            let (device, mut context) =
+                self.context(record_number, record_type);
+
+            device.pattern_blt(&mut context, (p1, p2), raster_operation);
+
+            return Ok(());
+        }
+
+        let Some((image, source_rect)) = source else {
+            return Err(PlayError::InvalidRecord {
+                cause: format!(
+                    "{raster_operation:?}: raster operation requires a source \
+                     bitmap",
+                ),
+            });
+        };
+
+        let image = match image {
+            Ok(image) => image,
+            Err(message) => {
+                self.report(
+                    record_number,
+                    record_type,
+                    DiagnosticKind::Skipped,
+                    format!("bitmap is not drawn: {message}"),
+                );
+
+                return Ok(());
+            }
+        };
+        let size = |v: usize| f32::from(u16::try_from(v).unwrap_or(u16::MAX));
+        let source_rect = match source_rect {
+            Some((x, y, w, h)) if w != 0 && h != 0 => (
+                f32::from(x),
+                f32::from(y),
+                f32::from(w).abs(),
+                f32::from(h).abs(),
+            ),
+            _ => (0.0, 0.0, size(image.width()), size(image.height())),
+        };
+        let (device, mut context) = self.context(record_number, record_type);
+
+        device.image(
+            &mut context,
+            image,
+            (p1, p2),
+            source_rect,
+            raster_operation,
+        );
+
+        Ok(())
+    }
+
+    /// String of a text record decoded in the character set of the
+    /// selected font, reporting the record as skipped when it cannot be
+    /// decoded.
+    fn decode(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        decoded: Result<String, ParseError>,
+    ) -> Option<String> {
+        match decoded {
+            Ok(text) => Some(text),
+            Err(err) => {
+                self.report(
+                    record_number,
+                    record_type,
+                    DiagnosticKind::Skipped,
+                    format!("failed to decode the string: {err}"),
+                );
+
+                None
+            }
+        }
+    }
+
+    /// Draw `run`, include it in the drawing bounds and advance the current
+    /// position past it when TA_UPDATECP is set.
+    fn text(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        run: &TextRun,
+    ) {
+        for corner in run.corners() {
+            self.state.extend_bounds(corner);
+        }
+
+        if let Some((left, top, right, bottom)) = run.opaque {
+            self.state.extend_bounds((left, top));
+            self.state.extend_bounds((right, bottom));
+        }
+
+        let (device, mut context) = self.context(record_number, record_type);
+
+        device.text(&mut context, run);
+
+        if self.state.text_align_update_cp() {
+            let (sx, sy, ..) = self.state.window().transform();
+            let direction = run.direction();
+            let position = self.state.drawing_position().clone();
+            let dx = (direction.0 * run.width / sx).round();
+            let dy = (direction.1 * run.width / sy).round();
+            let clamp = |v: f32| {
+                v.clamp(f32::from(i16::MIN), f32::from(i16::MAX)) as i16
+            };
+
+            self.state.set_drawing_position(PointS {
+                x: position.x.saturating_add(clamp(dx)),
+                y: position.y.saturating_add(clamp(dy)),
+            });
+        }
+    }
+}
@@ -0,0 +1,1657 @@
+use crate::{
+    converter::{
+        DiagnosticKind, PlayError, Player,
+        drawing::{Device, Drawing, Paint, TextRun},
+        page::{Image, Path},
+    },
+    imports::*,
+    parser::*,
+};
+
+impl<D: Device> Player for Drawing<D> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn generate(self) -> Result<Vec<u8>, PlayError> {
+        let Self { state, device, .. } = self;
+
+        device.generate(&state)
+    }
+
+    // .
+    // .
+    // Functions to handle Bitmap Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn bit_blt(
+        mut self,
+        record_number: usize,
+        record: META_BITBLT,
+    ) -> Result<Self, PlayError> {
+        let (raster_operation, dest, source) = match record {
+            META_BITBLT::WithBitmap {
+                raster_operation,
+                y_src,
+                x_src,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, width, height),
+                Some((
+                    Image::from_bitmap16(&target),
+                    Some((x_src, y_src, width, height)),
+                )),
+            ),
+            META_BITBLT::WithoutBitmap {
+                raster_operation,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                ..
+            } => (raster_operation, (x_dest, y_dest, width, height), None),
+        };
+
+        self.blit(
+            record_number,
+            RecordType::META_BITBLT,
+            raster_operation,
+            dest,
+            source,
+        )?;
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_bit_blt(
+        mut self,
+        record_number: usize,
+        record: META_DIBBITBLT,
+    ) -> Result<Self, PlayError> {
+        let (raster_operation, dest, source) = match record {
+            META_DIBBITBLT::WithBitmap {
+                raster_operation,
+                y_src,
+                x_src,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, width, height),
+                Some((
+                    Image::from_dib(&target),
+                    Some((x_src, y_src, width, height)),
+                )),
+            ),
+            META_DIBBITBLT::WithoutBitmap {
+                raster_operation,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                ..
+            } => (raster_operation, (x_dest, y_dest, width, height), None),
+        };
+
+        self.blit(
+            record_number,
+            RecordType::META_DIBBITBLT,
+            raster_operation,
+            dest,
+            source,
+        )?;
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_stretch_blt(
+        mut self,
+        record_number: usize,
+        record: META_DIBSTRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        let (raster_operation, dest, source) = match record {
+            META_DIBSTRETCHBLT::WithBitmap {
+                raster_operation,
+                src_height,
+                src_width,
+                y_src,
+                x_src,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                Some((
+                    Image::from_dib(&target),
+                    Some((x_src, y_src, src_width, src_height)),
+                )),
+            ),
+            META_DIBSTRETCHBLT::WithoutBitmap {
+                raster_operation,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                None,
+            ),
+        };
+
+        self.blit(
+            record_number,
+            RecordType::META_DIBSTRETCHBLT,
+            raster_operation,
+            dest,
+            source,
+        )?;
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_device_independent_bitmap_to_dev(
+        mut self,
+        record_number: usize,
+        record: META_SETDIBTODEV,
+    ) -> Result<Self, PlayError> {
+        // Clamp to i16::MAX when u16 exceeds i16 range
+        let clamp = |v: u16| i16::try_from(v).unwrap_or(i16::MAX);
+        let dest = (
+            clamp(record.x_dest),
+            clamp(record.y_dest),
+            clamp(record.width),
+            clamp(record.height),
+        );
+
+        self.blit(
+            record_number,
+            RecordType::META_SETDIBTODEV,
+            TernaryRasterOperation::SRCCOPY,
+            dest,
+            Some((Image::from_dib(&record.dib), None)),
+        )?;
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_blt(
+        mut self,
+        record_number: usize,
+        record: META_STRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        let (raster_operation, dest, source) = match record {
+            META_STRETCHBLT::WithBitmap {
+                raster_operation,
+                src_height,
+                src_width,
+                y_src,
+                x_src,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                Some((
+                    Image::from_bitmap16(&target),
+                    Some((x_src, y_src, src_width, src_height)),
+                )),
+            ),
+            META_STRETCHBLT::WithoutBitmap {
+                raster_operation,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                None,
+            ),
+        };
+
+        self.blit(
+            record_number,
+            RecordType::META_STRETCHBLT,
+            raster_operation,
+            dest,
+            source,
+        )?;
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_device_independent_bitmap(
+        mut self,
+        record_number: usize,
+        record: META_STRETCHDIB,
+    ) -> Result<Self, PlayError> {
+        let META_STRETCHDIB {
+            raster_operation,
+            src_height,
+            src_width,
+            y_src,
+            x_src,
+            dest_height,
+            dest_width,
+            y_dst,
+            x_dst,
+            dib,
+            ..
+        } = record;
+
+        self.blit(
+            record_number,
+            RecordType::META_STRETCHDIB,
+            raster_operation,
+            (x_dst, y_dst, dest_width, dest_height),
+            Some((
+                Image::from_dib(&dib),
+                Some((x_src, y_src, src_width, src_height)),
+            )),
+        )?;
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Control Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn eof(self, record_number: usize, _: META_EOF) -> Result<Self, PlayError> {
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn header(
+        mut self,
+        record_number: usize,
+        header: MetafileHeader,
+    ) -> Result<Self, PlayError> {
+        self.state.header(&header);
+
+        self.device.header(&header);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Drawing Record
+    // .
+    // .
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn arc(
+        mut self,
+        record_number: usize,
+        record: META_ARC,
+    ) -> Result<Self, PlayError> {
+        let Some(arc) = self.elliptical_arc(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            (record.x_start_arc, record.y_start_arc),
+            (record.x_end_arc, record.y_end_arc),
+            0.0,
+        ) else {
+            info!("META_ARC is skipped because rx or ry is zero.");
+            return Ok(self);
+        };
+
+        let path = self.path().move_to(arc.start()).elliptical_arc(&arc);
+
+        self.draw(record_number, RecordType::META_ARC, path, None, true);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn chord(
+        mut self,
+        record_number: usize,
+        record: META_CHORD,
+    ) -> Result<Self, PlayError> {
+        let inset = self.state.inside_frame_inset();
+        let Some(arc) = self.elliptical_arc(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            (record.x_radial1, record.y_radial1),
+            (record.x_radial2, record.y_radial2),
+            inset,
+        ) else {
+            info!("META_CHORD is skipped because rx or ry is zero.");
+            return Ok(self);
+        };
+
+        // Closing the path draws the chord back to the start point.
+        let path =
+            self.path().move_to(arc.start()).elliptical_arc(&arc).close();
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_CHORD,
+            path,
+            Some(brush),
+            true,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ellipse(
+        mut self,
+        record_number: usize,
+        record: META_ELLIPSE,
+    ) -> Result<Self, PlayError> {
+        if record.right_rect == record.left_rect
+            || record.bottom_rect == record.top_rect
+        {
+            info!("META_ELLIPSE is skipped because rx or ry is zero.");
+            return Ok(self);
+        }
+
+        let (p1, p2) = self.rect(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            self.state.inside_frame_inset(),
+        );
+        let path = self.path().ellipse(p1, p2);
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_ELLIPSE,
+            path,
+            Some(brush),
+            true,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_flood_fill(
+        mut self,
+        record_number: usize,
+        record: META_EXTFLOODFILL,
+    ) -> Result<Self, PlayError> {
+        let point =
+            self.state.point_to_device(&PointS { x: record.x, y: record.y });
+        let (device, mut context) =
+            self.context(record_number, RecordType::META_EXTFLOODFILL);
+
+        device.flood_fill(&mut context, point);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_text_out(
+        mut self,
+        record_number: usize,
+        record: META_EXTTEXTOUT,
+    ) -> Result<Self, PlayError> {
+        let Some(text) = self.decode(
+            record_number,
+            RecordType::META_EXTTEXTOUT,
+            record.into_utf8(self.state.font().charset),
+        ) else {
+            return Ok(self);
+        };
+        // Both ETO_OPAQUE and ETO_CLIPPED refer to the rectangle.
+        let with_option = |option| {
+            record
+                .rectangle
+                .as_ref()
+                .filter(|_| record.fw_opts.contains(&option))
+        };
+        let run = TextRun::new(
+            &self.state,
+            (record.x, record.y),
+            &record.string,
+            &text,
+            &record.dx,
+            with_option(ExtTextOutOptions::ETO_OPAQUE),
+            with_option(ExtTextOutOptions::ETO_CLIPPED),
+        );
+
+        self.text(record_number, RecordType::META_EXTTEXTOUT, &run);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn fill_region(
+        mut self,
+        record_number: usize,
+        record: META_FILLREGION,
+    ) -> Result<Self, PlayError> {
+        let region = self.state.region_at(record.region)?;
+        let brush = self.state.brush_at(record.brush)?;
+        let path = self.region_path(&region);
+
+        self.paint(record_number, RecordType::META_FILLREGION, path, &Paint {
+            brush: Some(brush),
+            stroke: false,
+            operation: BinaryRasterOperation::R2_COPYPEN,
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn flood_fill(
+        mut self,
+        record_number: usize,
+        record: META_FLOODFILL,
+    ) -> Result<Self, PlayError> {
+        let point = self
+            .state
+            .point_to_device(&PointS { x: record.x_start, y: record.y_start });
+        let (device, mut context) =
+            self.context(record_number, RecordType::META_FLOODFILL);
+
+        device.flood_fill(&mut context, point);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn frame_region(
+        mut self,
+        record_number: usize,
+        record: META_FRAMEREGION,
+    ) -> Result<Self, PlayError> {
+        let region = self.state.region_at(record.region)?;
+        let brush = self.state.brush_at(record.brush)?;
+        let (left, top, right, bottom) =
+            self.state.rect_to_device(&region.bounding_rectangle);
+        let (sx, sy, ..) = self.state.window().transform();
+        // The frame is drawn inside the region, so it cannot be thicker
+        // than half of it.
+        let width = (f32::from(record.width).abs() * sx.abs())
+            .min((right - left) / 2.0);
+        let height = (f32::from(record.height).abs() * sy.abs())
+            .min((bottom - top) / 2.0);
+        let path = self.rects_path(&[
+            (left, top, right, top + height),
+            (left, bottom - height, right, bottom),
+            (left, top + height, left + width, bottom - height),
+            (right - width, top + height, right, bottom - height),
+        ]);
+
+        self.state.extend_bounds((left, top));
+        self.state.extend_bounds((right, bottom));
+        self.paint(record_number, RecordType::META_FRAMEREGION, path, &Paint {
+            brush: Some(brush),
+            stroke: false,
+            operation: BinaryRasterOperation::R2_COPYPEN,
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn invert_region(
+        mut self,
+        record_number: usize,
+        record: META_INVERTREGION,
+    ) -> Result<Self, PlayError> {
+        let region = self.state.region_at(record.region)?;
+        let path = self.region_path(&region);
+
+        self.paint(
+            record_number,
+            RecordType::META_INVERTREGION,
+            path,
+            &Paint {
+                brush: Some(Brush::Solid { color_ref: ColorRef::white() }),
+                stroke: false,
+                operation: BinaryRasterOperation::R2_NOT,
+            },
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn line_to(
+        mut self,
+        record_number: usize,
+        record: META_LINETO,
+    ) -> Result<Self, PlayError> {
+        let PointS { x, y } = self.state.drawing_position().clone();
+        let from = self.point(x, y);
+        let to = self.point(record.x, record.y);
+        let path = self.path().move_to(from).line_to(to);
+
+        self.state.set_drawing_position(PointS { x: record.x, y: record.y });
+
+        self.draw(record_number, RecordType::META_LINETO, path, None, true);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn paint_region(
+        mut self,
+        record_number: usize,
+        record: META_PAINTREGION,
+    ) -> Result<Self, PlayError> {
+        let region = self.state.region_at(record.region)?;
+        let path = self.region_path(&region);
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_PAINTREGION,
+            path,
+            Some(brush),
+            false,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pat_blt(
+        mut self,
+        record_number: usize,
+        record: META_PATBLT,
+    ) -> Result<Self, PlayError> {
+        if record.width == 0 || record.height == 0 {
+            info!(
+                %record.width,
+                %record.height,
+                "META_PATBLT is skipped because width or height is zero.",
+            );
+
+            return Ok(self);
+        }
+
+        if record.raster_operation.use_source() {
+            self.report(
+                record_number,
+                RecordType::META_PATBLT,
+                DiagnosticKind::Skipped,
+                format!(
+                    "{:?}: raster operation requires a source bitmap",
+                    record.raster_operation,
+                ),
+            );
+
+            return Ok(self);
+        }
+
+        self.blit(
+            record_number,
+            RecordType::META_PATBLT,
+            record.raster_operation,
+            (record.x_left, record.y_left, record.width, record.height),
+            None,
+        )?;
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pie(
+        mut self,
+        record_number: usize,
+        record: META_PIE,
+    ) -> Result<Self, PlayError> {
+        let inset = self.state.inside_frame_inset();
+        let Some(arc) = self.elliptical_arc(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            (record.x_radial1, record.y_radial1),
+            (record.x_radial2, record.y_radial2),
+            inset,
+        ) else {
+            info!("META_PIE is skipped because rx or ry is zero.");
+            return Ok(self);
+        };
+
+        let path = self.path().move_to(arc.center()).line_to(arc.start());
+        let path = path.elliptical_arc(&arc).close();
+        let brush = self.state.brush().clone();
+
+        self.draw(record_number, RecordType::META_PIE, path, Some(brush), true);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polyline(
+        mut self,
+        record_number: usize,
+        record: META_POLYLINE,
+    ) -> Result<Self, PlayError> {
+        if record.a_points.is_empty() {
+            return Err(PlayError::InvalidRecord {
+                cause: "aPoints[0] is not defined".to_owned(),
+            });
+        }
+
+        let points = self.points(
+            &record.a_points,
+            usize::from(record.number_of_points.unsigned_abs()).max(1),
+        )?;
+        let (first, rest) = points.split_first().expect("points is not empty");
+        let mut path = self.path().move_to(*first);
+
+        for point in rest {
+            path = path.line_to(*point);
+        }
+
+        if let Some(last) = record.a_points.get(points.len() - 1) {
+            self.state.set_drawing_position(last.clone());
+        }
+
+        self.draw(record_number, RecordType::META_POLYLINE, path, None, true);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polygon(
+        mut self,
+        record_number: usize,
+        record: META_POLYGON,
+    ) -> Result<Self, PlayError> {
+        if record.number_of_points == 0 {
+            info!(%record.number_of_points, "polygon has no points");
+            return Ok(self);
+        }
+
+        let points = self.points(
+            &record.a_points,
+            usize::from(record.number_of_points.unsigned_abs()),
+        )?;
+        let path = self.path().polygon(&points);
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_POLYGON,
+            path,
+            Some(brush),
+            true,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn poly_polygon(
+        mut self,
+        record_number: usize,
+        record: META_POLYPOLYGON,
+    ) -> Result<Self, PlayError> {
+        let poly_polygon = &record.poly_polygon;
+        let mut path = self.path();
+        let mut offset = 0_usize;
+
+        // All polygons form one path, so that the fill mode applies where
+        // they overlap.
+        for i in 0..poly_polygon.number_of_polygons {
+            let Some(count) = poly_polygon.a_points_per_polygon.get(i as usize)
+            else {
+                return Err(PlayError::InvalidRecord {
+                    cause: format!("aPointsPerPolygon[{i}] is not defined"),
+                });
+            };
+
+            let count = usize::from(*count);
+            let Some(points) =
+                poly_polygon.a_points.get(offset..offset.saturating_add(count))
+            else {
+                return Err(PlayError::InvalidRecord {
+                    cause: format!(
+                        "aPoints[{}] is not defined",
+                        poly_polygon.a_points.len(),
+                    ),
+                });
+            };
+
+            let points = self.points(points, count)?;
+
+            path = path.polygon(&points);
+            offset += count;
+        }
+
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_POLYPOLYGON,
+            path,
+            Some(brush),
+            true,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn rectangle(
+        mut self,
+        record_number: usize,
+        record: META_RECTANGLE,
+    ) -> Result<Self, PlayError> {
+        let (p1, p2) = self.rect(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            self.state.inside_frame_inset(),
+        );
+        let path = self.path().rect(p1, p2);
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_RECTANGLE,
+            path,
+            Some(brush),
+            true,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn round_rect(
+        mut self,
+        record_number: usize,
+        record: META_ROUNDRECT,
+    ) -> Result<Self, PlayError> {
+        if record.right_rect == record.left_rect
+            || record.bottom_rect == record.top_rect
+        {
+            info!("META_ROUNDRECT is skipped because width or height is zero.");
+            return Ok(self);
+        }
+
+        let (p1, p2) = self.rect(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            self.state.inside_frame_inset(),
+        );
+        let (sx, sy, ..) = self.state.window().transform();
+        let corner = (
+            f32::from(record.width).abs() * sx.abs(),
+            f32::from(record.height).abs() * sy.abs(),
+        );
+        let path = self.path().round_rect(p1, p2, corner);
+        let brush = self.state.brush().clone();
+
+        self.draw(
+            record_number,
+            RecordType::META_ROUNDRECT,
+            path,
+            Some(brush),
+            true,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pixel(
+        mut self,
+        record_number: usize,
+        record: META_SETPIXEL,
+    ) -> Result<Self, PlayError> {
+        let (x, y) = self.point(record.x, record.y);
+        let size = self.state.window().device_pixel_size();
+        let path = self.path().rect((x, y), (x + size, y + size));
+
+        self.state.extend_bounds((x + size, y + size));
+        self.paint(record_number, RecordType::META_SETPIXEL, path, &Paint {
+            brush: Some(Brush::Solid { color_ref: record.color_ref }),
+            stroke: false,
+            operation: BinaryRasterOperation::R2_COPYPEN,
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn text_out(
+        mut self,
+        record_number: usize,
+        record: META_TEXTOUT,
+    ) -> Result<Self, PlayError> {
+        let Some(text) = self.decode(
+            record_number,
+            RecordType::META_TEXTOUT,
+            record.into_utf8(self.state.font().charset),
+        ) else {
+            return Ok(self);
+        };
+        let run = TextRun::new(
+            &self.state,
+            (record.x_start, record.y_start),
+            &record.string,
+            &text,
+            &[],
+            None,
+            None,
+        );
+
+        self.text(record_number, RecordType::META_TEXTOUT, &run);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Object Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_brush_indirect(
+        mut self,
+        record_number: usize,
+        record: META_CREATEBRUSHINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.create_brush_indirect(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_font_indirect(
+        mut self,
+        record_number: usize,
+        record: META_CREATEFONTINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.create_font_indirect(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_palette(
+        mut self,
+        record_number: usize,
+        record: META_CREATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.state.create_palette(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pattern_brush(
+        mut self,
+        record_number: usize,
+        record: META_CREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.state.create_pattern_brush(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pen_indirect(
+        mut self,
+        record_number: usize,
+        record: META_CREATEPENINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.create_pen_indirect(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_region(
+        mut self,
+        record_number: usize,
+        record: META_CREATEREGION,
+    ) -> Result<Self, PlayError> {
+        self.state.create_region(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn delete_object(
+        mut self,
+        record_number: usize,
+        record: META_DELETEOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.state.delete_object(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_device_independent_bitmap_pattern_brush(
+        mut self,
+        record_number: usize,
+        record: META_DIBCREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.state.create_device_independent_bitmap_pattern_brush(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_clip_region(
+        mut self,
+        record_number: usize,
+        record: META_SELECTCLIPREGION,
+    ) -> Result<Self, PlayError> {
+        self.state.select_clip_region(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_object(
+        mut self,
+        record_number: usize,
+        record: META_SELECTOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.state.select_object(&record)?;
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_palette(
+        mut self,
+        record_number: usize,
+        record: META_SELECTPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.state.select_palette(&record)?;
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle State Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn animate_palette(
+        self,
+        record_number: usize,
+        record: META_ANIMATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        info!(
+            "META_ANIMATEPALETTE: skipped (palette animation is not \
+             applicable to the drawing)",
+        );
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn exclude_clip_rect(
+        mut self,
+        record_number: usize,
+        record: META_EXCLUDECLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.exclude_clip_rect(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn intersect_clip_rect(
+        mut self,
+        record_number: usize,
+        record: META_INTERSECTCLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.intersect_clip_rect(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn move_to(
+        mut self,
+        record_number: usize,
+        record: META_MOVETO,
+    ) -> Result<Self, PlayError> {
+        self.point(record.x, record.y);
+        self.state.move_to(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_clip_region(
+        mut self,
+        record_number: usize,
+        record: META_OFFSETCLIPRGN,
+    ) -> Result<Self, PlayError> {
+        self.state.offset_clip_region(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_viewport_origin(
+        mut self,
+        record_number: usize,
+        record: META_OFFSETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.state.offset_viewport_origin(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_window_origin(
+        mut self,
+        record_number: usize,
+        record: META_OFFSETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.state.offset_window_origin(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn realize_palette(
+        self,
+        record_number: usize,
+        record: META_REALIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        info!(
+            "META_REALIZEPALETTE: skipped (system palette mapping is not \
+             applicable to the drawing)",
+        );
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn resize_palette(
+        self,
+        record_number: usize,
+        record: META_RESIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        info!(
+            "META_RESIZEPALETTE: skipped (palette management is not \
+             applicable to the drawing)",
+        );
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn restore_device_context(
+        mut self,
+        record_number: usize,
+        record: META_RESTOREDC,
+    ) -> Result<Self, PlayError> {
+        self.state.restore_device_context(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn save_device_context(
+        mut self,
+        record_number: usize,
+        record: META_SAVEDC,
+    ) -> Result<Self, PlayError> {
+        self.state.save_device_context(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_viewport_ext(
+        mut self,
+        record_number: usize,
+        record: META_SCALEVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.scale_viewport_ext(&record)?;
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_window_ext(
+        mut self,
+        record_number: usize,
+        record: META_SCALEWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.scale_window_ext(&record)?;
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_color(
+        mut self,
+        record_number: usize,
+        record: META_SETBKCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.state.set_bk_color(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETBKMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_bk_mode(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_layout(
+        self,
+        record_number: usize,
+        record: META_SETLAYOUT,
+    ) -> Result<Self, PlayError> {
+        info!(
+            "META_SETLAYOUT: skipped (right-to-left layout is not supported)"
+        );
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_map_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETMAPMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_map_mode(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_mapper_flags(
+        self,
+        record_number: usize,
+        record: META_SETMAPPERFLAGS,
+    ) -> Result<Self, PlayError> {
+        info!(
+            "META_SETMAPPERFLAGS: skipped (font mapper algorithm control is \
+             not applicable to the drawing)",
+        );
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pal_entries(
+        self,
+        record_number: usize,
+        record: META_SETPALENTRIES,
+    ) -> Result<Self, PlayError> {
+        info!(
+            "META_SETPALENTRIES: skipped (palette entry management is not \
+             applicable to the drawing)",
+        );
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_polyfill_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETPOLYFILLMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_polyfill_mode(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_relabs(
+        self,
+        record_number: usize,
+        record: META_SETRELABS,
+    ) -> Result<Self, PlayError> {
+        info!("META_SETRELABS: reserved record and not supported");
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_raster_operation(
+        mut self,
+        record_number: usize,
+        record: META_SETROP2,
+    ) -> Result<Self, PlayError> {
+        self.state.set_raster_operation(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_stretch_blt_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETSTRETCHBLTMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_stretch_blt_mode(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_align(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTALIGN,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_align(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_char_extra(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTCHAREXTRA,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_char_extra(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_color(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_color(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_justification(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTJUSTIFICATION,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_justification(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_ext(
+        mut self,
+        record_number: usize,
+        record: META_SETVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.set_viewport_ext(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_origin(
+        mut self,
+        record_number: usize,
+        record: META_SETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.state.set_viewport_origin(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_ext(
+        mut self,
+        record_number: usize,
+        record: META_SETWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.set_window_ext(&record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_origin(
+        mut self,
+        record_number: usize,
+        record: META_SETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.state.set_window_origin(&record);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Escape Record
+    // .
+    // .
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn escape(
+        mut self,
+        record_number: usize,
+        record: META_ESCAPE,
+    ) -> Result<Self, PlayError> {
+        let (device, mut context) =
+            self.context(record_number, RecordType::META_ESCAPE);
+
+        device.escape(&mut context, &record);
+
+        Ok(self)
+    }
+}
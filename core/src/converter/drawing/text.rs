@@ -0,0 +1,209 @@
+use crate::{
+    converter::{
+        GdiState,
+        page::{StandardFont, float},
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Text of a META_TEXTOUT or META_EXTTEXTOUT record laid out in device
+/// units with the widths of the [`StandardFont`] resembling the selected
+/// font, as the metafile does not give the font metrics.
+#[derive(Clone, Debug)]
+pub struct TextRun {
+    /// Logical font the text is drawn in.
+    pub font: Font,
+    pub glyphs: Vec<Glyph>,
+    /// Whether the Dx array of the record gives the advances, rather than
+    /// the widths of the font.
+    pub spaced: bool,
+    /// Whether the glyphs are placed one by one, as the Dx array or extra
+    /// spacing moves them from where the font puts them.
+    pub custom: bool,
+    /// Em size.
+    pub size: f32,
+    /// Angle of the baseline in radians counter-clockwise from the x-axis.
+    pub angle: f32,
+    /// Fraction of the width before the reference point: 0 for TA_LEFT,
+    /// 0.5 for TA_CENTER and 1 for TA_RIGHT.
+    pub align: f32,
+    /// Reference point moved onto the baseline.
+    pub origin: (f32, f32),
+    pub width: f32,
+    /// Rectangle `(left, top, right, bottom)` filled by ETO_OPAQUE.
+    pub opaque: Option<(f32, f32, f32, f32)>,
+    /// Rectangle `(left, top, right, bottom)` clipping by ETO_CLIPPED.
+    pub clip: Option<(f32, f32, f32, f32)>,
+}
+
+/// Grapheme cluster of a [`TextRun`].
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    pub text: String,
+    /// Bytes of the record string the grapheme is decoded from.
+    pub bytes: Vec<u8>,
+    /// Advance including the extra spacing.
+    pub advance: f32,
+    /// Extra spacing of META_SETTEXTCHAREXTRA and
+    /// META_SETTEXTJUSTIFICATION.
+    pub extra: f32,
+}
+
+impl TextRun {
+    /// Lay out `text`, decoded from `string`, from the logical reference
+    /// point `(x, y)` or the current position when TA_UPDATECP is set.
+    /// ETO_OPAQUE and ETO_CLIPPED pass their rectangles as `opaque` and
+    /// `clip`.
+    pub fn new(
+        state: &GdiState,
+        (x, y): (i16, i16),
+        string: &[u8],
+        text: &str,
+        dx: &[i16],
+        opaque: Option<&Rect>,
+        clip: Option<&Rect>,
+    ) -> Self {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let font = state.font().clone();
+        let standard_font = StandardFont::new(&font);
+        let (sx, sy, ..) = state.window().transform();
+        let height =
+            if font.height == 0 { 12.0 } else { f32::from(font.height).abs() };
+        let size = height * sy.abs();
+        let spaced = dx.len() > 1;
+        let graphemes: Vec<&str> = text.graphemes(true).collect();
+        let is_break: Vec<bool> =
+            graphemes.iter().map(|grapheme| *grapheme == " ").collect();
+        let extras = state.text_extra_advances(&is_break);
+        // Per the MS-WMF spec, the Dx array has an entry per byte of the
+        // string, so the advance of a grapheme sums the entries of all the
+        // bytes it occupies in the original encoding.
+        let encoding: &'static encoding_rs::Encoding = font.charset.into();
+        let mut glyphs = vec![];
+        let mut offset = 0_usize;
+
+        for (grapheme, extra) in graphemes.into_iter().zip(extras) {
+            let length = if font.charset == CharacterSet::SYMBOL_CHARSET {
+                grapheme.chars().count()
+            } else {
+                encoding.encode(grapheme).0.len()
+            };
+            let natural = if spaced {
+                let sum = dx.iter().skip(offset).take(length);
+
+                float(sum.copied().map(i32::from).sum()) * sx.abs()
+            } else {
+                standard_font.advance(grapheme) * size
+            };
+            let extra = float(extra) * sx.abs();
+
+            glyphs.push(Glyph {
+                text: grapheme.to_owned(),
+                bytes: string
+                    .iter()
+                    .skip(offset)
+                    .take(length)
+                    .copied()
+                    .collect(),
+                advance: natural + extra,
+                extra,
+            });
+            offset = offset.saturating_add(length);
+        }
+
+        let custom = spaced || glyphs.iter().any(|glyph| glyph.extra != 0.0);
+        let width = glyphs.iter().map(|glyph| glyph.advance).sum();
+        let align = match state.text_align_horizontal() {
+            TextAlignmentMode::TA_CENTER => 0.5,
+            TextAlignmentMode::TA_RIGHT => 1.0,
+            _ => 0.0,
+        };
+        let reference = if state.text_align_update_cp() {
+            state.drawing_position().clone()
+        } else {
+            PointS { x, y }
+        };
+        let mut run = Self {
+            font,
+            glyphs,
+            spaced,
+            custom,
+            size,
+            // Escapement is in tenths of degrees.
+            angle: (f32::from(state.font().escapement) / 10.0).to_radians(),
+            align,
+            origin: state.point_to_device(&reference),
+            width,
+            opaque: opaque.map(|rect| state.rect_to_device(rect)),
+            clip: clip.map(|rect| state.rect_to_device(rect)),
+        };
+
+        run.origin = run.at(run.origin, 0.0, state.baseline_offset() * size);
+        run
+    }
+
+    /// Unit vector along the baseline.
+    pub fn direction(&self) -> (f32, f32) {
+        (self.angle.cos(), -self.angle.sin())
+    }
+
+    /// Unit vector from the baseline towards the descent.
+    pub fn down(&self) -> (f32, f32) {
+        (self.angle.sin(), self.angle.cos())
+    }
+
+    /// Point moved from `(x, y)` by `along` the baseline and `below` it.
+    pub fn at(&self, (x, y): (f32, f32), along: f32, below: f32) -> (f32, f32) {
+        let (direction, down) = (self.direction(), self.down());
+
+        (
+            x + direction.0 * along + down.0 * below,
+            y + direction.1 * along + down.1 * below,
+        )
+    }
+
+    /// Start of the text on the baseline.
+    pub fn start(&self) -> (f32, f32) {
+        self.at(self.origin, -self.align * self.width, 0.0)
+    }
+
+    /// Corners of the box from the ascent to the descent of the text.
+    pub fn corners(&self) -> [(f32, f32); 4] {
+        let start = self.start();
+
+        [
+            self.at(start, 0.0, -0.8 * self.size),
+            self.at(start, self.width, -0.8 * self.size),
+            self.at(start, self.width, 0.2 * self.size),
+            self.at(start, 0.0, 0.2 * self.size),
+        ]
+    }
+
+    /// Points on the baseline where each glyph starts.
+    pub fn glyph_origins(&self) -> Vec<(f32, f32)> {
+        let start = self.start();
+        let mut along = 0.0;
+
+        self.glyphs
+            .iter()
+            .map(|glyph| {
+                let origin = self.at(start, along, 0.0);
+
+                along += glyph.advance;
+                origin
+            })
+            .collect()
+    }
+
+    /// Distances below the baseline of the underline and strike-out lines
+    /// of the font, at the usual positions of Latin fonts.
+    pub fn lines(&self) -> Vec<f32> {
+        [(self.font.underline, 0.1), (self.font.strike_out, -0.25)]
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, below)| below * self.size)
+            .collect()
+    }
+}
@@ -0,0 +1,759 @@
+use core::fmt::Write;
+
+use crate::{
+    converter::{
+        DiagnosticKind, GdiState, PlayError,
+        drawing::{Context, Device, Image, Paint, Path, TextRun},
+        dxf::fill::{edges, hatch_lines, trapezoids},
+        page::number,
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Point in drawing units, with the y-axis pointing up.
+pub type Point = (f32, f32);
+
+/// Segments approximating a whole turn of an ellipse in fills and in
+/// outlines of unequal radii.
+const SEGMENTS_PER_TURN: u16 = 64;
+
+/// Segments approximating a Bézier curve.
+const SEGMENTS_PER_CURVE: u16 = 16;
+
+/// Reason for skipping bitmap records.
+const BITMAP: &str = "DXF R12 has no raster images";
+
+/// Reason for skipping flood fills.
+const FLOOD_FILL: &str =
+    "flood fill needs the pixels of the drawing, which DXF does not have";
+
+/// Drawing of a [`DxfPlayer`](crate::converter::DxfPlayer): its entities
+/// and the layers they are put on.
+#[derive(Default)]
+pub struct Dxf {
+    /// Colour index of each layer by name.
+    layers: BTreeMap<String, u8>,
+    entities: String,
+    /// Corners of the area of the entities, as `(min, max)`.
+    extent: Option<(Point, Point)>,
+}
+
+/// Mapping of device units back to logical units, with the signs of the
+/// axes changed so that the drawing is upright with the y-axis pointing
+/// up.
+#[derive(Clone, Copy)]
+struct Mapping {
+    /// Device point of the logical origin.
+    origin: Point,
+    /// Logical units per device unit.
+    scale: Point,
+}
+
+impl Mapping {
+    fn new(state: &GdiState) -> Self {
+        let (sx, sy, tx, ty) = state.window().transform();
+        let scale = |s: f32| {
+            if s.abs() > f32::EPSILON { 1.0 / s.abs() } else { 1.0 }
+        };
+
+        Self { origin: (tx, ty), scale: (scale(sx), scale(sy)) }
+    }
+
+    fn point(self, (x, y): (f32, f32)) -> Point {
+        ((x - self.origin.0) * self.scale.0, (self.origin.1 - y) * self.scale.1)
+    }
+}
+
+/// Figure in drawing units, as subpaths of lines and elliptical arcs.
+pub struct Outline {
+    mapping: Mapping,
+    subpaths: Vec<Subpath>,
+}
+
+struct Subpath {
+    start: Point,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+enum Segment {
+    Line(Point),
+    Arc(Arc),
+}
+
+/// Elliptical arc in drawing units, with the angles running
+/// counter-clockwise.
+#[derive(Clone, Copy)]
+struct Arc {
+    center: Point,
+    radii: Point,
+    /// Parametric angle of the start point in radians.
+    start: f32,
+    /// Signed angle in radians from the start to the end point.
+    sweep: f32,
+}
+
+impl Arc {
+    fn point(&self, angle: f32) -> Point {
+        (
+            self.center.0 + self.radii.0 * angle.cos(),
+            self.center.1 + self.radii.1 * angle.sin(),
+        )
+    }
+
+    fn end(&self) -> Point {
+        self.point(self.start + self.sweep)
+    }
+
+    fn is_circular(&self) -> bool {
+        (self.radii.0 - self.radii.1).abs() < 1e-3
+    }
+
+    fn is_full_turn(&self) -> bool {
+        self.sweep.abs() >= core::f32::consts::TAU - 1e-4
+    }
+
+    /// Points along the arc after its start, for fills and elliptical
+    /// outlines.
+    fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        use core::f32::consts::TAU;
+
+        let turns = self.sweep.abs() / TAU;
+        let steps =
+            ((turns * f32::from(SEGMENTS_PER_TURN)).ceil() as u16).max(1);
+
+        (1..=steps).map(move |i| {
+            self.point(
+                self.start + self.sweep * f32::from(i) / f32::from(steps),
+            )
+        })
+    }
+}
+
+impl Subpath {
+    fn end(&self) -> Point {
+        match self.segments.last() {
+            Some(Segment::Line(point)) => *point,
+            Some(Segment::Arc(arc)) => arc.end(),
+            None => self.start,
+        }
+    }
+
+    /// Corners of the subpath with the arcs cut into lines, for fills.
+    fn points(&self) -> Vec<Point> {
+        let mut points = vec![self.start];
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Line(point) => points.push(*point),
+                Segment::Arc(arc) => points.extend(arc.points()),
+            }
+        }
+
+        points
+    }
+
+    /// Vertices of the POLYLINE along the subpath, each with the bulge of
+    /// the arc to the next vertex. Circular arcs become bulges of at most
+    /// half a turn, and elliptical ones lines.
+    fn vertices(&self) -> Vec<(Point, f32)> {
+        use core::f32::consts::PI;
+
+        let mut vertices = vec![(self.start, 0.0)];
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Line(point) => vertices.push((*point, 0.0)),
+                Segment::Arc(arc) if arc.is_circular() => {
+                    let pieces = (arc.sweep.abs() / PI).ceil().max(1.0);
+                    let sweep = arc.sweep / pieces;
+
+                    for i in 1..=pieces as u8 {
+                        if let Some(last) = vertices.last_mut() {
+                            last.1 = (sweep / 4.0).tan();
+                        }
+
+                        vertices.push((
+                            arc.point(arc.start + sweep * f32::from(i)),
+                            0.0,
+                        ));
+                    }
+                }
+                Segment::Arc(arc) => {
+                    vertices.extend(arc.points().map(|point| (point, 0.0)));
+                }
+            }
+        }
+
+        // The closing segment of a closed polyline is implied.
+        if self.closed && vertices.len() > 1 {
+            let (first, last) = (vertices[0].0, vertices[vertices.len() - 1].0);
+
+            if (first.0 - last.0).abs() < 1e-3
+                && (first.1 - last.1).abs() < 1e-3
+            {
+                vertices.pop();
+            }
+        }
+
+        vertices
+    }
+}
+
+impl Outline {
+    fn current(&mut self) -> &mut Subpath {
+        if self.subpaths.is_empty() {
+            self.subpaths.push(Subpath {
+                start: (0.0, 0.0),
+                segments: vec![],
+                closed: false,
+            });
+        }
+
+        let last = self.subpaths.len() - 1;
+
+        &mut self.subpaths[last]
+    }
+}
+
+impl Path for Outline {
+    fn move_to(mut self, point: (f32, f32)) -> Self {
+        self.subpaths.push(Subpath {
+            start: self.mapping.point(point),
+            segments: vec![],
+            closed: false,
+        });
+        self
+    }
+
+    fn line_to(mut self, point: (f32, f32)) -> Self {
+        let point = self.mapping.point(point);
+
+        self.current().segments.push(Segment::Line(point));
+        self
+    }
+
+    fn curve_to(
+        mut self,
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+    ) -> Self {
+        let mapping = self.mapping;
+        let subpath = self.current();
+        let (p0, p1, p2, p3) = (
+            subpath.end(),
+            mapping.point(control1),
+            mapping.point(control2),
+            mapping.point(to),
+        );
+
+        for i in 1..=SEGMENTS_PER_CURVE {
+            let t = f32::from(i) / f32::from(SEGMENTS_PER_CURVE);
+            // Bernstein weights of the control points at `t`.
+            let weights = [
+                (1.0 - t).powi(3),
+                3.0 * (1.0 - t).powi(2) * t,
+                3.0 * (1.0 - t) * t * t,
+                t.powi(3),
+            ];
+            let along = |axis: fn(Point) -> f32| {
+                [p0, p1, p2, p3]
+                    .into_iter()
+                    .zip(weights)
+                    .map(|(point, weight)| axis(point) * weight)
+                    .sum()
+            };
+
+            subpath
+                .segments
+                .push(Segment::Line((along(|p| p.0), along(|p| p.1))));
+        }
+
+        self
+    }
+
+    fn close(mut self) -> Self {
+        self.current().closed = true;
+        self
+    }
+
+    /// Keep the arc as it is, for ARC entities and bulges. Mirroring the
+    /// y-axis reverses the angles.
+    fn arc_to(
+        mut self,
+        center: (f32, f32),
+        (rx, ry): (f32, f32),
+        start: f32,
+        sweep: f32,
+    ) -> Self {
+        let Mapping { scale, .. } = self.mapping;
+        let arc = Arc {
+            center: self.mapping.point(center),
+            radii: (rx * scale.0, ry * scale.1),
+            start: -start,
+            sweep: -sweep,
+        };
+
+        self.current().segments.push(Segment::Arc(arc));
+        self
+    }
+}
+
+impl Dxf {
+    /// Register the layer of the selected pen and return its name.
+    fn layer(&mut self, state: &GdiState) -> String {
+        let color = &state.pen().color_ref;
+        let name = format!(
+            "PEN_{:02X}{:02X}{:02X}",
+            color.red, color.green, color.blue
+        );
+
+        self.layers.entry(name.clone()).or_insert_with(|| color_index(color));
+        name
+    }
+
+    /// Start an entity of type `typ` on the layer of the selected pen,
+    /// with its own colour index when `color` is given.
+    fn entity(
+        &mut self,
+        state: &GdiState,
+        typ: &str,
+        color: Option<&ColorRef>,
+    ) {
+        let layer = self.layer(state);
+
+        group(&mut self.entities, 0, typ);
+        group(&mut self.entities, 8, layer);
+
+        if let Some(color) = color {
+            group(&mut self.entities, 62, color_index(color));
+        }
+    }
+
+    /// Write `point` with the group codes of its x and y coordinates
+    /// and include it in the extent.
+    fn coordinates(&mut self, code: u16, point: Point) {
+        group(&mut self.entities, code, number(point.0));
+        group(&mut self.entities, code + 10, number(point.1));
+
+        self.extent = Some(self.extent.map_or((point, point), |(min, max)| {
+            (
+                (min.0.min(point.0), min.1.min(point.1)),
+                (max.0.max(point.0), max.1.max(point.1)),
+            )
+        }));
+    }
+
+    /// Write a POLYLINE through `vertices`, each with the bulge of the arc
+    /// to the next vertex.
+    fn polyline_entity(
+        &mut self,
+        state: &GdiState,
+        vertices: &[(Point, f32)],
+        closed: bool,
+    ) {
+        self.entity(state, "POLYLINE", None);
+        group(&mut self.entities, 66, 1);
+        // The elevation of the polyline, as a point.
+        group(&mut self.entities, 10, 0);
+        group(&mut self.entities, 20, 0);
+        group(&mut self.entities, 30, 0);
+        group(&mut self.entities, 70, u8::from(closed));
+
+        for (point, bulge) in vertices {
+            self.entity(state, "VERTEX", None);
+            self.coordinates(10, *point);
+
+            if *bulge != 0.0 {
+                group(&mut self.entities, 42, number(*bulge));
+            }
+        }
+
+        self.entity(state, "SEQEND", None);
+    }
+
+    /// Write an ARC along the circular `arc`.
+    fn arc_entity(&mut self, state: &GdiState, arc: &Arc) {
+        use core::f32::consts::TAU;
+
+        // ARC entities run along increasing angles.
+        let (start, end) = if arc.is_full_turn() {
+            (0.0, TAU)
+        } else {
+            let start = (arc.start + arc.sweep.min(0.0)).rem_euclid(TAU);
+
+            (start, (start + arc.sweep.abs()).rem_euclid(TAU))
+        };
+
+        self.entity(state, "ARC", None);
+        self.coordinates(10, arc.center);
+        group(&mut self.entities, 40, number(arc.radii.0));
+        group(&mut self.entities, 50, number(start.to_degrees()));
+        group(&mut self.entities, 51, number(end.to_degrees()));
+    }
+
+    /// Write the outline of `subpath`: a LINE for a single line, an ARC
+    /// for a single circular arc and a POLYLINE otherwise.
+    fn stroke(&mut self, state: &GdiState, subpath: &Subpath) {
+        match subpath.segments.as_slice() {
+            [] => {}
+            [Segment::Line(to)] if !subpath.closed => {
+                self.entity(state, "LINE", None);
+                self.coordinates(10, subpath.start);
+                self.coordinates(11, *to);
+            }
+            [Segment::Arc(arc)]
+                if arc.is_circular()
+                    && (!subpath.closed || arc.is_full_turn()) =>
+            {
+                self.arc_entity(state, arc);
+            }
+            _ => {
+                let vertices = subpath.vertices();
+
+                self.polyline_entity(state, &vertices, subpath.closed);
+            }
+        }
+    }
+
+    /// Fill the polygons `paths` with `brush`, unless the brush is null.
+    fn fill(
+        &mut self,
+        context: &mut Context<'_>,
+        paths: &[Vec<Point>],
+        brush: &Brush,
+    ) {
+        let state = context.state;
+        let (color, style) = match brush {
+            Brush::Null => return,
+            Brush::Solid { color_ref } => (color_ref.clone(), None),
+            Brush::Hatched { color_ref, brush_hatch } => {
+                (color_ref.clone(), Some(*brush_hatch))
+            }
+            Brush::Pattern { .. } | Brush::DIBPatternPT { .. } => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    "pattern brushes have no counterpart in DXF".to_owned(),
+                );
+                return;
+            }
+        };
+        let paths: Vec<_> =
+            paths.iter().filter(|path| path.len() > 2).collect();
+        let winding = state.poly_fill_mode() == PolyFillMode::WINDING;
+
+        match style {
+            None => {
+                let edges = edges(paths.iter().map(|path| path.as_slice()));
+                let Some(quads) = trapezoids(&edges, winding) else {
+                    context.report(
+                        DiagnosticKind::Skipped,
+                        "the fill has too many edges to draw".to_owned(),
+                    );
+                    return;
+                };
+
+                for corners in quads {
+                    self.entity(state, "SOLID", Some(&color));
+
+                    for (code, corner) in (10..).zip(corners) {
+                        self.coordinates(code, corner);
+                    }
+                }
+            }
+            Some(style) => {
+                // GDI spaces the lines of hatches 8 pixels apart.
+                let window = state.window();
+                let spacing = 8.0 * window.device_pixel_size()
+                    / window.device_units_per_logical_x().max(f32::EPSILON);
+                let angles: &[f32] = match style {
+                    HatchStyle::HS_HORIZONTAL => &[0.0],
+                    HatchStyle::HS_VERTICAL => &[90.0],
+                    HatchStyle::HS_FDIAGONAL => &[-45.0],
+                    HatchStyle::HS_BDIAGONAL => &[45.0],
+                    HatchStyle::HS_CROSS => &[0.0, 90.0],
+                    HatchStyle::HS_DIAGCROSS => &[45.0, -45.0],
+                };
+
+                let Some(lines) = angles
+                    .iter()
+                    .map(|angle| {
+                        hatch_lines(
+                            &paths,
+                            angle.to_radians(),
+                            spacing,
+                            winding,
+                        )
+                    })
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    context.report(
+                        DiagnosticKind::Skipped,
+                        "the hatch has too many lines or edges to draw"
+                            .to_owned(),
+                    );
+                    return;
+                };
+
+                for (from, to) in lines.into_iter().flatten() {
+                    self.entity(state, "LINE", Some(&color));
+                    self.coordinates(10, from);
+                    self.coordinates(11, to);
+                }
+            }
+        }
+    }
+}
+
+impl Device for Dxf {
+    type Path = Outline;
+
+    fn path(&self, state: &GdiState) -> Outline {
+        Outline { mapping: Mapping::new(state), subpaths: vec![] }
+    }
+
+    /// Draw `path` as R2_COPYPEN, as DXF entities cannot combine with the
+    /// drawing, except that inverting is left out.
+    fn draw(
+        &mut self,
+        context: &mut Context<'_>,
+        path: Outline,
+        paint: &Paint,
+    ) {
+        use BinaryRasterOperation as Rop2;
+
+        match paint.operation {
+            Rop2::R2_COPYPEN => {}
+            Rop2::R2_NOT => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    "inverting the drawing has no counterpart in DXF"
+                        .to_owned(),
+                );
+
+                return;
+            }
+            operation => context.report(
+                DiagnosticKind::Approximated,
+                format!("{operation:?}: drawn as R2_COPYPEN"),
+            ),
+        }
+
+        if let Some(brush) = &paint.brush {
+            let paths: Vec<_> =
+                path.subpaths.iter().map(Subpath::points).collect();
+
+            self.fill(context, &paths, brush);
+        }
+
+        if paint.stroke && context.state.pen().style.style != PenStyle::PS_NULL
+        {
+            for subpath in &path.subpaths {
+                self.stroke(context.state, subpath);
+            }
+        }
+    }
+
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        _: Image,
+        _: ((f32, f32), (f32, f32)),
+        _: (f32, f32, f32, f32),
+        _: TernaryRasterOperation,
+    ) {
+        context.report(DiagnosticKind::Skipped, BITMAP.to_owned());
+    }
+
+    /// Write a TEXT entity from the reference point of `run`, after
+    /// filling the ETO_OPAQUE rectangle.
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let state = context.state;
+        let mapping = Mapping::new(state);
+
+        if let Some((left, top, right, bottom)) = run.opaque {
+            let corners =
+                [(left, top), (right, top), (right, bottom), (left, bottom)];
+            let brush = Brush::Solid { color_ref: state.bk_color().clone() };
+
+            self.fill(
+                context,
+                &[corners.map(|corner| mapping.point(corner)).to_vec()],
+                &brush,
+            );
+        }
+
+        let text: String =
+            run.glyphs.iter().map(|glyph| glyph.text.as_str()).collect();
+
+        if text.trim().is_empty() {
+            return;
+        }
+
+        // The reference point of the record, which TEXT entities align
+        // to as the text alignment tells.
+        let point = mapping.point(run.at(
+            run.origin,
+            0.0,
+            -state.baseline_offset() * run.size,
+        ));
+        let height = run.size * mapping.scale.1;
+        // Escapement is in tenths of degrees.
+        let angle = f32::from(run.font.escapement) / 10.0;
+        let horizontal = match state.text_align_horizontal() {
+            TextAlignmentMode::TA_CENTER => 1,
+            TextAlignmentMode::TA_RIGHT => 2,
+            _ => 0,
+        };
+        let vertical = match state.text_align_vertical() {
+            VerticalTextAlignmentMode::VTA_BOTTOM => 1,
+            VerticalTextAlignmentMode::VTA_CENTER => 2,
+            VerticalTextAlignmentMode::VTA_TOP => 3,
+            _ => 0,
+        };
+
+        self.entity(state, "TEXT", Some(state.text_color()));
+        self.coordinates(10, point);
+        group(&mut self.entities, 40, number(height));
+        group(&mut self.entities, 1, escape(&text));
+
+        if angle != 0.0 {
+            group(&mut self.entities, 50, number(angle));
+        }
+
+        if horizontal != 0 || vertical != 0 {
+            group(&mut self.entities, 72, horizontal);
+            self.coordinates(11, point);
+            group(&mut self.entities, 73, vertical);
+        }
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+        context.report(DiagnosticKind::Skipped, FLOOD_FILL.to_owned());
+    }
+
+    fn generate(self, _: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let Self { mut layers, entities, extent } = self;
+        let mut output = String::new();
+
+        group(&mut output, 0, "SECTION");
+        group(&mut output, 2, "HEADER");
+        group(&mut output, 9, "$ACADVER");
+        group(&mut output, 1, "AC1009");
+
+        if let Some((min, max)) = extent {
+            group(&mut output, 9, "$EXTMIN");
+            group(&mut output, 10, number(min.0));
+            group(&mut output, 20, number(min.1));
+            group(&mut output, 9, "$EXTMAX");
+            group(&mut output, 10, number(max.0));
+            group(&mut output, 20, number(max.1));
+        }
+
+        group(&mut output, 0, "ENDSEC");
+
+        group(&mut output, 0, "SECTION");
+        group(&mut output, 2, "TABLES");
+        group(&mut output, 0, "TABLE");
+        group(&mut output, 2, "LTYPE");
+        group(&mut output, 70, 1);
+        group(&mut output, 0, "LTYPE");
+        group(&mut output, 2, "CONTINUOUS");
+        group(&mut output, 70, 0);
+        group(&mut output, 3, "Solid line");
+        group(&mut output, 72, 65);
+        group(&mut output, 73, 0);
+        group(&mut output, 40, 0);
+        group(&mut output, 0, "ENDTAB");
+
+        layers.entry("0".to_owned()).or_insert(7);
+
+        group(&mut output, 0, "TABLE");
+        group(&mut output, 2, "LAYER");
+        group(&mut output, 70, layers.len());
+
+        for (name, color) in &layers {
+            group(&mut output, 0, "LAYER");
+            group(&mut output, 2, name);
+            group(&mut output, 70, 0);
+            group(&mut output, 62, color);
+            group(&mut output, 6, "CONTINUOUS");
+        }
+
+        group(&mut output, 0, "ENDTAB");
+        group(&mut output, 0, "ENDSEC");
+
+        group(&mut output, 0, "SECTION");
+        group(&mut output, 2, "ENTITIES");
+        output.push_str(&entities);
+        group(&mut output, 0, "ENDSEC");
+        group(&mut output, 0, "EOF");
+
+        Ok(output.into_bytes())
+    }
+}
+
+/// Append a group of the code `code` and the value `value`.
+fn group(output: &mut String, code: u16, value: impl core::fmt::Display) {
+    let _ = write!(output, "{code:>3}\n{value}\n");
+}
+
+/// Index of the AutoCAD colour nearest to `color`, among the standard
+/// colours and the shades of grey.
+fn color_index(color: &ColorRef) -> u8 {
+    const COLORS: [(u8, (u8, u8, u8)); 15] = [
+        (1, (255, 0, 0)),
+        (2, (255, 255, 0)),
+        (3, (0, 255, 0)),
+        (4, (0, 255, 255)),
+        (5, (0, 0, 255)),
+        (6, (255, 0, 255)),
+        // Drawn black on a light background and white on a dark one.
+        (7, (0, 0, 0)),
+        (8, (128, 128, 128)),
+        (9, (192, 192, 192)),
+        (250, (51, 51, 51)),
+        (251, (91, 91, 91)),
+        (252, (132, 132, 132)),
+        (253, (173, 173, 173)),
+        (254, (214, 214, 214)),
+        (255, (255, 255, 255)),
+    ];
+
+    let distance = |(red, green, blue): (u8, u8, u8)| {
+        let square = |a: u8, b: u8| {
+            let d = i32::from(a) - i32::from(b);
+            d * d
+        };
+
+        square(red, color.red)
+            + square(green, color.green)
+            + square(blue, color.blue)
+    };
+
+    COLORS
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map_or(7, |(index, _)| *index)
+}
+
+/// Text as a DXF string, with characters outside ASCII written as
+/// `\U+XXXX` and control characters left out.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match u32::from(c) {
+            0x20..=0x7E => escaped.push(c),
+            code @ 0x80..=0xFFFF => {
+                let _ = write!(escaped, "\\U+{code:04X}");
+            }
+            0x10000.. => escaped.push('?'),
+            _ => {}
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,228 @@
+//! Fills cut into the entities of DXF Release 12, which has no hatch or
+//! region entity: quadrilaterals for solid brushes and lines for hatched
+//! ones.
+
+use crate::{converter::dxf::device::Point, imports::*};
+
+/// Most lines a hatched brush draws over one fill.
+const MAX_HATCH_LINES: f32 = 10_000.0;
+
+/// Most edges one fill visits, counting an edge again for every band and
+/// every line of a hatch it crosses.
+const MAX_FILL_WORK: usize = 1 << 26;
+
+/// Edge of a fill from its lower to its upper end, with the direction it
+/// winds around the inside.
+pub struct Edge {
+    from: Point,
+    to: Point,
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        let (from, to) = (self.from, self.to);
+
+        from.0 + (to.0 - from.0) * (y - from.1) / (to.1 - from.1)
+    }
+}
+
+/// Edges of the closed polygons `paths` by their lower ends, leaving out
+/// horizontal ones.
+pub fn edges<'a>(paths: impl Iterator<Item = &'a [Point]>) -> Vec<Edge> {
+    let mut edges: Vec<_> = paths
+        .flat_map(|path| {
+            path.iter().zip(path.iter().cycle().skip(1)).filter_map(
+                |(&a, &b)| match a.1.partial_cmp(&b.1) {
+                    Some(core::cmp::Ordering::Less) => {
+                        Some(Edge { from: a, to: b, winding: 1 })
+                    }
+                    Some(core::cmp::Ordering::Greater) => {
+                        Some(Edge { from: b, to: a, winding: -1 })
+                    }
+                    _ => None,
+                },
+            )
+        })
+        .collect();
+
+    edges.sort_by(|a, b| a.from.1.total_cmp(&b.from.1));
+    edges
+}
+
+/// Horizontal line sweeping up over edges sorted by their lower ends.
+struct Sweep<'a> {
+    edges: &'a [Edge],
+    next: usize,
+    crossing: Vec<&'a Edge>,
+    work: usize,
+}
+
+impl<'a> Sweep<'a> {
+    fn new(edges: &'a [Edge]) -> Self {
+        Self { edges, next: 0, crossing: vec![], work: 0 }
+    }
+
+    /// Move the line up to `y` and return the edges crossing it, or `None`
+    /// once the sweep has visited more than [`MAX_FILL_WORK`] edges.
+    fn advance(&mut self, y: f32) -> Option<&[&'a Edge]> {
+        while let Some(edge) =
+            self.edges.get(self.next).filter(|edge| edge.from.1 <= y)
+        {
+            self.crossing.push(edge);
+            self.next += 1;
+        }
+
+        self.crossing.retain(|edge| y < edge.to.1);
+        self.work += self.crossing.len();
+
+        (self.work <= MAX_FILL_WORK).then_some(self.crossing.as_slice())
+    }
+}
+
+/// Pairs of the edges of `crossing` that bound the inside on the line at
+/// `y`, from left to right, by the nonzero winding rule when `winding` is
+/// set and by the even-odd rule otherwise.
+fn inside<'a>(
+    crossing: &[&'a Edge],
+    y: f32,
+    winding: bool,
+) -> Vec<(&'a Edge, &'a Edge)> {
+    let mut crossing = crossing.to_vec();
+    let mut pairs = vec![];
+    let mut count = 0;
+    let mut left = None;
+
+    crossing.sort_by(|a, b| a.x_at(y).total_cmp(&b.x_at(y)));
+
+    for edge in crossing {
+        count += if winding { edge.winding } else { 1 };
+
+        let is_inside = if winding { count != 0 } else { count % 2 != 0 };
+
+        match (left, is_inside) {
+            (None, true) => left = Some(edge),
+            (Some(from), false) => {
+                pairs.push((from, edge));
+                left = None;
+            }
+            _ => {}
+        }
+    }
+
+    pairs
+}
+
+/// Quadrilaterals covering the inside of `edges`, with the corners in the
+/// order of a SOLID entity: both lower corners, then both upper ones. `None`
+/// when the edges are too many to cut through.
+///
+/// The inside is cut into horizontal bands at every vertex and crossing of
+/// edges, so that the edges bounding it in a band run straight through.
+pub fn trapezoids(edges: &[Edge], winding: bool) -> Option<Vec<[Point; 4]>> {
+    let mut ys: Vec<f32> =
+        edges.iter().flat_map(|edge| [edge.from.1, edge.to.1]).collect();
+    let mut sweep = Sweep::new(edges);
+    let mut quads = vec![];
+
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    for band in ys.windows(2) {
+        let (mut bottom, top) = (band[0], band[1]);
+
+        while top - bottom > f32::EPSILON * top.abs().max(1.0) {
+            let mut crossing = sweep.advance(bottom)?.to_vec();
+
+            // Edges crossing inside the band split it at the lowest
+            // crossing, which is between neighbours at its bottom.
+            crossing.sort_by(|a, b| {
+                a.x_at(bottom)
+                    .total_cmp(&b.x_at(bottom))
+                    .then(a.x_at(top).total_cmp(&b.x_at(top)))
+            });
+
+            let split = crossing
+                .windows(2)
+                .filter_map(|pair| {
+                    let low = pair[0].x_at(bottom) - pair[1].x_at(bottom);
+                    let high = pair[0].x_at(top) - pair[1].x_at(top);
+
+                    (high > 0.0 && low < 0.0)
+                        .then(|| bottom + (top - bottom) * low / (low - high))
+                })
+                .filter(|y| *y > bottom)
+                .min_by(f32::total_cmp)
+                .unwrap_or(top);
+
+            for (left, right) in
+                inside(&crossing, f32::midpoint(bottom, split), winding)
+            {
+                quads.push([
+                    (left.x_at(bottom), bottom),
+                    (right.x_at(bottom), bottom),
+                    (left.x_at(split), split),
+                    (right.x_at(split), split),
+                ]);
+            }
+
+            bottom = split;
+        }
+    }
+
+    Some(quads)
+}
+
+/// Lines of a hatch at `angle` to the x-axis over the inside of `paths`,
+/// `spacing` apart, or `None` when there would be too many of them.
+pub fn hatch_lines(
+    paths: &[&Vec<Point>],
+    angle: f32,
+    spacing: f32,
+    winding: bool,
+) -> Option<Vec<(Point, Point)>> {
+    let (sin, cos) = angle.sin_cos();
+    // The paths turned so that the lines of the hatch are horizontal.
+    let turned: Vec<Vec<Point>> = paths
+        .iter()
+        .map(|path| {
+            path.iter()
+                .map(|(x, y)| (x * cos + y * sin, y * cos - x * sin))
+                .collect()
+        })
+        .collect();
+    let (low, high) = turned
+        .iter()
+        .flatten()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), point| {
+            (low.min(point.1), high.max(point.1))
+        });
+
+    if low > high {
+        return Some(vec![]);
+    }
+
+    let (first, last) = ((low / spacing).ceil(), (high / spacing).floor());
+
+    if (last - first).is_nan() || last - first >= MAX_HATCH_LINES {
+        return None;
+    }
+
+    let edges = edges(turned.iter().map(Vec::as_slice));
+    let mut sweep = Sweep::new(&edges);
+    let unturn = |x: f32, y: f32| (x * cos - y * sin, x * sin + y * cos);
+    let mut lines = vec![];
+    let mut line = first;
+
+    while line <= last {
+        let y = line * spacing;
+
+        for (left, right) in inside(sweep.advance(y)?, y, winding) {
+            lines.push((unturn(left.x_at(y), y), unturn(right.x_at(y), y)));
+        }
+
+        line += 1.0;
+    }
+
+    Some(lines)
+}
@@ -0,0 +1,34 @@
+mod device;
+mod fill;
+
+pub use self::device::Dxf;
+use crate::converter::drawing::Drawing;
+
+/// Player writing the metafile as an ASCII DXF drawing for CAD
+/// applications.
+///
+/// Coordinates stay in logical units, so the drawing keeps the scale the
+/// metafile was made at. Only their signs change, so that the drawing is
+/// upright with the y-axis of DXF pointing up.
+///
+/// The file is an AutoCAD Release 12 drawing, which any DXF reader opens.
+/// Lines become LINE entities and outlines POLYLINE entities, with bulges
+/// for circular arcs along them such as the corners of rounded
+/// rectangles. Circular arcs on their own become ARC entities, and
+/// elliptical ones polylines, as Release 12 has no ellipse. Solid brushes
+/// fill with SOLID entities, quadrilaterals cut from the figure along its
+/// vertices, and hatched brushes with a LINE for each line of the hatch.
+/// Text becomes TEXT entities.
+///
+/// Every entity is put on the layer of the colour of the selected pen,
+/// named `PEN_RRGGBB`. DXF colours are indices into a palette, so layers
+/// get the nearest index, and fills and text the index nearest to the
+/// colour of the brush or text. Pen styles and widths, the clipping region
+/// and bitmaps have no counterpart and are left out.
+pub type DxfPlayer = Drawing<Dxf>;
+
+impl DxfPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
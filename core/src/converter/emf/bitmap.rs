@@ -0,0 +1,106 @@
+use crate::{imports::*, parser::*};
+
+/// A bitmap as EMF records store it: a BITMAPINFO structure and the pixel
+/// data that follows it.
+#[derive(Clone, Debug)]
+pub struct Dib {
+    pub info: Vec<u8>,
+    pub bits: Vec<u8>,
+    /// DIBColors Enumeration telling what the colour table holds.
+    pub usage: u32,
+}
+
+impl Dib {
+    /// Copy a DIB with its colour table and bits as they are, so that
+    /// compressed bitmaps are passed through.
+    pub fn from_dib(dib: &DeviceIndependentBitmap) -> Self {
+        let mut info = dib.dib_header_info.to_bytes();
+        let usage = match &dib.colors {
+            Colors::PaletteIndices(indices) => {
+                for index in indices {
+                    info.extend(index.to_le_bytes());
+                }
+
+                ColorUsage::DIB_PAL_COLORS as u32
+            }
+            Colors::RGBQuad(colors) => {
+                for color in colors {
+                    info.extend([
+                        color.blue,
+                        color.green,
+                        color.red,
+                        color.reserved,
+                    ]);
+                }
+
+                ColorUsage::DIB_RGB_COLORS as u32
+            }
+            Colors::RGBTriple(colors) => {
+                for color in colors {
+                    info.extend([color.blue, color.green, color.red]);
+                }
+
+                ColorUsage::DIB_RGB_COLORS as u32
+            }
+            Colors::Null => ColorUsage::DIB_RGB_COLORS as u32,
+        };
+
+        Self { info, bits: dib.bitmap_buffer.a_data.clone(), usage }
+    }
+
+    /// Convert a monochrome device-dependent bitmap to a top-down DIB with
+    /// black and white as its colours.
+    pub fn from_bitmap16(bitmap: &Bitmap16) -> Result<Self, String> {
+        if bitmap.bits_pixel != BitCount::BI_BITCOUNT_1 || bitmap.planes != 1 {
+            return Err(format!(
+                "{:?} device-dependent bitmaps are not supported",
+                bitmap.bits_pixel,
+            ));
+        }
+
+        let width = usize::from(bitmap.width.unsigned_abs());
+        let height = usize::from(bitmap.height.unsigned_abs());
+        // Rows of a Bitmap16 are aligned to 16 bits and those of a DIB to
+        // 32 bits.
+        let source_stride = usize::from(bitmap.width_bytes.unsigned_abs())
+            .max(width.div_ceil(16) * 2);
+        let stride = width.div_ceil(32) * 4;
+        let mut bits = Vec::with_capacity(stride * height);
+
+        for row in 0..height {
+            let start = row * source_stride;
+            let Some(line) = bitmap.bits.get(start..start + width.div_ceil(8))
+            else {
+                return Err(format!(
+                    "bitmap has {} bytes, fewer than {height} rows of \
+                     {source_stride} bytes",
+                    bitmap.bits.len(),
+                ));
+            };
+
+            bits.extend_from_slice(line);
+            bits.resize((row + 1) * stride, 0);
+        }
+
+        let info = BitmapInfoHeader::Info(BitmapInfoHeaderInfo {
+            header_size: 40,
+            width: i32::from(bitmap.width.unsigned_abs()),
+            // A negative height makes the rows run from top to bottom, as
+            // those of a Bitmap16 do.
+            height: -i32::from(bitmap.height.unsigned_abs()),
+            planes: 1,
+            bit_count: BitCount::BI_BITCOUNT_1,
+            compression: Compression::BI_RGB,
+            image_size: u32::try_from(bits.len()).unwrap_or(u32::MAX),
+            x_pels_per_meter: 0,
+            y_pels_per_meter: 0,
+            color_used: 2,
+            color_important: 0,
+        });
+        let mut info = info.to_bytes();
+
+        info.extend([0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x00]);
+
+        Ok(Self { info, bits, usage: ColorUsage::DIB_RGB_COLORS as u32 })
+    }
+}
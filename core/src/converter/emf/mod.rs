@@ -0,0 +1,2225 @@
+mod bitmap;
+mod object_table;
+mod record;
+
+use crate::{
+    converter::{
+        Diagnostic, DiagnosticKind, Diagnostics, GdiState, PlayError,
+        emf::{
+            bitmap::Dib,
+            object_table::{Object, ObjectKind, ObjectTable},
+            record::{EmfRecordType, Record, bounding_rect},
+        },
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Handle of the DEFAULT_PALETTE stock object, selected until the metafile
+/// selects a palette of its own.
+const DEFAULT_PALETTE: u32 = 0x8000_000F;
+
+/// Player writing the metafile as an Enhanced Metafile (EMF).
+///
+/// Each record becomes its EMF counterpart in the same logical units, so
+/// that GDI replays the output as it does the original. Object table
+/// indexes become EMF object handles and text is converted to UTF-16 from
+/// the character set of the selected font. The header frames the
+/// META_PLACEABLE bounding box at its resolution, or the area of the
+/// drawing at 96 DPI without one.
+#[derive(Default)]
+pub struct EmfPlayer {
+    records: Vec<Vec<u8>>,
+    objects: ObjectTable,
+    state: GdiState,
+    /// Handle of the selected palette, or `None` for DEFAULT_PALETTE,
+    /// which EMR_SAVEDC and EMR_RESTOREDC save and restore along with
+    /// the device context.
+    palette: Option<u32>,
+    palette_stack: Vec<Option<u32>>,
+    diagnostics: Diagnostics,
+    // Size of the META_PLACEABLE bounding box in pixels.
+    page: Option<(f32, f32)>,
+}
+
+impl EmfPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive a [`Diagnostic`] for every record that cannot be converted
+    /// exactly.
+    #[must_use]
+    pub fn diagnostic_sink(
+        mut self,
+        sink: impl FnMut(Diagnostic) + Send + 'static,
+    ) -> Self {
+        self.diagnostics.set_sink(sink);
+        self
+    }
+
+    fn push(&mut self, record: Record) {
+        self.records.push(record.finish());
+    }
+
+    /// Handle of the selected palette.
+    fn palette_handle(&self) -> u32 {
+        self.palette.unwrap_or(DEFAULT_PALETTE)
+    }
+
+    /// Append the record creating an object of `kind` under the next free
+    /// handle, which `record` is given.
+    fn create(&mut self, kind: ObjectKind, record: impl FnOnce(u32) -> Record) {
+        let Some(handle) = self.objects.next_handle() else {
+            warn!("object table is full, ignoring object");
+            return;
+        };
+
+        self.push(record(handle));
+        self.objects.insert(Object::Handle { handle, kind });
+    }
+
+    /// Convert a logical point to device units and include it in the
+    /// drawing bounds.
+    fn point(&mut self, x: i16, y: i16) -> (f32, f32) {
+        let point = self.state.point_to_device(&PointS { x, y });
+
+        self.state.extend_bounds(point);
+        point
+    }
+
+    /// Bounds of the logical points in pixels, as the Bounds field of
+    /// drawing records holds them, including the points in the drawing
+    /// bounds.
+    fn bounds(&mut self, points: &[(i16, i16)]) -> (i32, i32, i32, i32) {
+        let pixel = self.state.window().device_pixel_size();
+
+        bounding_rect(points.iter().map(|(x, y)| {
+            let (x, y) = self.point(*x, *y);
+            let (x, y) = (x / pixel, y / pixel);
+
+            (
+                x.floor() as i32,
+                y.floor() as i32,
+                x.ceil() as i32,
+                y.ceil() as i32,
+            )
+        }))
+    }
+
+    /// Region at `index` of the object table as rectangles in logical
+    /// units, as RegionData Objects hold them.
+    fn region(
+        &self,
+        index: u16,
+    ) -> Result<Vec<(i32, i32, i32, i32)>, PlayError> {
+        self.state.region_at(index).map(|region| region_rects(&region))
+    }
+
+    /// Handle of the brush at `index` of the object table.
+    fn brush(&self, index: u16) -> Result<u32, PlayError> {
+        match self.objects.get(index) {
+            Some(Object::Handle { handle, kind: ObjectKind::Brush }) => {
+                Ok(*handle)
+            }
+            _ => Err(PlayError::InvalidBrush {
+                cause: format!("object at index {index} is not a Brush"),
+            }),
+        }
+    }
+
+    /// Append a record drawing the logical region `rects`, where `fields`
+    /// writes what follows Bounds and RgnDataSize.
+    fn region_record(
+        &mut self,
+        typ: EmfRecordType,
+        rects: &[(i32, i32, i32, i32)],
+        fields: impl FnOnce(Record) -> Record,
+    ) {
+        let clamp = |v: i32| {
+            i16::try_from(v.clamp(i16::MIN.into(), i16::MAX.into()))
+                .unwrap_or_default()
+        };
+        let (left, top, right, bottom) = bounding_rect(rects.iter().copied());
+        let bounds = self.bounds(&[
+            (clamp(left), clamp(top)),
+            (clamp(right), clamp(bottom)),
+        ]);
+        let record = Record::new(typ).rect(bounds).size(32 + rects.len() * 16);
+
+        self.push(fields(record).region(rects));
+    }
+
+    /// Append the record of a blit to the logical rectangle `(x, y, width,
+    /// height)`, copying the part `(x, y, width, height)` of the bitmap in
+    /// `source` when given.
+    #[allow(clippy::type_complexity)]
+    fn blit(
+        &mut self,
+        raster_operation: TernaryRasterOperation,
+        (x, y, width, height): (i16, i16, i16, i16),
+        source: Option<(Dib, (i16, i16, i16, i16))>,
+    ) {
+        let bounds = self.bounds(&[
+            (x, y),
+            (x.saturating_add(width), y.saturating_add(height)),
+        ]);
+        let typ = if source.is_some() {
+            EmfRecordType::EMR_STRETCHBLT
+        } else {
+            EmfRecordType::EMR_BITBLT
+        };
+        let (x_src, y_src) =
+            source.as_ref().map_or((0, 0), |(_, (x, y, ..))| (*x, *y));
+        let record = Record::new(typ)
+            .rect(bounds)
+            .point(x, y)
+            .point(width, height)
+            .u32(raster_operation as u32)
+            .point(x_src, y_src)
+            // XformSrc, the identity transform
+            .f32(1.0)
+            .f32(0.0)
+            .f32(0.0)
+            .f32(1.0)
+            .f32(0.0)
+            .f32(0.0)
+            // BkColorSrc
+            .u32(0);
+
+        let record = match source {
+            Some((dib, (_, _, src_width, src_height))) => record
+                .u32(dib.usage)
+                .dib_offsets(&dib, 108)
+                .point(src_width, src_height)
+                .dib(&dib),
+            None => record.u32(0).u32(0).u32(0).u32(0).u32(0).u32(0),
+        };
+
+        self.push(record);
+    }
+
+    /// Append EMR_EXTTEXTOUTW drawing `text` at the logical reference point
+    /// `(x, y)`.
+    ///
+    /// The WMF Dx array has an advance per byte of the string while the EMF
+    /// one has an advance per UTF-16 code unit, so the advance of a
+    /// character sums those of the bytes it takes in the character set of
+    /// the font.
+    fn text_out_w(
+        &mut self,
+        (x, y): (i16, i16),
+        text: &str,
+        dx: &[i16],
+        options: u32,
+        rectangle: Option<&Rect>,
+    ) {
+        let charset = self.state.font().charset;
+        let encoding: &'static encoding_rs::Encoding = charset.into();
+        let mut units = vec![];
+        let mut advances = vec![];
+        let mut offset = 0_usize;
+
+        for c in text.chars() {
+            let mut buffer = [0; 2];
+            let encoded = c.encode_utf16(&mut buffer);
+            let length = if charset == CharacterSet::SYMBOL_CHARSET {
+                1
+            } else {
+                let mut bytes = [0; 4];
+
+                encoding.encode(c.encode_utf8(&mut bytes)).0.len()
+            };
+            let advance = dx
+                .iter()
+                .skip(offset)
+                .take(length)
+                .copied()
+                .map(i32::from)
+                .sum::<i32>();
+
+            units.extend_from_slice(encoded);
+            advances.push(advance);
+            // The second unit of a surrogate pair does not advance.
+            advances.extend(core::iter::repeat_n(0, encoded.len() - 1));
+            offset = offset.saturating_add(length);
+        }
+
+        let mut points = vec![(x, y)];
+
+        if let Some(rect) = rectangle {
+            points.extend([(rect.left, rect.top), (rect.right, rect.bottom)]);
+        }
+
+        let bounds = self.bounds(&points);
+        let string_size = (units.len() * 2).next_multiple_of(4);
+        let rectangle = rectangle.map_or((0, 0, -1, -1), |rect| {
+            (
+                i32::from(rect.left),
+                i32::from(rect.top),
+                i32::from(rect.right),
+                i32::from(rect.bottom),
+            )
+        });
+        let mut record = Record::new(EmfRecordType::EMR_EXTTEXTOUTW)
+            .rect(bounds)
+            // GM_COMPATIBLE
+            .u32(1)
+            .f32(0.0)
+            .f32(0.0)
+            .point(x, y)
+            .size(units.len())
+            .u32(76)
+            .u32(options)
+            .rect(rectangle)
+            .size(if dx.is_empty() { 0 } else { 76 + string_size });
+
+        for unit in units {
+            record = record.u16(unit);
+        }
+
+        record = record.align();
+
+        if !dx.is_empty() {
+            for advance in advances {
+                record = record.i32(advance);
+            }
+        }
+
+        self.push(record);
+    }
+
+    /// Append EMR_SETPALETTEENTRIES setting the entries of the selected
+    /// palette.
+    fn set_palette_entries(&mut self, palette: &Palette) {
+        let mut record = Record::new(EmfRecordType::EMR_SETPALETTEENTRIES)
+            .u32(self.palette_handle())
+            .u32(u32::from(palette.start))
+            .size(palette.a_palette_entries.len());
+
+        for entry in &palette.a_palette_entries {
+            record = palette_entry(record, entry);
+        }
+
+        self.push(record);
+    }
+
+    fn set_window_origin_ex(&mut self) {
+        let window = self.state.window();
+        let record = Record::new(EmfRecordType::EMR_SETWINDOWORGEX)
+            .point(window.origin_x, window.origin_y);
+
+        self.push(record);
+    }
+
+    fn set_viewport_origin_ex(&mut self) {
+        let window = self.state.window();
+        let record = Record::new(EmfRecordType::EMR_SETVIEWPORTORGEX)
+            .i32(window.viewport_origin_x.round() as i32)
+            .i32(window.viewport_origin_y.round() as i32);
+
+        self.push(record);
+    }
+}
+
+impl crate::converter::Player for EmfPlayer {
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn generate(self) -> Result<Vec<u8>, PlayError> {
+        let Self { records, objects, state, page, .. } = self;
+        let window = state.window();
+        let (x, y, width, height) = if let Some((width, height)) = page {
+            (0.0, 0.0, width, height)
+        } else {
+            let pixel = window.device_pixel_size();
+            let (x, y, width, height) = window.as_view_box();
+            let pixels = |v: i32| (f64::from(v) / f64::from(pixel)) as f32;
+
+            (pixels(x), pixels(y), pixels(width), pixels(height))
+        };
+
+        // Bounds are inclusive pixels, which leaves them empty for a
+        // metafile drawing nothing, and Frame is in 0.01 millimeters with a
+        // pixel taken as 1/96 inch.
+        let bounds = (
+            x.round() as i32,
+            y.round() as i32,
+            (x + width).round() as i32 - 1,
+            (y + height).round() as i32 - 1,
+        );
+        let frame = |v: f32| (v * 2540.0 / 96.0).round() as i32;
+        let size = 108 + records.iter().map(Vec::len).sum::<usize>() + 20;
+        let header = Record::new(EmfRecordType::EMR_HEADER)
+            .rect(bounds)
+            .rect((frame(x), frame(y), frame(x + width), frame(y + height)))
+            // " EMF"
+            .u32(0x464D_4520)
+            .u32(0x0001_0000)
+            .size(size)
+            .size(records.len() + 2)
+            .u16(u16::try_from(objects.handles()).unwrap_or(u16::MAX))
+            .u16(0)
+            // nDescription, offDescription and nPalEntries
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            // Device and Millimeters of a 96 DPI reference device
+            .i32(3840)
+            .i32(2400)
+            .i32(1016)
+            .i32(635)
+            // cbPixelFormat, offPixelFormat and bOpenGL
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .i32(1_016_000)
+            .i32(635_000);
+        let eof = Record::new(EmfRecordType::EMR_EOF).u32(0).u32(16).u32(20);
+        let mut data = header.finish();
+
+        data.reserve(size);
+
+        for record in records {
+            data.extend(record);
+        }
+
+        data.extend(eof.finish());
+
+        Ok(data)
+    }
+
+    // .
+    // .
+    // Functions to handle Bitmap Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn bit_blt(
+        mut self,
+        record_number: usize,
+        record: META_BITBLT,
+    ) -> Result<Self, PlayError> {
+        match record {
+            META_BITBLT::WithBitmap {
+                raster_operation,
+                y_src,
+                x_src,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => match Dib::from_bitmap16(&target) {
+                Ok(dib) => self.blit(
+                    raster_operation,
+                    (x_dest, y_dest, width, height),
+                    Some((dib, (x_src, y_src, width, height))),
+                ),
+                Err(cause) => self.diagnostics.report(
+                    record_number,
+                    RecordType::META_BITBLT,
+                    DiagnosticKind::Skipped,
+                    cause,
+                ),
+            },
+            META_BITBLT::WithoutBitmap {
+                raster_operation,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                ..
+            } => {
+                self.blit(
+                    raster_operation,
+                    (x_dest, y_dest, width, height),
+                    None,
+                );
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_bit_blt(
+        mut self,
+        record_number: usize,
+        record: META_DIBBITBLT,
+    ) -> Result<Self, PlayError> {
+        let (raster_operation, dest, source) = match record {
+            META_DIBBITBLT::WithBitmap {
+                raster_operation,
+                y_src,
+                x_src,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, width, height),
+                Some((Dib::from_dib(&target), (x_src, y_src, width, height))),
+            ),
+            META_DIBBITBLT::WithoutBitmap {
+                raster_operation,
+                height,
+                width,
+                y_dest,
+                x_dest,
+                ..
+            } => (raster_operation, (x_dest, y_dest, width, height), None),
+        };
+
+        self.blit(raster_operation, dest, source);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_stretch_blt(
+        mut self,
+        record_number: usize,
+        record: META_DIBSTRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        let (raster_operation, dest, source) = match record {
+            META_DIBSTRETCHBLT::WithBitmap {
+                raster_operation,
+                src_height,
+                src_width,
+                y_src,
+                x_src,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                Some((
+                    Dib::from_dib(&target),
+                    (x_src, y_src, src_width, src_height),
+                )),
+            ),
+            META_DIBSTRETCHBLT::WithoutBitmap {
+                raster_operation,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                ..
+            } => (
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                None,
+            ),
+        };
+
+        self.blit(raster_operation, dest, source);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_device_independent_bitmap_to_dev(
+        mut self,
+        record_number: usize,
+        record: META_SETDIBTODEV,
+    ) -> Result<Self, PlayError> {
+        // Clamp to i16::MAX when u16 exceeds i16 range
+        let clamp = |v: u16| i16::try_from(v).unwrap_or(i16::MAX);
+        let (x, y) = (clamp(record.x_dest), clamp(record.y_dest));
+        let (width, height) = (clamp(record.width), clamp(record.height));
+        let bounds = self.bounds(&[
+            (x, y),
+            (x.saturating_add(width), y.saturating_add(height)),
+        ]);
+        let dib = Dib::from_dib(&record.dib);
+        let record = Record::new(EmfRecordType::EMR_SETDIBITSTODEVICE)
+            .rect(bounds)
+            .point(x, y)
+            .u32(u32::from(record.x_dib))
+            .u32(u32::from(record.y_dib))
+            .point(width, height)
+            .dib_offsets(&dib, 76)
+            .u32(dib.usage)
+            .u32(u32::from(record.start_scan))
+            .u32(u32::from(record.scan_count))
+            .dib(&dib);
+
+        self.push(record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_blt(
+        mut self,
+        record_number: usize,
+        record: META_STRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        match record {
+            META_STRETCHBLT::WithBitmap {
+                raster_operation,
+                src_height,
+                src_width,
+                y_src,
+                x_src,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                target,
+                ..
+            } => match Dib::from_bitmap16(&target) {
+                Ok(dib) => self.blit(
+                    raster_operation,
+                    (x_dest, y_dest, dest_width, dest_height),
+                    Some((dib, (x_src, y_src, src_width, src_height))),
+                ),
+                Err(cause) => self.diagnostics.report(
+                    record_number,
+                    RecordType::META_STRETCHBLT,
+                    DiagnosticKind::Skipped,
+                    cause,
+                ),
+            },
+            META_STRETCHBLT::WithoutBitmap {
+                raster_operation,
+                dest_height,
+                dest_width,
+                y_dest,
+                x_dest,
+                ..
+            } => self.blit(
+                raster_operation,
+                (x_dest, y_dest, dest_width, dest_height),
+                None,
+            ),
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_device_independent_bitmap(
+        mut self,
+        record_number: usize,
+        record: META_STRETCHDIB,
+    ) -> Result<Self, PlayError> {
+        let META_STRETCHDIB {
+            raster_operation,
+            src_height,
+            src_width,
+            y_src,
+            x_src,
+            dest_height,
+            dest_width,
+            y_dst,
+            x_dst,
+            dib,
+            ..
+        } = record;
+        let bounds = self.bounds(&[
+            (x_dst, y_dst),
+            (
+                x_dst.saturating_add(dest_width),
+                y_dst.saturating_add(dest_height),
+            ),
+        ]);
+        let dib = Dib::from_dib(&dib);
+        let record = Record::new(EmfRecordType::EMR_STRETCHDIBITS)
+            .rect(bounds)
+            .point(x_dst, y_dst)
+            .point(x_src, y_src)
+            .point(src_width, src_height)
+            .dib_offsets(&dib, 80)
+            .u32(dib.usage)
+            .u32(raster_operation as u32)
+            .point(dest_width, dest_height)
+            .dib(&dib);
+
+        self.push(record);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Control Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn eof(self, record_number: usize, _: META_EOF) -> Result<Self, PlayError> {
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn header(
+        mut self,
+        record_number: usize,
+        header: MetafileHeader,
+    ) -> Result<Self, PlayError> {
+        self.state.header(&header);
+
+        let (placeable, header) = match header {
+            MetafileHeader::StartsWithHeader(header) => (None, header),
+            MetafileHeader::StartsWithPlaceable(placeable, header) => {
+                (Some(placeable), header)
+            }
+        };
+
+        if let Some(placeable) = placeable {
+            let Rect { left, top, right, bottom } = placeable.bounding_box;
+            // A hostile bounding box can span more than i16 holds.
+            let (width, height) =
+                (right.saturating_sub(left), bottom.saturating_sub(top));
+            // A logical unit is a pixel when the resolution is missing.
+            let pixels_per_unit = match placeable.inch {
+                0 => 1.0,
+                inch => 96.0 / f32::from(inch),
+            };
+            let (pixel_width, pixel_height) = (
+                (f32::from(right) - f32::from(left)).abs() * pixels_per_unit,
+                (f32::from(bottom) - f32::from(top)).abs() * pixels_per_unit,
+            );
+            self.page = Some((pixel_width, pixel_height));
+
+            // Map the bounding box onto the picture as a player of the
+            // placeable metafile does.
+            self.push(
+                Record::new(EmfRecordType::EMR_SETMAPMODE)
+                    .u32(MapMode::MM_ANISOTROPIC as u32),
+            );
+            self.push(
+                Record::new(EmfRecordType::EMR_SETWINDOWORGEX).point(left, top),
+            );
+            self.push(
+                Record::new(EmfRecordType::EMR_SETWINDOWEXTEX)
+                    .point(width, height),
+            );
+            self.push(
+                Record::new(EmfRecordType::EMR_SETVIEWPORTORGEX).i32(0).i32(0),
+            );
+            self.push(
+                Record::new(EmfRecordType::EMR_SETVIEWPORTEXTEX)
+                    .i32(pixel_width.round() as i32)
+                    .i32(pixel_height.round() as i32),
+            );
+        }
+
+        self.objects = ObjectTable::new(usize::from(header.number_of_objects));
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Drawing Record
+    // .
+    // .
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn arc(
+        mut self,
+        record_number: usize,
+        record: META_ARC,
+    ) -> Result<Self, PlayError> {
+        self.bounds(&[
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+        ]);
+        self.push(
+            Record::new(EmfRecordType::EMR_ARC)
+                .point(record.left_rect, record.top_rect)
+                .point(record.right_rect, record.bottom_rect)
+                .point(record.x_start_arc, record.y_start_arc)
+                .point(record.x_end_arc, record.y_end_arc),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn chord(
+        mut self,
+        record_number: usize,
+        record: META_CHORD,
+    ) -> Result<Self, PlayError> {
+        self.bounds(&[
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+        ]);
+        self.push(
+            Record::new(EmfRecordType::EMR_CHORD)
+                .point(record.left_rect, record.top_rect)
+                .point(record.right_rect, record.bottom_rect)
+                .point(record.x_radial1, record.y_radial1)
+                .point(record.x_radial2, record.y_radial2),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ellipse(
+        mut self,
+        record_number: usize,
+        record: META_ELLIPSE,
+    ) -> Result<Self, PlayError> {
+        self.bounds(&[
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+        ]);
+        self.push(
+            Record::new(EmfRecordType::EMR_ELLIPSE)
+                .point(record.left_rect, record.top_rect)
+                .point(record.right_rect, record.bottom_rect),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_flood_fill(
+        mut self,
+        record_number: usize,
+        record: META_EXTFLOODFILL,
+    ) -> Result<Self, PlayError> {
+        self.point(record.x, record.y);
+        self.push(
+            Record::new(EmfRecordType::EMR_EXTFLOODFILL)
+                .point(record.x, record.y)
+                .color(&record.color_ref)
+                .u32(record.mode as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_text_out(
+        mut self,
+        record_number: usize,
+        record: META_EXTTEXTOUT,
+    ) -> Result<Self, PlayError> {
+        let text =
+            record.into_utf8(self.state.font().charset).map_err(|err| {
+                PlayError::InvalidRecord { cause: err.to_string() }
+            })?;
+        let options =
+            record.fw_opts.iter().map(|option| *option as u32).sum::<u32>();
+
+        self.text_out_w(
+            (record.x, record.y),
+            &text,
+            &record.dx,
+            options,
+            record.rectangle.as_ref(),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn fill_region(
+        mut self,
+        record_number: usize,
+        record: META_FILLREGION,
+    ) -> Result<Self, PlayError> {
+        let rects = self.region(record.region)?;
+        let brush = self.brush(record.brush)?;
+
+        self.region_record(EmfRecordType::EMR_FILLRGN, &rects, |record| {
+            record.u32(brush)
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn flood_fill(
+        mut self,
+        record_number: usize,
+        record: META_FLOODFILL,
+    ) -> Result<Self, PlayError> {
+        self.point(record.x_start, record.y_start);
+        self.push(
+            Record::new(EmfRecordType::EMR_EXTFLOODFILL)
+                .point(record.x_start, record.y_start)
+                .color(&record.color_ref)
+                .u32(FloodFill::FLOODFILLBORDER as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn frame_region(
+        mut self,
+        record_number: usize,
+        record: META_FRAMEREGION,
+    ) -> Result<Self, PlayError> {
+        let rects = self.region(record.region)?;
+        let brush = self.brush(record.brush)?;
+
+        self.region_record(EmfRecordType::EMR_FRAMERGN, &rects, |r| {
+            r.u32(brush)
+                .i32(i32::from(record.width))
+                .i32(i32::from(record.height))
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn invert_region(
+        mut self,
+        record_number: usize,
+        record: META_INVERTREGION,
+    ) -> Result<Self, PlayError> {
+        let rects = self.region(record.region)?;
+
+        self.region_record(EmfRecordType::EMR_INVERTRGN, &rects, |r| r);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn line_to(
+        mut self,
+        record_number: usize,
+        record: META_LINETO,
+    ) -> Result<Self, PlayError> {
+        self.point(record.x, record.y);
+        self.push(
+            Record::new(EmfRecordType::EMR_LINETO).point(record.x, record.y),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn paint_region(
+        mut self,
+        record_number: usize,
+        record: META_PAINTREGION,
+    ) -> Result<Self, PlayError> {
+        let rects = self.region(record.region)?;
+
+        self.region_record(EmfRecordType::EMR_PAINTRGN, &rects, |r| r);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pat_blt(
+        mut self,
+        record_number: usize,
+        record: META_PATBLT,
+    ) -> Result<Self, PlayError> {
+        self.blit(
+            record.raster_operation,
+            (record.x_left, record.y_left, record.width, record.height),
+            None,
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pie(
+        mut self,
+        record_number: usize,
+        record: META_PIE,
+    ) -> Result<Self, PlayError> {
+        self.bounds(&[
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+        ]);
+        self.push(
+            Record::new(EmfRecordType::EMR_PIE)
+                .point(record.left_rect, record.top_rect)
+                .point(record.right_rect, record.bottom_rect)
+                .point(record.x_radial1, record.y_radial1)
+                .point(record.x_radial2, record.y_radial2),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polyline(
+        mut self,
+        record_number: usize,
+        record: META_POLYLINE,
+    ) -> Result<Self, PlayError> {
+        let record =
+            poly16(&mut self, EmfRecordType::EMR_POLYLINE16, &record.a_points);
+
+        self.push(record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polygon(
+        mut self,
+        record_number: usize,
+        record: META_POLYGON,
+    ) -> Result<Self, PlayError> {
+        let record =
+            poly16(&mut self, EmfRecordType::EMR_POLYGON16, &record.a_points);
+
+        self.push(record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn poly_polygon(
+        mut self,
+        record_number: usize,
+        record: META_POLYPOLYGON,
+    ) -> Result<Self, PlayError> {
+        let PolyPolygon { a_points_per_polygon, a_points, .. } =
+            record.poly_polygon;
+        let points: Vec<_> =
+            a_points.iter().map(|point| (point.x, point.y)).collect();
+        let bounds = self.bounds(&points);
+        let mut record = Record::new(EmfRecordType::EMR_POLYPOLYGON16)
+            .rect(bounds)
+            .size(a_points_per_polygon.len())
+            .size(a_points.len());
+
+        for count in a_points_per_polygon {
+            record = record.u32(u32::from(count));
+        }
+
+        for point in a_points {
+            record = record.i16(point.x).i16(point.y);
+        }
+
+        self.push(record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn rectangle(
+        mut self,
+        record_number: usize,
+        record: META_RECTANGLE,
+    ) -> Result<Self, PlayError> {
+        self.bounds(&[
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+        ]);
+        self.push(
+            Record::new(EmfRecordType::EMR_RECTANGLE)
+                .point(record.left_rect, record.top_rect)
+                .point(record.right_rect, record.bottom_rect),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn round_rect(
+        mut self,
+        record_number: usize,
+        record: META_ROUNDRECT,
+    ) -> Result<Self, PlayError> {
+        self.bounds(&[
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+        ]);
+        self.push(
+            Record::new(EmfRecordType::EMR_ROUNDRECT)
+                .point(record.left_rect, record.top_rect)
+                .point(record.right_rect, record.bottom_rect)
+                .point(record.width, record.height),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pixel(
+        mut self,
+        record_number: usize,
+        record: META_SETPIXEL,
+    ) -> Result<Self, PlayError> {
+        self.point(record.x, record.y);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETPIXELV)
+                .point(record.x, record.y)
+                .color(&record.color_ref),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn text_out(
+        mut self,
+        record_number: usize,
+        record: META_TEXTOUT,
+    ) -> Result<Self, PlayError> {
+        let text =
+            record.into_utf8(self.state.font().charset).map_err(|err| {
+                PlayError::InvalidRecord { cause: err.to_string() }
+            })?;
+
+        self.text_out_w((record.x_start, record.y_start), &text, &[], 0, None);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Object Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_brush_indirect(
+        mut self,
+        record_number: usize,
+        record: META_CREATEBRUSHINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.create_brush_indirect(&record);
+
+        let (style, color, hatch) = match record.log_brush {
+            LogBrush::Solid { color_ref } => {
+                (BrushStyle::BS_SOLID, color_ref, 0)
+            }
+            LogBrush::Hatched { color_ref, brush_hatch } => {
+                (BrushStyle::BS_HATCHED, color_ref, brush_hatch as u32)
+            }
+            LogBrush::Null => (BrushStyle::BS_NULL, ColorRef::black(), 0),
+            LogBrush::DIBPattern
+            | LogBrush::DIBPatternPT
+            | LogBrush::Pattern => {
+                self.diagnostics.report(
+                    record_number,
+                    RecordType::META_CREATEBRUSHINDIRECT,
+                    DiagnosticKind::Approximated,
+                    "pattern brush without a bitmap is created as a null brush"
+                        .to_owned(),
+                );
+
+                (BrushStyle::BS_NULL, ColorRef::black(), 0)
+            }
+        };
+
+        self.create(ObjectKind::Brush, |handle| {
+            Record::new(EmfRecordType::EMR_CREATEBRUSHINDIRECT)
+                .u32(handle)
+                .u32(style as u32)
+                .color(&color)
+                .u32(hatch)
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_font_indirect(
+        mut self,
+        record_number: usize,
+        record: META_CREATEFONTINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.create_font_indirect(&record);
+
+        let font = record.font;
+        let PitchAndFamily { family, pitch } = font.pitch_and_family;
+        let mut facename = [0_u16; 32];
+
+        // The face name is a null-terminated string of at most 32 units.
+        for (unit, c) in
+            facename.iter_mut().take(31).zip(font.facename.encode_utf16())
+        {
+            *unit = c;
+        }
+
+        self.create(ObjectKind::Font, |handle| {
+            let mut record =
+                Record::new(EmfRecordType::EMR_EXTCREATEFONTINDIRECTW)
+                    .u32(handle)
+                    .i32(i32::from(font.height))
+                    .i32(i32::from(font.width))
+                    .i32(i32::from(font.escapement))
+                    .i32(i32::from(font.orientation))
+                    .i32(i32::from(font.weight))
+                    .u8(u8::from(font.italic))
+                    .u8(u8::from(font.underline))
+                    .u8(u8::from(font.strike_out))
+                    .u8(font.charset as u8)
+                    .u8(font.out_precision as u8)
+                    .u8(font.clip_precision as u8)
+                    .u8(font.quality as u8)
+                    .u8((family as u8) << 4 | pitch as u8);
+
+            for unit in facename {
+                record = record.u16(unit);
+            }
+
+            record
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_palette(
+        mut self,
+        record_number: usize,
+        record: META_CREATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.state.create_palette(&record);
+
+        let entries = record.palette.a_palette_entries;
+
+        self.create(ObjectKind::Palette, |handle| {
+            let record = Record::new(EmfRecordType::EMR_CREATEPALETTE)
+                .u32(handle)
+                .u16(0x0300)
+                .u16(u16::try_from(entries.len()).unwrap_or(u16::MAX));
+
+            entries.iter().fold(record, palette_entry)
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pattern_brush(
+        mut self,
+        record_number: usize,
+        record: META_CREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.state.create_pattern_brush(&record);
+
+        let mut bitmap = record.bitmap16;
+
+        bitmap.bits = record.pattern;
+
+        match Dib::from_bitmap16(&bitmap) {
+            Ok(dib) => self.create(ObjectKind::Brush, |handle| {
+                Record::new(EmfRecordType::EMR_CREATEMONOBRUSH)
+                    .u32(handle)
+                    .u32(dib.usage)
+                    .dib_offsets(&dib, 32)
+                    .dib(&dib)
+            }),
+            Err(cause) => {
+                self.diagnostics.report(
+                    record_number,
+                    RecordType::META_CREATEPATTERNBRUSH,
+                    DiagnosticKind::Approximated,
+                    format!("{cause}, so the brush is created as solid black"),
+                );
+                // Create a brush anyway to keep the indexes of the later
+                // objects.
+                self.create(ObjectKind::Brush, |handle| {
+                    Record::new(EmfRecordType::EMR_CREATEBRUSHINDIRECT)
+                        .u32(handle)
+                        .u32(BrushStyle::BS_SOLID as u32)
+                        .color(&ColorRef::black())
+                        .u32(0)
+                });
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pen_indirect(
+        mut self,
+        record_number: usize,
+        record: META_CREATEPENINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.create_pen_indirect(&record);
+
+        let Pen { style, width, color_ref } = record.pen;
+        let style = style.style as u32
+            | style.end_cap as u32
+            | style.line_join as u32
+            | style.typ as u32;
+
+        self.create(ObjectKind::Pen, |handle| {
+            Record::new(EmfRecordType::EMR_CREATEPEN)
+                .u32(handle)
+                .u32(style)
+                .point(width.x, 0)
+                .color(&color_ref)
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_region(
+        mut self,
+        record_number: usize,
+        record: META_CREATEREGION,
+    ) -> Result<Self, PlayError> {
+        self.state.create_region(&record);
+        self.objects.insert(Object::Region);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn delete_object(
+        mut self,
+        record_number: usize,
+        record: META_DELETEOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.state.delete_object(&record);
+
+        if let Some(Object::Handle { handle, kind }) =
+            self.objects.remove(record.object_index)
+        {
+            if kind == ObjectKind::Palette && self.palette == Some(handle) {
+                self.palette = None;
+            }
+
+            self.push(Record::new(EmfRecordType::EMR_DELETEOBJECT).u32(handle));
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_device_independent_bitmap_pattern_brush(
+        mut self,
+        record_number: usize,
+        record: META_DIBCREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.state.create_device_independent_bitmap_pattern_brush(&record);
+
+        let dib = Dib::from_dib(&record.target);
+
+        self.create(ObjectKind::Brush, |handle| {
+            Record::new(EmfRecordType::EMR_CREATEDIBPATTERNBRUSHPT)
+                .u32(handle)
+                .u32(dib.usage)
+                .dib_offsets(&dib, 32)
+                .dib(&dib)
+        });
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_clip_region(
+        mut self,
+        record_number: usize,
+        record: META_SELECTCLIPREGION,
+    ) -> Result<Self, PlayError> {
+        self.state.select_clip_region(&record);
+
+        // Without a region, the clipping region is reset.
+        let rects = self.region(record.region).ok();
+
+        self.push(select_clip_region(rects.as_deref()));
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_object(
+        mut self,
+        record_number: usize,
+        record: META_SELECTOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.state.select_object(&record)?;
+
+        let record = match self.objects.get(record.object_index) {
+            Some(Object::Handle { handle, kind: ObjectKind::Palette }) => {
+                self.palette = Some(*handle);
+
+                Record::new(EmfRecordType::EMR_SELECTPALETTE).u32(*handle)
+            }
+            Some(Object::Handle { handle, .. }) => {
+                Record::new(EmfRecordType::EMR_SELECTOBJECT).u32(*handle)
+            }
+            Some(Object::Region) => {
+                select_clip_region(Some(&self.region(record.object_index)?))
+            }
+            None => {
+                return Err(PlayError::UnexpectedGraphicsObject {
+                    cause: "Graphics Object is null".to_owned(),
+                });
+            }
+        };
+
+        self.push(record);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_palette(
+        mut self,
+        record_number: usize,
+        record: META_SELECTPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.state.select_palette(&record)?;
+
+        let Some(Object::Handle { handle, kind: ObjectKind::Palette }) =
+            self.objects.get(record.palette)
+        else {
+            return Err(PlayError::UnexpectedGraphicsObject {
+                cause: "Graphics Object is not palette object".to_owned(),
+            });
+        };
+        let handle = *handle;
+
+        self.palette = Some(handle);
+        self.push(Record::new(EmfRecordType::EMR_SELECTPALETTE).u32(handle));
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle State Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn animate_palette(
+        mut self,
+        record_number: usize,
+        record: META_ANIMATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_ANIMATEPALETTE,
+            DiagnosticKind::Approximated,
+            "EMF has no palette animation, so the entries are set instead"
+                .to_owned(),
+        );
+        self.set_palette_entries(&record.palette);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn exclude_clip_rect(
+        mut self,
+        record_number: usize,
+        record: META_EXCLUDECLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.exclude_clip_rect(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_EXCLUDECLIPRECT)
+                .point(record.left, record.top)
+                .point(record.right, record.bottom),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn intersect_clip_rect(
+        mut self,
+        record_number: usize,
+        record: META_INTERSECTCLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.state.intersect_clip_rect(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_INTERSECTCLIPRECT)
+                .point(record.left, record.top)
+                .point(record.right, record.bottom),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn move_to(
+        mut self,
+        record_number: usize,
+        record: META_MOVETO,
+    ) -> Result<Self, PlayError> {
+        self.state.move_to(&record);
+        self.point(record.x, record.y);
+        self.push(
+            Record::new(EmfRecordType::EMR_MOVETOEX).point(record.x, record.y),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_clip_region(
+        mut self,
+        record_number: usize,
+        record: META_OFFSETCLIPRGN,
+    ) -> Result<Self, PlayError> {
+        self.state.offset_clip_region(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_OFFSETCLIPRGN)
+                .point(record.x_offset, record.y_offset),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_viewport_origin(
+        mut self,
+        record_number: usize,
+        record: META_OFFSETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        // EMR_OFFSETVIEWPORTORGEX is not defined, so the resulting origin
+        // is set instead.
+        self.state.offset_viewport_origin(&record);
+        self.set_viewport_origin_ex();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_window_origin(
+        mut self,
+        record_number: usize,
+        record: META_OFFSETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        // EMR_OFFSETWINDOWORGEX is not defined, so the resulting origin is
+        // set instead.
+        self.state.offset_window_origin(&record);
+        self.set_window_origin_ex();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn realize_palette(
+        mut self,
+        record_number: usize,
+        record: META_REALIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(Record::new(EmfRecordType::EMR_REALIZEPALETTE));
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn resize_palette(
+        mut self,
+        record_number: usize,
+        record: META_RESIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(
+            Record::new(EmfRecordType::EMR_RESIZEPALETTE)
+                .u32(self.palette_handle())
+                .u32(u32::from(record.number_of_entries)),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn restore_device_context(
+        mut self,
+        record_number: usize,
+        record: META_RESTOREDC,
+    ) -> Result<Self, PlayError> {
+        // The palette stack grows and shrinks with the SaveDC stack, so
+        // the index of the restored context is its index there too.
+        if let Some(idx) = self.state.restore_device_context(&record) {
+            // EMR_RESTOREDC only takes an offset relative to the top.
+            let relative = i32::try_from(self.palette_stack.len() - idx)
+                .map_or(i32::MIN, |offset| -offset);
+
+            self.palette = self.palette_stack[idx];
+            self.palette_stack.truncate(idx);
+            self.push(Record::new(EmfRecordType::EMR_RESTOREDC).i32(relative));
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn save_device_context(
+        mut self,
+        record_number: usize,
+        record: META_SAVEDC,
+    ) -> Result<Self, PlayError> {
+        self.state.save_device_context(&record);
+        self.palette_stack.push(self.palette);
+        self.push(Record::new(EmfRecordType::EMR_SAVEDC));
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_viewport_ext(
+        mut self,
+        record_number: usize,
+        record: META_SCALEVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.scale_viewport_ext(&record)?;
+        self.push(
+            Record::new(EmfRecordType::EMR_SCALEVIEWPORTEXTEX)
+                .point(record.x_num, record.x_denom)
+                .point(record.y_num, record.y_denom),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_window_ext(
+        mut self,
+        record_number: usize,
+        record: META_SCALEWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.scale_window_ext(&record)?;
+        self.push(
+            Record::new(EmfRecordType::EMR_SCALEWINDOWEXTEX)
+                .point(record.x_num, record.x_denom)
+                .point(record.y_num, record.y_denom),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_color(
+        mut self,
+        record_number: usize,
+        record: META_SETBKCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.state.set_bk_color(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETBKCOLOR).color(&record.color_ref),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETBKMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_bk_mode(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETBKMODE)
+                .u32(record.bk_mode as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_layout(
+        mut self,
+        record_number: usize,
+        record: META_SETLAYOUT,
+    ) -> Result<Self, PlayError> {
+        self.state.set_layout(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETLAYOUT).u32(record.layout as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_map_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETMAPMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_map_mode(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETMAPMODE)
+                .u32(record.map_mode as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_mapper_flags(
+        mut self,
+        record_number: usize,
+        record: META_SETMAPPERFLAGS,
+    ) -> Result<Self, PlayError> {
+        self.push(
+            Record::new(EmfRecordType::EMR_SETMAPPERFLAGS)
+                .u32(record.mapper_values),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pal_entries(
+        mut self,
+        record_number: usize,
+        record: META_SETPALENTRIES,
+    ) -> Result<Self, PlayError> {
+        self.set_palette_entries(&record.palette);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_polyfill_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETPOLYFILLMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_polyfill_mode(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETPOLYFILLMODE)
+                .u32(record.poly_fill_mode as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_relabs(
+        self,
+        record_number: usize,
+        record: META_SETRELABS,
+    ) -> Result<Self, PlayError> {
+        info!("META_SETRELABS: reserved record and not supported");
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_raster_operation(
+        mut self,
+        record_number: usize,
+        record: META_SETROP2,
+    ) -> Result<Self, PlayError> {
+        self.state.set_raster_operation(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETROP2)
+                .u32(record.draw_mode as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_stretch_blt_mode(
+        mut self,
+        record_number: usize,
+        record: META_SETSTRETCHBLTMODE,
+    ) -> Result<Self, PlayError> {
+        self.state.set_stretch_blt_mode(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETSTRETCHBLTMODE)
+                .u32(record.stretch_mode as u32),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_align(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTALIGN,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_align(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETTEXTALIGN)
+                .u32(u32::from(record.text_alignment_mode)),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_char_extra(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTCHAREXTRA,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_char_extra(&record);
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_SETTEXTCHAREXTRA,
+            DiagnosticKind::Skipped,
+            "EMF has no record for the inter-character spacing".to_owned(),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_color(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_color(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETTEXTCOLOR)
+                .color(&record.color_ref),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_justification(
+        mut self,
+        record_number: usize,
+        record: META_SETTEXTJUSTIFICATION,
+    ) -> Result<Self, PlayError> {
+        self.state.set_text_justification(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETTEXTJUSTIFICATION)
+                .u32(u32::from(record.break_extra))
+                .u32(u32::from(record.break_count)),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_ext(
+        mut self,
+        record_number: usize,
+        record: META_SETVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.set_viewport_ext(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETVIEWPORTEXTEX)
+                .point(record.x, record.y),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_origin(
+        mut self,
+        record_number: usize,
+        record: META_SETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.state.set_viewport_origin(&record);
+        self.set_viewport_origin_ex();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_ext(
+        mut self,
+        record_number: usize,
+        record: META_SETWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.state.set_window_ext(&record);
+        self.push(
+            Record::new(EmfRecordType::EMR_SETWINDOWEXTEX)
+                .point(record.x, record.y),
+        );
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_origin(
+        mut self,
+        record_number: usize,
+        record: META_SETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.state.set_window_origin(&record);
+        self.set_window_origin_ex();
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Escape Record
+    // .
+    // .
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn escape(
+        self,
+        record_number: usize,
+        record: META_ESCAPE,
+    ) -> Result<Self, PlayError> {
+        info!("META_ESCAPE: skipped (escapes are addressed to the printer)");
+        Ok(self)
+    }
+}
+
+/// Build EMR_POLYGON16 or EMR_POLYLINE16 of the logical points.
+fn poly16(
+    player: &mut EmfPlayer,
+    typ: EmfRecordType,
+    points: &[PointS],
+) -> Record {
+    let coordinates: Vec<_> =
+        points.iter().map(|point| (point.x, point.y)).collect();
+    let bounds = player.bounds(&coordinates);
+
+    points
+        .iter()
+        .fold(Record::new(typ).rect(bounds).size(points.len()), |r, point| {
+            r.i16(point.x).i16(point.y)
+        })
+}
+
+/// EMR_EXTSELECTCLIPRGN replacing the clipping region with the logical
+/// region `rects`, or resetting it without one.
+fn select_clip_region(rects: Option<&[(i32, i32, i32, i32)]>) -> Record {
+    const RGN_COPY: u32 = 5;
+
+    let record = Record::new(EmfRecordType::EMR_EXTSELECTCLIPRGN);
+
+    match rects {
+        Some(rects) => {
+            record.size(32 + rects.len() * 16).u32(RGN_COPY).region(rects)
+        }
+        None => record.u32(0).u32(RGN_COPY),
+    }
+}
+
+/// Append a LogPaletteEntry Object.
+fn palette_entry(record: Record, entry: &PaletteEntry) -> Record {
+    record
+        .u8(entry.red)
+        .u8(entry.green)
+        .u8(entry.blue)
+        .u8(entry.values.map_or(0, |flag| flag as u8))
+}
+
+/// Rectangles of the scanlines of `region` in logical units, or its
+/// bounding rectangle when it has none.
+fn region_rects(region: &Region) -> Vec<(i32, i32, i32, i32)> {
+    if region.a_scans.is_empty() {
+        let Rect { left, top, right, bottom } = region.bounding_rectangle;
+
+        return vec![(
+            i32::from(left),
+            i32::from(top),
+            i32::from(right),
+            i32::from(bottom),
+        )];
+    }
+
+    region
+        .a_scans
+        .iter()
+        .flat_map(|scan| {
+            scan.scan_lines.iter().map(|scan_line| {
+                (
+                    i32::from(scan_line.left),
+                    i32::from(scan.top),
+                    i32::from(scan_line.right),
+                    i32::from(scan.bottom),
+                )
+            })
+        })
+        .collect()
+}
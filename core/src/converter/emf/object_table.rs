@@ -0,0 +1,78 @@
+use crate::imports::*;
+
+/// Entry of the WMF object table.
+#[derive(Clone, Debug)]
+pub enum Object {
+    /// Object created in the EMF object table under the handle.
+    Handle { handle: u32, kind: ObjectKind },
+    /// EMF has no region objects, so records using a region embed its
+    /// rectangles, which the [`GdiState`](crate::converter::GdiState) keeps,
+    /// instead.
+    Region,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectKind {
+    Brush,
+    Font,
+    Palette,
+    Pen,
+}
+
+/// WMF object table, translating its indexes to EMF object handles.
+///
+/// A WMF object takes the lowest free index of the table, which
+/// META_SELECTOBJECT and META_DELETEOBJECT refer to. EMF records name
+/// objects by the handle given when creating them instead, where handle 0
+/// is the metafile itself, so an object at index `i` becomes handle
+/// `i + 1`.
+#[derive(Clone, Debug, Default)]
+pub struct ObjectTable(Vec<Option<Object>>);
+
+impl ObjectTable {
+    pub fn new(length: usize) -> Self {
+        Self(vec![None; length])
+    }
+
+    /// Number of handles the EMF header declares, including handle 0.
+    pub fn handles(&self) -> usize {
+        self.0.len() + 1
+    }
+
+    /// Handle that the next inserted object gets, or `None` when the table
+    /// is full.
+    pub fn next_handle(&self) -> Option<u32> {
+        let index = self.0.iter().position(Option::is_none)?;
+
+        u32::try_from(index + 1).ok()
+    }
+
+    pub fn insert(&mut self, object: Object) {
+        if let Some(slot) = self.0.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(object);
+        } else {
+            warn!(
+                capacity = self.0.len(),
+                "object table is full, ignoring push"
+            );
+        }
+    }
+
+    pub fn get(&self, index: u16) -> Option<&Object> {
+        self.0.get(usize::from(index)).and_then(Option::as_ref)
+    }
+
+    pub fn remove(&mut self, index: u16) -> Option<Object> {
+        let Some(slot) = self.0.get_mut(usize::from(index)) else {
+            warn!(
+                index,
+                capacity = self.0.len(),
+                "object table index out of bounds on delete",
+            );
+
+            return None;
+        };
+
+        slot.take()
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{converter::emf::bitmap::Dib, imports::*, parser::*};
+
+/// Types of the EMF records written by [`crate::converter::EmfPlayer`], as
+/// defined in MS-EMF 2.1.1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum EmfRecordType {
+    EMR_HEADER = 0x0000_0001,
+    EMR_SETWINDOWEXTEX = 0x0000_0009,
+    EMR_SETWINDOWORGEX = 0x0000_000A,
+    EMR_SETVIEWPORTEXTEX = 0x0000_000B,
+    EMR_SETVIEWPORTORGEX = 0x0000_000C,
+    EMR_EOF = 0x0000_000E,
+    EMR_SETPIXELV = 0x0000_000F,
+    EMR_SETMAPPERFLAGS = 0x0000_0010,
+    EMR_SETMAPMODE = 0x0000_0011,
+    EMR_SETBKMODE = 0x0000_0012,
+    EMR_SETPOLYFILLMODE = 0x0000_0013,
+    EMR_SETROP2 = 0x0000_0014,
+    EMR_SETSTRETCHBLTMODE = 0x0000_0015,
+    EMR_SETTEXTALIGN = 0x0000_0016,
+    EMR_SETTEXTCOLOR = 0x0000_0018,
+    EMR_SETBKCOLOR = 0x0000_0019,
+    EMR_OFFSETCLIPRGN = 0x0000_001A,
+    EMR_MOVETOEX = 0x0000_001B,
+    EMR_EXCLUDECLIPRECT = 0x0000_001D,
+    EMR_INTERSECTCLIPRECT = 0x0000_001E,
+    EMR_SCALEVIEWPORTEXTEX = 0x0000_001F,
+    EMR_SCALEWINDOWEXTEX = 0x0000_0020,
+    EMR_SAVEDC = 0x0000_0021,
+    EMR_RESTOREDC = 0x0000_0022,
+    EMR_SELECTOBJECT = 0x0000_0025,
+    EMR_CREATEPEN = 0x0000_0026,
+    EMR_CREATEBRUSHINDIRECT = 0x0000_0027,
+    EMR_DELETEOBJECT = 0x0000_0028,
+    EMR_ELLIPSE = 0x0000_002A,
+    EMR_RECTANGLE = 0x0000_002B,
+    EMR_ROUNDRECT = 0x0000_002C,
+    EMR_ARC = 0x0000_002D,
+    EMR_CHORD = 0x0000_002E,
+    EMR_PIE = 0x0000_002F,
+    EMR_SELECTPALETTE = 0x0000_0030,
+    EMR_CREATEPALETTE = 0x0000_0031,
+    EMR_SETPALETTEENTRIES = 0x0000_0032,
+    EMR_RESIZEPALETTE = 0x0000_0033,
+    EMR_REALIZEPALETTE = 0x0000_0034,
+    EMR_EXTFLOODFILL = 0x0000_0035,
+    EMR_LINETO = 0x0000_0036,
+    EMR_FILLRGN = 0x0000_0047,
+    EMR_FRAMERGN = 0x0000_0048,
+    EMR_INVERTRGN = 0x0000_0049,
+    EMR_PAINTRGN = 0x0000_004A,
+    EMR_EXTSELECTCLIPRGN = 0x0000_004B,
+    EMR_BITBLT = 0x0000_004C,
+    EMR_STRETCHBLT = 0x0000_004D,
+    EMR_SETDIBITSTODEVICE = 0x0000_0050,
+    EMR_STRETCHDIBITS = 0x0000_0051,
+    EMR_EXTCREATEFONTINDIRECTW = 0x0000_0052,
+    EMR_EXTTEXTOUTW = 0x0000_0054,
+    EMR_POLYGON16 = 0x0000_0056,
+    EMR_POLYLINE16 = 0x0000_0057,
+    EMR_POLYPOLYGON16 = 0x0000_005B,
+    EMR_CREATEMONOBRUSH = 0x0000_005D,
+    EMR_CREATEDIBPATTERNBRUSHPT = 0x0000_005E,
+    EMR_SETLAYOUT = 0x0000_0073,
+    EMR_SETTEXTJUSTIFICATION = 0x0000_0078,
+}
+
+/// Builder of one EMF record.
+///
+/// Fields are appended in little-endian order after the type and size; the
+/// size is filled in and the record padded to a multiple of 4 bytes by
+/// [`Self::finish`].
+#[derive(Clone, Debug)]
+pub struct Record(Vec<u8>);
+
+impl Record {
+    pub fn new(typ: EmfRecordType) -> Self {
+        let mut data = Vec::with_capacity(16);
+
+        data.extend((typ as u32).to_le_bytes());
+        data.extend(0_u32.to_le_bytes());
+
+        Self(data)
+    }
+
+    pub fn u8(mut self, value: u8) -> Self {
+        self.0.push(value);
+        self
+    }
+
+    pub fn u16(mut self, value: u16) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn i16(mut self, value: i16) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn u32(mut self, value: u32) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn i32(mut self, value: i32) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    pub fn f32(mut self, value: f32) -> Self {
+        self.0.extend(value.to_le_bytes());
+        self
+    }
+
+    /// Append a `usize` as a 32-bit unsigned integer, saturating.
+    pub fn size(self, value: usize) -> Self {
+        self.u32(u32::try_from(value).unwrap_or(u32::MAX))
+    }
+
+    pub fn bytes(mut self, value: &[u8]) -> Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    /// Append zero bytes until the length is a multiple of 4.
+    pub fn align(mut self) -> Self {
+        let padding = self.0.len().next_multiple_of(4) - self.0.len();
+
+        self.0.extend(core::iter::repeat_n(0, padding));
+        self
+    }
+
+    pub fn color(self, color: &ColorRef) -> Self {
+        self.u8(color.red).u8(color.green).u8(color.blue).u8(0)
+    }
+
+    /// Append a PointL Object.
+    pub fn point(self, x: i16, y: i16) -> Self {
+        self.i32(i32::from(x)).i32(i32::from(y))
+    }
+
+    /// Append a RectL Object.
+    pub fn rect(
+        self,
+        (left, top, right, bottom): (i32, i32, i32, i32),
+    ) -> Self {
+        self.i32(left).i32(top).i32(right).i32(bottom)
+    }
+
+    /// Append a RegionData Object holding `rects`.
+    pub fn region(mut self, rects: &[(i32, i32, i32, i32)]) -> Self {
+        let bounds = bounding_rect(rects.iter().copied());
+
+        self = self
+            .u32(0x20)
+            // RDH_RECTANGLES
+            .u32(0x01)
+            .size(rects.len())
+            .size(rects.len() * 16)
+            .rect(bounds);
+
+        for rect in rects {
+            self = self.rect(*rect);
+        }
+
+        self
+    }
+
+    /// Append the offsets and sizes of the BITMAPINFO and the bits of
+    /// `dib`, which [`Self::dib`] places after the `fixed` bytes of the
+    /// record.
+    pub fn dib_offsets(self, dib: &Dib, fixed: usize) -> Self {
+        let bits = (fixed + dib.info.len()).next_multiple_of(4);
+
+        self.size(fixed).size(dib.info.len()).size(bits).size(dib.bits.len())
+    }
+
+    /// Append the BITMAPINFO and the bits of `dib`.
+    pub fn dib(self, dib: &Dib) -> Self {
+        self.bytes(&dib.info).align().bytes(&dib.bits)
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self = self.align();
+
+        let size = u32::try_from(self.0.len()).unwrap_or(u32::MAX);
+
+        self.0[4..8].copy_from_slice(&size.to_le_bytes());
+        self.0
+    }
+}
+
+/// Smallest rectangle containing `rects`, or an empty rectangle.
+pub fn bounding_rect(
+    rects: impl IntoIterator<Item = (i32, i32, i32, i32)>,
+) -> (i32, i32, i32, i32) {
+    rects
+        .into_iter()
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+        .unwrap_or((0, 0, -1, -1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finish_writes_size_and_padding() {
+        let record = Record::new(EmfRecordType::EMR_SETMAPMODE).u8(1).finish();
+
+        assert_eq!(record, [0x11, 0, 0, 0, 12, 0, 0, 0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn region_has_header_and_bounds() {
+        let record = Record::new(EmfRecordType::EMR_INVERTRGN)
+            .region(&[(0, 0, 10, 10), (5, 10, 20, 15)])
+            .finish();
+
+        assert_eq!(record.len(), 8 + 32 + 32);
+        assert_eq!(&record[16..20], 2_u32.to_le_bytes());
+        assert_eq!(&record[20..24], 32_u32.to_le_bytes());
+        assert_eq!(&record[32..36], 20_i32.to_le_bytes());
+        assert_eq!(&record[36..40], 15_i32.to_le_bytes());
+    }
+}
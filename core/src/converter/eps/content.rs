@@ -0,0 +1,129 @@
+use crate::{
+    converter::page::{Path, components, number},
+    imports::*,
+    parser::*,
+};
+
+/// Operators of a PostScript program.
+///
+/// Coordinates are device units with the y-axis pointing down; the page
+/// transform written by [`crate::converter::EpsPlayer`] maps them to
+/// points.
+#[derive(Clone, Debug, Default)]
+pub struct Content(Vec<u8>);
+
+impl Content {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `operator` preceded by its operands.
+    pub fn push(mut self, operands: &[f32], operator: &str) -> Self {
+        for operand in operands {
+            self.0.extend_from_slice(number(*operand).as_bytes());
+            self.0.push(b' ');
+        }
+
+        self.0.extend_from_slice(operator.as_bytes());
+        self.0.push(b'\n');
+        self
+    }
+
+    /// Append operators written by another builder.
+    pub fn append(mut self, content: &Content) -> Self {
+        self.0.extend_from_slice(&content.0);
+        self
+    }
+
+    /// Append `operator` preceded by the array of a transformation matrix.
+    pub fn matrix(self, matrix: [f32; 6], operator: &str) -> Self {
+        self.raw(format!("[{}] {operator}", matrix.map(number).join(" ")))
+    }
+
+    /// Append an operator line as it is.
+    pub fn raw(mut self, line: impl core::fmt::Display) -> Self {
+        self.0.extend_from_slice(format!("{line}\n").as_bytes());
+        self
+    }
+
+    /// Append `data` as it is, ending it with a newline so that the next
+    /// operator does not run into its last token.
+    pub fn verbatim(mut self, data: &[u8]) -> Self {
+        self.0.extend_from_slice(data);
+
+        if !matches!(self.0.last(), None | Some(b'\n' | b'\r')) {
+            self.0.push(b'\n');
+        }
+
+        self
+    }
+
+    /// Set the colour of both fills and strokes, as PostScript has only
+    /// one current colour.
+    pub fn color(self, color: &ColorRef) -> Self {
+        let (r, g, b) = components(color);
+        self.push(&[r, g, b], "setrgbcolor")
+    }
+
+    /// Set the dash pattern of strokes, solid when `dashes` is empty.
+    pub fn dash(self, dashes: &[f32]) -> Self {
+        let dashes: Vec<_> = dashes.iter().map(|v| number(*v)).collect();
+
+        self.raw(format!("[{}] 0 setdash", dashes.join(" ")))
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl Path for Content {
+    fn move_to(self, (x, y): (f32, f32)) -> Self {
+        self.push(&[x, y], "moveto")
+    }
+
+    fn line_to(self, (x, y): (f32, f32)) -> Self {
+        self.push(&[x, y], "lineto")
+    }
+
+    fn curve_to(
+        self,
+        (x1, y1): (f32, f32),
+        (x2, y2): (f32, f32),
+        (x3, y3): (f32, f32),
+    ) -> Self {
+        self.push(&[x1, y1, x2, y2, x3, y3], "curveto")
+    }
+
+    fn close(self) -> Self {
+        self.push(&[], "closepath")
+    }
+}
+
+impl core::fmt::Display for Content {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&String::from_utf8_lossy(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_is_a_closed_subpath() {
+        let content = Content::new().rect((10.0, 20.0), (0.0, 5.0));
+
+        assert_eq!(
+            content.to_string(),
+            "10 20 moveto\n0 20 lineto\n0 5 lineto\n10 5 lineto\nclosepath\n",
+        );
+    }
+
+    #[test]
+    fn verbatim_data_ends_with_newline() {
+        let content = Content::new().verbatim(b"1 0 0 setrgbcolor").raw("fill");
+
+        assert_eq!(content.to_string(), "1 0 0 setrgbcolor\nfill\n");
+    }
+}
@@ -0,0 +1,682 @@
+use crate::{
+    converter::{
+        Clip, DiagnosticKind, GdiState, LineCap, LineJoin, LineStyle,
+        PlayError,
+        drawing::{Context, Device, Paint, TextRun},
+        eps::{
+            content::Content,
+            document::{self, Fonts},
+        },
+        page::{Image, Path, Placeable, StandardFont, hex, number},
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Program of an [`EpsPlayer`](crate::converter::EpsPlayer): its
+/// operators and the fonts they use.
+#[derive(Default)]
+pub struct Eps {
+    fonts: Fonts,
+    content: Content,
+    // META_PLACEABLE bounding box, which becomes the bounding box.
+    placeable: Placeable,
+}
+
+/// Colour change standing in for a raster operation. PostScript paints
+/// opaquely, so operations combining with the pixels already painted are
+/// approximated by one of these.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Copy,
+    Invert,
+    Black,
+    White,
+}
+
+impl Operation {
+    /// Operation standing in for the ROP2 mode `operation`, or `None` when
+    /// nothing is drawn.
+    fn from_rop2(
+        context: &mut Context<'_>,
+        operation: BinaryRasterOperation,
+    ) -> Option<Self> {
+        use BinaryRasterOperation as Rop2;
+
+        let approximated = match operation {
+            Rop2::R2_NOP => return None,
+            Rop2::R2_COPYPEN => return Some(Self::Copy),
+            Rop2::R2_NOTCOPYPEN => return Some(Self::Invert),
+            Rop2::R2_BLACK => return Some(Self::Black),
+            Rop2::R2_WHITE => return Some(Self::White),
+            Rop2::R2_NOT => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!(
+                        "{operation:?}: inverting the page is not possible in \
+                         PostScript"
+                    ),
+                );
+
+                return None;
+            }
+            Rop2::R2_NOTXORPEN | Rop2::R2_MASKNOTPEN | Rop2::R2_MERGENOTPEN => {
+                Self::Invert
+            }
+            Rop2::R2_XORPEN
+            | Rop2::R2_MASKPEN
+            | Rop2::R2_MERGEPEN
+            | Rop2::R2_NOTMERGEPEN
+            | Rop2::R2_MASKPENNOT
+            | Rop2::R2_NOTMASKPEN
+            | Rop2::R2_MERGEPENNOT => Self::Copy,
+        };
+
+        context.report(
+            DiagnosticKind::Approximated,
+            format!(
+                "{operation:?}: drawn as {}",
+                if approximated == Self::Invert {
+                    "R2_NOTCOPYPEN"
+                } else {
+                    "R2_COPYPEN"
+                },
+            ),
+        );
+
+        Some(approximated)
+    }
+
+    fn color(self, color: &ColorRef) -> ColorRef {
+        match self {
+            Self::Copy => color.clone(),
+            Self::Invert => ColorRef {
+                red: !color.red,
+                green: !color.green,
+                blue: !color.blue,
+                ..color.clone()
+            },
+            Self::Black => ColorRef::black(),
+            Self::White => ColorRef::white(),
+        }
+    }
+}
+
+impl Eps {
+    /// Append `body` in a graphics state of its own, clipped to the
+    /// clipping region.
+    fn paint(&mut self, state: &GdiState, body: &Content) {
+        let block = clip(state.clips(), Content::new().push(&[], "gsave"))
+            .append(body)
+            .push(&[], "grestore");
+
+        self.content = core::mem::take(&mut self.content).append(&block);
+    }
+
+    /// Draw the part `(x, y, source_width, source_height)` of `image`,
+    /// stretched from the device point `p1` to `p2`.
+    fn draw_image(
+        &mut self,
+        state: &GdiState,
+        image: &Image,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        (x, y, source_width, source_height): (f32, f32, f32, f32),
+    ) {
+        let size = |v: usize| f32::from(u16::try_from(v).unwrap_or(u16::MAX));
+        let (width, height) = (size(image.width()), size(image.height()));
+        // Device units per source pixel, negative when mirrored.
+        let kx = (p2.0 - p1.0) / source_width;
+        let ky = (p2.1 - p1.1) / source_height;
+        let source = match image.filter() {
+            Some(filter) => format!("3 index /{filter} filter"),
+            None => "3 index".to_owned(),
+        };
+        // The data follows the procedure in the file, which reads it
+        // through the end-of-data marker even when a filter stops early.
+        let mut body = Content::new()
+            .push(&[], "newpath")
+            .rect(p1, p2)
+            .push(&[], "clip")
+            .push(&[], "newpath")
+            .matrix(
+                [
+                    width * kx,
+                    0.0,
+                    0.0,
+                    -height * ky,
+                    p1.0 - x * kx,
+                    p1.1 + (height - y) * ky,
+                ],
+                "concat",
+            )
+            .raw("{")
+            .raw("currentfile /ASCIIHexDecode filter")
+            .append(&image_operators(image))
+            .raw(format!("dup /DataSource {source} put"))
+            .raw("image flushfile")
+            .raw("} exec");
+
+        for chunk in image.data().chunks(32) {
+            body = body.raw(hex(chunk));
+        }
+
+        self.paint(state, &body.raw(">"));
+    }
+}
+
+/// Operators selecting `brush` as the current colour, or `None` when it
+/// paints nothing.
+fn fill_paint(
+    context: &mut Context<'_>,
+    brush: &Brush,
+    operation: Operation,
+) -> Option<Content> {
+    let state = context.state;
+    let pixel_size = state.window().device_pixel_size();
+    let tile = match brush {
+        Brush::Null => return None,
+        _ if matches!(operation, Operation::Black | Operation::White) => {
+            let color = operation.color(&ColorRef::black());
+
+            return Some(Content::new().color(&color));
+        }
+        Brush::Solid { color_ref } => {
+            return Some(Content::new().color(&operation.color(color_ref)));
+        }
+        Brush::Hatched { color_ref, brush_hatch } => {
+            let background = (state.bk_mode() == MixMode::OPAQUE)
+                .then(|| operation.color(state.bk_color()));
+
+            (
+                hatch(
+                    *brush_hatch,
+                    &operation.color(color_ref),
+                    background.as_ref(),
+                ),
+                (8, 8),
+            )
+        }
+        Brush::DIBPatternPT { brush_hatch, .. } => {
+            image_tile(context, Image::from_dib(brush_hatch), operation)?
+        }
+        Brush::Pattern { brush_hatch } => {
+            image_tile(context, Image::from_bitmap16(brush_hatch), operation)?
+        }
+    };
+    let (tile, (width, height)) = tile;
+
+    // The pattern is made in the device space of the drawing, where a tile
+    // pixel is a device pixel.
+    Some(
+        Content::new()
+            .raw(format!(
+                "<< /PatternType 1 /PaintType 1 /TilingType 1 /BBox [0 0 \
+                 {width} {height}] /XStep {width} /YStep {height} /PaintProc \
+                 {{ pop"
+            ))
+            .append(&tile)
+            .raw("} >>")
+            .matrix(
+                [pixel_size, 0.0, 0.0, pixel_size, 0.0, 0.0],
+                "makepattern setpattern",
+            ),
+    )
+}
+
+/// Tile of a pattern brush showing `image`, one image pixel per tile pixel,
+/// with its size.
+fn image_tile(
+    context: &mut Context<'_>,
+    image: Result<Image, String>,
+    operation: Operation,
+) -> Option<(Content, (usize, usize))> {
+    // The procedure holds the data in a string, which PostScript limits to
+    // 65535 bytes.
+    let image = image.and_then(|image| {
+        supported(&image)?;
+
+        if image.data().len() > usize::from(u16::MAX) {
+            return Err("bitmap is too large for a pattern".to_owned());
+        }
+
+        Ok(image)
+    });
+    let image = match image {
+        Ok(image) if operation == Operation::Invert => image.invert(),
+        Ok(image) => image,
+        Err(message) => {
+            context.report(
+                DiagnosticKind::Skipped,
+                format!("pattern brush is not painted: {message}"),
+            );
+
+            return None;
+        }
+    };
+    let (width, height) = (image.width(), image.height());
+    let mut source = format!("<{}>", hex(image.data()));
+
+    if let Some(filter) = image.filter() {
+        source = format!("{source} /{filter} filter");
+    }
+
+    // Image space has its first row at the top of the unit square, which
+    // the y-down tile space turns upside down.
+    let tile = Content::new()
+        .raw(format!("[{width} 0 0 -{height} 0 {height}] concat"))
+        .append(&image_operators(&image))
+        .raw(format!("dup /DataSource {source} put image"));
+
+    Some((tile, (width, height)))
+}
+
+/// Operators setting up the selected pen, or `None` for PS_NULL.
+fn stroke_paint(state: &GdiState, operation: Operation) -> Option<Content> {
+    let line = LineStyle::selected(state)?;
+    let cap = match line.cap {
+        LineCap::Flat => 0.0,
+        LineCap::Round => 1.0,
+        LineCap::Square => 2.0,
+    };
+    let join = match line.join {
+        LineJoin::Miter => 0.0,
+        LineJoin::Round => 1.0,
+        LineJoin::Bevel => 2.0,
+    };
+
+    Some(
+        Content::new()
+            .color(&operation.color(&state.pen().color_ref))
+            .push(&[line.width], "setlinewidth")
+            .push(&[cap], "setlinecap")
+            .push(&[join], "setlinejoin")
+            .dash(&line.dashes),
+    )
+}
+
+/// Byte codes of the glyphs of `run` in its standard font.
+fn encode_text(
+    context: &mut Context<'_>,
+    run: &TextRun,
+    font: StandardFont,
+) -> Vec<u8> {
+    // The Symbol font takes the byte codes of SYMBOL_CHARSET text.
+    if font.is_symbolic() {
+        return run
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.bytes.first().copied().unwrap_or(b'?'))
+            .collect();
+    }
+
+    let mut unmapped = 0;
+    let codes = run
+        .glyphs
+        .iter()
+        .map(|glyph| {
+            StandardFont::encode(&glyph.text).unwrap_or_else(|| {
+                unmapped += 1;
+                b'?'
+            })
+        })
+        .collect();
+
+    if unmapped > 0 {
+        context.report(
+            DiagnosticKind::Approximated,
+            format!(
+                "{unmapped} characters outside WinAnsiEncoding are drawn as \
+                 '?'",
+            ),
+        );
+    }
+
+    codes
+}
+
+/// Append operators intersecting the clipping path with each of `clips`.
+fn clip(clips: &[Clip], mut content: Content) -> Content {
+    for clip in clips {
+        content = content.push(&[], "newpath");
+        content = match clip {
+            Clip::Rects(rects) if rects.is_empty() => {
+                content.rect((0.0, 0.0), (0.0, 0.0)).push(&[], "clip")
+            }
+            Clip::Rects(rects) => {
+                for (left, top, right, bottom) in rects {
+                    content = content.rect((*left, *top), (*right, *bottom));
+                }
+
+                content.push(&[], "clip")
+            }
+            Clip::Exclude((left, top, right, bottom)) => content
+                .rect((-32768.0, -32768.0), (32767.0, 32767.0))
+                .rect((*left, *top), (*right, *bottom))
+                .push(&[], "eoclip"),
+        }
+        .push(&[], "newpath");
+    }
+
+    content
+}
+
+/// Check that PostScript language level 2 can decode the data of `image`.
+fn supported(image: &Image) -> Result<(), String> {
+    match image.filter() {
+        Some("FlateDecode") => Err("PNG data needs FlateDecode, which \
+                                    PostScript language level 2 does not have"
+            .to_owned()),
+        _ => Ok(()),
+    }
+}
+
+/// Operators selecting the colour space of `image` and leaving its image
+/// dictionary on the operand stack, for the caller to add `/DataSource`.
+fn image_operators(image: &Image) -> Content {
+    let (width, height) = (image.width(), image.height());
+
+    Content::new().raw(format!("{} setcolorspace", image.color_space())).raw(
+        format!(
+            "<< /ImageType 1 /Width {width} /Height {height} \
+             /BitsPerComponent {} /Decode {} /ImageMatrix [{width} 0 0 \
+             -{height} 0 {height}] >>",
+            image.bits_per_component(),
+            image.decode(),
+        ),
+    )
+}
+
+/// Tile of a hatched brush, in device pixels.
+fn hatch(
+    style: HatchStyle,
+    color: &ColorRef,
+    background: Option<&ColorRef>,
+) -> Content {
+    // Lines run past the tile so that diagonals join across tiles; the
+    // bounding box clips the overhang.
+    type Line = ((f32, f32), (f32, f32));
+
+    let lines: &[Line] = match style {
+        HatchStyle::HS_HORIZONTAL => &[((-1.0, 0.5), (9.0, 0.5))],
+        HatchStyle::HS_VERTICAL => &[((0.5, -1.0), (0.5, 9.0))],
+        HatchStyle::HS_FDIAGONAL => &[((-1.0, -1.0), (9.0, 9.0))],
+        HatchStyle::HS_BDIAGONAL => &[((-1.0, 9.0), (9.0, -1.0))],
+        HatchStyle::HS_CROSS => {
+            &[((-1.0, 0.5), (9.0, 0.5)), ((0.5, -1.0), (0.5, 9.0))]
+        }
+        HatchStyle::HS_DIAGCROSS => {
+            &[((-1.0, -1.0), (9.0, 9.0)), ((-1.0, 9.0), (9.0, -1.0))]
+        }
+    };
+    let mut tile = Content::new();
+
+    if let Some(background) = background {
+        tile = tile
+            .color(background)
+            .push(&[], "newpath")
+            .rect((0.0, 0.0), (8.0, 8.0))
+            .push(&[], "fill");
+    }
+
+    tile = tile.color(color).push(&[1.0], "setlinewidth").push(&[], "newpath");
+
+    for (from, to) in lines {
+        tile = tile.move_to(*from).line_to(*to);
+    }
+
+    tile.push(&[], "stroke")
+}
+
+impl Device for Eps {
+    type Path = Content;
+
+    fn path(&self, _: &GdiState) -> Content {
+        Content::new()
+    }
+
+    fn header(&mut self, header: &MetafileHeader) {
+        self.placeable = Placeable::new(header);
+    }
+
+    fn draw(
+        &mut self,
+        context: &mut Context<'_>,
+        path: Content,
+        paint: &Paint,
+    ) {
+        let Some(operation) = Operation::from_rop2(context, paint.operation)
+        else {
+            return;
+        };
+        let fill = paint
+            .brush
+            .as_ref()
+            .and_then(|brush| fill_paint(context, brush, operation));
+        let stroke = if paint.stroke {
+            stroke_paint(context.state, operation)
+        } else {
+            None
+        };
+
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        let mut body = Content::new().push(&[], "newpath").append(&path);
+
+        // The fill keeps the path for the stroke by painting in a graphics
+        // state of its own.
+        if let Some(fill) = fill {
+            let operator =
+                if context.state.fill_even_odd() { "eofill" } else { "fill" };
+
+            body = body
+                .push(&[], "gsave")
+                .append(&fill)
+                .push(&[], operator)
+                .push(&[], "grestore");
+        }
+
+        if let Some(stroke) = stroke {
+            body = body.append(&stroke).push(&[], "stroke");
+        }
+
+        self.paint(context.state, &body);
+    }
+
+    /// Operations combining the source with the page are approximated by
+    /// the operation painting the source alone.
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Image,
+        corners: ((f32, f32), (f32, f32)),
+        source: (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    ) {
+        use TernaryRasterOperation as Rop3;
+
+        let invert = match operation {
+            Rop3::SRCCOPY
+            | Rop3::SRCAND
+            | Rop3::SRCPAINT
+            | Rop3::SRCINVERT
+            | Rop3::SRCERASE => false,
+            Rop3::NOTSRCCOPY | Rop3::MERGEPAINT | Rop3::NOTSRCERASE => true,
+            _ => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("{operation:?}: raster operation is not supported"),
+                );
+
+                return;
+            }
+        };
+
+        if let Err(message) = supported(&image) {
+            context.report(
+                DiagnosticKind::Skipped,
+                format!("bitmap is not drawn: {message}"),
+            );
+
+            return;
+        }
+
+        if !matches!(operation, Rop3::SRCCOPY | Rop3::NOTSRCCOPY) {
+            context.report(
+                DiagnosticKind::Approximated,
+                format!(
+                    "{operation:?}: drawn as {}",
+                    if invert { "NOTSRCCOPY" } else { "SRCCOPY" },
+                ),
+            );
+        }
+
+        let image = if invert { image.invert() } else { image };
+
+        self.draw_image(context.state, &image, corners, source);
+    }
+
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let state = context.state;
+        let standard_font = StandardFont::new(&run.font);
+        let codes = encode_text(context, run, standard_font);
+        let name = self.fonts.name(standard_font);
+        let text_color = state.text_color();
+        let bk_color = state.bk_color();
+        let (start, size, width) = (run.start(), run.size, run.width);
+        let (direction, down) = (run.direction(), run.down());
+
+        // ETO_OPAQUE fills the rectangle before the text is drawn.
+        if let Some((left, top, right, bottom)) = run.opaque {
+            let body = Content::new()
+                .color(bk_color)
+                .push(&[], "newpath")
+                .rect((left, top), (right, bottom))
+                .push(&[], "fill");
+
+            self.paint(state, &body);
+        }
+
+        let mut body = Content::new();
+
+        if let Some((left, top, right, bottom)) = run.clip {
+            body = body
+                .push(&[], "newpath")
+                .rect((left, top), (right, bottom))
+                .push(&[], "clip");
+        }
+
+        if state.bk_mode() == MixMode::OPAQUE {
+            body = body
+                .color(bk_color)
+                .push(&[], "newpath")
+                .polygon(&run.corners())
+                .push(&[], "fill");
+        }
+
+        // Text space has its y-axis pointing up from the baseline, against
+        // the device space of the drawing.
+        let show = |body: Content, origin: (f32, f32), codes: &[u8]| {
+            body.push(&[], "gsave")
+                .matrix(
+                    [
+                        direction.0,
+                        direction.1,
+                        -down.0,
+                        -down.1,
+                        origin.0,
+                        origin.1,
+                    ],
+                    "concat",
+                )
+                .raw(format!("0 0 moveto <{}> show", hex(codes)))
+                .push(&[], "grestore")
+        };
+
+        body = body
+            .raw(format!("{name} {} scalefont setfont", number(size)))
+            .color(text_color);
+
+        if run.custom {
+            for (code, origin) in codes.iter().zip(run.glyph_origins()) {
+                body = show(body, origin, &[*code]);
+            }
+        } else {
+            body = show(body, start, &codes);
+        }
+
+        for below in run.lines() {
+            body = body
+                .push(&[0.05 * size], "setlinewidth")
+                .push(&[], "newpath")
+                .move_to(run.at(start, 0.0, below))
+                .line_to(run.at(start, width, below))
+                .push(&[], "stroke");
+        }
+
+        self.paint(state, &body);
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+        context.report(
+            DiagnosticKind::Skipped,
+            "flood fill needs the pixels of the drawing, which PostScript \
+             output does not keep"
+                .to_owned(),
+        );
+    }
+
+    fn escape(&mut self, context: &mut Context<'_>, record: &META_ESCAPE) {
+        match record {
+            // PostScript-centric data is sent as it is, outside of the
+            // graphics state of the drawing.
+            META_ESCAPE::POSTSCRIPT_PASSTHROUGH { data, .. } => {
+                self.content =
+                    core::mem::take(&mut self.content).verbatim(data);
+            }
+            // GDI-centric data runs in the graphics state of the drawing.
+            META_ESCAPE::POSTSCRIPT_DATA { data, .. }
+            | META_ESCAPE::PASSTHROUGH { data, .. } => {
+                self.paint(context.state, &Content::new().verbatim(data));
+            }
+            META_ESCAPE::ENCAPSULATED_POSTSCRIPT { .. } => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    "embedded EPS is placed in the device coordinates of the \
+                     printer, which the metafile does not give"
+                        .to_owned(),
+                );
+            }
+            _ => {
+                info!("META_ESCAPE: skipped (not addressed to PostScript)");
+            }
+        }
+    }
+
+    fn generate(self, state: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let Self { fonts, content, placeable } = self;
+        let window = state.window();
+        let (x, y, width, height) = placeable.area(window);
+
+        if width <= 0.0 || height <= 0.0 {
+            return Err(PlayError::FailedGenerate {
+                cause: format!("page size is empty: {width}x{height}"),
+            });
+        }
+
+        let scale = placeable.scale(window, 72.0);
+        // Device space has the y-axis pointing down from the top left of the
+        // drawing, while PostScript user space points up from the bottom
+        // left.
+        let matrix =
+            [scale, 0.0, 0.0, -scale, -x * scale, (y + height) * scale];
+
+        Ok(document::write(
+            fonts,
+            &content,
+            (width * scale, height * scale),
+            matrix,
+        ))
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{
+    converter::{
+        eps::content::Content,
+        page::{StandardFont, number},
+    },
+    imports::*,
+};
+
+/// Fonts used by the program, named as the operators refer to them.
+#[derive(Debug, Default)]
+pub struct Fonts(Vec<StandardFont>);
+
+impl Fonts {
+    /// Name of `font`, added unless it is in use already.
+    pub fn name(&mut self, font: StandardFont) -> String {
+        let index =
+            self.0.iter().position(|v| *v == font).unwrap_or_else(|| {
+                self.0.push(font);
+                self.0.len() - 1
+            });
+
+        format!("F{index}")
+    }
+}
+
+/// Procedures of the prolog, which live in `WMFDict` so that they do not
+/// collide with the names of the document the file is placed in.
+///
+/// `ReEncode` copies a font with the encoding replaced by
+/// `WinAnsiEncoding`, which is ISOLatin1Encoding with the quotes, hyphen
+/// and the characters of 0x80 to 0x9F of Windows-1252.
+const PROLOG: &str = "\
+/WinAnsiEncoding ISOLatin1Encoding dup length array copy def
+WinAnsiEncoding 16#27 /quotesingle put
+WinAnsiEncoding 16#2D /hyphen put
+WinAnsiEncoding 16#60 /grave put
+WinAnsiEncoding 16#80 [
+/Euro /.notdef /quotesinglbase /florin /quotedblbase /ellipsis /dagger
+/daggerdbl /circumflex /perthousand /Scaron /guilsinglleft /OE /.notdef
+/Zcaron /.notdef /.notdef /quoteleft /quoteright /quotedblleft
+/quotedblright /bullet /endash /emdash /tilde /trademark /scaron
+/guilsinglright /oe /.notdef /zcaron /Ydieresis
+] putinterval
+/ReEncode {
+findfont dup length dict begin
+{ 1 index /FID ne { def } { pop pop } ifelse } forall
+/Encoding WinAnsiEncoding def
+currentdict end definefont
+} bind def
+";
+
+/// Write an encapsulated PostScript file, `width` by `height` points,
+/// running `content` through the page transform `matrix`.
+pub fn write(
+    fonts: Fonts,
+    content: &Content,
+    (width, height): (f32, f32),
+    matrix: [f32; 6],
+) -> Vec<u8> {
+    use core::fmt::Write;
+
+    let Fonts(fonts) = fonts;
+    let mut header = format!(
+        "%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 {} \
+         {}\n%%HiResBoundingBox: 0 0 {} {}\n%%LanguageLevel: 2\n",
+        bounding(width),
+        bounding(height),
+        number(width),
+        number(height),
+    );
+
+    for (i, font) in fonts.iter().enumerate() {
+        let comment = if i == 0 { "%%DocumentNeededResources:" } else { "%%+" };
+        let _ = writeln!(header, "{comment} font {}", font.base_font());
+    }
+
+    // Two entries for WinAnsiEncoding and ReEncode, and one per font.
+    let _ = write!(
+        header,
+        "%%EndComments\n%%BeginProlog\n/WMFDict {} dict def\nWMFDict \
+         begin\n{PROLOG}end\n%%EndProlog\n%%BeginSetup\nWMFDict begin\n",
+        2 + fonts.len(),
+    );
+
+    for (i, font) in fonts.iter().enumerate() {
+        let name = font.base_font();
+
+        if font.is_symbolic() {
+            let _ = writeln!(header, "/F{i} /{name} findfont def");
+        } else {
+            let _ = writeln!(header, "/F{i} /WMF-{name} /{name} ReEncode def");
+        }
+    }
+
+    header.push_str("end\n%%EndSetup\n");
+
+    Content::new()
+        .raw(header.trim_end())
+        .raw("WMFDict begin")
+        .raw("gsave")
+        .matrix(matrix, "concat")
+        .append(content)
+        .raw("grestore")
+        .raw("end")
+        .raw("showpage")
+        .raw("%%Trailer")
+        .raw("%%EOF")
+        .into_bytes()
+}
+
+/// Smallest whole number of points covering `size`, ignoring the rounding
+/// error below the precision of [`number`].
+fn bounding(size: f32) -> String {
+    format!("{}", ((size * 1000.0).round() / 1000.0).ceil().max(0.0))
+}
@@ -0,0 +1,26 @@
+mod content;
+mod device;
+mod document;
+
+pub use self::device::Eps;
+use crate::converter::drawing::Drawing;
+
+/// Player writing the metafile as an encapsulated PostScript file.
+///
+/// Drawing records become PostScript language level 2 operators, bitmaps
+/// become image dictionaries and text is set in the standard fonts that
+/// every PostScript interpreter provides. The `%%BoundingBox` is the
+/// META_PLACEABLE bounding box at its resolution, or the area of the
+/// drawing at 96 DPI without one.
+///
+/// The payloads of POSTSCRIPT_DATA and PASSTHROUGH escapes are inserted as
+/// they are in a graphics state of their own, with the clipping region and
+/// device units of the drawing as a GDI-centric PostScript driver does.
+/// POSTSCRIPT_PASSTHROUGH payloads are inserted without either.
+pub type EpsPlayer = Drawing<Eps>;
+
+impl EpsPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{
+    converter::{
+        Clip, DiagnosticKind, GdiState, PlayError,
+        drawing::{Context, Device, Paint, TextRun},
+        page::{Image, Path},
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Rectangle `(left, top, right, bottom)` in device units.
+type Bounds = (f32, f32, f32, f32);
+
+/// Bounding box of what an [`ExtentPlayer`](crate::converter::ExtentPlayer)
+/// has seen painted.
+#[derive(Default)]
+pub struct Extent {
+    bounds: Option<Bounds>,
+}
+
+/// Bounds of the points of a path, which is all of a figure the extent
+/// needs. Arcs add their exact extent rather than the control points of
+/// the curves standing in for them.
+#[derive(Default)]
+pub struct Hull {
+    bounds: Option<Bounds>,
+}
+
+impl Hull {
+    fn include(mut self, point: (f32, f32)) -> Self {
+        let point = point_bounds(point);
+
+        self.bounds =
+            Some(self.bounds.map_or(point, |bounds| union(bounds, point)));
+        self
+    }
+}
+
+impl Path for Hull {
+    fn move_to(self, point: (f32, f32)) -> Self {
+        self.include(point)
+    }
+
+    fn line_to(self, point: (f32, f32)) -> Self {
+        self.include(point)
+    }
+
+    fn curve_to(
+        self,
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+    ) -> Self {
+        // A Bézier curve stays inside the hull of its control points.
+        self.include(control1).include(control2).include(to)
+    }
+
+    fn close(self) -> Self {
+        self
+    }
+
+    /// Include the end point of the arc and the points where it crosses
+    /// the axes of the ellipse.
+    fn arc_to(
+        mut self,
+        center: (f32, f32),
+        (rx, ry): (f32, f32),
+        start: f32,
+        sweep: f32,
+    ) -> Self {
+        use core::f32::consts::FRAC_PI_2;
+
+        let point = |angle: f32| {
+            (center.0 + rx * angle.cos(), center.1 + ry * angle.sin())
+        };
+        let end = start + sweep;
+        let mut quadrant = (start.min(end) / FRAC_PI_2).ceil();
+
+        self = self.include(point(end));
+
+        while quadrant * FRAC_PI_2 <= start.max(end) {
+            self = self.include(point(quadrant * FRAC_PI_2));
+            quadrant += 1.0;
+        }
+
+        self
+    }
+}
+
+impl Extent {
+    /// Bounding box of the painted output, rounded outwards to whole
+    /// units.
+    pub fn extent(&self) -> Option<RectL> {
+        let (left, top, right, bottom) = self.bounds?;
+        // Snap to thousandths first, so that the error of trigonometry does
+        // not widen the box by a unit.
+        #[allow(clippy::cast_possible_truncation)]
+        let round = |v: f32, to: fn(f64) -> f64| {
+            let v = to((f64::from(v) * 1000.0).round() / 1000.0);
+
+            v.clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32
+        };
+
+        Some(RectL {
+            left: round(left, f64::floor),
+            top: round(top, f64::floor),
+            right: round(right, f64::ceil),
+            bottom: round(bottom, f64::ceil),
+        })
+    }
+
+    /// Include `bounds` in the extent, after clipping it to the clipping
+    /// region.
+    fn paint(&mut self, state: &GdiState, bounds: Bounds) {
+        let mut bounds = Some(bounds);
+
+        for clip in state.clips() {
+            bounds = bounds.and_then(|bounds| match clip {
+                Clip::Rects(rects) => rects
+                    .iter()
+                    .filter_map(|rect| intersect(bounds, *rect))
+                    .reduce(union),
+                // Only an excluded area covering all of the bounds shrinks
+                // them.
+                Clip::Exclude(rect) => {
+                    (intersect(bounds, *rect) != Some(bounds)).then_some(bounds)
+                }
+            });
+        }
+
+        if let Some(bounds) = bounds {
+            self.bounds = Some(
+                self.bounds.map_or(bounds, |extent| union(extent, bounds)),
+            );
+        }
+    }
+
+    /// Include the destination rectangle from `p1` to `p2` of a bit block
+    /// transfer, unless it is empty or the raster operation leaves the
+    /// destination as it is.
+    fn blit(
+        &mut self,
+        state: &GdiState,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        operation: TernaryRasterOperation,
+    ) {
+        let (left, top, right, bottom) =
+            union(point_bounds(p1), point_bounds(p2));
+
+        if operation != TernaryRasterOperation::D
+            && left < right
+            && top < bottom
+        {
+            self.paint(state, (left, top, right, bottom));
+        }
+    }
+}
+
+/// Half the width of the selected pen in device units, which strokes
+/// extend on either side of their path, or `None` for a null pen.
+///
+/// Pens no wider than a device pixel are drawn a pixel wide. Mitered joins
+/// reaching beyond half the width are not accounted for.
+fn half_pen_width(state: &GdiState) -> Option<f32> {
+    if state.pen().style.style == PenStyle::PS_NULL {
+        return None;
+    }
+
+    Some(state.pen_width().max(state.window().device_pixel_size()) / 2.0)
+}
+
+impl Device for Extent {
+    type Path = Hull;
+
+    fn path(&self, _: &GdiState) -> Hull {
+        Hull::default()
+    }
+
+    fn draw(&mut self, context: &mut Context<'_>, hull: Hull, paint: &Paint) {
+        let Some(outline) = hull.bounds else {
+            return;
+        };
+
+        if paint
+            .brush
+            .as_ref()
+            .is_some_and(|brush| !matches!(brush, Brush::Null))
+        {
+            self.paint(context.state, outline);
+        }
+
+        if let Some(half) =
+            paint.stroke.then(|| half_pen_width(context.state)).flatten()
+        {
+            let (left, top, right, bottom) = outline;
+
+            self.paint(
+                context.state,
+                (left - half, top - half, right + half, bottom + half),
+            );
+        }
+    }
+
+    fn pattern_blt(
+        &mut self,
+        context: &mut Context<'_>,
+        corners: ((f32, f32), (f32, f32)),
+        operation: TernaryRasterOperation,
+    ) {
+        self.blit(context.state, corners, operation);
+    }
+
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        _: Image,
+        corners: ((f32, f32), (f32, f32)),
+        _: (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    ) {
+        self.blit(context.state, corners, operation);
+    }
+
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let glyphs = run.corners().into_iter().map(point_bounds).reduce(union);
+        let blank = run.glyphs.iter().all(|glyph| glyph.text.trim().is_empty());
+        // The background of the glyphs is painted in OPAQUE mode, so it
+        // falls in the same box.
+        let painted = [glyphs.filter(|_| !blank), run.opaque]
+            .into_iter()
+            .flatten()
+            .filter_map(|bounds| match run.clip {
+                Some(clip) => intersect(bounds, clip),
+                None => Some(bounds),
+            })
+            .reduce(union);
+
+        if let Some(bounds) = painted {
+            self.paint(context.state, bounds);
+        }
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, (x, y): (f32, f32)) {
+        let size = context.state.window().device_pixel_size();
+
+        context.report(
+            DiagnosticKind::Approximated,
+            "flood fill needs the pixels of the drawing, so only the pixel it \
+             starts from is included"
+                .to_owned(),
+        );
+        self.paint(context.state, (x, y, x + size, y + size));
+    }
+
+    fn escape(&mut self, _: &mut Context<'_>, _: &META_ESCAPE) {
+        info!("META_ESCAPE: skipped (escapes paint nothing of their own)");
+    }
+
+    fn generate(self, _: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let Some(RectL { left, top, right, bottom }) = self.extent() else {
+            return Err(PlayError::FailedGenerate {
+                cause: "nothing is painted".to_owned(),
+            });
+        };
+
+        Ok(format!("{left} {top} {right} {bottom}\n").into_bytes())
+    }
+}
+
+fn point_bounds((x, y): (f32, f32)) -> Bounds {
+    (x, y, x, y)
+}
+
+fn union(a: Bounds, b: Bounds) -> Bounds {
+    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+}
+
+/// Overlap of `a` and `b`, or `None` when they do not overlap.
+fn intersect(a: Bounds, b: Bounds) -> Option<Bounds> {
+    let bounds = (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3));
+
+    (bounds.0 <= bounds.2 && bounds.1 <= bounds.3).then_some(bounds)
+}
@@ -0,0 +1,54 @@
+mod device;
+
+pub use self::device::Extent;
+use crate::{converter::drawing::Drawing, parser::*};
+
+/// Player computing the bounding box of everything the metafile paints, in
+/// device units.
+///
+/// Unlike the drawing bounds the other players keep, the box covers the
+/// width of the pen around strokes, the extent of arcs rather than their
+/// bounding rectangles, the boxes of text and the destinations of bitmaps,
+/// and leaves out what the clipping region hides. Records painting nothing,
+/// such as figures drawn with both a null pen and a null brush, do not
+/// extend it.
+///
+/// The metafile does not give the metrics of its fonts, so text boxes are
+/// estimated from the standard font resembling the selected font, with an
+/// ascent of 0.8em and a descent of 0.2em.
+///
+/// ```
+/// use wmf_core::converter::{ExtentPlayer, WMFConverter};
+///
+/// # fn crop(buffer: &[u8]) -> Result<(), wmf_core::converter::ConvertError> {
+/// let player = WMFConverter::new(buffer, ExtentPlayer::new()).play()?;
+///
+/// if let Some(extent) = player.extent() {
+///     println!(
+///         "{}x{} at ({}, {})",
+///         extent.right - extent.left,
+///         extent.bottom - extent.top,
+///         extent.left,
+///         extent.top,
+///     );
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub type ExtentPlayer = Drawing<Extent>;
+
+impl ExtentPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bounding box of the painted output in device units, rounded
+    /// outwards to whole units, or `None` when nothing is painted.
+    ///
+    /// The window and viewport mapping can scale 16-bit logical units past
+    /// the range of `i32`, so each side saturates at `i32::MIN` or
+    /// `i32::MAX`.
+    pub fn extent(&self) -> Option<RectL> {
+        self.device().extent()
+    }
+}
@@ -0,0 +1,234 @@
+use crate::converter::*;
+
+#[derive(Clone, Debug)]
+pub struct DeviceContext {
+    // graphics object
+    pub object_table: GraphicsObjects,
+
+    // structures
+    /// Current position in logical units
+    pub drawing_position: PointS,
+    pub text_bk_color: ColorRef,
+    pub text_color: ColorRef,
+    pub window: Window,
+
+    // graphics props
+    pub bk_mode: MixMode,
+    /// Areas intersected to form the clipping region, in device units
+    pub clips: Vec<Clip>,
+    pub poly_fill_mode: PolyFillMode,
+    pub text_align_horizontal: TextAlignmentMode,
+    pub text_align_vertical: VerticalTextAlignmentMode,
+    pub text_align_update_cp: bool,
+
+    pub draw_mode: Option<BinaryRasterOperation>,
+    pub layout: Layout,
+    pub stretch_mode: StretchMode,
+    /// Extra inter-character spacing in logical units
+    pub text_char_extra: u16,
+    /// Number of break characters in the line
+    pub text_break_count: u16,
+    /// Total extra space in logical units to distribute
+    /// across break characters
+    pub text_break_extra: u16,
+}
+
+/// Part of the clipping region, as `(left, top, right, bottom)` rectangles
+/// in device units.
+///
+/// GDI keeps the clipping region in device units, so a later change of
+/// the mapping mode leaves it where it was.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Clip {
+    /// Inside of any of the rectangles.
+    Rects(Vec<(f32, f32, f32, f32)>),
+    /// Outside of the rectangle.
+    Exclude((f32, f32, f32, f32)),
+}
+
+impl Default for DeviceContext {
+    fn default() -> Self {
+        Self {
+            object_table: GraphicsObjects::new(0),
+            bk_mode: MixMode::TRANSPARENT,
+            clips: vec![],
+            drawing_position: PointS { x: 0, y: 0 },
+            draw_mode: None,
+            layout: Layout::LAYOUT_LTR,
+            poly_fill_mode: PolyFillMode::ALTERNATE,
+            stretch_mode: StretchMode::BLACKONWHITE,
+            text_align_horizontal: TextAlignmentMode::TA_LEFT,
+            // Per MS-WMF 2.3.5.24, the default text alignment is
+            // TA_TOP | TA_LEFT.
+            text_align_vertical: VerticalTextAlignmentMode::VTA_TOP,
+            text_align_update_cp: false,
+            text_bk_color: ColorRef::white(),
+            text_break_count: 0,
+            text_break_extra: 0,
+            text_char_extra: 0,
+            text_color: ColorRef::black(),
+            window: Window::new(),
+        }
+    }
+}
+
+impl Clip {
+    fn offset(&mut self, dx: f32, dy: f32) {
+        let offset = |(l, t, r, b): &mut (f32, f32, f32, f32)| {
+            *l += dx;
+            *t += dy;
+            *r += dx;
+            *b += dy;
+        };
+
+        match self {
+            Self::Rects(rects) => rects.iter_mut().for_each(offset),
+            Self::Exclude(rect) => offset(rect),
+        }
+    }
+}
+
+// mutations
+impl DeviceContext {
+    pub fn create_object_table(&mut self, length: u16) {
+        self.object_table = GraphicsObjects::new(length as usize);
+    }
+
+    /// Expand the tracked drawing bounds to include the device point.
+    pub fn extend_window(&mut self, (x, y): (f32, f32)) {
+        let clamp = |v: f32| v.clamp(f32::from(i16::MIN), f32::from(i16::MAX));
+        let (left, top) = (clamp(x.floor()) as i16, clamp(y.floor()) as i16);
+        let (right, bottom) = (clamp(x.ceil()) as i16, clamp(y.ceil()) as i16);
+
+        self.window.min_x = self.window.min_x.min(left);
+        self.window.min_y = self.window.min_y.min(top);
+        self.window.x = self.window.x.max(right);
+        self.window.y = self.window.y.max(bottom);
+    }
+
+    /// Intersect the clipping region with the logical rectangle.
+    pub fn intersect_clip(&mut self, rect: &Rect) {
+        let rect = self.rect_to_device(rect);
+        self.clips.push(Clip::Rects(vec![rect]));
+    }
+
+    /// Remove the logical rectangle from the clipping region.
+    pub fn exclude_clip(&mut self, rect: &Rect) {
+        let rect = self.rect_to_device(rect);
+        self.clips.push(Clip::Exclude(rect));
+    }
+
+    /// Move the clipping region by a logical offset.
+    pub fn offset_clip(&mut self, x: i16, y: i16) {
+        let (x0, y0) = self.window.logical_to_device(0.0, 0.0);
+        let (x1, y1) =
+            self.window.logical_to_device(f32::from(x), f32::from(y));
+
+        for clip in &mut self.clips {
+            clip.offset(x1 - x0, y1 - y0);
+        }
+    }
+
+    pub fn map_mode(&mut self, map_mode: MapMode) {
+        self.window.map_mode = map_mode;
+    }
+}
+
+impl DeviceContext {
+    pub fn point_to_device(&self, point: &PointS) -> (f32, f32) {
+        self.window.point_to_device(f32::from(point.x), f32::from(point.y))
+    }
+
+    /// Convert a logical rectangle to `(left, top, right, bottom)` in
+    /// device units.
+    pub fn rect_to_device(&self, rect: &Rect) -> (f32, f32, f32, f32) {
+        let (x1, y1) =
+            self.point_to_device(&PointS { x: rect.left, y: rect.top });
+        let (x2, y2) =
+            self.point_to_device(&PointS { x: rect.right, y: rect.bottom });
+
+        (x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2))
+    }
+
+    /// Convert the scanlines of `region` to rectangles in device units,
+    /// falling back to its bounding rectangle when it has none.
+    pub fn region_to_device(
+        &self,
+        region: &Region,
+    ) -> Vec<(f32, f32, f32, f32)> {
+        // Scan coordinates are u16; clamp to i16 range
+        let clamp = |v: u16| i16::try_from(v).unwrap_or(i16::MAX);
+
+        if region.a_scans.is_empty() {
+            return vec![self.rect_to_device(&region.bounding_rectangle)];
+        }
+
+        region
+            .a_scans
+            .iter()
+            .flat_map(|scan| {
+                scan.scan_lines.iter().map(|scan_line| {
+                    self.rect_to_device(&Rect {
+                        left: clamp(scan_line.left),
+                        top: clamp(scan.top),
+                        right: clamp(scan_line.right),
+                        bottom: clamp(scan.bottom),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    pub fn fill_even_odd(&self) -> bool {
+        self.poly_fill_mode == PolyFillMode::ALTERNATE
+    }
+
+    /// Offset from the reference point of text to its baseline in ems,
+    /// downwards.
+    ///
+    /// The font is not embedded, so the ascent of 0.8em and descent of
+    /// 0.2em of typical Latin fonts stand in for its metrics.
+    pub fn baseline_offset(&self) -> f32 {
+        match self.text_align_vertical {
+            VerticalTextAlignmentMode::VTA_TOP => 0.8,
+            VerticalTextAlignmentMode::VTA_BOTTOM => -0.2,
+            VerticalTextAlignmentMode::VTA_CENTER => 0.3,
+            _ => 0.0,
+        }
+    }
+
+    /// Extra advance, in logical units, that META_SETTEXTCHAREXTRA and
+    /// META_SETTEXTJUSTIFICATION add after each glyph, where `is_break`
+    /// tells the break characters.
+    pub fn text_extra_advances(&self, is_break: &[bool]) -> Vec<i32> {
+        let char_extra = i32::from(self.text_char_extra);
+        // GDI reads the break extra as signed, so a negative value
+        // narrows the breaks.
+        let (break_extra, mut break_remainder) =
+            match i32::from(self.text_break_count) {
+                0 => (0, 0),
+                count => {
+                    let extra = i32::from(self.text_break_extra.cast_signed());
+                    (extra / count, extra % count)
+                }
+            };
+
+        is_break
+            .iter()
+            .map(|is_break| {
+                let mut advance = char_extra;
+
+                if *is_break {
+                    advance += break_extra;
+
+                    // The remainder goes to the first breaks, one unit
+                    // each, with its sign.
+                    advance += break_remainder.signum();
+                    break_remainder -= break_remainder.signum();
+                }
+
+                advance
+            })
+            .collect()
+    }
+}
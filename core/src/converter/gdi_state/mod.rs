@@ -0,0 +1,681 @@
+mod device_context;
+
+pub use self::device_context::Clip;
+use self::device_context::DeviceContext;
+use crate::{
+    converter::{
+        GraphicsObject, GraphicsObjects, PlayError, SelectedGraphicsObject,
+        Window,
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Playback device context of GDI, kept apart from any output format.
+///
+/// It plays the object, state and clipping records: the object table and
+/// the selected objects, the window and viewport mapping, the SaveDC
+/// stack, the text alignment and the other attributes of the device
+/// context. A player forwards those records here and reads the resolved
+/// pens, brushes, fonts, mapping and clipping region back when it draws,
+/// so only the drawing records are left for it to implement.
+#[derive(Clone, Debug, Default)]
+pub struct GdiState {
+    context_stack: Vec<DeviceContext>,
+    context_current: DeviceContext,
+    object_selected: SelectedGraphicsObject,
+}
+
+impl GdiState {
+    /// Create the default device context with an empty object table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Size the object table and, for a placeable metafile, map its
+    /// bounding box as the window.
+    pub fn header(&mut self, header: &MetafileHeader) {
+        let header = match header {
+            MetafileHeader::StartsWithHeader(header) => header,
+            MetafileHeader::StartsWithPlaceable(placeable, header) => {
+                let Rect { left, top, right, bottom } = placeable.bounding_box;
+                let window = &mut self.context_current.window;
+
+                // A hostile bounding box can span more than i16 holds.
+                window.origin(left, top);
+                window.ext(
+                    right.saturating_sub(left),
+                    bottom.saturating_sub(top),
+                );
+                window.units_per_inch = Some(placeable.inch);
+
+                header
+            }
+        };
+
+        self.context_current.create_object_table(header.number_of_objects);
+    }
+}
+
+// .
+// .
+// Object Records
+// .
+// .
+impl GdiState {
+    /// Add the brush of META_CREATEBRUSHINDIRECT to the object table.
+    pub fn create_brush_indirect(&mut self, record: &META_CREATEBRUSHINDIRECT) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Brush(record.create_brush()));
+    }
+
+    /// Add the font of META_CREATEFONTINDIRECT to the object table.
+    pub fn create_font_indirect(&mut self, record: &META_CREATEFONTINDIRECT) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Font(record.font.clone()));
+    }
+
+    /// Add the palette of META_CREATEPALETTE to the object table.
+    pub fn create_palette(&mut self, record: &META_CREATEPALETTE) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Palette(record.palette.clone()));
+    }
+
+    /// Add the pattern brush of META_CREATEPATTERNBRUSH to the object table.
+    pub fn create_pattern_brush(&mut self, record: &META_CREATEPATTERNBRUSH) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Brush(record.create_brush()));
+    }
+
+    /// Add the pen of META_CREATEPENINDIRECT to the object table.
+    pub fn create_pen_indirect(&mut self, record: &META_CREATEPENINDIRECT) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Pen(record.pen.clone()));
+    }
+
+    /// Add the region of META_CREATEREGION to the object table.
+    pub fn create_region(&mut self, record: &META_CREATEREGION) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Region(record.region.clone()));
+    }
+
+    /// Free the object table entry that META_DELETEOBJECT names.
+    pub fn delete_object(&mut self, record: &META_DELETEOBJECT) {
+        self.context_current.object_table.delete(record.object_index as usize);
+    }
+
+    /// Add the DIB pattern brush of META_DIBCREATEPATTERNBRUSH to the object
+    /// table.
+    pub fn create_device_independent_bitmap_pattern_brush(
+        &mut self,
+        record: &META_DIBCREATEPATTERNBRUSH,
+    ) {
+        self.context_current
+            .object_table
+            .push(GraphicsObject::Brush(record.create_brush()));
+    }
+
+    /// Replace the clipping region with the region object, or clear it
+    /// when the object is not a region.
+    pub fn select_clip_region(&mut self, record: &META_SELECTCLIPREGION) {
+        let object =
+            self.context_current.object_table.get(record.region as usize);
+
+        self.context_current.clips =
+            if let GraphicsObject::Region(region) = object {
+                vec![Clip::Rects(self.context_current.region_to_device(region))]
+            } else {
+                vec![]
+            };
+    }
+
+    /// Select the pen, brush, font, palette or region at the object index.
+    pub fn select_object(
+        &mut self,
+        record: &META_SELECTOBJECT,
+    ) -> Result<(), PlayError> {
+        let object =
+            self.context_current.object_table.get(record.object_index as usize);
+
+        match object {
+            GraphicsObject::Brush(v) => {
+                self.object_selected.set_brush(v.clone());
+            }
+            GraphicsObject::Font(v) => {
+                self.object_selected.set_font(v.clone());
+            }
+            GraphicsObject::Palette(v) => {
+                self.object_selected.set_palette(v.clone());
+            }
+            GraphicsObject::Pen(v) => {
+                self.object_selected.set_pen(v.clone());
+            }
+            GraphicsObject::Region(v) => {
+                self.object_selected.set_region(v.clone());
+            }
+            GraphicsObject::Null => {
+                return Err(PlayError::UnexpectedGraphicsObject {
+                    cause: "Graphics Object is null".to_owned(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Select the palette at the object index of META_SELECTPALETTE.
+    pub fn select_palette(
+        &mut self,
+        record: &META_SELECTPALETTE,
+    ) -> Result<(), PlayError> {
+        let object =
+            self.context_current.object_table.get(record.palette as usize);
+
+        let GraphicsObject::Palette(palette) = object else {
+            return Err(PlayError::UnexpectedGraphicsObject {
+                cause: "Graphics Object is not palette object".to_owned(),
+            });
+        };
+
+        self.object_selected.set_palette(palette.clone());
+
+        Ok(())
+    }
+}
+
+// .
+// .
+// State Records
+// .
+// .
+impl GdiState {
+    /// Remove the logical rectangle from the clipping region.
+    pub fn exclude_clip_rect(&mut self, record: &META_EXCLUDECLIPRECT) {
+        let META_EXCLUDECLIPRECT { bottom, right, top, left, .. } = *record;
+
+        self.context_current.exclude_clip(&Rect { left, top, right, bottom });
+    }
+
+    /// Intersect the clipping region with the logical rectangle.
+    pub fn intersect_clip_rect(&mut self, record: &META_INTERSECTCLIPRECT) {
+        let META_INTERSECTCLIPRECT { bottom, right, top, left, .. } = *record;
+
+        self.context_current.intersect_clip(&Rect { left, top, right, bottom });
+    }
+
+    /// Set the current position to the point of META_MOVETO.
+    pub fn move_to(&mut self, record: &META_MOVETO) {
+        self.context_current.drawing_position =
+            PointS { x: record.x, y: record.y };
+    }
+
+    /// Move the clipping region by the logical offset.
+    pub fn offset_clip_region(&mut self, record: &META_OFFSETCLIPRGN) {
+        self.context_current.offset_clip(record.x_offset, record.y_offset);
+    }
+
+    /// Move the viewport origin by the device offset.
+    pub fn offset_viewport_origin(&mut self, record: &META_OFFSETVIEWPORTORG) {
+        self.context_current
+            .window
+            .offset_viewport_origin(record.x_offset, record.y_offset);
+    }
+
+    /// Move the window origin by the logical offset.
+    pub fn offset_window_origin(&mut self, record: &META_OFFSETWINDOWORG) {
+        self.context_current
+            .window
+            .offset_origin(record.x_offset, record.y_offset);
+    }
+
+    /// Restore the saved device context and return its index in the
+    /// SaveDC stack, which is also the number of contexts left saved, or
+    /// `None` when there is no such context.
+    pub fn restore_device_context(
+        &mut self,
+        record: &META_RESTOREDC,
+    ) -> Option<usize> {
+        // MS-WMF spec:
+        // Negative: relative offset from the top of the stack
+        //   (-1 = most recent, -2 = one before that, ...)
+        // Positive: 1-based absolute index
+        // In both cases, all entries saved after the target are discarded
+        let index = match record.n_saved_dc.cmp(&0) {
+            core::cmp::Ordering::Less => {
+                let offset = record.n_saved_dc.unsigned_abs() as usize;
+
+                self.context_stack.len().checked_sub(offset)
+            }
+            core::cmp::Ordering::Greater => {
+                let idx = (record.n_saved_dc as usize) - 1;
+
+                if idx < self.context_stack.len() { Some(idx) } else { None }
+            }
+            core::cmp::Ordering::Equal => None,
+        };
+
+        if let Some(idx) = index {
+            // Object Table is not subject to save/restore
+            let object_table = core::mem::replace(
+                &mut self.context_current.object_table,
+                GraphicsObjects::new(0),
+            );
+            // Drawing bounds are not subject to save/restore
+            let window = self.context_current.window.clone();
+
+            self.context_current = self.context_stack[idx].clone();
+            self.context_current.object_table = object_table;
+            self.context_current.window.min_x =
+                window.min_x.min(self.context_current.window.min_x);
+            self.context_current.window.min_y =
+                window.min_y.min(self.context_current.window.min_y);
+            self.context_current.window.x =
+                window.x.max(self.context_current.window.x);
+            self.context_current.window.y =
+                window.y.max(self.context_current.window.y);
+            self.context_stack.truncate(idx);
+        }
+
+        index
+    }
+
+    /// Push a copy of the device context onto the SaveDC stack.
+    pub fn save_device_context(&mut self, _: &META_SAVEDC) {
+        self.context_stack.push(self.context_current.clone());
+    }
+
+    /// Scale the viewport extent, failing on a zero denominator.
+    pub fn scale_viewport_ext(
+        &mut self,
+        record: &META_SCALEVIEWPORTEXT,
+    ) -> Result<(), PlayError> {
+        if record.x_denom == 0 || record.y_denom == 0 {
+            return Err(PlayError::InvalidRecord {
+                cause: format!(
+                    "META_SCALEVIEWPORTEXT has zero denominator: x_denom={}, \
+                     y_denom={}",
+                    record.x_denom, record.y_denom,
+                ),
+            });
+        }
+
+        self.context_current.window.scale_viewport_ext(
+            f32::from(record.x_num),
+            f32::from(record.x_denom),
+            f32::from(record.y_num),
+            f32::from(record.y_denom),
+        );
+
+        Ok(())
+    }
+
+    /// Scale the window extent, failing on a zero denominator.
+    pub fn scale_window_ext(
+        &mut self,
+        record: &META_SCALEWINDOWEXT,
+    ) -> Result<(), PlayError> {
+        if record.x_denom == 0 || record.y_denom == 0 {
+            return Err(PlayError::InvalidRecord {
+                cause: format!(
+                    "META_SCALEWINDOWEXT has zero denominator: x_denom={}, \
+                     y_denom={}",
+                    record.x_denom, record.y_denom,
+                ),
+            });
+        }
+
+        let window = &mut self.context_current.window;
+        let scale_x = (window.scale_x * f32::from(record.x_num))
+            / f32::from(record.x_denom);
+        let scale_y = (window.scale_y * f32::from(record.y_num))
+            / f32::from(record.y_denom);
+
+        window.scale(scale_x, scale_y);
+
+        Ok(())
+    }
+
+    /// Set the background color of text and hatched brushes.
+    pub fn set_bk_color(&mut self, record: &META_SETBKCOLOR) {
+        self.context_current.text_bk_color = record.color_ref.clone();
+    }
+
+    /// Set whether backgrounds are filled (OPAQUE) or left (TRANSPARENT).
+    pub fn set_bk_mode(&mut self, record: &META_SETBKMODE) {
+        self.context_current.bk_mode = record.bk_mode;
+    }
+
+    /// Set the layout of the device context, e.g. right-to-left.
+    pub fn set_layout(&mut self, record: &META_SETLAYOUT) {
+        self.context_current.layout = record.layout;
+    }
+
+    /// Set the mapping mode of logical units to device units.
+    pub fn set_map_mode(&mut self, record: &META_SETMAPMODE) {
+        self.context_current.map_mode(record.map_mode);
+    }
+
+    /// Set the ALTERNATE or WINDING fill mode of polygons.
+    pub fn set_polyfill_mode(&mut self, record: &META_SETPOLYFILLMODE) {
+        self.context_current.poly_fill_mode = record.poly_fill_mode;
+    }
+
+    /// Set the ROP2 mix of pens and brushes with the background.
+    pub fn set_raster_operation(&mut self, record: &META_SETROP2) {
+        self.context_current.draw_mode = Some(record.draw_mode);
+    }
+
+    /// Set how stretched bitmaps drop or blend pixels.
+    pub fn set_stretch_blt_mode(&mut self, record: &META_SETSTRETCHBLTMODE) {
+        self.context_current.stretch_mode = record.stretch_mode;
+    }
+
+    /// Set the horizontal and vertical text alignment and TA_UPDATECP.
+    pub fn set_text_align(&mut self, record: &META_SETTEXTALIGN) {
+        let update_cp = record.text_alignment_mode
+            & (TextAlignmentMode::TA_UPDATECP as u16)
+            == TextAlignmentMode::TA_UPDATECP as u16;
+        let align_horizontal =
+            [TextAlignmentMode::TA_CENTER, TextAlignmentMode::TA_RIGHT]
+                .into_iter()
+                .find(|a| record.text_alignment_mode & (*a as u16) == *a as u16)
+                .unwrap_or(TextAlignmentMode::TA_LEFT);
+        // Per MS-WMF section 2.3.5.24, the value is interpreted as
+        // TextAlignmentMode for fonts with a horizontal default
+        // baseline (the common case), and VerticalTextAlignmentMode
+        // only when the font has a vertical default baseline (e.g.,
+        // CJK vertical layout). Map the horizontal-baseline values
+        // (TA_TOP / TA_BOTTOM / TA_BASELINE) to the internal vertical
+        // alignment representation.
+        //
+        // Test TA_BASELINE first because it shares the TA_BOTTOM bit
+        // (0x18 vs 0x08); a plain bit-AND against TA_BOTTOM would
+        // incorrectly match TA_BASELINE as well.
+        let ta_baseline = TextAlignmentMode::TA_BASELINE as u16;
+        let ta_bottom = TextAlignmentMode::TA_BOTTOM as u16;
+        let align_vertical =
+            if (record.text_alignment_mode & ta_baseline) == ta_baseline {
+                VerticalTextAlignmentMode::VTA_BASELINE
+            } else if (record.text_alignment_mode & ta_bottom) == ta_bottom {
+                VerticalTextAlignmentMode::VTA_BOTTOM
+            } else {
+                VerticalTextAlignmentMode::VTA_TOP
+            };
+
+        self.context_current.text_align_update_cp = update_cp;
+        self.context_current.text_align_horizontal = align_horizontal;
+        self.context_current.text_align_vertical = align_vertical;
+    }
+
+    /// Set the extra space added after each character.
+    pub fn set_text_char_extra(&mut self, record: &META_SETTEXTCHAREXTRA) {
+        self.context_current.text_char_extra = record.char_extra;
+    }
+
+    /// Set the foreground color of text.
+    pub fn set_text_color(&mut self, record: &META_SETTEXTCOLOR) {
+        self.context_current.text_color = record.color_ref.clone();
+    }
+
+    /// Set the extra space spread over the break characters of text.
+    pub fn set_text_justification(
+        &mut self,
+        record: &META_SETTEXTJUSTIFICATION,
+    ) {
+        self.context_current.text_break_count = record.break_count;
+        self.context_current.text_break_extra = record.break_extra;
+    }
+
+    /// Set the viewport extent in device units.
+    pub fn set_viewport_ext(&mut self, record: &META_SETVIEWPORTEXT) {
+        self.context_current.window.viewport_ext(record.x, record.y);
+    }
+
+    /// Set the viewport origin in device units.
+    pub fn set_viewport_origin(&mut self, record: &META_SETVIEWPORTORG) {
+        self.context_current.window.viewport_origin(record.x, record.y);
+    }
+
+    /// Set the window extent in logical units.
+    pub fn set_window_ext(&mut self, record: &META_SETWINDOWEXT) {
+        let window = &mut self.context_current.window;
+
+        // Inside a SAVEDC scope, a META_SETWINDOWEXT call usually
+        // defines a sub-region (e.g. a chart panel) that should
+        // remap the saved (outer) device area to a smaller logical
+        // window. Capture the prior window extent as an implicit
+        // viewport extent so the new logical coordinates rescale to
+        // fit the outer area; otherwise the sub-region would render
+        // at unscaled logical size and collapse to a fraction of
+        // its intended bounds. RESTOREDC reverts the captured
+        // viewport extent along with the rest of the saved state.
+        // Only triggered inside a SAVEDC scope to avoid regressing
+        // metafiles whose only META_SETWINDOWEXT establishes the
+        // overall logical coordinate system in MM_TEXT mode.
+        if !self.context_stack.is_empty()
+            && window.viewport_ext_x.is_none()
+            && window.viewport_ext_y.is_none()
+        {
+            window.viewport_ext_x = Some(window.current_ext_x);
+            window.viewport_ext_y = Some(window.current_ext_y);
+        }
+
+        window.ext(record.x, record.y);
+    }
+
+    /// Set the window origin in logical units.
+    pub fn set_window_origin(&mut self, record: &META_SETWINDOWORG) {
+        self.context_current.window.origin(record.x, record.y);
+    }
+}
+
+// .
+// .
+// Resolved State
+// .
+// .
+impl GdiState {
+    /// Selected brush.
+    pub fn brush(&self) -> &Brush {
+        &self.object_selected.brush
+    }
+
+    /// Selected font.
+    pub fn font(&self) -> &Font {
+        &self.object_selected.font
+    }
+
+    /// Selected palette, if any.
+    pub fn palette(&self) -> Option<&Palette> {
+        self.object_selected.palette.as_ref()
+    }
+
+    /// Selected pen.
+    pub fn pen(&self) -> &Pen {
+        &self.object_selected.pen
+    }
+
+    /// Width of the selected pen in device units.
+    pub fn pen_width(&self) -> f32 {
+        let window = self.window();
+
+        f32::from(self.pen().width.x).abs()
+            * window.device_units_per_logical_x()
+    }
+
+    /// Distance to move the edges of a closed figure inwards so that the
+    /// stroke of a PS_INSIDEFRAME pen stays inside its bounding rectangle.
+    /// Pens no wider than a device pixel are not moved inside.
+    pub fn inside_frame_inset(&self) -> f32 {
+        let width = self.pen_width();
+
+        if self.pen().style.style != PenStyle::PS_INSIDEFRAME
+            || width <= self.window().device_pixel_size()
+        {
+            return 0.0;
+        }
+
+        width / 2.0
+    }
+
+    /// Selected region, if any.
+    pub fn region(&self) -> Option<&Region> {
+        self.object_selected.region.as_ref()
+    }
+
+    /// Look up a Brush from the object table by index.
+    pub fn brush_at(&self, brush_index: u16) -> Result<Brush, PlayError> {
+        let object =
+            self.context_current.object_table.get(brush_index as usize).clone();
+
+        let GraphicsObject::Brush(brush) = object else {
+            return Err(PlayError::InvalidBrush {
+                cause: format!("object at index {brush_index} is not a Brush"),
+            });
+        };
+
+        Ok(brush)
+    }
+
+    /// Look up a Region from the object table by index.
+    pub fn region_at(&self, region_index: u16) -> Result<Region, PlayError> {
+        let object = self
+            .context_current
+            .object_table
+            .get(region_index as usize)
+            .clone();
+
+        match object {
+            GraphicsObject::Region(r) => Ok(r),
+            _ => Err(PlayError::InvalidRecord {
+                cause: format!(
+                    "object at index {region_index} is not a Region"
+                ),
+            }),
+        }
+    }
+
+    /// Background color of text and hatched brushes.
+    pub fn bk_color(&self) -> &ColorRef {
+        &self.context_current.text_bk_color
+    }
+
+    /// Whether backgrounds are filled or left transparent.
+    pub fn bk_mode(&self) -> MixMode {
+        self.context_current.bk_mode
+    }
+
+    /// ROP2 mix set by META_SETROP2, if any.
+    pub fn draw_mode(&self) -> Option<BinaryRasterOperation> {
+        self.context_current.draw_mode
+    }
+
+    /// Layout of the device context.
+    pub fn layout(&self) -> Layout {
+        self.context_current.layout
+    }
+
+    /// Fill mode of polygons.
+    pub fn poly_fill_mode(&self) -> PolyFillMode {
+        self.context_current.poly_fill_mode
+    }
+
+    /// Whether polygons fill with the even-odd rule.
+    pub fn fill_even_odd(&self) -> bool {
+        self.context_current.fill_even_odd()
+    }
+
+    /// How stretched bitmaps drop or blend pixels.
+    pub fn stretch_mode(&self) -> StretchMode {
+        self.context_current.stretch_mode
+    }
+
+    /// Foreground color of text.
+    pub fn text_color(&self) -> &ColorRef {
+        &self.context_current.text_color
+    }
+
+    /// Horizontal alignment of text to its reference point.
+    pub fn text_align_horizontal(&self) -> TextAlignmentMode {
+        self.context_current.text_align_horizontal
+    }
+
+    /// Vertical alignment of text to its reference point.
+    pub fn text_align_vertical(&self) -> VerticalTextAlignmentMode {
+        self.context_current.text_align_vertical
+    }
+
+    /// Whether text is drawn at, and advances, the current position.
+    pub fn text_align_update_cp(&self) -> bool {
+        self.context_current.text_align_update_cp
+    }
+
+    /// Offset from the reference point of text to its baseline in ems,
+    /// downwards.
+    ///
+    /// The font is not embedded, so the ascent of 0.8em and descent of
+    /// 0.2em of typical Latin fonts stand in for its metrics.
+    pub fn baseline_offset(&self) -> f32 {
+        self.context_current.baseline_offset()
+    }
+
+    /// Extra advance, in logical units, that META_SETTEXTCHAREXTRA and
+    /// META_SETTEXTJUSTIFICATION add after each glyph, where `is_break`
+    /// tells the break characters.
+    pub fn text_extra_advances(&self, is_break: &[bool]) -> Vec<i32> {
+        self.context_current.text_extra_advances(is_break)
+    }
+
+    /// Current position in logical units, as META_MOVETO left it.
+    pub fn drawing_position(&self) -> &PointS {
+        &self.context_current.drawing_position
+    }
+
+    /// Move the current position, as drawing records ending at a point do.
+    pub fn set_drawing_position(&mut self, drawing_position: PointS) {
+        self.context_current.drawing_position = drawing_position;
+    }
+
+    /// Window and viewport mapping logical units to device units, and the
+    /// bounds of the drawing in device units.
+    pub fn window(&self) -> &Window {
+        &self.context_current.window
+    }
+
+    /// Convert a logical point to device units.
+    pub fn point_to_device(&self, point: &PointS) -> (f32, f32) {
+        self.context_current.point_to_device(point)
+    }
+
+    /// Convert a logical rectangle to `(left, top, right, bottom)` in
+    /// device units.
+    pub fn rect_to_device(&self, rect: &Rect) -> (f32, f32, f32, f32) {
+        self.context_current.rect_to_device(rect)
+    }
+
+    /// Convert the scanlines of `region` to rectangles in device units,
+    /// falling back to its bounding rectangle when it has none.
+    pub fn region_to_device(
+        &self,
+        region: &Region,
+    ) -> Vec<(f32, f32, f32, f32)> {
+        self.context_current.region_to_device(region)
+    }
+
+    /// Areas intersected to form the clipping region, in device units. The
+    /// drawing is not clipped when there are none.
+    pub fn clips(&self) -> &[Clip] {
+        &self.context_current.clips
+    }
+
+    /// Expand the drawing bounds to include the device point.
+    pub fn extend_bounds(&mut self, point: (f32, f32)) {
+        self.context_current.extend_window(point);
+    }
+}
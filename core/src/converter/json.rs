@@ -0,0 +1,1096 @@
+use crate::{converter::PlayError, imports::*, parser::*};
+
+/// Player writing the header and every record as JSON, for inspecting and
+/// diffing metafiles.
+///
+/// Records keep the fields of their parser structures, named as in the
+/// [`crate::parser`] module, and each is written on a line of its own:
+///
+/// ```json
+/// {"header":{"StartsWithHeader":{...}},"records":[
+/// {"record_number":1,"record_type":"META_SETWINDOWORG","record":{...}},
+/// ...
+/// ]}
+/// ```
+///
+/// Bitmaps and other opaque data are summarised by their length in bytes,
+/// as `{"length":1024}`, unless [`JsonPlayer::bitmap_data`] is set.
+#[derive(Debug, Default)]
+pub struct JsonPlayer {
+    header: Option<String>,
+    records: Vec<String>,
+    bitmap_data: bool,
+}
+
+impl JsonPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write bitmaps and other opaque data in full, as
+    /// `{"length":1024,"base64":"..."}`.
+    #[must_use]
+    pub fn bitmap_data(mut self) -> Self {
+        self.bitmap_data = true;
+        self
+    }
+
+    fn serialize(
+        &self,
+        value: &impl serde::Serialize,
+    ) -> Result<String, PlayError> {
+        let json = serde_json::to_string(value).map_err(|err| {
+            PlayError::InvalidRecord { cause: err.to_string() }
+        })?;
+
+        if self.bitmap_data { Ok(json) } else { Ok(strip_base64(&json)) }
+    }
+
+    fn push(
+        mut self,
+        record_number: usize,
+        record_type: RecordType,
+        record: &impl serde::Serialize,
+    ) -> Result<Self, PlayError> {
+        #[derive(serde::Serialize)]
+        struct Entry<'a, R> {
+            record_number: usize,
+            record_type: RecordType,
+            record: &'a R,
+        }
+
+        let entry =
+            self.serialize(&Entry { record_number, record_type, record })?;
+
+        self.records.push(entry);
+
+        Ok(self)
+    }
+}
+
+/// Remove the `base64` member that opaque data is serialized with.
+///
+/// Quotes in JSON strings are escaped, so `,"base64":"` only appears as
+/// that member, and the base64 alphabet has no quote to end it early.
+fn strip_base64(json: &str) -> String {
+    const KEY: &str = ",\"base64\":\"";
+
+    let mut stripped = String::with_capacity(json.len());
+    let mut rest = json;
+
+    while let Some(start) = rest.find(KEY) {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start + KEY.len()..];
+
+        let end = rest.find('"').map_or(rest.len(), |end| end + 1);
+
+        rest = &rest[end..];
+    }
+
+    stripped.push_str(rest);
+    stripped
+}
+
+impl crate::converter::Player for JsonPlayer {
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn generate(self) -> Result<Vec<u8>, PlayError> {
+        let Self { header, records, .. } = self;
+        let mut output = String::new();
+
+        output.push_str("{\"header\":");
+        output.push_str(header.as_deref().unwrap_or("null"));
+        output.push_str(",\"records\":[");
+
+        for (i, record) in records.iter().enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+
+            output.push('\n');
+            output.push_str(record);
+        }
+
+        output.push_str("\n]}\n");
+
+        Ok(output.into_bytes())
+    }
+
+    // .
+    // .
+    // Functions to handle Bitmap Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn bit_blt(
+        self,
+        record_number: usize,
+        record: META_BITBLT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_BITBLT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_bit_blt(
+        self,
+        record_number: usize,
+        record: META_DIBBITBLT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_DIBBITBLT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_stretch_blt(
+        self,
+        record_number: usize,
+        record: META_DIBSTRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_DIBSTRETCHBLT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_device_independent_bitmap_to_dev(
+        self,
+        record_number: usize,
+        record: META_SETDIBTODEV,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETDIBTODEV, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_blt(
+        self,
+        record_number: usize,
+        record: META_STRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_STRETCHBLT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_device_independent_bitmap(
+        self,
+        record_number: usize,
+        record: META_STRETCHDIB,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_STRETCHDIB, &record)
+    }
+
+    // .
+    // .
+    // Functions to handle Control Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn eof(
+        self,
+        record_number: usize,
+        record: META_EOF,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_EOF, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn header(
+        mut self,
+        record_number: usize,
+        header: MetafileHeader,
+    ) -> Result<Self, PlayError> {
+        self.header = Some(self.serialize(&header)?);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Drawing Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn arc(
+        self,
+        record_number: usize,
+        record: META_ARC,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_ARC, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn chord(
+        self,
+        record_number: usize,
+        record: META_CHORD,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CHORD, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ellipse(
+        self,
+        record_number: usize,
+        record: META_ELLIPSE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_ELLIPSE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_flood_fill(
+        self,
+        record_number: usize,
+        record: META_EXTFLOODFILL,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_EXTFLOODFILL, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_text_out(
+        self,
+        record_number: usize,
+        record: META_EXTTEXTOUT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_EXTTEXTOUT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn fill_region(
+        self,
+        record_number: usize,
+        record: META_FILLREGION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_FILLREGION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn flood_fill(
+        self,
+        record_number: usize,
+        record: META_FLOODFILL,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_FLOODFILL, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn frame_region(
+        self,
+        record_number: usize,
+        record: META_FRAMEREGION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_FRAMEREGION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn invert_region(
+        self,
+        record_number: usize,
+        record: META_INVERTREGION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_INVERTREGION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn line_to(
+        self,
+        record_number: usize,
+        record: META_LINETO,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_LINETO, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn paint_region(
+        self,
+        record_number: usize,
+        record: META_PAINTREGION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_PAINTREGION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pat_blt(
+        self,
+        record_number: usize,
+        record: META_PATBLT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_PATBLT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pie(
+        self,
+        record_number: usize,
+        record: META_PIE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_PIE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polyline(
+        self,
+        record_number: usize,
+        record: META_POLYLINE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_POLYLINE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polygon(
+        self,
+        record_number: usize,
+        record: META_POLYGON,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_POLYGON, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn poly_polygon(
+        self,
+        record_number: usize,
+        record: META_POLYPOLYGON,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_POLYPOLYGON, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn rectangle(
+        self,
+        record_number: usize,
+        record: META_RECTANGLE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_RECTANGLE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn round_rect(
+        self,
+        record_number: usize,
+        record: META_ROUNDRECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_ROUNDRECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pixel(
+        self,
+        record_number: usize,
+        record: META_SETPIXEL,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETPIXEL, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn text_out(
+        self,
+        record_number: usize,
+        record: META_TEXTOUT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_TEXTOUT, &record)
+    }
+
+    // .
+    // .
+    // Functions to handle Object Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_brush_indirect(
+        self,
+        record_number: usize,
+        record: META_CREATEBRUSHINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CREATEBRUSHINDIRECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_font_indirect(
+        self,
+        record_number: usize,
+        record: META_CREATEFONTINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CREATEFONTINDIRECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_palette(
+        self,
+        record_number: usize,
+        record: META_CREATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CREATEPALETTE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pattern_brush(
+        self,
+        record_number: usize,
+        record: META_CREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CREATEPATTERNBRUSH, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pen_indirect(
+        self,
+        record_number: usize,
+        record: META_CREATEPENINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CREATEPENINDIRECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_region(
+        self,
+        record_number: usize,
+        record: META_CREATEREGION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_CREATEREGION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn delete_object(
+        self,
+        record_number: usize,
+        record: META_DELETEOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_DELETEOBJECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_device_independent_bitmap_pattern_brush(
+        self,
+        record_number: usize,
+        record: META_DIBCREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.push(
+            record_number,
+            RecordType::META_DIBCREATEPATTERNBRUSH,
+            &record,
+        )
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_clip_region(
+        self,
+        record_number: usize,
+        record: META_SELECTCLIPREGION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SELECTCLIPREGION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_object(
+        self,
+        record_number: usize,
+        record: META_SELECTOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SELECTOBJECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_palette(
+        self,
+        record_number: usize,
+        record: META_SELECTPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SELECTPALETTE, &record)
+    }
+
+    // .
+    // .
+    // Functions to handle State Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn animate_palette(
+        self,
+        record_number: usize,
+        record: META_ANIMATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_ANIMATEPALETTE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn exclude_clip_rect(
+        self,
+        record_number: usize,
+        record: META_EXCLUDECLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_EXCLUDECLIPRECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn intersect_clip_rect(
+        self,
+        record_number: usize,
+        record: META_INTERSECTCLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_INTERSECTCLIPRECT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn move_to(
+        self,
+        record_number: usize,
+        record: META_MOVETO,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_MOVETO, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_clip_region(
+        self,
+        record_number: usize,
+        record: META_OFFSETCLIPRGN,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_OFFSETCLIPRGN, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_viewport_origin(
+        self,
+        record_number: usize,
+        record: META_OFFSETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_OFFSETVIEWPORTORG, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_window_origin(
+        self,
+        record_number: usize,
+        record: META_OFFSETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_OFFSETWINDOWORG, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn realize_palette(
+        self,
+        record_number: usize,
+        record: META_REALIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_REALIZEPALETTE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn resize_palette(
+        self,
+        record_number: usize,
+        record: META_RESIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_RESIZEPALETTE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn restore_device_context(
+        self,
+        record_number: usize,
+        record: META_RESTOREDC,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_RESTOREDC, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn save_device_context(
+        self,
+        record_number: usize,
+        record: META_SAVEDC,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SAVEDC, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_viewport_ext(
+        self,
+        record_number: usize,
+        record: META_SCALEVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SCALEVIEWPORTEXT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_window_ext(
+        self,
+        record_number: usize,
+        record: META_SCALEWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SCALEWINDOWEXT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_color(
+        self,
+        record_number: usize,
+        record: META_SETBKCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETBKCOLOR, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_mode(
+        self,
+        record_number: usize,
+        record: META_SETBKMODE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETBKMODE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_layout(
+        self,
+        record_number: usize,
+        record: META_SETLAYOUT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETLAYOUT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_map_mode(
+        self,
+        record_number: usize,
+        record: META_SETMAPMODE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETMAPMODE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_mapper_flags(
+        self,
+        record_number: usize,
+        record: META_SETMAPPERFLAGS,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETMAPPERFLAGS, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pal_entries(
+        self,
+        record_number: usize,
+        record: META_SETPALENTRIES,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETPALENTRIES, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_polyfill_mode(
+        self,
+        record_number: usize,
+        record: META_SETPOLYFILLMODE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETPOLYFILLMODE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_relabs(
+        self,
+        record_number: usize,
+        record: META_SETRELABS,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETRELABS, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_raster_operation(
+        self,
+        record_number: usize,
+        record: META_SETROP2,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETROP2, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_stretch_blt_mode(
+        self,
+        record_number: usize,
+        record: META_SETSTRETCHBLTMODE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETSTRETCHBLTMODE, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_align(
+        self,
+        record_number: usize,
+        record: META_SETTEXTALIGN,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETTEXTALIGN, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_char_extra(
+        self,
+        record_number: usize,
+        record: META_SETTEXTCHAREXTRA,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETTEXTCHAREXTRA, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_color(
+        self,
+        record_number: usize,
+        record: META_SETTEXTCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETTEXTCOLOR, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_justification(
+        self,
+        record_number: usize,
+        record: META_SETTEXTJUSTIFICATION,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETTEXTJUSTIFICATION, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_ext(
+        self,
+        record_number: usize,
+        record: META_SETVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETVIEWPORTEXT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_origin(
+        self,
+        record_number: usize,
+        record: META_SETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETVIEWPORTORG, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_ext(
+        self,
+        record_number: usize,
+        record: META_SETWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETWINDOWEXT, &record)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_origin(
+        self,
+        record_number: usize,
+        record: META_SETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_SETWINDOWORG, &record)
+    }
+
+    // .
+    // .
+    // Functions to handle Escape Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn escape(
+        self,
+        record_number: usize,
+        record: META_ESCAPE,
+    ) -> Result<Self, PlayError> {
+        self.push(record_number, RecordType::META_ESCAPE, &record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_base64_keeps_strings_quoting_the_key() {
+        let json =
+            r#"{"a":{"length":3,"base64":"AAAA"},"b":",\"base64\":\"x"}"#;
+
+        assert_eq!(
+            strip_base64(json),
+            r#"{"a":{"length":3},"b":",\"base64\":\"x"}"#
+        );
+    }
+}
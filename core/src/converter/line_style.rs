@@ -0,0 +1,100 @@
+use crate::{converter::GdiState, imports::*, parser::*};
+
+/// End cap of the lines of a pen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    Flat,
+    Round,
+    Square,
+}
+
+/// Join of the lines of a pen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How a pen draws its lines: their width, end caps, joins and dash
+/// pattern.
+///
+/// A pen whose width maps to at most one pixel is drawn like a cosmetic
+/// pen: one pixel wide, with GDI's fixed dash patterns and no end caps or
+/// joins. Wider pens are geometric: the end cap and join bits of the style
+/// apply and dash lengths scale with the width.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineStyle {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Lengths of the dashes and the gaps after them in turn, empty for a
+    /// solid line.
+    pub dashes: Vec<f32>,
+}
+
+impl LineStyle {
+    /// Lines of the selected pen in device units, or `None` for PS_NULL.
+    pub fn selected(state: &GdiState) -> Option<Self> {
+        let pen = state.pen();
+
+        if pen.style.style == PenStyle::PS_NULL {
+            return None;
+        }
+
+        let pixel_size = state.window().device_pixel_size();
+        let width = state.pen_width();
+
+        Some(if width <= pixel_size {
+            Self::cosmetic(pen, pixel_size)
+        } else {
+            Self::geometric(pen, width)
+        })
+    }
+
+    /// Lines of `pen` drawn as a cosmetic pen, where a pixel is
+    /// `pixel_size` units.
+    pub fn cosmetic(pen: &Pen, pixel_size: f32) -> Self {
+        let dashes: &[f32] = match pen.style.style {
+            PenStyle::PS_DASH => &[18.0, 6.0],
+            PenStyle::PS_DOT => &[3.0, 3.0],
+            PenStyle::PS_DASHDOT => &[9.0, 6.0, 3.0, 6.0],
+            PenStyle::PS_DASHDOTDOT => &[9.0, 3.0, 3.0, 3.0, 3.0, 3.0],
+            PenStyle::PS_ALTERNATE => &[1.0, 1.0],
+            _ => &[],
+        };
+
+        Self {
+            width: pixel_size,
+            cap: LineCap::Flat,
+            join: LineJoin::Miter,
+            dashes: dashes.iter().map(|dash| dash * pixel_size).collect(),
+        }
+    }
+
+    /// Lines of `pen` drawn as a geometric pen `width` units wide.
+    pub fn geometric(pen: &Pen, width: f32) -> Self {
+        // PS_ENDCAP_ROUND and PS_JOIN_ROUND are zero, so the parser reports
+        // them as PS_SOLID.
+        let cap = match pen.style.end_cap {
+            PenStyle::PS_ENDCAP_FLAT => LineCap::Flat,
+            PenStyle::PS_ENDCAP_SQUARE => LineCap::Square,
+            _ => LineCap::Round,
+        };
+        let join = match pen.style.line_join {
+            PenStyle::PS_JOIN_MITER => LineJoin::Miter,
+            PenStyle::PS_JOIN_BEVEL => LineJoin::Bevel,
+            _ => LineJoin::Round,
+        };
+        let (dash, dot, gap) = (width * 4.0, width, width * 2.0);
+        let dashes = match pen.style.style {
+            PenStyle::PS_DASH => vec![dash, gap],
+            PenStyle::PS_DOT => vec![dot, gap],
+            PenStyle::PS_DASHDOT => vec![dash, gap, dot, gap],
+            PenStyle::PS_DASHDOTDOT => vec![dash, gap, dot, gap, dot, gap],
+            _ => vec![],
+        };
+
+        Self { width, cap, join, dashes }
+    }
+}
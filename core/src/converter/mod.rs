@@ -1,16 +1,83 @@
+#[cfg(any(feature = "drawing", feature = "svg"))]
+mod arc;
 mod bitmap;
+mod diagnostic;
+mod gdi_state;
 mod graphics_object;
+#[cfg(any(feature = "drawing", feature = "svg"))]
+mod line_style;
 mod player;
+mod window;
 
+#[cfg(any(feature = "drawing", feature = "svg"))]
+pub use self::arc::EllipticalArc;
 use self::graphics_object::*;
-pub use self::{bitmap::Bitmap, player::*};
+#[cfg(any(feature = "drawing", feature = "svg"))]
+pub use self::line_style::{LineCap, LineJoin, LineStyle};
+pub use self::{
+    bitmap::Bitmap,
+    diagnostic::*,
+    gdi_state::{Clip, GdiState},
+    player::*,
+    window::Window,
+};
 use crate::{imports::*, parser::*};
 
+#[cfg(feature = "canvas")]
+mod canvas;
+#[cfg(feature = "drawing")]
+pub mod drawing;
+#[cfg(feature = "dxf")]
+mod dxf;
+#[cfg(feature = "emf")]
+mod emf;
+#[cfg(feature = "eps")]
+mod eps;
+#[cfg(feature = "extent")]
+mod extent;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "drawing")]
+mod page;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "svg")]
+mod raster;
+#[cfg(feature = "statistics")]
+mod statistics;
 #[cfg(feature = "svg")]
 mod svg;
-
+#[cfg(feature = "text")]
+mod text;
+#[cfg(feature = "thumbnail")]
+mod thumbnail;
+#[cfg(feature = "tikz")]
+mod tikz;
+
+#[cfg(feature = "canvas")]
+pub use self::canvas::*;
+#[cfg(feature = "dxf")]
+pub use self::dxf::*;
+#[cfg(feature = "emf")]
+pub use self::emf::*;
+#[cfg(feature = "eps")]
+pub use self::eps::*;
+#[cfg(feature = "extent")]
+pub use self::extent::*;
+#[cfg(feature = "json")]
+pub use self::json::*;
+#[cfg(feature = "pdf")]
+pub use self::pdf::*;
+#[cfg(feature = "statistics")]
+pub use self::statistics::*;
 #[cfg(feature = "svg")]
 pub use self::svg::*;
+#[cfg(feature = "text")]
+pub use self::text::*;
+#[cfg(feature = "thumbnail")]
+pub use self::thumbnail::*;
+#[cfg(feature = "tikz")]
+pub use self::tikz::*;
 
 #[derive(Clone, Debug, snafu::prelude::Snafu)]
 pub enum ConvertError {
@@ -54,6 +121,17 @@ where
         err(level = tracing::Level::ERROR, Display),
     ))]
     pub fn run(self) -> Result<Vec<u8>, ConvertError> {
+        Ok(self.play()?.generate()?)
+    }
+
+    /// Play every record and return the player without generating its
+    /// output, for players that also expose what they collected.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip_all,
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    pub fn play(self) -> Result<P, ConvertError> {
         let Self { mut buffer, mut player } = self;
         let buf = &mut buffer;
 
@@ -813,6 +891,6 @@ where
             }
         }
 
-        Ok(player.generate()?)
+        Ok(player)
     }
 }
@@ -0,0 +1,181 @@
+#[cfg(feature = "tikz")]
+use crate::imports::*;
+use crate::parser::*;
+
+/// One of the standard 14 fonts, which every PDF reader and PostScript
+/// interpreter provides, chosen to resemble the logical font selected by
+/// the metafile.
+///
+/// Text is written in WinAnsiEncoding, except for the Symbol font which
+/// takes the byte codes of SYMBOL_CHARSET text as they are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StandardFont {
+    family: Family,
+    bold: bool,
+    italic: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Family {
+    Courier,
+    Helvetica,
+    Symbol,
+    Times,
+}
+
+impl StandardFont {
+    pub fn new(font: &Font) -> Self {
+        let facename = font.facename.trim_start_matches('@').to_lowercase();
+        let family = if font.charset == CharacterSet::SYMBOL_CHARSET
+            && facename.contains("symbol")
+        {
+            Family::Symbol
+        } else if ["courier", "mono", "consol", "lucida console"]
+            .iter()
+            .any(|name| facename.contains(name))
+        {
+            Family::Courier
+        } else if ["times", "roman", "serif", "georgia", "garamond", "century"]
+            .iter()
+            .any(|name| facename.contains(name) && !facename.contains("sans"))
+        {
+            Family::Times
+        } else if ["arial", "helvetica", "sans", "verdana", "tahoma"]
+            .iter()
+            .any(|name| facename.contains(name))
+        {
+            Family::Helvetica
+        } else {
+            match (font.pitch_and_family.family, font.pitch_and_family.pitch) {
+                (_, PitchFont::FIXED_PITCH) | (FamilyFont::FF_MODERN, _) => {
+                    Family::Courier
+                }
+                (FamilyFont::FF_ROMAN, _) => Family::Times,
+                _ => Family::Helvetica,
+            }
+        };
+
+        Self { family, bold: font.weight >= 600, italic: font.italic }
+    }
+
+    /// PostScript name of the font.
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    pub fn base_font(self) -> &'static str {
+        match (self.family, self.bold, self.italic) {
+            (Family::Courier, false, false) => "Courier",
+            (Family::Courier, true, false) => "Courier-Bold",
+            (Family::Courier, false, true) => "Courier-Oblique",
+            (Family::Courier, true, true) => "Courier-BoldOblique",
+            (Family::Helvetica, false, false) => "Helvetica",
+            (Family::Helvetica, true, false) => "Helvetica-Bold",
+            (Family::Helvetica, false, true) => "Helvetica-Oblique",
+            (Family::Helvetica, true, true) => "Helvetica-BoldOblique",
+            (Family::Times, false, false) => "Times-Roman",
+            (Family::Times, true, false) => "Times-Bold",
+            (Family::Times, false, true) => "Times-Italic",
+            (Family::Times, true, true) => "Times-BoldItalic",
+            (Family::Symbol, ..) => "Symbol",
+        }
+    }
+
+    /// Whether the font uses its built-in encoding instead of
+    /// WinAnsiEncoding.
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    pub fn is_symbolic(self) -> bool {
+        self.family == Family::Symbol
+    }
+
+    /// LaTeX declarations selecting the family, series and shape of the
+    /// document fonts that resemble the font. The Symbol font has no
+    /// counterpart, as its text is set in Unicode characters.
+    #[cfg(feature = "tikz")]
+    pub fn latex_declarations(self) -> String {
+        let family = match self.family {
+            Family::Courier => "\\ttfamily",
+            Family::Helvetica => "\\sffamily",
+            Family::Symbol | Family::Times => "\\rmfamily",
+        };
+        let series = if self.bold { "\\bfseries" } else { "" };
+        let shape = if self.italic { "\\itshape" } else { "" };
+
+        format!("{family}{series}{shape}")
+    }
+
+    /// Byte code of `grapheme` in WinAnsiEncoding, or `None` when the
+    /// encoding has no such character.
+    pub fn encode(grapheme: &str) -> Option<u8> {
+        let mut chars = grapheme.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+
+        if matches!(c, ' '..='~' | '\u{A0}'..='\u{FF}') {
+            return u8::try_from(u32::from(c)).ok();
+        }
+
+        let (bytes, _, had_errors) = encoding_rs::WINDOWS_1252.encode(grapheme);
+
+        match (had_errors, bytes.as_ref()) {
+            (false, [byte]) if *byte >= 0x80 => Some(*byte),
+            _ => None,
+        }
+    }
+
+    /// Advance of `text` in ems, from the widths of the characters in
+    /// WinAnsiEncoding. Other characters advance by half an em per column
+    /// they take up in a terminal.
+    pub fn advance(self, text: &str) -> f32 {
+        use unicode_segmentation::UnicodeSegmentation;
+        use unicode_width::UnicodeWidthStr;
+
+        text.graphemes(true)
+            .map(|grapheme| {
+                Self::encode(grapheme).map_or_else(
+                    || {
+                        f32::from(u16::try_from(grapheme.width()).unwrap_or(1))
+                            / 2.0
+                    },
+                    |code| f32::from(self.width(code)) / 1000.0,
+                )
+            })
+            .sum()
+    }
+
+    /// Advance width of the byte code `code` in thousandths of the em.
+    ///
+    /// Codes outside printable ASCII get the average width of the font.
+    pub fn width(self, code: u8) -> u16 {
+        let index = usize::from(code.wrapping_sub(0x20));
+
+        match self.family {
+            Family::Courier => 600,
+            Family::Helvetica => {
+                HELVETICA_WIDTHS.get(index).copied().unwrap_or(556)
+            }
+            Family::Times => TIMES_WIDTHS.get(index).copied().unwrap_or(500),
+            Family::Symbol => 500,
+        }
+    }
+}
+
+/// Widths of Helvetica from space (0x20) to tilde (0x7E).
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+    278, 556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584,
+    584, 556, 1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556,
+    833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278,
+    278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222,
+    500, 222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500,
+    500, 334, 260, 334, 584,
+];
+
+/// Widths of Times-Roman from space (0x20) to tilde (0x7E).
+const TIMES_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250,
+    278, 500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564,
+    564, 444, 921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611,
+    889, 722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333,
+    278, 333, 469, 500, 333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278,
+    500, 278, 778, 500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500,
+    444, 480, 200, 480, 541,
+];
@@ -0,0 +1,727 @@
+use crate::{imports::*, parser::*};
+
+/// Sampled image holding the pixels of a bitmap, as a PDF image XObject, a
+/// PostScript image dictionary or canvas `ImageData`.
+///
+/// Uncompressed bitmaps are repacked into rows from top to bottom, while
+/// JPEG and PNG data embedded in a DIB are passed through to the matching
+/// filter without decoding.
+#[derive(Clone, Debug)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    color_space: ColorSpace,
+    bits_per_component: u8,
+    filter: Option<Filter>,
+    inverted: bool,
+    data: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+enum ColorSpace {
+    Gray,
+    Rgb,
+    Cmyk,
+    Indexed(Vec<[u8; 3]>),
+}
+
+#[derive(Clone, Debug)]
+enum Filter {
+    Dct,
+    /// zlib data with PNG row predictors.
+    #[cfg_attr(not(feature = "pdf"), allow(dead_code))]
+    Flate {
+        colors: u8,
+        columns: usize,
+    },
+}
+
+impl Image {
+    /// Build the image of `dib`, or describe why it cannot be shown.
+    pub fn from_dib(dib: &DeviceIndependentBitmap) -> Result<Self, String> {
+        let header = &dib.dib_header_info;
+        let data = dib.bitmap_buffer.a_data.as_slice();
+        let (compression, top_down, masks) = match header {
+            BitmapInfoHeader::Core(_) => (Compression::BI_RGB, false, None),
+            BitmapInfoHeader::Info(v) => {
+                (v.compression, v.height < 0, Self::color_masks(&dib.colors))
+            }
+            BitmapInfoHeader::V4(v) => (
+                v.compression,
+                v.height < 0,
+                Some([v.red_mask, v.green_mask, v.blue_mask]),
+            ),
+            BitmapInfoHeader::V5(v) => (
+                v.compression,
+                v.height < 0,
+                Some([v.red_mask, v.green_mask, v.blue_mask]),
+            ),
+        };
+
+        match compression {
+            Compression::BI_JPEG => Self::jpeg(data),
+            Compression::BI_PNG => Self::png(data),
+            Compression::BI_RGB | Compression::BI_BITFIELDS => {
+                let masks = if compression == Compression::BI_BITFIELDS {
+                    masks
+                } else {
+                    None
+                };
+
+                Self::uncompressed(dib, top_down, masks)
+            }
+            compression => {
+                Err(format!("{compression:?} bitmaps are not supported"))
+            }
+        }
+    }
+
+    /// Build the image of a monochrome device-dependent bitmap.
+    pub fn from_bitmap16(bitmap: &Bitmap16) -> Result<Self, String> {
+        if bitmap.bits_pixel != BitCount::BI_BITCOUNT_1 || bitmap.planes != 1 {
+            return Err(format!(
+                "{:?} device-dependent bitmaps are not supported",
+                bitmap.bits_pixel,
+            ));
+        }
+
+        let width = usize::from(bitmap.width.unsigned_abs());
+        let height = usize::from(bitmap.height.unsigned_abs());
+        // Rows of a Bitmap16 are aligned to 16 bits.
+        let stride = usize::from(bitmap.width_bytes.unsigned_abs())
+            .max(width.div_ceil(16) * 2);
+        let rows = (0..height).map(|row| row * stride);
+
+        Ok(Self {
+            width,
+            height,
+            color_space: ColorSpace::Gray,
+            bits_per_component: 1,
+            filter: None,
+            inverted: false,
+            data: Self::pack_rows(&bitmap.bits, rows, width.div_ceil(8))?,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Invert the colours of the image, as NOTSRCCOPY does.
+    #[must_use]
+    pub fn invert(mut self) -> Self {
+        if let ColorSpace::Indexed(palette) = &mut self.color_space {
+            for color in palette.iter_mut() {
+                *color = color.map(|component| !component);
+            }
+        } else {
+            self.inverted = !self.inverted;
+        }
+
+        self
+    }
+
+    /// Entries of the PDF stream dictionary, except for `/Length`.
+    #[cfg(feature = "pdf")]
+    pub(crate) fn dictionary(&self) -> String {
+        use core::fmt::Write;
+
+        let mut dictionary = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace \
+             {} /BitsPerComponent {}",
+            self.width,
+            self.height,
+            self.color_space(),
+            self.bits_per_component(),
+        );
+
+        match &self.filter {
+            Some(Filter::Dct) => dictionary.push_str(" /Filter /DCTDecode"),
+            Some(Filter::Flate { colors, columns }) => {
+                let _ = write!(
+                    dictionary,
+                    " /Filter /FlateDecode /DecodeParms << /Predictor 15 \
+                     /Colors {colors} /BitsPerComponent {} /Columns {columns} \
+                     >>",
+                    self.bits_per_component,
+                );
+            }
+            None => {}
+        }
+
+        if self.inverted {
+            let _ = write!(dictionary, " /Decode {}", self.decode());
+        }
+
+        dictionary
+    }
+
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Colour space of the samples, written as PDF and PostScript share it.
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    pub(crate) fn color_space(&self) -> String {
+        self.color_space.name()
+    }
+
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    pub(crate) fn bits_per_component(&self) -> u8 {
+        self.bits_per_component
+    }
+
+    /// Decode array mapping sample values to colour components.
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    pub(crate) fn decode(&self) -> String {
+        let range = match self.color_space {
+            ColorSpace::Indexed(_) => {
+                format!("0 {}", (1_u32 << self.bits_per_component) - 1)
+            }
+            _ if self.inverted => "1 0".to_owned(),
+            _ => "0 1".to_owned(),
+        };
+
+        format!("[{}]", vec![range; self.color_space.components()].join(" "))
+    }
+
+    /// Name of the filter decoding the data, if it is compressed.
+    #[cfg(feature = "eps")]
+    pub(crate) fn filter(&self) -> Option<&'static str> {
+        match self.filter {
+            Some(Filter::Dct) => Some("DCTDecode"),
+            Some(Filter::Flate { .. }) => Some("FlateDecode"),
+            None => None,
+        }
+    }
+
+    /// Pixels as RGBA, four bytes each in rows from top to bottom, as
+    /// canvas `ImageData` takes them. Compressed data is not decoded.
+    pub fn rgba(&self) -> Result<Vec<u8>, String> {
+        if self.filter.is_some() {
+            return Err("JPEG and PNG data are not decoded".to_owned());
+        }
+
+        let bits = usize::from(self.bits_per_component);
+        let components = self.color_space.components();
+        let stride = (self.width * bits * components).div_ceil(8);
+        let max = (1_u16 << bits) - 1;
+        let sample = |row: &[u8], index: usize| {
+            let bit = index * bits;
+            let value = u16::from(row[bit / 8] >> (8 - bits - bit % 8)) & max;
+
+            if let ColorSpace::Indexed(_) = self.color_space {
+                value as u8
+            } else {
+                let value = (u32::from(value) * 255 / u32::from(max)) as u8;
+
+                if self.inverted { !value } else { value }
+            }
+        };
+        let mut pixels = Vec::with_capacity(self.width * self.height * 4);
+
+        for row in self.data.chunks_exact(stride.max(1)).take(self.height) {
+            for x in 0..self.width {
+                let at = |component| sample(row, x * components + component);
+                let [red, green, blue] = match &self.color_space {
+                    ColorSpace::Gray => [at(0); 3],
+                    ColorSpace::Rgb => [at(0), at(1), at(2)],
+                    ColorSpace::Cmyk => {
+                        let k = 255 - u16::from(at(3));
+
+                        [at(0), at(1), at(2)]
+                            .map(|v| ((255 - u16::from(v)) * k / 255) as u8)
+                    }
+                    ColorSpace::Indexed(palette) => palette
+                        .get(usize::from(at(0)))
+                        .copied()
+                        .unwrap_or_default(),
+                };
+
+                pixels.extend([red, green, blue, 0xFF]);
+            }
+        }
+
+        if pixels.len() < self.width * self.height * 4 {
+            return Err(Self::truncated());
+        }
+
+        Ok(pixels)
+    }
+
+    /// Average colour of the pixels, which stands in for the image where
+    /// only a flat colour can be painted.
+    pub fn average_color(&self) -> Result<[u8; 3], String> {
+        let pixels = self.rgba()?;
+        let count = pixels.len() as u64 / 4;
+
+        if count == 0 {
+            return Ok([0xFF; 3]);
+        }
+
+        let sum = pixels.chunks_exact(4).fold([0_u64; 3], |mut sum, pixel| {
+            for (sum, value) in sum.iter_mut().zip(pixel) {
+                *sum += u64::from(*value);
+            }
+
+            sum
+        });
+
+        Ok(sum.map(|sum| (sum / count) as u8))
+    }
+
+    /// Channel masks of a BI_BITFIELDS bitmap with a BITMAPINFOHEADER,
+    /// which follow the header in place of the colour table.
+    fn color_masks(colors: &Colors) -> Option<[u32; 3]> {
+        let Colors::RGBQuad(quads) = colors else {
+            return None;
+        };
+        let mask = |quad: &RGBQuad| {
+            u32::from_le_bytes([quad.blue, quad.green, quad.red, quad.reserved])
+        };
+
+        match quads.as_slice() {
+            [red, green, blue, ..] => {
+                Some([mask(red), mask(green), mask(blue)])
+            }
+            _ => None,
+        }
+    }
+
+    fn uncompressed(
+        dib: &DeviceIndependentBitmap,
+        top_down: bool,
+        masks: Option<[u32; 3]>,
+    ) -> Result<Self, String> {
+        let header = &dib.dib_header_info;
+        let (width, height) = (header.width(), header.height());
+        let bits = header.bit_count() as usize;
+        let stride = (width * bits).div_ceil(32) * 4;
+        let rows = (0..height).map(move |row| {
+            if top_down { row * stride } else { (height - 1 - row) * stride }
+        });
+        let data = dib.bitmap_buffer.a_data.as_slice();
+
+        let (color_space, bits_per_component, data) = match header.bit_count() {
+            BitCount::BI_BITCOUNT_0 => {
+                return Err(
+                    "bitmap without bit count or compression".to_owned()
+                );
+            }
+            BitCount::BI_BITCOUNT_1
+            | BitCount::BI_BITCOUNT_2
+            | BitCount::BI_BITCOUNT_3 => {
+                let mut palette: Vec<[u8; 3]> = match &dib.colors {
+                    Colors::RGBQuad(values) => values
+                        .iter()
+                        .map(|v| [v.red, v.green, v.blue])
+                        .collect(),
+                    Colors::RGBTriple(values) => values
+                        .iter()
+                        .map(|v| [v.red, v.green, v.blue])
+                        .collect(),
+                    Colors::PaletteIndices(_) | Colors::Null => {
+                        return Err("bitmaps with colours from the logical \
+                                    palette are not supported"
+                            .to_owned());
+                    }
+                };
+
+                // Indices beyond the colour table show black.
+                palette.resize(1 << bits, [0, 0, 0]);
+
+                (
+                    ColorSpace::Indexed(palette),
+                    u8::try_from(bits).unwrap_or(8),
+                    Self::pack_rows(data, rows, (width * bits).div_ceil(8))?,
+                )
+            }
+            BitCount::BI_BITCOUNT_4 => {
+                let masks = masks.unwrap_or([0x7C00, 0x03E0, 0x001F]);
+
+                (ColorSpace::Rgb, 8, Self::unpack(data, rows, width, 2, masks)?)
+            }
+            BitCount::BI_BITCOUNT_5 => {
+                let mut pixels = Vec::with_capacity(width * height * 3);
+
+                for start in rows {
+                    let row = data
+                        .get(start..start + width * 3)
+                        .ok_or_else(Self::truncated)?;
+
+                    for bgr in row.chunks_exact(3) {
+                        pixels.extend([bgr[2], bgr[1], bgr[0]]);
+                    }
+                }
+
+                (ColorSpace::Rgb, 8, pixels)
+            }
+            BitCount::BI_BITCOUNT_6 => {
+                let masks = masks.unwrap_or([0x00FF_0000, 0x0000_FF00, 0xFF]);
+
+                (ColorSpace::Rgb, 8, Self::unpack(data, rows, width, 4, masks)?)
+            }
+        };
+
+        Ok(Self {
+            width,
+            height,
+            color_space,
+            bits_per_component,
+            filter: None,
+            inverted: false,
+            data,
+        })
+    }
+
+    /// Copy the first `length` bytes of each row starting at `rows`.
+    fn pack_rows(
+        data: &[u8],
+        rows: impl Iterator<Item = usize>,
+        length: usize,
+    ) -> Result<Vec<u8>, String> {
+        let mut packed = vec![];
+
+        for start in rows {
+            let row =
+                data.get(start..start + length).ok_or_else(Self::truncated)?;
+
+            packed.extend_from_slice(row);
+        }
+
+        Ok(packed)
+    }
+
+    /// Expand little-endian pixels of `bytes` bytes into RGB with the
+    /// channel `masks`.
+    fn unpack(
+        data: &[u8],
+        rows: impl Iterator<Item = usize>,
+        width: usize,
+        bytes: usize,
+        masks: [u32; 3],
+    ) -> Result<Vec<u8>, String> {
+        let channel = |pixel: u32, mask: u32| {
+            if mask == 0 {
+                return 0;
+            }
+
+            let max = mask >> mask.trailing_zeros();
+            let value = (pixel & mask) >> mask.trailing_zeros();
+
+            u8::try_from(
+                (u64::from(value) * 255 + u64::from(max) / 2) / u64::from(max),
+            )
+            .unwrap_or(u8::MAX)
+        };
+        let mut pixels = vec![];
+
+        for start in rows {
+            let row = data
+                .get(start..start + width * bytes)
+                .ok_or_else(Self::truncated)?;
+
+            for chunk in row.chunks_exact(bytes) {
+                let mut le = [0u8; 4];
+                le[..bytes].copy_from_slice(chunk);
+                let pixel = u32::from_le_bytes(le);
+
+                pixels.extend(masks.map(|mask| channel(pixel, mask)));
+            }
+        }
+
+        Ok(pixels)
+    }
+
+    fn jpeg(data: &[u8]) -> Result<Self, String> {
+        if !data.starts_with(&[0xFF, 0xD8]) {
+            return Err("embedded JPEG data has no SOI marker".to_owned());
+        }
+
+        let mut position = 2;
+
+        // Walk the marker segments up to the start of frame.
+        while let Some(&[0xFF, marker, high, low]) =
+            data.get(position..position + 4)
+        {
+            if matches!(marker, 0xC0..=0xCF)
+                && !matches!(marker, 0xC4 | 0xC8 | 0xCC)
+            {
+                let frame = data
+                    .get(position + 5..position + 10)
+                    .ok_or_else(Self::truncated)?;
+                let height =
+                    usize::from(u16::from_be_bytes([frame[0], frame[1]]));
+                let width =
+                    usize::from(u16::from_be_bytes([frame[2], frame[3]]));
+                let color_space = match frame[4] {
+                    1 => ColorSpace::Gray,
+                    3 => ColorSpace::Rgb,
+                    4 => ColorSpace::Cmyk,
+                    components => {
+                        return Err(format!(
+                            "JPEG with {components} components is not \
+                             supported"
+                        ));
+                    }
+                };
+
+                return Ok(Self {
+                    width,
+                    height,
+                    color_space,
+                    bits_per_component: 8,
+                    filter: Some(Filter::Dct),
+                    inverted: false,
+                    data: data.to_vec(),
+                });
+            }
+
+            position += 2 + usize::from(u16::from_be_bytes([high, low]));
+        }
+
+        Err("embedded JPEG data has no frame header".to_owned())
+    }
+
+    fn png(data: &[u8]) -> Result<Self, String> {
+        const SIGNATURE: [u8; 8] =
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+        let Some(mut chunks) = data.strip_prefix(&SIGNATURE) else {
+            return Err("embedded PNG data has no signature".to_owned());
+        };
+        let mut header = None;
+        let mut palette = vec![];
+        let mut compressed = vec![];
+
+        while let Some((length, rest)) = chunks.split_first_chunk::<4>() {
+            let length = u32::from_be_bytes(*length) as usize;
+            let (typ, rest) =
+                rest.split_first_chunk::<4>().ok_or_else(Self::truncated)?;
+            let body = rest.get(..length).ok_or_else(Self::truncated)?;
+
+            match typ {
+                b"IHDR" if body.len() >= 13 => header = Some(body),
+                b"PLTE" => {
+                    palette = body
+                        .chunks_exact(3)
+                        .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+                        .collect();
+                }
+                b"IDAT" => compressed.extend_from_slice(body),
+                b"IEND" => break,
+                _ => {}
+            }
+
+            // Skip the body and the CRC.
+            chunks = rest.get(length + 4..).unwrap_or_default();
+        }
+
+        let Some(header) = header else {
+            return Err("embedded PNG data has no IHDR chunk".to_owned());
+        };
+        let width =
+            u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let height =
+            u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        let (depth, color_type, interlace) = (header[8], header[9], header[12]);
+
+        if interlace != 0 {
+            return Err("interlaced PNG is not supported".to_owned());
+        }
+
+        let (color_space, colors) = match color_type {
+            0 => (ColorSpace::Gray, 1),
+            2 => (ColorSpace::Rgb, 3),
+            3 if !palette.is_empty() => (ColorSpace::Indexed(palette), 1),
+            4 | 6 => {
+                return Err(
+                    "PNG with an alpha channel is not supported".to_owned()
+                );
+            }
+            _ => {
+                return Err(format!(
+                    "PNG colour type {color_type} is not supported"
+                ));
+            }
+        };
+
+        Ok(Self {
+            width: width as usize,
+            height: height as usize,
+            color_space,
+            bits_per_component: depth,
+            filter: Some(Filter::Flate { colors, columns: width as usize }),
+            inverted: false,
+            data: compressed,
+        })
+    }
+
+    fn truncated() -> String {
+        "bitmap data is truncated".to_owned()
+    }
+}
+
+impl ColorSpace {
+    #[cfg(any(feature = "eps", feature = "pdf"))]
+    fn name(&self) -> String {
+        use crate::converter::page::hex;
+
+        match self {
+            Self::Gray => "/DeviceGray".to_owned(),
+            Self::Rgb => "/DeviceRGB".to_owned(),
+            Self::Cmyk => "/DeviceCMYK".to_owned(),
+            Self::Indexed(palette) => {
+                let table = hex(palette.iter().flatten());
+
+                format!(
+                    "[/Indexed /DeviceRGB {} <{table}>]",
+                    palette.len().saturating_sub(1),
+                )
+            }
+        }
+    }
+
+    fn components(&self) -> usize {
+        match self {
+            Self::Gray | Self::Indexed(_) => 1,
+            Self::Rgb => 3,
+            Self::Cmyk => 4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dib(
+        bit_count: BitCount,
+        height: i32,
+        colors: Colors,
+        a_data: Vec<u8>,
+    ) -> DeviceIndependentBitmap {
+        DeviceIndependentBitmap {
+            dib_header_info: BitmapInfoHeader::Info(BitmapInfoHeaderInfo {
+                header_size: 40,
+                width: 2,
+                height,
+                planes: 1,
+                bit_count,
+                compression: Compression::BI_RGB,
+                image_size: 0,
+                x_pels_per_meter: 0,
+                y_pels_per_meter: 0,
+                color_used: 0,
+                color_important: 0,
+            }),
+            colors,
+            bitmap_buffer: BitmapBuffer { a_data },
+        }
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn from_dib_flips_bottom_up_rows_and_swaps_bgr() {
+        // Two rows of two 24-bit pixels, each padded to four bytes.
+        let data = vec![
+            0x00, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0,
+            0, // bottom: red, green
+            0xFF, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0, 0, // top: blue, white
+        ];
+        let image = Image::from_dib(&dib(
+            BitCount::BI_BITCOUNT_5,
+            2,
+            Colors::Null,
+            data,
+        ))
+        .expect("24-bit bitmap should be supported");
+
+        assert_eq!(image.data(), [
+            0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, // blue, white
+            0xFF, 0x00, 0x00, 0x00, 0xFF, 0x00, // red, green
+        ]);
+        assert_eq!(
+            image.dictionary(),
+            "/Type /XObject /Subtype /Image /Width 2 /Height 2 /ColorSpace \
+             /DeviceRGB /BitsPerComponent 8",
+        );
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn from_dib_keeps_colour_table_as_indexed_colour_space() {
+        let colors = Colors::RGBQuad(vec![
+            RGBQuad { blue: 0, green: 0, red: 0, reserved: 0 },
+            RGBQuad { blue: 0, green: 0, red: 0xFF, reserved: 0 },
+        ]);
+        // A top-down row of one byte holding two 1-bit pixels.
+        let data = vec![0b0100_0000, 0, 0, 0];
+        let image =
+            Image::from_dib(&dib(BitCount::BI_BITCOUNT_1, -1, colors, data))
+                .expect("1-bit bitmap should be supported")
+                .invert();
+
+        assert_eq!(image.data(), [0b0100_0000]);
+        assert!(
+            image
+                .dictionary()
+                .contains("/ColorSpace [/Indexed /DeviceRGB 1 <FFFFFF00FFFF>]")
+        );
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn from_dib_passes_jpeg_through() {
+        let jpeg = vec![
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // SOI, APP0
+            0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x03, 0x00, 0x05,
+            0x01, // SOF0
+        ];
+        let mut bitmap = dib(BitCount::BI_BITCOUNT_0, 3, Colors::Null, jpeg);
+
+        if let BitmapInfoHeader::Info(header) = &mut bitmap.dib_header_info {
+            header.compression = Compression::BI_JPEG;
+        }
+
+        let image = Image::from_dib(&bitmap).expect("JPEG should pass through");
+
+        assert_eq!((image.width(), image.height()), (5, 3));
+        assert_eq!(image.data(), bitmap.bitmap_buffer.a_data.as_slice());
+        assert!(image.dictionary().contains("/ColorSpace /DeviceGray"));
+        assert!(image.dictionary().ends_with("/Filter /DCTDecode"));
+    }
+
+    #[test]
+    fn rgba_looks_up_indexed_samples() {
+        let colors = Colors::RGBQuad(vec![
+            RGBQuad { blue: 0, green: 0, red: 0, reserved: 0 },
+            RGBQuad { blue: 0, green: 0, red: 0xFF, reserved: 0 },
+        ]);
+        let data = vec![0b0100_0000, 0, 0, 0];
+        let image =
+            Image::from_dib(&dib(BitCount::BI_BITCOUNT_1, -1, colors, data))
+                .expect("1-bit bitmap should be supported");
+
+        assert_eq!(image.rgba(), Ok(vec![0, 0, 0, 0xFF, 0xFF, 0, 0, 0xFF]));
+    }
+
+    #[test]
+    fn average_color_mixes_all_pixels() {
+        let colors = Colors::RGBQuad(vec![
+            RGBQuad { blue: 0, green: 0, red: 0, reserved: 0 },
+            RGBQuad { blue: 0xFF, green: 0xFF, red: 0xFF, reserved: 0 },
+        ]);
+        let data = vec![0b0100_0000, 0, 0, 0];
+        let image =
+            Image::from_dib(&dib(BitCount::BI_BITCOUNT_1, -1, colors, data))
+                .expect("1-bit bitmap should be supported");
+
+        assert_eq!(image.average_color(), Ok([0x7F; 3]));
+    }
+}
@@ -0,0 +1,112 @@
+//! Pieces shared by the players writing page description languages, PDF
+//! and PostScript, HTML canvas drawing commands and TikZ pictures, whose
+//! imaging models are close enough to draw the metafile in the same way.
+//! The thumbnail player paints its figures with them too, and the text
+//! extractor, the extent player and the DXF player place text and figures
+//! as they do.
+
+mod font;
+mod image;
+mod path;
+#[cfg(any(
+    feature = "canvas",
+    feature = "eps",
+    feature = "pdf",
+    feature = "tikz"
+))]
+mod placeable;
+
+#[cfg(any(
+    feature = "canvas",
+    feature = "eps",
+    feature = "pdf",
+    feature = "tikz"
+))]
+pub use self::placeable::Placeable;
+pub use self::{font::StandardFont, image::Image, path::Path};
+#[cfg(any(
+    feature = "canvas",
+    feature = "dxf",
+    feature = "eps",
+    feature = "pdf",
+    feature = "tikz"
+))]
+use crate::imports::*;
+#[cfg(any(feature = "eps", feature = "pdf"))]
+use crate::parser::*;
+
+/// Format a real number without exponent notation, which neither PDF nor
+/// PostScript readers are required to accept, with at most three decimal
+/// places.
+#[cfg(any(
+    feature = "canvas",
+    feature = "dxf",
+    feature = "eps",
+    feature = "pdf",
+    feature = "tikz"
+))]
+pub fn number(value: f32) -> String {
+    if !value.is_finite() {
+        return "0".to_owned();
+    }
+
+    let formatted = format!("{value:.3}");
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" | "" => "0".to_owned(),
+        _ => trimmed.to_owned(),
+    }
+}
+
+/// Convert a value to `f32`, clamping it to the range of `i16` as the
+/// coordinates of the metafile.
+pub fn float(value: i32) -> f32 {
+    let clamped = value.clamp(i32::from(i16::MIN), i32::from(i16::MAX));
+
+    f32::from(i16::try_from(clamped).unwrap_or_default())
+}
+
+/// Bytes as a string of hexadecimal digits, for hexadecimal strings.
+#[cfg(any(feature = "eps", feature = "pdf"))]
+pub fn hex<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> String {
+    use core::fmt::Write;
+
+    bytes.into_iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02X}");
+        hex
+    })
+}
+
+/// Colour components in the range 0 to 1.
+#[cfg(any(feature = "eps", feature = "pdf"))]
+pub fn components(color: &ColorRef) -> (f32, f32, f32) {
+    (
+        f32::from(color.red) / 255.0,
+        f32::from(color.green) / 255.0,
+        f32::from(color.blue) / 255.0,
+    )
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "canvas",
+        feature = "dxf",
+        feature = "eps",
+        feature = "pdf",
+        feature = "tikz"
+    )
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_trims_trailing_zeros() {
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(0.5), "0.5");
+        assert_eq!(number(-0.0001), "0");
+        assert_eq!(number(2.0 / 3.0), "0.667");
+        assert_eq!(number(f32::NAN), "0");
+    }
+}
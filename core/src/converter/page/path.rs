@@ -0,0 +1,141 @@
+use crate::converter::EllipticalArc;
+
+/// Builder of paths in device units, which writes the curves and shapes of
+/// GDI with the path construction operators of the implementor.
+pub trait Path: Sized {
+    #[must_use]
+    fn move_to(self, point: (f32, f32)) -> Self;
+
+    #[must_use]
+    fn line_to(self, point: (f32, f32)) -> Self;
+
+    #[must_use]
+    fn curve_to(
+        self,
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+    ) -> Self;
+
+    #[must_use]
+    fn close(self) -> Self;
+
+    #[must_use]
+    fn polygon(mut self, points: &[(f32, f32)]) -> Self {
+        let Some((first, rest)) = points.split_first() else {
+            return self;
+        };
+
+        self = self.move_to(*first);
+
+        for point in rest {
+            self = self.line_to(*point);
+        }
+
+        self.close()
+    }
+
+    /// Append a rectangle spanned by two corners in any order, as a closed
+    /// subpath.
+    #[must_use]
+    fn rect(self, (x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> Self {
+        self.polygon(&[(x1, y1), (x2, y1), (x2, y2), (x1, y2)])
+    }
+
+    /// Append `arc`, whose start point is the current point.
+    #[must_use]
+    fn elliptical_arc(self, arc: &EllipticalArc) -> Self {
+        self.arc_to(arc.center(), arc.radii(), arc.start_angle(), arc.sweep())
+    }
+
+    /// Append an elliptical arc around `center` from the parametric angle
+    /// `start` sweeping by `sweep` radians, as Bézier curves of at most a
+    /// quarter turn each. The current point is the start of the arc.
+    #[must_use]
+    fn arc_to(
+        mut self,
+        center: (f32, f32),
+        (rx, ry): (f32, f32),
+        start: f32,
+        sweep: f32,
+    ) -> Self {
+        let segments =
+            (sweep.abs() / core::f32::consts::FRAC_PI_2).ceil().clamp(1.0, 4.0);
+        let step = sweep / segments;
+        // Length of the control handles relative to the radius.
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        let point = |angle: f32| {
+            (center.0 + rx * angle.cos(), center.1 + ry * angle.sin())
+        };
+        let tangent = |angle: f32| (-rx * angle.sin(), ry * angle.cos());
+
+        let mut from = start;
+
+        for _ in 0..segments as u8 {
+            let to = from + step;
+            let (p0, p3) = (point(from), point(to));
+            let (t0, t3) = (tangent(from), tangent(to));
+
+            self = self.curve_to(
+                (p0.0 + k * t0.0, p0.1 + k * t0.1),
+                (p3.0 - k * t3.0, p3.1 - k * t3.1),
+                p3,
+            );
+            from = to;
+        }
+
+        self
+    }
+
+    /// Append a closed ellipse inscribed in the rectangle spanned by two
+    /// corners.
+    #[must_use]
+    fn ellipse(self, (x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> Self {
+        let center = (f32::midpoint(x1, x2), f32::midpoint(y1, y2));
+        let radii = ((x2 - x1).abs() / 2.0, (y2 - y1).abs() / 2.0);
+
+        self.move_to((center.0 + radii.0, center.1))
+            .arc_to(center, radii, 0.0, core::f32::consts::TAU)
+            .close()
+    }
+
+    /// Append a closed rectangle with corners rounded by ellipses of the
+    /// given size.
+    #[must_use]
+    fn round_rect(
+        self,
+        (x1, y1): (f32, f32),
+        (x2, y2): (f32, f32),
+        (width, height): (f32, f32),
+    ) -> Self {
+        use core::f32::consts::{FRAC_PI_2, PI};
+
+        let (left, right) = (x1.min(x2), x1.max(x2));
+        let (top, bottom) = (y1.min(y2), y1.max(y2));
+        let rx = (width.abs() / 2.0).min((right - left) / 2.0);
+        let ry = (height.abs() / 2.0).min((bottom - top) / 2.0);
+        let radii = (rx, ry);
+
+        self.move_to((left + rx, top))
+            .line_to((right - rx, top))
+            .arc_to((right - rx, top + ry), radii, -FRAC_PI_2, FRAC_PI_2)
+            .line_to((right, bottom - ry))
+            .arc_to((right - rx, bottom - ry), radii, 0.0, FRAC_PI_2)
+            .line_to((left + rx, bottom))
+            .arc_to((left + rx, bottom - ry), radii, FRAC_PI_2, FRAC_PI_2)
+            .line_to((left, top + ry))
+            .arc_to((left + rx, top + ry), radii, PI, FRAC_PI_2)
+            .close()
+    }
+}
+
+/// Path of a device drawing no figures, which keeps nothing of them.
+impl Path for () {
+    fn move_to(self, _: (f32, f32)) -> Self {}
+
+    fn line_to(self, _: (f32, f32)) -> Self {}
+
+    fn curve_to(self, _: (f32, f32), _: (f32, f32), _: (f32, f32)) -> Self {}
+
+    fn close(self) -> Self {}
+}
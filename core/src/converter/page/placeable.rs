@@ -0,0 +1,55 @@
+use crate::{
+    converter::{Window, page::float},
+    parser::*,
+};
+
+/// META_PLACEABLE bounding box of a metafile, which becomes the page of
+/// the output in place of the drawing bounds and gives it a physical size.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Placeable {
+    /// Size of the bounding box in device units.
+    size: Option<(f32, f32)>,
+    /// Device units per inch given by the resolution.
+    units_per_inch: Option<f32>,
+}
+
+impl Placeable {
+    /// Take the bounding box of a placeable metafile from `header`.
+    pub fn new(header: &MetafileHeader) -> Self {
+        let MetafileHeader::StartsWithPlaceable(placeable, _) = header else {
+            return Self::default();
+        };
+        let Rect { left, top, right, bottom } = placeable.bounding_box;
+
+        Self {
+            size: Some((
+                (f32::from(right) - f32::from(left)).abs(),
+                (f32::from(bottom) - f32::from(top)).abs(),
+            )),
+            units_per_inch: (placeable.inch > 0)
+                .then(|| f32::from(placeable.inch)),
+        }
+    }
+
+    /// Area `(x, y, width, height)` of the page in device units: the
+    /// bounding box, or the drawing bounds of `window` without one.
+    pub fn area(&self, window: &Window) -> (f32, f32, f32, f32) {
+        if let Some((width, height)) = self.size {
+            return (0.0, 0.0, width, height);
+        }
+
+        let (x, y, width, height) = window.as_view_box();
+
+        (float(x), float(y), float(width), float(height))
+    }
+
+    /// Output units per device unit, for an output measured in `per_inch`
+    /// units per inch. Without a resolution, a device pixel is taken as
+    /// 1/96 inch.
+    pub fn scale(&self, window: &Window, per_inch: f32) -> f32 {
+        self.units_per_inch.map_or_else(
+            || per_inch / 96.0 / window.device_pixel_size(),
+            |units_per_inch| per_inch / units_per_inch,
+        )
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    converter::page::{Path, components, number},
+    imports::*,
+    parser::*,
+};
+
+/// Operators of a PDF content stream.
+///
+/// Coordinates are device units with the y-axis pointing down; the page
+/// transform written by [`crate::converter::PdfPlayer`] maps them to
+/// points.
+#[derive(Clone, Debug, Default)]
+pub struct Content(String);
+
+impl Content {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `operator` preceded by its operands.
+    pub fn push(mut self, operands: &[f32], operator: &str) -> Self {
+        for operand in operands {
+            self.0.push_str(&number(*operand));
+            self.0.push(' ');
+        }
+
+        self.0.push_str(operator);
+        self.0.push('\n');
+        self
+    }
+
+    /// Append operators written by another builder.
+    pub fn append(mut self, content: &Content) -> Self {
+        self.0.push_str(&content.0);
+        self
+    }
+
+    /// Append an operator line as it is.
+    pub fn raw(mut self, line: impl core::fmt::Display) -> Self {
+        use core::fmt::Write;
+
+        let _ = writeln!(self.0, "{line}");
+        self
+    }
+
+    pub fn fill_color(self, color: &ColorRef) -> Self {
+        let (r, g, b) = components(color);
+        self.push(&[r, g, b], "rg")
+    }
+
+    pub fn stroke_color(self, color: &ColorRef) -> Self {
+        let (r, g, b) = components(color);
+        self.push(&[r, g, b], "RG")
+    }
+
+    /// Set the dash pattern of strokes, solid when `dashes` is empty.
+    pub fn dash(self, dashes: &[f32]) -> Self {
+        let dashes: Vec<_> = dashes.iter().map(|v| number(*v)).collect();
+
+        self.raw(format!("[{}] 0 d", dashes.join(" ")))
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0.into_bytes()
+    }
+}
+
+impl Path for Content {
+    fn move_to(self, (x, y): (f32, f32)) -> Self {
+        self.push(&[x, y], "m")
+    }
+
+    fn line_to(self, (x, y): (f32, f32)) -> Self {
+        self.push(&[x, y], "l")
+    }
+
+    fn curve_to(
+        self,
+        (x1, y1): (f32, f32),
+        (x2, y2): (f32, f32),
+        (x3, y3): (f32, f32),
+    ) -> Self {
+        self.push(&[x1, y1, x2, y2, x3, y3], "c")
+    }
+
+    fn close(self) -> Self {
+        self.push(&[], "h")
+    }
+
+    fn rect(self, (x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> Self {
+        self.push(
+            &[x1.min(x2), y1.min(y2), (x2 - x1).abs(), (y2 - y1).abs()],
+            "re",
+        )
+    }
+}
+
+impl core::fmt::Display for Content {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ellipse_is_drawn_with_four_curves() {
+        let content = Content::new().ellipse((0.0, 0.0), (20.0, 10.0));
+        let content = content.to_string();
+
+        assert!(content.starts_with("20 5 m\n"));
+        assert_eq!(content.matches(" c\n").count(), 4);
+        assert!(content.contains(" 10 0 c\n"));
+        assert!(content.ends_with("20 5 c\nh\n"));
+    }
+}
@@ -0,0 +1,521 @@
+use crate::{
+    converter::{
+        Clip, DiagnosticKind, GdiState, LineCap, LineJoin, LineStyle,
+        PlayError,
+        drawing::{Context, Device, Paint, TextRun},
+        page::{Image, Path, Placeable, StandardFont, hex, number},
+        pdf::{
+            content::Content,
+            document::{self, Pattern, Resources},
+        },
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Page of a [`PdfPlayer`](crate::converter::PdfPlayer): its content
+/// stream and the resources it refers to.
+#[derive(Default)]
+pub struct Pdf {
+    resources: Resources,
+    content: Content,
+    // META_PLACEABLE bounding box, which becomes the page.
+    placeable: Placeable,
+}
+
+/// Colour change and blend mode standing in for a raster operation, which
+/// PDF cannot apply to the pixels already painted.
+#[derive(Clone, Copy, Debug)]
+struct Operation {
+    color: ColorMode,
+    blend_mode: Option<&'static str>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColorMode {
+    Keep,
+    Invert,
+    Black,
+    White,
+}
+
+impl Operation {
+    fn new(color: ColorMode, blend_mode: Option<&'static str>) -> Self {
+        Self { color, blend_mode }
+    }
+
+    /// Operation standing in for the ROP2 mode `operation`.
+    fn from_rop2(
+        context: &mut Context<'_>,
+        operation: BinaryRasterOperation,
+    ) -> Self {
+        use BinaryRasterOperation as Rop2;
+
+        let (color, blend_mode) = match operation {
+            Rop2::R2_NOP | Rop2::R2_COPYPEN => (ColorMode::Keep, None),
+            Rop2::R2_NOTCOPYPEN => (ColorMode::Invert, None),
+            Rop2::R2_BLACK => (ColorMode::Black, None),
+            Rop2::R2_WHITE => (ColorMode::White, None),
+            Rop2::R2_NOT => (ColorMode::White, Some("Difference")),
+            Rop2::R2_XORPEN => (ColorMode::Keep, Some("Difference")),
+            Rop2::R2_NOTXORPEN => (ColorMode::Invert, Some("Difference")),
+            Rop2::R2_MASKPEN => (ColorMode::Keep, Some("Multiply")),
+            Rop2::R2_MASKNOTPEN => (ColorMode::Invert, Some("Multiply")),
+            Rop2::R2_MERGEPEN => (ColorMode::Keep, Some("Screen")),
+            Rop2::R2_MERGENOTPEN => (ColorMode::Invert, Some("Screen")),
+            Rop2::R2_NOTMERGEPEN
+            | Rop2::R2_MASKPENNOT
+            | Rop2::R2_NOTMASKPEN
+            | Rop2::R2_MERGEPENNOT => {
+                context.report(
+                    DiagnosticKind::Approximated,
+                    format!("{operation:?}: drawn as R2_COPYPEN"),
+                );
+
+                (ColorMode::Keep, None)
+            }
+        };
+
+        Self::new(color, blend_mode)
+    }
+
+    fn color(self, color: &ColorRef) -> ColorRef {
+        match self.color {
+            ColorMode::Keep => color.clone(),
+            ColorMode::Invert => ColorRef {
+                red: !color.red,
+                green: !color.green,
+                blue: !color.blue,
+                ..color.clone()
+            },
+            ColorMode::Black => ColorRef::black(),
+            ColorMode::White => ColorRef::white(),
+        }
+    }
+}
+
+impl Pdf {
+    /// Append `body` in a graphics state of its own, clipped to the
+    /// clipping region and combined with the page by `blend_mode`.
+    fn paint(
+        &mut self,
+        state: &GdiState,
+        body: &Content,
+        blend_mode: Option<&'static str>,
+    ) {
+        let mut block = clip(state.clips(), Content::new().push(&[], "q"));
+
+        if let Some(blend_mode) = blend_mode {
+            let name = self.resources.blend_mode(blend_mode);
+
+            block = block.raw(format!("/{name} gs"));
+        }
+
+        let block = block.append(body).push(&[], "Q");
+
+        self.content = core::mem::take(&mut self.content).append(&block);
+    }
+
+    /// Operators selecting `brush` as the fill paint, or `None` when it
+    /// paints nothing.
+    fn fill_paint(
+        &mut self,
+        context: &mut Context<'_>,
+        brush: &Brush,
+        operation: Operation,
+    ) -> Option<Content> {
+        let state = context.state;
+        let pixel_size = state.window().device_pixel_size();
+        let pattern = match brush {
+            Brush::Null => return None,
+            _ if matches!(
+                operation.color,
+                ColorMode::Black | ColorMode::White
+            ) =>
+            {
+                let color = operation.color(&ColorRef::black());
+
+                return Some(Content::new().fill_color(&color));
+            }
+            Brush::Solid { color_ref } => {
+                return Some(
+                    Content::new().fill_color(&operation.color(color_ref)),
+                );
+            }
+            Brush::Hatched { color_ref, brush_hatch } => Pattern::Hatch {
+                style: *brush_hatch,
+                color: operation.color(color_ref),
+                background: (state.bk_mode() == MixMode::OPAQUE)
+                    .then(|| operation.color(state.bk_color())),
+                pixel_size,
+            },
+            Brush::DIBPatternPT { brush_hatch, .. } => self.image_pattern(
+                context,
+                Image::from_dib(brush_hatch),
+                operation,
+                pixel_size,
+            )?,
+            Brush::Pattern { brush_hatch } => self.image_pattern(
+                context,
+                Image::from_bitmap16(brush_hatch),
+                operation,
+                pixel_size,
+            )?,
+        };
+        let name = self.resources.pattern(pattern);
+
+        Some(Content::new().raw("/Pattern cs").raw(format!("/{name} scn")))
+    }
+
+    fn image_pattern(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Result<Image, String>,
+        operation: Operation,
+        pixel_size: f32,
+    ) -> Option<Pattern> {
+        let image = match image {
+            Ok(image) if operation.color == ColorMode::Invert => image.invert(),
+            Ok(image) => image,
+            Err(message) => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("pattern brush is not painted: {message}"),
+                );
+
+                return None;
+            }
+        };
+        let (width, height) = (image.width(), image.height());
+
+        Some(Pattern::Image {
+            image: self.resources.image(image),
+            width,
+            height,
+            pixel_size,
+        })
+    }
+
+    /// Draw the part `(x, y, source_width, source_height)` of `image`,
+    /// stretched from the device point `p1` to `p2`.
+    fn draw_image(
+        &mut self,
+        state: &GdiState,
+        image: Image,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        (x, y, source_width, source_height): (f32, f32, f32, f32),
+        blend_mode: Option<&'static str>,
+    ) {
+        let size = |v: usize| f32::from(u16::try_from(v).unwrap_or(u16::MAX));
+        let (width, height) = (size(image.width()), size(image.height()));
+        // Device units per source pixel, negative when mirrored.
+        let kx = (p2.0 - p1.0) / source_width;
+        let ky = (p2.1 - p1.1) / source_height;
+        let name = self.resources.image(image);
+        // Image space has its first row at the top of the unit square.
+        let body = Content::new()
+            .rect(p1, p2)
+            .push(&[], "W")
+            .push(&[], "n")
+            .push(
+                &[
+                    width * kx,
+                    0.0,
+                    0.0,
+                    -height * ky,
+                    p1.0 - x * kx,
+                    p1.1 + (height - y) * ky,
+                ],
+                "cm",
+            )
+            .raw(format!("/{name} Do"));
+
+        self.paint(state, &body, blend_mode);
+    }
+}
+
+/// Operators setting up the selected pen, or `None` for PS_NULL.
+fn stroke_paint(state: &GdiState, operation: Operation) -> Option<Content> {
+    let line = LineStyle::selected(state)?;
+    let cap = match line.cap {
+        LineCap::Flat => 0.0,
+        LineCap::Round => 1.0,
+        LineCap::Square => 2.0,
+    };
+    let join = match line.join {
+        LineJoin::Miter => 0.0,
+        LineJoin::Round => 1.0,
+        LineJoin::Bevel => 2.0,
+    };
+
+    Some(
+        Content::new()
+            .stroke_color(&operation.color(&state.pen().color_ref))
+            .push(&[line.width], "w")
+            .push(&[cap], "J")
+            .push(&[join], "j")
+            .dash(&line.dashes),
+    )
+}
+
+/// Byte codes of the glyphs of `run` in its standard font.
+fn encode_text(
+    context: &mut Context<'_>,
+    run: &TextRun,
+    font: StandardFont,
+) -> Vec<u8> {
+    // The Symbol font takes the byte codes of SYMBOL_CHARSET text.
+    if font.is_symbolic() {
+        return run
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.bytes.first().copied().unwrap_or(b'?'))
+            .collect();
+    }
+
+    let mut unmapped = 0;
+    let codes = run
+        .glyphs
+        .iter()
+        .map(|glyph| {
+            StandardFont::encode(&glyph.text).unwrap_or_else(|| {
+                unmapped += 1;
+                b'?'
+            })
+        })
+        .collect();
+
+    if unmapped > 0 {
+        context.report(
+            DiagnosticKind::Approximated,
+            format!(
+                "{unmapped} characters outside WinAnsiEncoding are drawn as \
+                 '?'",
+            ),
+        );
+    }
+
+    codes
+}
+
+/// Append operators intersecting the clipping path with each of `clips`.
+fn clip(clips: &[Clip], mut content: Content) -> Content {
+    for clip in clips {
+        content = match clip {
+            Clip::Rects(rects) if rects.is_empty() => {
+                content.rect((0.0, 0.0), (0.0, 0.0)).push(&[], "W")
+            }
+            Clip::Rects(rects) => {
+                for (left, top, right, bottom) in rects {
+                    content = content.rect((*left, *top), (*right, *bottom));
+                }
+
+                content.push(&[], "W")
+            }
+            Clip::Exclude((left, top, right, bottom)) => content
+                .rect((-32768.0, -32768.0), (32767.0, 32767.0))
+                .rect((*left, *top), (*right, *bottom))
+                .push(&[], "W*"),
+        }
+        .push(&[], "n");
+    }
+
+    content
+}
+
+impl Device for Pdf {
+    type Path = Content;
+
+    fn path(&self, _: &GdiState) -> Content {
+        Content::new()
+    }
+
+    fn header(&mut self, header: &MetafileHeader) {
+        self.placeable = Placeable::new(header);
+    }
+
+    fn draw(
+        &mut self,
+        context: &mut Context<'_>,
+        path: Content,
+        paint: &Paint,
+    ) {
+        let operation = Operation::from_rop2(context, paint.operation);
+        let fill = paint
+            .brush
+            .as_ref()
+            .and_then(|brush| self.fill_paint(context, brush, operation));
+        let stroke = if paint.stroke {
+            stroke_paint(context.state, operation)
+        } else {
+            None
+        };
+        let even_odd = context.state.fill_even_odd();
+        let operator = match (&fill, &stroke) {
+            (Some(_), Some(_)) if even_odd => "B*",
+            (Some(_), Some(_)) => "B",
+            (Some(_), None) if even_odd => "f*",
+            (Some(_), None) => "f",
+            (None, Some(_)) => "S",
+            (None, None) => return,
+        };
+        let mut body = Content::new();
+
+        for paint in [&fill, &stroke].into_iter().flatten() {
+            body = body.append(paint);
+        }
+
+        let body = body.append(&path).push(&[], operator);
+
+        self.paint(context.state, &body, operation.blend_mode);
+    }
+
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Image,
+        corners: ((f32, f32), (f32, f32)),
+        source: (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    ) {
+        use TernaryRasterOperation as Rop3;
+
+        let (invert, blend_mode) = match operation {
+            Rop3::SRCCOPY => (false, None),
+            Rop3::NOTSRCCOPY => (true, None),
+            Rop3::SRCAND => (false, Some("Multiply")),
+            Rop3::SRCPAINT => (false, Some("Screen")),
+            Rop3::SRCINVERT => (false, Some("Difference")),
+            Rop3::MERGEPAINT => (true, Some("Screen")),
+            Rop3::SRCERASE | Rop3::NOTSRCERASE => {
+                let invert = operation == Rop3::NOTSRCERASE;
+
+                context.report(
+                    DiagnosticKind::Approximated,
+                    format!(
+                        "{operation:?}: drawn as {}",
+                        if invert { "NOTSRCCOPY" } else { "SRCCOPY" },
+                    ),
+                );
+
+                (invert, None)
+            }
+            _ => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("{operation:?}: raster operation is not supported"),
+                );
+
+                return;
+            }
+        };
+        let image = if invert { image.invert() } else { image };
+
+        self.draw_image(context.state, image, corners, source, blend_mode);
+    }
+
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let state = context.state;
+        let standard_font = StandardFont::new(&run.font);
+        let codes = encode_text(context, run, standard_font);
+        let name = self.resources.font(standard_font);
+        let text_color = state.text_color();
+        let bk_color = state.bk_color();
+        let (start, size, width) = (run.start(), run.size, run.width);
+        let (direction, down) = (run.direction(), run.down());
+
+        // ETO_OPAQUE fills the rectangle before the text is drawn.
+        if let Some((left, top, right, bottom)) = run.opaque {
+            let body = Content::new()
+                .fill_color(bk_color)
+                .rect((left, top), (right, bottom))
+                .push(&[], "f");
+
+            self.paint(state, &body, None);
+        }
+
+        let mut body = Content::new();
+
+        if let Some((left, top, right, bottom)) = run.clip {
+            body = body
+                .rect((left, top), (right, bottom))
+                .push(&[], "W")
+                .push(&[], "n");
+        }
+
+        if state.bk_mode() == MixMode::OPAQUE {
+            body = body
+                .fill_color(bk_color)
+                .polygon(&run.corners())
+                .push(&[], "f");
+        }
+
+        let matrix = |(x, y): (f32, f32)| {
+            [direction.0, direction.1, -down.0, -down.1, x, y]
+        };
+
+        body = body
+            .raw("BT")
+            .raw(format!("/{name} {} Tf", number(size)))
+            .fill_color(text_color);
+
+        if run.custom {
+            for (code, origin) in codes.iter().zip(run.glyph_origins()) {
+                body = body
+                    .push(&matrix(origin), "Tm")
+                    .raw(format!("<{}> Tj", hex([code])));
+            }
+        } else {
+            body = body
+                .push(&matrix(start), "Tm")
+                .raw(format!("<{}> Tj", hex(&codes)));
+        }
+
+        body = body.raw("ET");
+
+        for below in run.lines() {
+            body = body
+                .stroke_color(text_color)
+                .push(&[0.05 * size], "w")
+                .move_to(run.at(start, 0.0, below))
+                .line_to(run.at(start, width, below))
+                .push(&[], "S");
+        }
+
+        self.paint(state, &body, None);
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+        context.report(
+            DiagnosticKind::Skipped,
+            "flood fill needs the pixels of the drawing, which PDF output \
+             does not keep"
+                .to_owned(),
+        );
+    }
+
+    fn generate(self, state: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let Self { resources, content, placeable } = self;
+        let window = state.window();
+        let (x, y, width, height) = placeable.area(window);
+
+        if width <= 0.0 || height <= 0.0 {
+            return Err(PlayError::FailedGenerate {
+                cause: format!("page size is empty: {width}x{height}"),
+            });
+        }
+
+        let scale = placeable.scale(window, 72.0);
+        // Device space has the y-axis pointing down from the top left of the
+        // page, while PDF user space points up from the bottom left.
+        let matrix =
+            [scale, 0.0, 0.0, -scale, -x * scale, (y + height) * scale];
+
+        Ok(document::write(
+            resources,
+            &content,
+            (width * scale, height * scale),
+            matrix,
+        ))
+    }
+}
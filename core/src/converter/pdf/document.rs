@@ -0,0 +1,281 @@
+use crate::{
+    converter::{
+        page::{Image, Path, StandardFont, components, number},
+        pdf::content::Content,
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Resources used by the page content, named as the content stream refers
+/// to them.
+#[derive(Debug, Default)]
+pub struct Resources {
+    fonts: Vec<StandardFont>,
+    images: Vec<Image>,
+    patterns: Vec<Pattern>,
+    blend_modes: Vec<&'static str>,
+}
+
+/// Tiling pattern painting a brush.
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    /// Hatch lines on an 8x8 tile of device pixels, whose gaps are painted
+    /// with `background` in OPAQUE background mode.
+    Hatch {
+        style: HatchStyle,
+        color: ColorRef,
+        background: Option<ColorRef>,
+        pixel_size: f32,
+    },
+    /// The image named `image`, one image pixel per device pixel.
+    Image { image: String, width: usize, height: usize, pixel_size: f32 },
+}
+
+impl Resources {
+    /// Name of `font`, added unless it is in use already.
+    pub fn font(&mut self, font: StandardFont) -> String {
+        let index =
+            self.fonts.iter().position(|v| *v == font).unwrap_or_else(|| {
+                self.fonts.push(font);
+                self.fonts.len() - 1
+            });
+
+        format!("F{index}")
+    }
+
+    pub fn image(&mut self, image: Image) -> String {
+        self.images.push(image);
+        format!("Im{}", self.images.len() - 1)
+    }
+
+    pub fn pattern(&mut self, pattern: Pattern) -> String {
+        self.patterns.push(pattern);
+        format!("P{}", self.patterns.len() - 1)
+    }
+
+    /// Name of a graphics state selecting the blend mode `blend_mode`.
+    pub fn blend_mode(&mut self, blend_mode: &'static str) -> String {
+        let index = self
+            .blend_modes
+            .iter()
+            .position(|v| *v == blend_mode)
+            .unwrap_or_else(|| {
+                self.blend_modes.push(blend_mode);
+                self.blend_modes.len() - 1
+            });
+
+        format!("GS{index}")
+    }
+}
+
+/// Write a document of one page, `width` by `height` points, showing
+/// `content` through the page transform `matrix`.
+pub fn write(
+    resources: Resources,
+    content: &Content,
+    (width, height): (f32, f32),
+    matrix: [f32; 6],
+) -> Vec<u8> {
+    let Resources { fonts, images, patterns, blend_modes } = resources;
+    // Objects 1 to 4 are the catalog, the page tree, the page and its
+    // content, followed by the resources in the order of the fields.
+    let first_font = 5;
+    let first_image = first_font + fonts.len();
+    let first_pattern = first_image + images.len();
+    let first_state = first_pattern + patterns.len();
+    let names = |prefix: &str, first: usize, count: usize| -> String {
+        use core::fmt::Write;
+
+        (0..count).fold(String::new(), |mut names, i| {
+            let _ = write!(names, " /{prefix}{i} {} 0 R", first + i);
+            names
+        })
+    };
+    let images_dictionary =
+        format!("<<{} >>", names("Im", first_image, images.len()));
+
+    let mut writer = Writer::default();
+
+    writer.object("<< /Type /Catalog /Pages 2 0 R >>");
+    writer.object("<< /Type /Pages /Kids [3 0 R] /Count 1 >>");
+    writer.object(&format!(
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << \
+         /Font <<{} >> /XObject {images_dictionary} /Pattern <<{} >> \
+         /ExtGState <<{} >> >> /Contents 4 0 R >>",
+        number(width),
+        number(height),
+        names("F", first_font, fonts.len()),
+        names("P", first_pattern, patterns.len()),
+        names("GS", first_state, blend_modes.len()),
+    ));
+
+    let page = Content::new().push(&matrix, "cm").append(content).into_bytes();
+
+    writer.stream("", &page);
+
+    for font in fonts {
+        let encoding =
+            if font.is_symbolic() { "" } else { " /Encoding /WinAnsiEncoding" };
+
+        writer.object(&format!(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /{}{encoding} >>",
+            font.base_font(),
+        ));
+    }
+
+    for image in &images {
+        writer.stream(&image.dictionary(), image.data());
+    }
+
+    for pattern in patterns {
+        let (tile, (width, height), pixel_size) = match pattern {
+            Pattern::Hatch { style, color, background, pixel_size } => {
+                (hatch(style, &color, background.as_ref()), (8, 8), pixel_size)
+            }
+            Pattern::Image { image, width, height, pixel_size } => {
+                // Image space has its first row at the top of the unit
+                // square, which the y-down tile space turns upside down.
+                let tile = Content::new()
+                    .raw(format!("{width} 0 0 -{height} 0 {height} cm"))
+                    .raw(format!("/{image} Do"));
+
+                (tile, (width, height), pixel_size)
+            }
+        };
+        // Pattern space is mapped from the default coordinate space of
+        // the page, so the tile goes through the page transform itself.
+        let mut pattern_matrix = matrix;
+
+        pattern_matrix[0] *= pixel_size;
+        pattern_matrix[3] *= pixel_size;
+
+        let pattern_matrix = pattern_matrix.map(number).join(" ");
+
+        writer.stream(
+            &format!(
+                "/Type /Pattern /PatternType 1 /PaintType 1 /TilingType 1 \
+                 /BBox [0 0 {width} {height}] /XStep {width} /YStep {height} \
+                 /Matrix [{pattern_matrix}] /Resources << /XObject \
+                 {images_dictionary} >>",
+            ),
+            &tile.into_bytes(),
+        );
+    }
+
+    for blend_mode in blend_modes {
+        writer.object(&format!("<< /Type /ExtGState /BM /{blend_mode} >>"));
+    }
+
+    writer.finish()
+}
+
+/// Tile of a hatched brush, in device pixels.
+fn hatch(
+    style: HatchStyle,
+    color: &ColorRef,
+    background: Option<&ColorRef>,
+) -> Content {
+    // Lines run past the tile so that diagonals join across tiles; the
+    // bounding box clips the overhang.
+    type Line = ((f32, f32), (f32, f32));
+
+    let lines: &[Line] = match style {
+        HatchStyle::HS_HORIZONTAL => &[((-1.0, 0.5), (9.0, 0.5))],
+        HatchStyle::HS_VERTICAL => &[((0.5, -1.0), (0.5, 9.0))],
+        HatchStyle::HS_FDIAGONAL => &[((-1.0, -1.0), (9.0, 9.0))],
+        HatchStyle::HS_BDIAGONAL => &[((-1.0, 9.0), (9.0, -1.0))],
+        HatchStyle::HS_CROSS => {
+            &[((-1.0, 0.5), (9.0, 0.5)), ((0.5, -1.0), (0.5, 9.0))]
+        }
+        HatchStyle::HS_DIAGCROSS => {
+            &[((-1.0, -1.0), (9.0, 9.0)), ((-1.0, 9.0), (9.0, -1.0))]
+        }
+    };
+    let mut tile = Content::new();
+
+    if let Some(background) = background {
+        tile = tile
+            .fill_color(background)
+            .rect((0.0, 0.0), (8.0, 8.0))
+            .push(&[], "f");
+    }
+
+    let (r, g, b) = components(color);
+
+    tile = tile.push(&[r, g, b], "RG").push(&[1.0], "w");
+
+    for (from, to) in lines {
+        tile = tile.move_to(*from).line_to(*to);
+    }
+
+    tile.push(&[], "S")
+}
+
+/// Serialises indirect objects and the cross-reference table.
+struct Writer {
+    buffer: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        // The comment of high-bit bytes marks the file as binary.
+        Self {
+            buffer: b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+            offsets: vec![],
+        }
+    }
+}
+
+impl Writer {
+    fn object(&mut self, body: &str) {
+        self.begin();
+        self.buffer.extend_from_slice(body.as_bytes());
+        self.buffer.extend_from_slice(b"\nendobj\n");
+    }
+
+    /// Write a stream object with the dictionary `entries` and `data`.
+    fn stream(&mut self, entries: &str, data: &[u8]) {
+        let separator = if entries.is_empty() { "" } else { " " };
+
+        self.begin();
+        self.buffer.extend_from_slice(
+            format!(
+                "<< {entries}{separator}/Length {} >>\nstream\n",
+                data.len()
+            )
+            .as_bytes(),
+        );
+        self.buffer.extend_from_slice(data);
+        self.buffer.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    fn begin(&mut self) {
+        self.offsets.push(self.buffer.len());
+        self.buffer.extend_from_slice(
+            format!("{} 0 obj\n", self.offsets.len()).as_bytes(),
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        use core::fmt::Write;
+
+        let start = self.buffer.len();
+        let size = self.offsets.len() + 1;
+        let mut table = format!("xref\n0 {size}\n0000000000 65535 f \n");
+
+        for offset in &self.offsets {
+            let _ = writeln!(table, "{offset:010} 00000 n ");
+        }
+
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {size} /Root 1 0 R \
+             >>\nstartxref\n{start}\n%%EOF\n",
+        );
+
+        self.buffer.extend_from_slice(table.as_bytes());
+        self.buffer
+    }
+}
@@ -0,0 +1,20 @@
+mod content;
+mod device;
+mod document;
+
+pub use self::device::Pdf;
+use crate::converter::drawing::Drawing;
+
+/// Player writing the metafile as a PDF document of one page.
+///
+/// Drawing records become vector paths, bitmaps become image XObjects and
+/// text is set in the standard 14 fonts that every PDF reader provides.
+/// The page is the META_PLACEABLE bounding box at its resolution, or the
+/// area of the drawing at 96 DPI without one.
+pub type PdfPlayer = Drawing<Pdf>;
+
+impl PdfPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
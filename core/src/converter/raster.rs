@@ -0,0 +1,506 @@
+// Pixel coordinates stay far below 2^23, so usize/i32 to f32 casts are
+// exact in practice.
+#![allow(clippy::cast_precision_loss)]
+
+use crate::{imports::*, parser::*};
+
+/// Upper bound on the number of pixels a scratch canvas allocates. Larger
+/// drawings are rasterised at a reduced scale so hostile extents cannot
+/// exhaust memory.
+const MAX_PIXELS: usize = 2048 * 2048;
+
+/// Number of line segments used to approximate a full ellipse.
+const ELLIPSE_SEGMENTS: usize = 64;
+
+/// Pack a ColorRef into a `0x00RRGGBB` value for the scratch canvas.
+pub fn rgb(c: &ColorRef) -> u32 {
+    (u32::from(c.red) << 16) | (u32::from(c.green) << 8) | u32::from(c.blue)
+}
+
+/// A device-space primitive recorded while drawing so that pixel-based
+/// records such as META_FLOODFILL can be evaluated later.
+#[derive(Clone, Debug)]
+pub enum Shape {
+    /// Closed outline, optionally filled and stroked.
+    Polygon {
+        points: Vec<(f32, f32)>,
+        winding: bool,
+        fill: Option<u32>,
+        stroke: Option<(u32, f32)>,
+    },
+    /// Open outline that is only stroked.
+    Polyline { points: Vec<(f32, f32)>, color: u32, width: f32 },
+    /// Axis-aligned rectangles as `(left, top, right, bottom)`.
+    Rects { rects: Vec<(f32, f32, f32, f32)>, color: u32 },
+}
+
+impl Shape {
+    /// Approximate the elliptical arc from `start` to `end` (radians,
+    /// measured with y pointing up) traversed counter-clockwise as seen on
+    /// a y-down device surface.
+    pub fn arc_points(
+        center: (f32, f32),
+        rx: f32,
+        ry: f32,
+        start: f32,
+        end: f32,
+    ) -> Vec<(f32, f32)> {
+        let mut sweep = end - start;
+        if sweep <= 0.0 {
+            sweep += core::f32::consts::TAU;
+        }
+
+        let steps = ((sweep / core::f32::consts::TAU * ELLIPSE_SEGMENTS as f32)
+            .ceil() as usize)
+            .max(1);
+
+        (0..=steps)
+            .map(|i| {
+                let t = start + sweep * (i as f32) / (steps as f32);
+                (center.0 + rx * t.cos(), center.1 - ry * t.sin())
+            })
+            .collect()
+    }
+
+    /// Approximate a full ellipse as a closed polygon.
+    pub fn ellipse_points(
+        center: (f32, f32),
+        rx: f32,
+        ry: f32,
+    ) -> Vec<(f32, f32)> {
+        let mut points =
+            Self::arc_points(center, rx, ry, 0.0, core::f32::consts::TAU);
+        points.pop();
+        points
+    }
+
+    /// Number of points the shape holds, counting both corners of each
+    /// rectangle.
+    pub fn point_count(&self) -> usize {
+        match self {
+            Shape::Polygon { points, .. } | Shape::Polyline { points, .. } => {
+                points.len()
+            }
+            Shape::Rects { rects, .. } => rects.len() * 2,
+        }
+    }
+}
+
+/// Non-antialiased RGB scratch surface. Antialiasing is deliberately
+/// avoided so that flood fills do not leak through blended edge pixels.
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    x: f32,
+    y: f32,
+    scale: f32,
+    width: usize,
+    height: usize,
+    pixels: Vec<u32>,
+}
+
+impl Canvas {
+    /// Create a canvas covering the given device rectangle, or `None` when
+    /// the rectangle is empty.
+    pub fn new(
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        background: u32,
+    ) -> Option<Self> {
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        let area = (width as f32) * (height as f32);
+        let scale = (MAX_PIXELS as f32 / area).sqrt().min(1.0);
+        let w = ((width as f32 * scale).ceil() as usize).max(1);
+        let h = ((height as f32 * scale).ceil() as usize).max(1);
+
+        Some(Self {
+            x: x as f32,
+            y: y as f32,
+            scale,
+            width: w,
+            height: h,
+            pixels: vec![background; w * h],
+        })
+    }
+
+    /// Number of pixels of the canvas, which a flood fill scans.
+    pub fn pixel_count(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Paint `shape` and return the work it took, counted as the rows its
+    /// edges were tested against.
+    pub fn paint(&mut self, shape: &Shape) -> usize {
+        match shape {
+            Shape::Polygon { points, winding, fill, stroke } => {
+                let mut work = 0;
+
+                if let Some(color) = fill {
+                    work += self.fill_polygon(points, *winding, *color);
+                }
+
+                if let Some((color, width)) = stroke {
+                    work += self.stroke_polyline(points, true, *width, *color);
+                }
+
+                work
+            }
+            Shape::Polyline { points, color, width } => {
+                self.stroke_polyline(points, false, *width, *color)
+            }
+            Shape::Rects { rects, color } => rects
+                .iter()
+                .map(|&(l, t, r, b)| {
+                    self.fill_polygon(
+                        &[(l, t), (r, t), (r, b), (l, b)],
+                        true,
+                        *color,
+                    )
+                })
+                .sum(),
+        }
+    }
+
+    /// Compute the area reached by a flood fill starting at the device
+    /// point `(x, y)`, returned as device-space rectangles.
+    ///
+    /// FLOODFILLBORDER spreads over every pixel that is not `color`;
+    /// FLOODFILLSURFACE spreads only over pixels that are `color`.
+    pub fn flood_fill(
+        &self,
+        x: f32,
+        y: f32,
+        mode: FloodFill,
+        color: u32,
+    ) -> Vec<(f32, f32, f32, f32)> {
+        let px = ((x - self.x) * self.scale).floor();
+        let py = ((y - self.y) * self.scale).floor();
+
+        if px < 0.0
+            || py < 0.0
+            || px >= self.width as f32
+            || py >= self.height as f32
+        {
+            return vec![];
+        }
+
+        let fillable = |v: u32| match mode {
+            FloodFill::FLOODFILLBORDER => v != color,
+            FloodFill::FLOODFILLSURFACE => v == color,
+        };
+
+        let (px, py) = (px as usize, py as usize);
+        if !fillable(self.pixels[py * self.width + px]) {
+            return vec![];
+        }
+
+        let mut filled = vec![false; self.pixels.len()];
+        let mut stack = vec![(px, py)];
+
+        while let Some((sx, sy)) = stack.pop() {
+            let row = sy * self.width;
+            if filled[row + sx] {
+                continue;
+            }
+
+            let mut left = sx;
+            while left > 0
+                && !filled[row + left - 1]
+                && fillable(self.pixels[row + left - 1])
+            {
+                left -= 1;
+            }
+
+            let mut right = sx;
+            while right + 1 < self.width
+                && !filled[row + right + 1]
+                && fillable(self.pixels[row + right + 1])
+            {
+                right += 1;
+            }
+
+            for i in left..=right {
+                filled[row + i] = true;
+            }
+
+            for ny in [sy.checked_sub(1), Some(sy + 1)] {
+                let Some(ny) = ny.filter(|v| *v < self.height) else {
+                    continue;
+                };
+
+                let nrow = ny * self.width;
+                let mut i = left;
+                while i <= right {
+                    if !filled[nrow + i] && fillable(self.pixels[nrow + i]) {
+                        stack.push((i, ny));
+                        while i <= right
+                            && !filled[nrow + i]
+                            && fillable(self.pixels[nrow + i])
+                        {
+                            i += 1;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        self.mask_to_rects(&filled)
+    }
+
+    /// Merge horizontal runs of set pixels into rectangles, extending a
+    /// rectangle downwards while the next row repeats the same run.
+    fn mask_to_rects(&self, mask: &[bool]) -> Vec<(f32, f32, f32, f32)> {
+        let mut done = vec![];
+        let mut open: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+
+        for y in 0..self.height {
+            let row = &mask[y * self.width..(y + 1) * self.width];
+            let mut runs = BTreeMap::new();
+            let mut x = 0;
+
+            while x < self.width {
+                if row[x] {
+                    let start = x;
+                    while x < self.width && row[x] {
+                        x += 1;
+                    }
+                    let top = open.remove(&(start, x)).unwrap_or(y);
+                    runs.insert((start, x), top);
+                } else {
+                    x += 1;
+                }
+            }
+
+            for ((l, r), top) in core::mem::replace(&mut open, runs) {
+                done.push((l, top, r, y));
+            }
+        }
+
+        for ((l, r), top) in open {
+            done.push((l, top, r, self.height));
+        }
+
+        done.into_iter()
+            .map(|(l, t, r, b)| {
+                (
+                    self.x + l as f32 / self.scale,
+                    self.y + t as f32 / self.scale,
+                    self.x + r as f32 / self.scale,
+                    self.y + b as f32 / self.scale,
+                )
+            })
+            .collect()
+    }
+
+    fn to_pixel(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        ((x - self.x) * self.scale, (y - self.y) * self.scale)
+    }
+
+    /// Scanline polygon fill sampling at pixel centres. Returns the rows
+    /// times the edges tested against them.
+    fn fill_polygon(
+        &mut self,
+        points: &[(f32, f32)],
+        winding: bool,
+        color: u32,
+    ) -> usize {
+        if points.len() < 3 {
+            return 0;
+        }
+
+        let points: Vec<_> = points.iter().map(|p| self.to_pixel(*p)).collect();
+        let (min_y, max_y) = points
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p.1), hi.max(p.1))
+            });
+        let first = (min_y - 0.5).ceil().max(0.0) as usize;
+        let last =
+            ((max_y - 0.5).floor().min(self.height as f32 - 1.0)).max(-1.0);
+
+        if last < 0.0 || (last as usize) < first {
+            return 0;
+        }
+
+        let mut crossings: Vec<(f32, i32)> = vec![];
+
+        for py in first..=(last as usize) {
+            let cy = py as f32 + 0.5;
+            crossings.clear();
+
+            for i in 0..points.len() {
+                let (x0, y0) = points[i];
+                let (x1, y1) = points[(i + 1) % points.len()];
+
+                if (y0 <= cy && cy < y1) || (y1 <= cy && cy < y0) {
+                    let x = x0 + (cy - y0) * (x1 - x0) / (y1 - y0);
+                    crossings.push((x, if y1 > y0 { 1 } else { -1 }));
+                }
+            }
+
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut count = 0;
+            for pair in crossings.windows(2) {
+                count += pair[0].1;
+                let inside = if winding { count != 0 } else { count % 2 != 0 };
+
+                if inside {
+                    self.fill_span(py, pair[0].0, pair[1].0, color);
+                }
+            }
+        }
+
+        (last as usize - first + 1) * points.len()
+    }
+
+    fn fill_span(&mut self, py: usize, x0: f32, x1: f32, color: u32) {
+        let start = (x0 - 0.5).ceil().max(0.0) as usize;
+        let end = (x1 - 0.5).ceil().min(self.width as f32);
+
+        if end <= 0.0 {
+            return;
+        }
+
+        let row = py * self.width;
+        for px in start..(end as usize) {
+            self.pixels[row + px] = color;
+        }
+    }
+
+    /// Stroke each segment as a quad of the pen width, plus a square at
+    /// every vertex so joins leave no gaps a flood fill could leak through.
+    /// Returns the work of the fills.
+    fn stroke_polyline(
+        &mut self,
+        points: &[(f32, f32)],
+        closed: bool,
+        width: f32,
+        color: u32,
+    ) -> usize {
+        if points.is_empty() {
+            return 0;
+        }
+
+        let half = width.max(1.0 / self.scale) / 2.0;
+        let count = if closed { points.len() } else { points.len() - 1 };
+        let mut work = 0;
+
+        for i in 0..count {
+            let (x0, y0) = points[i];
+            let (x1, y1) = points[(i + 1) % points.len()];
+            let (dx, dy) = (x1 - x0, y1 - y0);
+            let len = (dx * dx + dy * dy).sqrt();
+
+            if len > f32::EPSILON {
+                let (nx, ny) = (-dy / len * half, dx / len * half);
+                work += self.fill_polygon(
+                    &[
+                        (x0 + nx, y0 + ny),
+                        (x1 + nx, y1 + ny),
+                        (x1 - nx, y1 - ny),
+                        (x0 - nx, y0 - ny),
+                    ],
+                    true,
+                    color,
+                );
+            }
+        }
+
+        for &(x, y) in points {
+            work += self.fill_polygon(
+                &[
+                    (x - half, y - half),
+                    (x + half, y - half),
+                    (x + half, y + half),
+                    (x - half, y + half),
+                ],
+                true,
+                color,
+            );
+        }
+
+        work
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: u32 = 0x00FF_FFFF;
+    const BLACK: u32 = 0x0000_0000;
+
+    fn square(l: f32, t: f32, r: f32, b: f32) -> Vec<(f32, f32)> {
+        vec![(l, t), (r, t), (r, b), (l, b)]
+    }
+
+    #[test]
+    fn flood_fill_border_stops_at_outline() {
+        let mut canvas = Canvas::new(0, 0, 100, 100, WHITE).unwrap();
+        canvas.paint(&Shape::Polygon {
+            points: square(10.0, 10.0, 50.0, 50.0),
+            winding: false,
+            fill: None,
+            stroke: Some((BLACK, 1.0)),
+        });
+
+        let rects =
+            canvas.flood_fill(30.0, 30.0, FloodFill::FLOODFILLBORDER, BLACK);
+        let area: f32 =
+            rects.iter().map(|(l, t, r, b)| (r - l) * (b - t)).sum();
+
+        assert!(!rects.is_empty());
+        assert!(area > 30.0 * 30.0 && area < 40.0 * 40.0, "area = {area}");
+        assert!(rects.iter().all(|(l, t, r, b)| {
+            *l >= 10.0 && *t >= 10.0 && *r <= 50.0 && *b <= 50.0
+        }));
+    }
+
+    #[test]
+    fn flood_fill_surface_covers_matching_color() {
+        let mut canvas = Canvas::new(0, 0, 100, 100, WHITE).unwrap();
+        canvas.paint(&Shape::Rects {
+            rects: vec![(20.0, 20.0, 40.0, 30.0)],
+            color: 0x00FF_0000,
+        });
+
+        let rects = canvas.flood_fill(
+            25.0,
+            25.0,
+            FloodFill::FLOODFILLSURFACE,
+            0x00FF_0000,
+        );
+
+        assert_eq!(rects, vec![(20.0, 20.0, 40.0, 30.0)]);
+    }
+
+    #[test]
+    fn flood_fill_rejects_start_outside_condition() {
+        let canvas = Canvas::new(0, 0, 10, 10, WHITE).unwrap();
+
+        assert!(
+            canvas
+                .flood_fill(5.0, 5.0, FloodFill::FLOODFILLBORDER, WHITE)
+                .is_empty()
+        );
+        assert!(
+            canvas
+                .flood_fill(50.0, 5.0, FloodFill::FLOODFILLSURFACE, WHITE)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn canvas_scales_down_large_extents() {
+        let canvas = Canvas::new(0, 0, 32767, 32767, WHITE).unwrap();
+
+        assert!(canvas.width * canvas.height <= MAX_PIXELS + 2 * 2048 + 1);
+        assert!(Canvas::new(0, 0, 0, 10, WHITE).is_none());
+    }
+}
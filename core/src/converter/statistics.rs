@@ -0,0 +1,1417 @@
+use core::fmt;
+
+use crate::{
+    converter::{Diagnostic, PlayError},
+    imports::*,
+    parser::*,
+};
+
+/// Format of a bitmap embedded in a record.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum BitmapFormat {
+    /// Device dependent [`Bitmap16`] of `META_BITBLT` and `META_STRETCHBLT`,
+    /// which is never compressed.
+    Bitmap16 { bit_count: BitCount },
+    /// [`DeviceIndependentBitmap`] of the DIB records and pattern brushes.
+    DeviceIndependent { bit_count: BitCount, compression: Compression },
+}
+
+impl fmt::Display for BitmapFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bitmap16 { bit_count } => {
+                write!(f, "Bitmap16 {} bpp", *bit_count as u16)
+            }
+            Self::DeviceIndependent { bit_count, compression } => {
+                write!(f, "DIB {} bpp {compression:?}", *bit_count as u16)
+            }
+        }
+    }
+}
+
+/// Records and GDI features used by a metafile, collected by
+/// [`StatisticsPlayer`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Statistics {
+    /// Number of records of each type. The header is not a record and is
+    /// not counted.
+    pub records: BTreeMap<RecordType, usize>,
+    /// Number of `META_ESCAPE` records of each escape function. Escapes
+    /// that cannot be parsed are dropped before reaching the player and
+    /// are counted in neither `records` nor `escapes`.
+    pub escapes: BTreeMap<MetafileEscapes, usize>,
+    /// Size of the object table declared by the header.
+    pub number_of_objects: u16,
+    /// Most objects that existed at the same time, which exceeds
+    /// `number_of_objects` when the header understates the object table.
+    pub objects_high_water_mark: usize,
+    /// Number of bitmaps of each format, including pattern brushes.
+    pub bitmaps: BTreeMap<BitmapFormat, usize>,
+    pub map_modes: BTreeSet<MapMode>,
+    /// Raster operations set by `META_SETROP2`.
+    pub binary_raster_operations: BTreeSet<BinaryRasterOperation>,
+    /// Raster operations of the bitmap records and `META_PATBLT`.
+    pub ternary_raster_operations: BTreeSet<TernaryRasterOperation>,
+    /// Character sets of the fonts created.
+    pub charsets: BTreeSet<CharacterSet>,
+    /// Records the SVG player approximates or skips, filled in by
+    /// [`analyze`] only when [`AnalyzeOptions::svg_diagnostics`] is set.
+    pub svg_diagnostics: Vec<Diagnostic>,
+    /// Error the SVG player stopped with, after the diagnostics of the
+    /// records before it.
+    pub svg_error: Option<String>,
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list<T: fmt::Debug>(
+            f: &mut fmt::Formatter<'_>,
+            name: &str,
+            values: &BTreeSet<T>,
+        ) -> fmt::Result {
+            write!(f, "{name}:")?;
+
+            if values.is_empty() {
+                write!(f, " none")?;
+            }
+
+            for value in values {
+                write!(f, " {value:?}")?;
+            }
+
+            writeln!(f)
+        }
+
+        writeln!(f, "records:")?;
+        for (record_type, count) in &self.records {
+            writeln!(f, "  {record_type:?}: {count}")?;
+        }
+
+        writeln!(f, "escapes:")?;
+        for (escape, count) in &self.escapes {
+            writeln!(f, "  {escape:?}: {count}")?;
+        }
+
+        writeln!(
+            f,
+            "objects: {} used of {} declared",
+            self.objects_high_water_mark, self.number_of_objects,
+        )?;
+
+        writeln!(f, "bitmaps:")?;
+        for (format, count) in &self.bitmaps {
+            writeln!(f, "  {format}: {count}")?;
+        }
+
+        list(f, "map modes", &self.map_modes)?;
+        list(f, "binary raster operations", &self.binary_raster_operations)?;
+        list(f, "ternary raster operations", &self.ternary_raster_operations)?;
+        list(f, "charsets", &self.charsets)?;
+
+        // The diagnostics are collected on request only, so their heading
+        // is left out without them.
+        if !self.svg_diagnostics.is_empty() {
+            writeln!(f, "svg diagnostics:")?;
+        }
+
+        for Diagnostic { record_number, record_type, kind, message } in
+            &self.svg_diagnostics
+        {
+            writeln!(
+                f,
+                "  #{record_number} {record_type:?} {kind:?}: {message}"
+            )?;
+        }
+
+        if let Some(error) = &self.svg_error {
+            writeln!(f, "svg error: {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Player collecting the [`Statistics`] of a metafile instead of rendering
+/// it. Its output is the report written by the `Display` implementation.
+///
+/// ```
+/// use wmf_core::converter::{StatisticsPlayer, WMFConverter};
+///
+/// # fn inspect(buffer: &[u8]) -> Result<(), wmf_core::converter::ConvertError> {
+/// let player = WMFConverter::new(buffer, StatisticsPlayer::new()).play()?;
+/// let statistics = player.statistics();
+///
+/// if statistics.objects_high_water_mark
+///     > usize::from(statistics.number_of_objects)
+/// {
+///     println!("the header understates the object table");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct StatisticsPlayer {
+    /// Slots of the object table, `true` while an object occupies them.
+    objects: Vec<bool>,
+    statistics: Statistics,
+}
+
+impl StatisticsPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn count(&mut self, record_type: RecordType) {
+        *self.statistics.records.entry(record_type).or_default() += 1;
+    }
+
+    fn bitmap(&mut self, format: BitmapFormat) {
+        *self.statistics.bitmaps.entry(format).or_default() += 1;
+    }
+
+    fn device_independent_bitmap(&mut self, dib: &DeviceIndependentBitmap) {
+        self.bitmap(BitmapFormat::DeviceIndependent {
+            bit_count: dib.dib_header_info.bit_count(),
+            compression: dib.dib_header_info.compression(),
+        });
+    }
+
+    /// Place an object in the lowest free slot as GDI does, growing the
+    /// table past the declared size rather than dropping the object.
+    fn create_object(&mut self) {
+        if let Some(slot) = self.objects.iter_mut().find(|used| !**used) {
+            *slot = true;
+        } else {
+            self.objects.push(true);
+        }
+
+        let used = self.objects.iter().filter(|used| **used).count();
+
+        self.statistics.objects_high_water_mark =
+            self.statistics.objects_high_water_mark.max(used);
+    }
+
+    fn free_object(&mut self, index: usize) {
+        if let Some(slot) = self.objects.get_mut(index) {
+            *slot = false;
+        }
+    }
+}
+
+/// What [`analyze`] collects besides the [`Statistics`] of the records.
+#[cfg(all(feature = "std", feature = "svg"))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnalyzeOptions {
+    /// Play the metafile through the SVG player as well, to collect the
+    /// records it approximates or skips into
+    /// [`Statistics::svg_diagnostics`]. This renders the whole metafile.
+    pub svg_diagnostics: bool,
+}
+
+/// Collect the [`Statistics`] of a metafile, and the records the SVG player
+/// would approximate or skip when `options` ask for them. A failure of the
+/// SVG player is kept in [`Statistics::svg_error`] rather than returned.
+#[cfg(all(feature = "std", feature = "svg"))]
+pub fn analyze(
+    buffer: &[u8],
+    options: AnalyzeOptions,
+) -> Result<Statistics, crate::converter::ConvertError> {
+    use std::sync::{Arc, Mutex};
+
+    use crate::converter::{SVGPlayer, WMFConverter};
+
+    let mut statistics =
+        WMFConverter::new(buffer, StatisticsPlayer::new()).play()?.statistics;
+
+    if !options.svg_diagnostics {
+        return Ok(statistics);
+    }
+
+    let diagnostics = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&diagnostics);
+    let player = SVGPlayer::new().diagnostic_sink(move |diagnostic| {
+        if let Ok(mut diagnostics) = sink.lock() {
+            diagnostics.push(diagnostic);
+        }
+    });
+
+    if let Err(error) = WMFConverter::new(buffer, player).play() {
+        statistics.svg_error = Some(error.to_string());
+    }
+
+    if let Ok(mut diagnostics) = diagnostics.lock() {
+        statistics.svg_diagnostics = core::mem::take(&mut *diagnostics);
+    }
+
+    Ok(statistics)
+}
+
+impl crate::converter::Player for StatisticsPlayer {
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn generate(self) -> Result<Vec<u8>, PlayError> {
+        Ok(self.statistics.to_string().into_bytes())
+    }
+
+    // .
+    // .
+    // Functions to handle Bitmap Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn bit_blt(
+        mut self,
+        _: usize,
+        record: META_BITBLT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_BITBLT);
+
+        match record {
+            META_BITBLT::WithBitmap { raster_operation, target, .. } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+                self.bitmap(BitmapFormat::Bitmap16 {
+                    bit_count: target.bits_pixel,
+                });
+            }
+            META_BITBLT::WithoutBitmap { raster_operation, .. } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_bit_blt(
+        mut self,
+        _: usize,
+        record: META_DIBBITBLT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_DIBBITBLT);
+
+        match record {
+            META_DIBBITBLT::WithBitmap { raster_operation, target, .. } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+                self.device_independent_bitmap(&target);
+            }
+            META_DIBBITBLT::WithoutBitmap { raster_operation, .. } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn device_independent_bitmap_stretch_blt(
+        mut self,
+        _: usize,
+        record: META_DIBSTRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_DIBSTRETCHBLT);
+
+        match record {
+            META_DIBSTRETCHBLT::WithBitmap {
+                raster_operation, target, ..
+            } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+                self.device_independent_bitmap(&target);
+            }
+            META_DIBSTRETCHBLT::WithoutBitmap { raster_operation, .. } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_device_independent_bitmap_to_dev(
+        mut self,
+        _: usize,
+        record: META_SETDIBTODEV,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETDIBTODEV);
+        self.device_independent_bitmap(&record.dib);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_blt(
+        mut self,
+        _: usize,
+        record: META_STRETCHBLT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_STRETCHBLT);
+
+        match record {
+            META_STRETCHBLT::WithBitmap {
+                raster_operation, target, ..
+            } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+                self.bitmap(BitmapFormat::Bitmap16 {
+                    bit_count: target.bits_pixel,
+                });
+            }
+            META_STRETCHBLT::WithoutBitmap { raster_operation, .. } => {
+                self.statistics
+                    .ternary_raster_operations
+                    .insert(raster_operation);
+            }
+        }
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn stretch_device_independent_bitmap(
+        mut self,
+        _: usize,
+        record: META_STRETCHDIB,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_STRETCHDIB);
+        self.statistics
+            .ternary_raster_operations
+            .insert(record.raster_operation);
+        self.device_independent_bitmap(&record.dib);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Control Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn eof(mut self, _: usize, _: META_EOF) -> Result<Self, PlayError> {
+        self.count(RecordType::META_EOF);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn header(
+        mut self,
+        _: usize,
+        header: MetafileHeader,
+    ) -> Result<Self, PlayError> {
+        let (MetafileHeader::StartsWithPlaceable(_, header)
+        | MetafileHeader::StartsWithHeader(header)) = header;
+
+        self.statistics.number_of_objects = header.number_of_objects;
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Drawing Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn arc(mut self, _: usize, _: META_ARC) -> Result<Self, PlayError> {
+        self.count(RecordType::META_ARC);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn chord(mut self, _: usize, _: META_CHORD) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CHORD);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ellipse(mut self, _: usize, _: META_ELLIPSE) -> Result<Self, PlayError> {
+        self.count(RecordType::META_ELLIPSE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_flood_fill(
+        mut self,
+        _: usize,
+        _: META_EXTFLOODFILL,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_EXTFLOODFILL);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn ext_text_out(
+        mut self,
+        _: usize,
+        _: META_EXTTEXTOUT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_EXTTEXTOUT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn fill_region(
+        mut self,
+        _: usize,
+        _: META_FILLREGION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_FILLREGION);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn flood_fill(
+        mut self,
+        _: usize,
+        _: META_FLOODFILL,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_FLOODFILL);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn frame_region(
+        mut self,
+        _: usize,
+        _: META_FRAMEREGION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_FRAMEREGION);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn invert_region(
+        mut self,
+        _: usize,
+        _: META_INVERTREGION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_INVERTREGION);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn line_to(mut self, _: usize, _: META_LINETO) -> Result<Self, PlayError> {
+        self.count(RecordType::META_LINETO);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn paint_region(
+        mut self,
+        _: usize,
+        _: META_PAINTREGION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_PAINTREGION);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pat_blt(
+        mut self,
+        _: usize,
+        record: META_PATBLT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_PATBLT);
+        self.statistics
+            .ternary_raster_operations
+            .insert(record.raster_operation);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn pie(mut self, _: usize, _: META_PIE) -> Result<Self, PlayError> {
+        self.count(RecordType::META_PIE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polyline(
+        mut self,
+        _: usize,
+        _: META_POLYLINE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_POLYLINE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn polygon(mut self, _: usize, _: META_POLYGON) -> Result<Self, PlayError> {
+        self.count(RecordType::META_POLYGON);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn poly_polygon(
+        mut self,
+        _: usize,
+        _: META_POLYPOLYGON,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_POLYPOLYGON);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn rectangle(
+        mut self,
+        _: usize,
+        _: META_RECTANGLE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_RECTANGLE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn round_rect(
+        mut self,
+        _: usize,
+        _: META_ROUNDRECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_ROUNDRECT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pixel(
+        mut self,
+        _: usize,
+        _: META_SETPIXEL,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETPIXEL);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn text_out(
+        mut self,
+        _: usize,
+        _: META_TEXTOUT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_TEXTOUT);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Object Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_brush_indirect(
+        mut self,
+        _: usize,
+        _: META_CREATEBRUSHINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CREATEBRUSHINDIRECT);
+        self.create_object();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_font_indirect(
+        mut self,
+        _: usize,
+        record: META_CREATEFONTINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CREATEFONTINDIRECT);
+        self.create_object();
+        self.statistics.charsets.insert(record.font.charset);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_palette(
+        mut self,
+        _: usize,
+        _: META_CREATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CREATEPALETTE);
+        self.create_object();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pattern_brush(
+        mut self,
+        _: usize,
+        _: META_CREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CREATEPATTERNBRUSH);
+        self.create_object();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_pen_indirect(
+        mut self,
+        _: usize,
+        _: META_CREATEPENINDIRECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CREATEPENINDIRECT);
+        self.create_object();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_region(
+        mut self,
+        _: usize,
+        _: META_CREATEREGION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_CREATEREGION);
+        self.create_object();
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn delete_object(
+        mut self,
+        _: usize,
+        record: META_DELETEOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_DELETEOBJECT);
+        self.free_object(usize::from(record.object_index));
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn create_device_independent_bitmap_pattern_brush(
+        mut self,
+        _: usize,
+        record: META_DIBCREATEPATTERNBRUSH,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_DIBCREATEPATTERNBRUSH);
+        self.create_object();
+        self.device_independent_bitmap(&record.target);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_clip_region(
+        mut self,
+        _: usize,
+        _: META_SELECTCLIPREGION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SELECTCLIPREGION);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_object(
+        mut self,
+        _: usize,
+        _: META_SELECTOBJECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SELECTOBJECT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn select_palette(
+        mut self,
+        _: usize,
+        _: META_SELECTPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SELECTPALETTE);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle State Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn animate_palette(
+        mut self,
+        _: usize,
+        _: META_ANIMATEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_ANIMATEPALETTE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn exclude_clip_rect(
+        mut self,
+        _: usize,
+        _: META_EXCLUDECLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_EXCLUDECLIPRECT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn intersect_clip_rect(
+        mut self,
+        _: usize,
+        _: META_INTERSECTCLIPRECT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_INTERSECTCLIPRECT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn move_to(mut self, _: usize, _: META_MOVETO) -> Result<Self, PlayError> {
+        self.count(RecordType::META_MOVETO);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_clip_region(
+        mut self,
+        _: usize,
+        _: META_OFFSETCLIPRGN,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_OFFSETCLIPRGN);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_viewport_origin(
+        mut self,
+        _: usize,
+        _: META_OFFSETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_OFFSETVIEWPORTORG);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn offset_window_origin(
+        mut self,
+        _: usize,
+        _: META_OFFSETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_OFFSETWINDOWORG);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn realize_palette(
+        mut self,
+        _: usize,
+        _: META_REALIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_REALIZEPALETTE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn resize_palette(
+        mut self,
+        _: usize,
+        _: META_RESIZEPALETTE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_RESIZEPALETTE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn restore_device_context(
+        mut self,
+        _: usize,
+        _: META_RESTOREDC,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_RESTOREDC);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn save_device_context(
+        mut self,
+        _: usize,
+        _: META_SAVEDC,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SAVEDC);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_viewport_ext(
+        mut self,
+        _: usize,
+        _: META_SCALEVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SCALEVIEWPORTEXT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn scale_window_ext(
+        mut self,
+        _: usize,
+        _: META_SCALEWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SCALEWINDOWEXT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_color(
+        mut self,
+        _: usize,
+        _: META_SETBKCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETBKCOLOR);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_bk_mode(
+        mut self,
+        _: usize,
+        _: META_SETBKMODE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETBKMODE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_layout(
+        mut self,
+        _: usize,
+        _: META_SETLAYOUT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETLAYOUT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_map_mode(
+        mut self,
+        _: usize,
+        record: META_SETMAPMODE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETMAPMODE);
+        self.statistics.map_modes.insert(record.map_mode);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_mapper_flags(
+        mut self,
+        _: usize,
+        _: META_SETMAPPERFLAGS,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETMAPPERFLAGS);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_pal_entries(
+        mut self,
+        _: usize,
+        _: META_SETPALENTRIES,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETPALENTRIES);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_polyfill_mode(
+        mut self,
+        _: usize,
+        _: META_SETPOLYFILLMODE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETPOLYFILLMODE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_relabs(
+        mut self,
+        _: usize,
+        _: META_SETRELABS,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETRELABS);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_raster_operation(
+        mut self,
+        _: usize,
+        record: META_SETROP2,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETROP2);
+        self.statistics.binary_raster_operations.insert(record.draw_mode);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_stretch_blt_mode(
+        mut self,
+        _: usize,
+        _: META_SETSTRETCHBLTMODE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETSTRETCHBLTMODE);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_align(
+        mut self,
+        _: usize,
+        _: META_SETTEXTALIGN,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETTEXTALIGN);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_char_extra(
+        mut self,
+        _: usize,
+        _: META_SETTEXTCHAREXTRA,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETTEXTCHAREXTRA);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_color(
+        mut self,
+        _: usize,
+        _: META_SETTEXTCOLOR,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETTEXTCOLOR);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_text_justification(
+        mut self,
+        _: usize,
+        _: META_SETTEXTJUSTIFICATION,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETTEXTJUSTIFICATION);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_ext(
+        mut self,
+        _: usize,
+        _: META_SETVIEWPORTEXT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETVIEWPORTEXT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_viewport_origin(
+        mut self,
+        _: usize,
+        _: META_SETVIEWPORTORG,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETVIEWPORTORG);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_ext(
+        mut self,
+        _: usize,
+        _: META_SETWINDOWEXT,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETWINDOWEXT);
+
+        Ok(self)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn set_window_origin(
+        mut self,
+        _: usize,
+        _: META_SETWINDOWORG,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_SETWINDOWORG);
+
+        Ok(self)
+    }
+
+    // .
+    // .
+    // Functions to handle Escape Record
+    // .
+    // .
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        level = tracing::Level::TRACE,
+        skip(self),
+        err(level = tracing::Level::ERROR, Display),
+    ))]
+    fn escape(
+        mut self,
+        _: usize,
+        record: META_ESCAPE,
+    ) -> Result<Self, PlayError> {
+        self.count(RecordType::META_ESCAPE);
+        *self
+            .statistics
+            .escapes
+            .entry(record.escape_function())
+            .or_default() += 1;
+
+        Ok(self)
+    }
+}
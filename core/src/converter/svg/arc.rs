@@ -0,0 +1,56 @@
+use crate::{
+    converter::{EllipticalArc, raster::Shape, svg::node::Data},
+    imports::*,
+};
+
+impl EllipticalArc {
+    /// Append the arc to `data`, whose current point is the start point.
+    pub fn arc_to(&self, data: Data) -> Data {
+        use core::f32::consts::{PI, TAU};
+
+        let (rx, ry) = self.radii();
+        let sweep = u8::from(self.sweep() > 0.0);
+        let start = self.start();
+
+        if self.sweep().abs() >= TAU {
+            // An SVG arc between identical points draws nothing, so go
+            // round in two halves.
+            let center = self.center();
+            let opposite = (2.0 * center.0 - start.0, 2.0 * center.1 - start.1);
+
+            return data
+                .elliptical_arc_to(format!(
+                    "{rx} {ry} 0 0 {sweep} {} {}",
+                    opposite.0, opposite.1,
+                ))
+                .elliptical_arc_to(format!(
+                    "{rx} {ry} 0 0 {sweep} {} {}",
+                    start.0, start.1,
+                ));
+        }
+
+        let large_arc = u8::from(self.sweep().abs() > PI);
+        let end = self.end();
+
+        data.elliptical_arc_to(format!(
+            "{rx} {ry} 0 {large_arc} {sweep} {} {}",
+            end.0, end.1,
+        ))
+    }
+
+    /// Points along the arc, for the scratch canvas.
+    pub fn points(&self) -> Vec<(f32, f32)> {
+        // Shape::arc_points measures angles with the y-axis pointing up and
+        // runs counter-clockwise on the page.
+        let (rx, ry) = self.radii();
+        let start = -self.start_angle() - self.sweep().max(0.0);
+
+        Shape::arc_points(
+            self.center(),
+            rx,
+            ry,
+            start,
+            start + self.sweep().abs(),
+        )
+    }
+}
@@ -0,0 +1,175 @@
+use crate::converter::{
+    svg::{node::Node, util::invert_css_color},
+    *,
+};
+
+/// Applies the ROP2 drawing mode selected by META_SETROP2 to an element
+/// painted with the selected pen and brush.
+pub struct BinaryRasterOperator {
+    operation: BinaryRasterOperation,
+}
+
+/// How the pen and brush colours are rewritten before compositing.
+enum Paint {
+    Keep,
+    Color(&'static str),
+    Inverted,
+}
+
+impl BinaryRasterOperator {
+    pub fn new(operation: BinaryRasterOperation) -> Self {
+        Self { operation }
+    }
+
+    /// Describe how rendering `element` deviates from GDI, or `None` when the
+    /// operation has an exact SVG equivalent.
+    pub fn approximation(&self, element: &Node) -> Option<&'static str> {
+        match self.operation {
+            BinaryRasterOperation::R2_COPYPEN
+            | BinaryRasterOperation::R2_NOP
+            | BinaryRasterOperation::R2_BLACK
+            | BinaryRasterOperation::R2_WHITE
+            | BinaryRasterOperation::R2_NOT
+            | BinaryRasterOperation::R2_XORPEN => None,
+            BinaryRasterOperation::R2_NOTCOPYPEN
+            | BinaryRasterOperation::R2_NOTXORPEN => Self::has_pattern(element)
+                .then_some(
+                    "inverting a pattern brush is not supported, rendered \
+                     with the original pattern",
+                ),
+            BinaryRasterOperation::R2_MASKPEN => Some(
+                "DPa approximated with mix-blend-mode multiply (exact for \
+                 black and white only)",
+            ),
+            BinaryRasterOperation::R2_MERGEPEN => Some(
+                "DPo approximated with mix-blend-mode screen (exact for black \
+                 and white only)",
+            ),
+            BinaryRasterOperation::R2_MASKNOTPEN => Some(
+                "DPna approximated with mix-blend-mode multiply of the \
+                 inverted pen (exact for black and white only)",
+            ),
+            BinaryRasterOperation::R2_MERGENOTPEN => Some(
+                "DPno approximated with mix-blend-mode screen of the inverted \
+                 pen (exact for black and white only)",
+            ),
+            BinaryRasterOperation::R2_MASKPENNOT
+            | BinaryRasterOperation::R2_MERGEPENNOT
+            | BinaryRasterOperation::R2_NOTMASKPEN
+            | BinaryRasterOperation::R2_NOTMERGEPEN => Some(
+                "no SVG equivalent for inverting the destination before or \
+                 after combining, rendered as R2_COPYPEN",
+            ),
+        }
+    }
+
+    /// Rewrite `element` so that compositing it onto the drawing follows
+    /// the operation. Returns `None` when the operation leaves the
+    /// destination unchanged.
+    pub fn run(&self, element: Node) -> Option<Node> {
+        let (paint, blend_mode) = match self.operation {
+            BinaryRasterOperation::R2_NOP => return None,
+            BinaryRasterOperation::R2_BLACK => (Paint::Color("#000000"), None),
+            BinaryRasterOperation::R2_WHITE => (Paint::Color("#FFFFFF"), None),
+            // ~D == |1 - D|
+            BinaryRasterOperation::R2_NOT => {
+                (Paint::Color("#FFFFFF"), Some("difference"))
+            }
+            BinaryRasterOperation::R2_NOTCOPYPEN => (Paint::Inverted, None),
+            BinaryRasterOperation::R2_XORPEN => {
+                (Paint::Keep, Some("difference"))
+            }
+            // ~(D ^ P) == D ^ ~P
+            BinaryRasterOperation::R2_NOTXORPEN => {
+                (Paint::Inverted, Some("difference"))
+            }
+            BinaryRasterOperation::R2_MASKPEN => {
+                (Paint::Keep, Some("multiply"))
+            }
+            BinaryRasterOperation::R2_MERGEPEN => (Paint::Keep, Some("screen")),
+            BinaryRasterOperation::R2_MASKNOTPEN => {
+                (Paint::Inverted, Some("multiply"))
+            }
+            BinaryRasterOperation::R2_MERGENOTPEN => {
+                (Paint::Inverted, Some("screen"))
+            }
+            BinaryRasterOperation::R2_COPYPEN
+            | BinaryRasterOperation::R2_MASKPENNOT
+            | BinaryRasterOperation::R2_MERGEPENNOT
+            | BinaryRasterOperation::R2_NOTMASKPEN
+            | BinaryRasterOperation::R2_NOTMERGEPEN => (Paint::Keep, None),
+        };
+
+        let mut element = element;
+
+        for name in ["fill", "stroke"] {
+            let Some(value) = element.get(name) else {
+                continue;
+            };
+
+            if value == "none" {
+                continue;
+            }
+
+            let value = match paint {
+                Paint::Keep => continue,
+                Paint::Color(color) => color.to_string(),
+                Paint::Inverted => match invert_css_color(value) {
+                    Some(inverted) => inverted,
+                    None => continue,
+                },
+            };
+
+            element = element.set(name, value);
+        }
+
+        if let Some(blend_mode) = blend_mode {
+            // Keep the declarations the element already has.
+            let style = match element.get("style") {
+                Some(style) => format!(
+                    "{}; mix-blend-mode: {blend_mode};",
+                    style.trim_end().trim_end_matches(';'),
+                ),
+                None => format!("mix-blend-mode: {blend_mode};"),
+            };
+
+            element = element.set("style", style);
+        }
+
+        Some(element)
+    }
+
+    fn has_pattern(element: &Node) -> bool {
+        element.get("fill").is_some_and(|value| value.starts_with("url("))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_appends_blend_mode_to_style() {
+        let operator =
+            BinaryRasterOperator::new(BinaryRasterOperation::R2_XORPEN);
+        let cases = [
+            (None, "mix-blend-mode: difference;"),
+            (
+                Some("opacity: 0.5;"),
+                "opacity: 0.5; mix-blend-mode: difference;",
+            ),
+            (Some("opacity: 0.5"), "opacity: 0.5; mix-blend-mode: difference;"),
+        ];
+
+        for (style, expected) in cases {
+            let element = Node::new("rect").set("fill", "#FF0000");
+            let element = match style {
+                Some(style) => element.set("style", style),
+                None => element,
+            };
+            let element = operator.run(element).expect("element dropped");
+
+            assert_eq!(element.get("style"), Some(expected), "{style:?}");
+        }
+    }
+}
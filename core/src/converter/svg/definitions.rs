@@ -0,0 +1,133 @@
+use core::hash::{Hash, Hasher};
+
+use crate::{converter::svg::node::Node, imports::*};
+
+/// Contents of the `<defs>` element.
+///
+/// Identical definitions are stored once and share an id, so a brush,
+/// clip path, filter or bitmap used by many records is written out only
+/// once.
+#[derive(Debug, Default)]
+pub struct Definitions {
+    // Definitions without their ids, which follow from the position.
+    nodes: Vec<Node>,
+    // Positions in `nodes` of the definitions with each content hash.
+    ids: BTreeMap<u64, Vec<usize>>,
+}
+
+impl Definitions {
+    /// Add `definition` unless an identical one exists, and return its id.
+    pub fn define(&mut self, definition: Node) -> String {
+        let mut hasher = Fnv::default();
+
+        definition.hash(&mut hasher);
+
+        let indices = self.ids.entry(hasher.finish()).or_default();
+
+        if let Some(&index) =
+            indices.iter().find(|&&index| self.nodes[index] == definition)
+        {
+            return Self::id(index);
+        }
+
+        indices.push(self.nodes.len());
+        self.nodes.push(definition);
+
+        Self::id(self.nodes.len() - 1)
+    }
+
+    /// Add the image data of a bitmap and return its id. Placements refer
+    /// to it with `<use>`.
+    pub fn image(
+        &mut self,
+        data_url: String,
+        width: usize,
+        height: usize,
+    ) -> String {
+        self.define(
+            Node::new("image")
+                .set("width", width)
+                .set("height", height)
+                .set("preserveAspectRatio", "none")
+                .set("href", data_url),
+        )
+    }
+
+    /// Add a color-inversion filter and return its id.
+    pub fn invert_filter(&mut self) -> String {
+        let table = |name| {
+            Node::new(name).set("type", "table").set("tableValues", "1 0")
+        };
+
+        self.define(
+            Node::new("filter").add(
+                Node::new("feComponentTransfer")
+                    .add(table("feFuncR"))
+                    .add(table("feFuncG"))
+                    .add(table("feFuncB")),
+            ),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn into_nodes(self) -> Vec<Node> {
+        self.nodes
+            .into_iter()
+            .enumerate()
+            .map(|(index, node)| node.set("id", Self::id(index)))
+            .collect()
+    }
+
+    fn id(index: usize) -> String {
+        format!("defs{index}")
+    }
+}
+
+/// 64-bit FNV-1a, which spreads the definitions over the map without the
+/// hashers of `std`.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xCBF2_9CE4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100_0000_01B3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn define_shares_identical_definitions() {
+        let mut definitions = Definitions::default();
+        let clip = || Node::new("clipPath").add(Node::new("rect").set("x", 1));
+
+        let first = definitions.define(clip());
+        let second = definitions.define(clip());
+        let other = definitions.define(Node::new("clipPath"));
+        // Same length as the first, different markup.
+        let same_length = definitions
+            .define(Node::new("clipPath").add(Node::new("rect").set("x", 2)));
+
+        assert_eq!(first, "defs0");
+        assert_eq!(second, "defs0");
+        assert_eq!(other, "defs1");
+        assert_eq!(same_length, "defs2");
+        assert_eq!(definitions.into_nodes().len(), 3);
+    }
+}
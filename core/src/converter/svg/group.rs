@@ -0,0 +1,277 @@
+use crate::{converter::svg::node::Node, imports::*};
+
+/// Element tree of the document.
+///
+/// Each META_SAVEDC opens a `<g>` that is closed by the matching
+/// META_RESTOREDC, and consecutive elements sharing a clip path and
+/// transform are wrapped in a `<g>` carrying them once instead of
+/// repeating them on every element.
+#[derive(Debug)]
+pub struct Groups {
+    // Open groups, outermost first. The first entry is the document root
+    // and is never closed.
+    stack: Vec<Group>,
+}
+
+#[derive(Debug)]
+struct Group {
+    kind: GroupKind,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum GroupKind {
+    Root,
+    /// Scope of a META_SAVEDC record.
+    Scope,
+    /// Run of elements sharing a clip path id and a transform.
+    Run {
+        clip_path: Option<String>,
+        transform: Option<String>,
+    },
+}
+
+impl Default for Groups {
+    fn default() -> Self {
+        Self { stack: vec![Group { kind: GroupKind::Root, children: vec![] }] }
+    }
+}
+
+impl Groups {
+    /// Append `element` to the innermost scope, clipped by the clip path
+    /// with id `clip_path` and placed by `transform`.
+    ///
+    /// Clip paths are given in untransformed coordinates.
+    pub fn push(
+        &mut self,
+        element: Node,
+        clip_path: Option<&str>,
+        transform: Option<&str>,
+    ) {
+        if clip_path.is_none() && transform.is_none() {
+            self.close_run();
+            self.top().children.push(element);
+            return;
+        }
+
+        // `clip-path` on a group isolates it, so blending children would
+        // composite with the group instead of the drawing underneath.
+        if let (Some(clip_path), None) = (clip_path, transform)
+            && element.has_style("mix-blend-mode")
+            && element.get("clip-path").is_none()
+        {
+            self.close_run();
+            self.top()
+                .children
+                .push(element.set("clip-path", format!("url(#{clip_path})")));
+            return;
+        }
+
+        let is_same_run = matches!(
+            &self.top().kind,
+            GroupKind::Run { clip_path: c, transform: t }
+                if c.as_deref() == clip_path && t.as_deref() == transform
+        );
+
+        if !is_same_run {
+            self.close_run();
+            self.stack.push(Group {
+                kind: GroupKind::Run {
+                    clip_path: clip_path.map(ToOwned::to_owned),
+                    transform: transform.map(ToOwned::to_owned),
+                },
+                children: vec![],
+            });
+        }
+
+        self.top().children.push(element);
+    }
+
+    /// Open a group for a META_SAVEDC scope.
+    pub fn save(&mut self) {
+        self.close_run();
+        self.stack.push(Group { kind: GroupKind::Scope, children: vec![] });
+    }
+
+    /// Close scopes until `depth` of them are left open.
+    pub fn restore(&mut self, depth: usize) {
+        self.close_run();
+
+        while self.stack.len() > depth + 1 {
+            self.close();
+        }
+    }
+
+    pub fn into_nodes(mut self) -> Vec<Node> {
+        while self.stack.len() > 1 {
+            self.close();
+        }
+
+        self.stack.pop().map(|root| root.children).unwrap_or_default()
+    }
+
+    fn top(&mut self) -> &mut Group {
+        self.stack.last_mut().expect("root group is never closed")
+    }
+
+    fn close_run(&mut self) {
+        if matches!(self.top().kind, GroupKind::Run { .. }) {
+            self.close();
+        }
+    }
+
+    fn close(&mut self) {
+        let Some(Group { kind, mut children }) = self.stack.pop() else {
+            return;
+        };
+
+        let node = match kind {
+            GroupKind::Root => unreachable!("root group is never closed"),
+            _ if children.is_empty() => return,
+            GroupKind::Run { clip_path, transform } => {
+                if let Some(transform) = transform {
+                    children = vec![children.into_iter().fold(
+                        Node::new("g").set("transform", transform),
+                        Node::add,
+                    )];
+                }
+
+                match clip_path {
+                    // The clip path of an element is in its own user space,
+                    // which includes its `transform`, so only untransformed
+                    // elements that are not clipped yet take it directly.
+                    Some(id)
+                        if children.len() == 1
+                            && children[0].get("transform").is_none()
+                            && children[0].get("clip-path").is_none() =>
+                    {
+                        children
+                            .pop()
+                            .expect("one child exists")
+                            .set("clip-path", format!("url(#{id})"))
+                    }
+                    Some(id) => children.into_iter().fold(
+                        Node::new("g").set("clip-path", format!("url(#{id})")),
+                        Node::add,
+                    ),
+                    None => children.pop().expect("transform group exists"),
+                }
+            }
+            GroupKind::Scope => {
+                children.into_iter().fold(Node::new("g"), Node::add)
+            }
+        };
+
+        self.top().children.push(node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i16) -> Node {
+        Node::new("rect").set("x", x)
+    }
+
+    fn render(groups: Groups) -> String {
+        groups.into_nodes().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn push_shares_clip_path_between_consecutive_elements() {
+        let mut groups = Groups::default();
+
+        groups.push(rect(1), Some("defs0"), None);
+        groups.push(rect(2), Some("defs0"), None);
+        groups.push(rect(3), Some("defs1"), None);
+        groups.push(rect(4), None, None);
+
+        assert_eq!(
+            render(groups),
+            concat!(
+                r##"<g clip-path="url(#defs0)"><rect x="1"></rect><rect x="2"></rect></g>"##,
+                r##"<rect clip-path="url(#defs1)" x="3"></rect>"##,
+                r#"<rect x="4"></rect>"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn push_keeps_clip_path_of_clipped_element() {
+        let mut groups = Groups::default();
+
+        groups.push(
+            rect(1).set("clip-path", "url(#defs1)"),
+            Some("defs0"),
+            None,
+        );
+
+        assert_eq!(
+            render(groups),
+            r#"<g clip-path="url(#defs0)"><rect clip-path="url(#defs1)" x="1"></rect></g>"#,
+        );
+    }
+
+    #[test]
+    fn push_applies_clip_path_outside_transform() {
+        let mut groups = Groups::default();
+
+        groups.push(rect(1), None, Some("matrix(2 0 0 2 0 0)"));
+        groups.push(rect(2), None, Some("matrix(2 0 0 2 0 0)"));
+        groups.push(rect(3), Some("defs0"), Some("matrix(2 0 0 2 0 0)"));
+
+        assert_eq!(
+            render(groups),
+            concat!(
+                r#"<g transform="matrix(2 0 0 2 0 0)"><rect x="1"></rect><rect x="2"></rect></g>"#,
+                r##"<g clip-path="url(#defs0)"><g transform="matrix(2 0 0 2 0 0)"><rect x="3"></rect></g></g>"##,
+            ),
+        );
+    }
+
+    #[test]
+    fn save_and_restore_nest_scopes() {
+        let mut groups = Groups::default();
+
+        groups.push(rect(1), None, None);
+        groups.save();
+        groups.push(rect(2), None, None);
+        groups.save();
+        groups.save();
+        groups.push(rect(3), None, None);
+        groups.restore(0);
+        groups.save();
+        groups.restore(0);
+        groups.push(rect(4), None, None);
+
+        assert_eq!(
+            render(groups),
+            concat!(
+                r#"<rect x="1"></rect>"#,
+                r#"<g><rect x="2"></rect><g><g><rect x="3"></rect></g></g></g>"#,
+                r#"<rect x="4"></rect>"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn push_keeps_blending_elements_out_of_clip_groups() {
+        let mut groups = Groups::default();
+
+        groups.push(rect(1), Some("defs0"), None);
+        groups.push(
+            rect(2).set("style", "mix-blend-mode: difference;"),
+            Some("defs0"),
+            None,
+        );
+
+        assert_eq!(
+            render(groups),
+            concat!(
+                r##"<rect clip-path="url(#defs0)" x="1"></rect>"##,
+                r##"<rect clip-path="url(#defs0)" style="mix-blend-mode: difference;" x="2"></rect>"##,
+            ),
+        );
+    }
+}
@@ -1,18 +1,27 @@
+mod arc;
+mod binary_raster_operator;
+mod definitions;
 mod device_context;
+mod group;
 mod node;
 mod ternary_raster_operator;
 mod util;
 
 use crate::{
     converter::{
-        GraphicsObject, GraphicsObjects, PlayError, SelectedGraphicsObject,
+        Clip, Diagnostic, DiagnosticKind, Diagnostics, EllipticalArc, GdiState,
+        PlayError,
+        raster::{Canvas, Shape, rgb},
         svg::{
-            device_context::DeviceContext,
+            binary_raster_operator::BinaryRasterOperator,
+            definitions::Definitions,
+            device_context::SvgDeviceContext,
+            group::Groups,
             node::{Data, Node},
             ternary_raster_operator::TernaryRasterOperator,
             util::{
                 Fill, Stroke, as_point_string, css_color_from_color_ref,
-                url_string,
+                image_placement, url_string,
             },
         },
     },
@@ -20,18 +29,41 @@ use crate::{
     parser::*,
 };
 
+/// Units of work flood fills may spend on the scratch canvas: a row tested
+/// against an edge while painting, or a pixel scanned by a fill.
+const MAX_SCRATCH_WORK: usize = 1 << 28;
+
+/// Points the shapes recorded for flood fills may hold, which bounds their
+/// memory in metafiles that never flood fill.
+const MAX_SCRATCH_POINTS: usize = 1 << 20;
+
 #[derive(Default)]
 pub struct SVGPlayer {
-    context_stack: Vec<DeviceContext>,
-    context_current: DeviceContext,
-    definitions: Vec<Node>,
-    elements: Vec<Node>,
-    object_selected: SelectedGraphicsObject,
-    current_clip_id: Option<String>,
+    state: GdiState,
+    // Clip path defined for the clipping region of the device context,
+    // kept until the region changes.
+    clip_path: Option<(Vec<Clip>, Option<String>)>,
+    definitions: Definitions,
+    groups: Groups,
     // Tracks how many elements have been emitted per WMF record so that
     // `id` attributes stay unique when a single record produces multiple
     // SVG elements (e.g. POLYPOLYGON emits one polygon per sub-polygon).
     record_element_counts: BTreeMap<usize, usize>,
+    // Device-space copy of everything painted so far. Pixel-based records
+    // (META_FLOODFILL, META_EXTFLOODFILL) rasterise it on demand.
+    shapes: Vec<Shape>,
+    // Points held by the shapes, up to MAX_SCRATCH_POINTS.
+    shape_points: usize,
+    // Canvas the shapes are painted on, once each, with the view box it
+    // covers. The first flood fill creates it, and it is repainted when
+    // the drawing area grows.
+    scratch: Option<((i32, i32, i32, i32), Canvas)>,
+    // Work spent on the scratch canvas, up to MAX_SCRATCH_WORK.
+    scratch_work: usize,
+    diagnostics: Diagnostics,
+    // Write logical coordinates and map them to the device with transforms
+    // instead of converting every point.
+    logical_coordinates: bool,
 }
 
 impl SVGPlayer {
@@ -39,13 +71,44 @@ impl SVGPlayer {
         Self::default()
     }
 
-    #[inline]
-    fn issue_definition_id(&self) -> String {
-        format!("defs{}", self.definitions.len())
+    /// Receive a [`Diagnostic`] for every record that cannot be rendered
+    /// exactly.
+    #[must_use]
+    pub fn diagnostic_sink(
+        mut self,
+        sink: impl FnMut(Diagnostic) + Send + 'static,
+    ) -> Self {
+        self.diagnostics.set_sink(sink);
+        self
+    }
+
+    /// Write coordinates in logical units as they appear in the records,
+    /// and express the window and viewport mapping as
+    /// `<g transform="matrix(...)">` around them.
+    #[must_use]
+    pub fn logical_coordinates(mut self) -> Self {
+        self.logical_coordinates = true;
+        self
     }
 
     #[inline]
-    fn push_element(&mut self, record_number: usize, mut element: Node) {
+    fn push_element(&mut self, record_number: usize, element: Node) {
+        let transform = self.output_transform();
+
+        self.push_element_with(record_number, element, transform.as_deref());
+    }
+
+    /// Push an element whose coordinates are already in device units.
+    fn push_device_element(&mut self, record_number: usize, element: Node) {
+        self.push_element_with(record_number, element, None);
+    }
+
+    fn push_element_with(
+        &mut self,
+        record_number: usize,
+        mut element: Node,
+        transform: Option<&str>,
+    ) {
         if record_number > 0 {
             // Append a suffix to second and later elements from the same
             // record to satisfy the SVG `id` uniqueness constraint.
@@ -61,42 +124,445 @@ impl SVGPlayer {
             element = element.set("id", id);
         }
 
-        if let Some(ref clip_id) = self.current_clip_id {
-            element = element.set("clip-path", format!("url(#{clip_id})"));
+        let mut clip_path = self.clip_path();
+
+        // `clip-path` on a group isolates it, so a blending element takes
+        // the clip path itself. Under a transform, the clip path has to
+        // undo the transform the element's user space includes.
+        if let (Some(id), Some(_)) = (&clip_path, transform)
+            && element.has_style("mix-blend-mode")
+            && element.get("clip-path").is_none()
+            && let Some(id) = self.untransformed_clip_path(id)
+        {
+            element = element.set("clip-path", url_string(&format!("#{id}")));
+            clip_path = None;
+        }
+
+        self.groups.push(element, clip_path.as_deref(), transform);
+    }
+
+    /// Id of a clip path clipping like the clip path `id`, given in device
+    /// units, in the user space of the output transform. `None` when the
+    /// transform cannot be undone.
+    fn untransformed_clip_path(&mut self, id: &str) -> Option<String> {
+        let (sx, sy, tx, ty) = self.state.window().transform();
+
+        if sx.abs() <= f32::EPSILON || sy.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        // Adding zero writes a zero translation as 0 rather than -0.
+        let (dx, dy) = (-tx / sx + 0.0, -ty / sy + 0.0);
+        let clip_path = Node::new("clipPath")
+            .set(
+                "transform",
+                format!("matrix({} 0 0 {} {dx} {dy})", 1.0 / sx, 1.0 / sy),
+            )
+            .add(
+                Node::new("rect")
+                    .set("x", i16::MIN)
+                    .set("y", i16::MIN)
+                    .set("width", u16::MAX)
+                    .set("height", u16::MAX)
+                    .set("clip-path", url_string(&format!("#{id}"))),
+            );
+
+        Some(self.definitions.define(clip_path))
+    }
+
+    /// Id of the clip path of the clipping region, defining it when the
+    /// region changed since the last element.
+    ///
+    /// The clipping region is kept in device units, so the clip path
+    /// applies outside of any transform of the element. Each clip nests
+    /// the one before it, which intersects their areas.
+    fn clip_path(&mut self) -> Option<String> {
+        let clips = self.state.clips();
+
+        if let Some((_, id)) = self
+            .clip_path
+            .as_ref()
+            .filter(|(cached, _)| cached.as_slice() == clips)
+        {
+            return id.clone();
+        }
+
+        let clips = clips.to_vec();
+        let round = |v: f32| v.round() as i16;
+        let mut id: Option<String> = None;
+
+        for clip in &clips {
+            let mut clip_path = match clip {
+                Clip::Rects(rects) => {
+                    rects.iter().fold(Node::new("clipPath"), |node, rect| {
+                        let (left, top, right, bottom) = *rect;
+
+                        node.add(
+                            Node::new("rect")
+                                .set("x", round(left))
+                                .set("y", round(top))
+                                .set("width", round(right - left))
+                                .set("height", round(bottom - top)),
+                        )
+                    })
+                }
+                // The excluded rectangle is a hole in the even-odd fill of
+                // the area around it.
+                Clip::Exclude((left, top, right, bottom)) => {
+                    let (left, top) = (round(*left), round(*top));
+                    let (right, bottom) = (round(*right), round(*bottom));
+
+                    Node::new("clipPath").add(
+                        Node::new("path")
+                            .set(
+                                "d",
+                                format!(
+                                    "M-32768,-32768 H32767 V32767 H-32768 Z \
+                                     M{left},{top} H{right} V{bottom} H{left} \
+                                     Z",
+                                ),
+                            )
+                            .set("clip-rule", "evenodd"),
+                    )
+                }
+            };
+
+            if let Some(id) = id {
+                clip_path =
+                    clip_path.set("clip-path", url_string(&format!("#{id}")));
+            }
+
+            id = Some(self.definitions.define(clip_path));
+        }
+
+        self.clip_path = Some((clips, id.clone()));
+
+        id
+    }
+
+    /// Push an element painted with the selected pen and brush, combining
+    /// it with the drawing according to the current ROP2 drawing mode.
+    fn push_drawing(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        element: Node,
+    ) {
+        let Some(operation) = self.state.draw_mode() else {
+            self.push_element(record_number, element);
+            return;
+        };
+
+        let operator = BinaryRasterOperator::new(operation);
+
+        if let Some(message) = operator.approximation(&element) {
+            self.diagnostics.report(
+                record_number,
+                record_type,
+                DiagnosticKind::Approximated,
+                format!("{operation:?}: {message}"),
+            );
         }
 
-        self.elements.push(element);
+        if let Some(element) = operator.run(element) {
+            self.push_element(record_number, element);
+        }
     }
 
     fn selected_brush(&self) -> &Brush {
-        &self.object_selected.brush
+        self.state.brush()
     }
 
     fn selected_pen(&self) -> &Pen {
-        &self.object_selected.pen
+        self.state.pen()
     }
 
-    fn resolve_fill(&mut self) -> String {
-        match Fill::from(self.selected_brush()) {
+    /// Resolve `brush` to an SVG paint, defining a pattern if necessary.
+    fn fill_for(&mut self, brush: &Brush) -> String {
+        let background = (self.state.bk_mode() == MixMode::OPAQUE)
+            .then_some(self.state.bk_color());
+        let window = self.state.window();
+        let pixel_size = if self.logical_coordinates {
+            window.device_pixel_size() / window.device_units_per_logical_x()
+        } else {
+            window.device_pixel_size()
+        };
+
+        match Fill::new(brush, background, pixel_size) {
             Fill::Pattern { pattern } => {
-                let id = self.issue_definition_id();
-                self.definitions.push(pattern.set("id", id.as_str()));
+                let id = self.definitions.define(pattern);
                 url_string(format!("#{id}").as_str())
             }
             Fill::Value { value } => value,
         }
     }
 
+    fn resolve_fill(&mut self) -> String {
+        let brush = self.selected_brush().clone();
+        self.fill_for(&brush)
+    }
+
+    fn resolve_stroke(&self) -> Stroke {
+        let scale = self.state.window().device_units_per_logical_x();
+
+        if self.logical_coordinates {
+            Stroke::new_logical(self.selected_pen(), scale)
+        } else {
+            Stroke::new(self.selected_pen(), scale)
+        }
+    }
+
+    /// Colour a brush leaves on the scratch canvas. Bitmap pattern brushes
+    /// have no single colour and are not tracked.
+    fn scratch_fill(brush: &Brush) -> Option<u32> {
+        match brush {
+            Brush::Solid { color_ref } | Brush::Hatched { color_ref, .. } => {
+                Some(rgb(color_ref))
+            }
+            Brush::DIBPatternPT { .. }
+            | Brush::Pattern { .. }
+            | Brush::Null => None,
+        }
+    }
+
+    fn scratch_stroke(&self) -> Option<(u32, f32)> {
+        let pen = self.selected_pen();
+
+        if pen.style.style == PenStyle::PS_NULL {
+            return None;
+        }
+
+        let scale = self.state.window().device_units_per_logical_x();
+        let width = f32::from(pen.width.x.unsigned_abs()) * scale;
+
+        Some((rgb(&pen.color_ref), width.max(1.0)))
+    }
+
+    /// Map points in output coordinates to the device-space scratch canvas.
+    fn scratch_points(&self, points: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+        if !self.logical_coordinates {
+            return points;
+        }
+
+        let window = self.state.window();
+
+        points.into_iter().map(|(x, y)| window.point_to_device(x, y)).collect()
+    }
+
+    /// Record rectangles, given as pairs of corners in output coordinates,
+    /// painted with `color` on the scratch canvas.
+    fn scratch_rects(&mut self, rects: &[(PointS, PointS)], color: u32) {
+        let rects = rects
+            .iter()
+            .map(|(p1, p2)| {
+                let corners = self.scratch_points(vec![
+                    (f32::from(p1.x), f32::from(p1.y)),
+                    (f32::from(p2.x), f32::from(p2.y)),
+                ]);
+                let ((x1, y1), (x2, y2)) = (corners[0], corners[1]);
+
+                (x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2))
+            })
+            .collect();
+
+        self.push_shape(Shape::Rects { rects, color });
+    }
+
+    fn scratch_polygon(&mut self, points: Vec<(f32, f32)>) {
+        let points = self.scratch_points(points);
+        let fill = Self::scratch_fill(self.selected_brush());
+        let stroke = self.scratch_stroke();
+
+        if fill.is_some() || stroke.is_some() {
+            self.push_shape(Shape::Polygon {
+                points,
+                winding: self.state.poly_fill_mode() == PolyFillMode::WINDING,
+                fill,
+                stroke,
+            });
+        }
+    }
+
+    fn scratch_polyline(&mut self, points: Vec<(f32, f32)>) {
+        let points = self.scratch_points(points);
+
+        if let Some((color, width)) = self.scratch_stroke() {
+            self.push_shape(Shape::Polyline { points, color, width });
+        }
+    }
+
+    /// Record a shape for flood fills, painting it on the scratch canvas
+    /// when there is one.
+    fn push_shape(&mut self, shape: Shape) {
+        if self.scratch_work >= MAX_SCRATCH_WORK {
+            return;
+        }
+
+        self.shape_points += shape.point_count();
+
+        if self.shape_points > MAX_SCRATCH_POINTS {
+            // Flood fills could no longer see the whole drawing, so they are
+            // given up like when the work runs out.
+            self.scratch_work = MAX_SCRATCH_WORK;
+            self.scratch = None;
+            self.shapes = Vec::new();
+
+            return;
+        }
+
+        if let Some((_, canvas)) = self.scratch.as_mut() {
+            self.scratch_work += canvas.paint(&shape);
+        }
+
+        self.shapes.push(shape);
+    }
+
+    /// Scratch canvas covering the drawing area, repainted when the area
+    /// grew since it was painted. `None` when the area is empty or the
+    /// work budget ran out.
+    fn scratch_canvas(&mut self) -> Option<&Canvas> {
+        let view_box = self.state.window().as_view_box();
+
+        if self.scratch_work < MAX_SCRATCH_WORK
+            && self.scratch.as_ref().is_none_or(|(area, _)| *area != view_box)
+        {
+            let (x, y, width, height) = view_box;
+            let mut canvas =
+                Canvas::new(x, y, width, height, rgb(&ColorRef::white()))?;
+
+            self.scratch_work += canvas.pixel_count();
+            for shape in &self.shapes {
+                if self.scratch_work >= MAX_SCRATCH_WORK {
+                    break;
+                }
+
+                self.scratch_work += canvas.paint(shape);
+            }
+
+            self.scratch = Some((view_box, canvas));
+        }
+
+        if self.scratch_work >= MAX_SCRATCH_WORK {
+            self.scratch = None;
+            self.shapes.clear();
+
+            return None;
+        }
+
+        self.scratch.as_ref().map(|(_, canvas)| canvas)
+    }
+
+    /// Flood fill the scratch canvas from the given logical point and emit
+    /// the reached area as a path painted with the selected brush.
+    ///
+    /// The scratch canvas only approximates the drawing: it paints solid,
+    /// full-width strokes and solid fills, and ignores pen styles, ROP2,
+    /// clipping, text and bitmaps.
+    fn flood_fill_from(
+        &mut self,
+        record_number: usize,
+        record_type: RecordType,
+        (x, y): (i16, i16),
+        mode: FloodFill,
+        color_ref: &ColorRef,
+    ) {
+        let fill = self.resolve_fill();
+        if fill == "none" {
+            info!("flood fill is skipped because the selected brush is null");
+            return;
+        }
+
+        let start = self.convert_point(x, y);
+        let start = self
+            .scratch_points(vec![(f32::from(start.x), f32::from(start.y))])[0];
+        let Some(canvas) = self.scratch_canvas() else {
+            if self.scratch_work >= MAX_SCRATCH_WORK {
+                self.diagnostics.report(
+                    record_number,
+                    record_type,
+                    DiagnosticKind::Skipped,
+                    "flood fill is skipped because the scratch canvas ran out \
+                     of work or memory"
+                        .to_owned(),
+                );
+            } else {
+                info!(
+                    "flood fill is skipped because the drawing area is empty"
+                );
+            }
+
+            return;
+        };
+
+        let scanned = canvas.pixel_count();
+        let rects = canvas.flood_fill(start.0, start.1, mode, rgb(color_ref));
+
+        self.scratch_work += scanned;
+        self.diagnostics.report(
+            record_number,
+            record_type,
+            DiagnosticKind::Approximated,
+            "flood fill reads a scratch canvas without pen styles, ROP2, \
+             clipping, text and bitmaps"
+                .to_owned(),
+        );
+
+        if rects.is_empty() {
+            info!(?mode, "flood fill did not reach any pixel");
+            return;
+        }
+
+        let mut data = Data::new();
+        for &(l, t, r, b) in &rects {
+            data = data
+                .move_to(format!("{l} {t}"))
+                .line_to(format!("{r} {t}"))
+                .line_to(format!("{r} {b}"))
+                .line_to(format!("{l} {b}"))
+                .close();
+        }
+
+        if let Some(color) = Self::scratch_fill(self.selected_brush()) {
+            self.push_shape(Shape::Rects { rects, color });
+        }
+
+        let path = Node::new("path")
+            .set("d", data)
+            .set("fill", fill)
+            .set("stroke", "none");
+
+        self.push_device_element(record_number, path);
+    }
+
     fn run_raster_operator(
         &mut self,
         record_number: usize,
+        record_type: RecordType,
         operator: TernaryRasterOperator,
     ) -> Result<(), PlayError> {
+        let operation = operator.operation();
+
+        if let Some(message) = operator.approximation() {
+            self.diagnostics.report(
+                record_number,
+                record_type,
+                DiagnosticKind::Approximated,
+                format!("{operation:?}: {message}"),
+            );
+        }
+
         let Some(elem) =
             operator.run(&mut self.definitions).map_err(|err| {
                 PlayError::InvalidRecord { cause: err.to_string() }
             })?
         else {
+            self.diagnostics.report(
+                record_number,
+                record_type,
+                DiagnosticKind::Skipped,
+                format!("{operation:?}: raster operation is not supported"),
+            );
+
             return Ok(());
         };
 
@@ -104,27 +570,203 @@ impl SVGPlayer {
         Ok(())
     }
 
+    /// Map the destination rectangle of a bitmap record, given by its corner
+    /// and extents in logical units, to output coordinates as `(x, y,
+    /// width, height)`. The extents stay signed, so a mapping that mirrors
+    /// an axis mirrors the bitmap like the shapes.
+    fn blit_destination(
+        &mut self,
+        x: i16,
+        y: i16,
+        width: i16,
+        height: i16,
+    ) -> (i16, i16, i16, i16) {
+        let p1 = self.convert_point(x, y);
+        let p2 = self
+            .convert_point(x.saturating_add(width), y.saturating_add(height));
+
+        (p1.x, p1.y, p2.x.saturating_sub(p1.x), p2.y.saturating_sub(p1.y))
+    }
+
+    /// Crop the source bitmap to the record's source rectangle and pick the
+    /// `image-rendering` from the StretchBlt mode when it is scaled.
+    fn stretch_source(
+        &self,
+        operator: TernaryRasterOperator,
+        (x_src, y_src, width_src, height_src): (i16, i16, i16, i16),
+        (width_dest, height_dest): (i16, i16),
+    ) -> TernaryRasterOperator {
+        let operator =
+            operator.source_rect(x_src, y_src, width_src, height_src);
+
+        if width_src.unsigned_abs() == width_dest.unsigned_abs()
+            && height_src.unsigned_abs() == height_dest.unsigned_abs()
+        {
+            return operator;
+        }
+
+        operator.image_rendering(self.state.image_rendering())
+    }
+
+    /// SVG `transform` mapping output coordinates to the device, or `None`
+    /// when points are written in device units.
+    fn output_transform(&self) -> Option<String> {
+        if !self.logical_coordinates {
+            return None;
+        }
+
+        let (sx, sy, tx, ty) = self.state.window().transform();
+
+        if (sx - 1.0).abs() <= f32::EPSILON
+            && (sy - 1.0).abs() <= f32::EPSILON
+            && tx.abs() <= f32::EPSILON
+            && ty.abs() <= f32::EPSILON
+        {
+            return None;
+        }
+
+        Some(format!("matrix({sx} 0 0 {sy} {tx} {ty})"))
+    }
+
+    /// Convert a logical point to output coordinates without tracking it
+    /// in the viewBox.
+    fn output_point(&self, point: &PointS) -> PointS {
+        if self.logical_coordinates {
+            point.clone()
+        } else {
+            self.state.point_s_to_absolute_point(point)
+        }
+    }
+
+    /// Expand the viewBox to include `point` given in output coordinates.
+    fn extend_window(&mut self, point: &PointS) {
+        let point = if self.logical_coordinates {
+            self.state.point_s_to_absolute_point(point)
+        } else {
+            point.clone()
+        };
+
+        self.state.extend_bounds((f32::from(point.x), f32::from(point.y)));
+    }
+
     fn convert_point(&mut self, x: i16, y: i16) -> PointS {
-        let point =
-            self.context_current.point_s_to_absolute_point(&PointS { x, y });
-        self.context_current.extend_window(&point);
+        let point = self.output_point(&PointS { x, y });
+        self.extend_window(&point);
         point
     }
 
     fn convert_point_for_text(&mut self, x: i16, y: i16) -> PointS {
-        let src = PointS { x, y };
-        let point = if self.context_current.text_align_update_cp {
-            self.context_current.point_s_to_relative_point(&src)
+        if self.state.text_align_update_cp() {
+            let position = self.state.drawing_position();
+
+            self.convert_point(
+                x.saturating_add(position.x),
+                y.saturating_add(position.y),
+            )
         } else {
-            self.context_current.point_s_to_absolute_point(&src)
-        };
-        self.context_current.extend_window(&point);
-        point
+            self.convert_point(x, y)
+        }
     }
 
-    /// Build an SVG path from a Region's scanlines.
-    fn region_to_path(&mut self, region: &Region) -> Data {
-        let mut data = Data::new();
+    /// Build the arc GDI draws for META_ARC, META_CHORD and META_PIE from the
+    /// corners of the bounding rectangle and the radial points in logical
+    /// units, with the radii reduced by `inset`.
+    fn elliptical_arc(
+        &mut self,
+        (left, top): (i16, i16),
+        (right, bottom): (i16, i16),
+        (x1, y1): (i16, i16),
+        (x2, y2): (i16, i16),
+        inset: f32,
+    ) -> Option<EllipticalArc> {
+        let top_left = self.convert_point(left, top);
+        let bottom_right = self.convert_point(right, bottom);
+        let radial1 = self.output_point(&PointS { x: x1, y: y1 });
+        let radial2 = self.output_point(&PointS { x: x2, y: y2 });
+        // Written in logical units, the transform of the group mirrors the
+        // arc along with the rest of the figure.
+        let clockwise =
+            !self.logical_coordinates && self.state.window().arcs_clockwise();
+        let float = |point: &PointS| (f32::from(point.x), f32::from(point.y));
+
+        EllipticalArc::new(
+            (float(&top_left), float(&bottom_right)),
+            float(&radial1),
+            float(&radial2),
+            inset,
+            clockwise,
+        )
+    }
+
+    /// Point at which the text drawn from the reference point `anchor` is
+    /// placed: on the alphabetic baseline for horizontal fonts, and on the
+    /// central baseline for vertical ones.
+    fn text_point(&self, anchor: &PointS) -> PointS {
+        let font = self.state.font();
+
+        if font.is_vertical() {
+            let offset =
+                self.state.text_central_x_offset(font.height, font.charset);
+
+            PointS { x: anchor.x.saturating_add(offset), y: anchor.y }
+        } else {
+            let offset =
+                self.state.text_baseline_y_offset(font.height, font.charset);
+
+            PointS { x: anchor.x, y: anchor.y.saturating_add(offset) }
+        }
+    }
+
+    /// Add `glyphs` to `text`, each shifted by the extra advance that
+    /// follows the previous one on top of the natural advance of the font.
+    fn add_spaced_glyphs(
+        mut text: Node,
+        glyphs: &[&str],
+        extras: &[i32],
+        is_vertical: bool,
+    ) -> Node {
+        let shift = if is_vertical { "dy" } else { "dx" };
+
+        for (i, glyph) in glyphs.iter().enumerate() {
+            let mut tspan = Node::new("tspan").add(Node::new_text(*glyph));
+
+            if let Some(extra) = i.checked_sub(1).map(|i| extras[i])
+                && extra != 0
+            {
+                tspan = tspan.set(shift, extra);
+            }
+
+            text = text.add(tspan);
+        }
+
+        text
+    }
+
+    /// Keep text upright when the output transform mirrors an axis, by
+    /// mirroring `element` back around its reference point `anchor`.
+    fn upright_text(&self, element: Node, anchor: &PointS) -> Node {
+        if !self.logical_coordinates {
+            return element;
+        }
+
+        let (sx, sy, ..) = self.state.window().transform();
+        let (fx, fy) = (sx.signum(), sy.signum());
+
+        if fx > 0.0 && fy > 0.0 {
+            return element;
+        }
+
+        let tx = (1.0 - fx) * f32::from(anchor.x);
+        let ty = (1.0 - fy) * f32::from(anchor.y);
+
+        Node::new("g")
+            .set("transform", format!("matrix({fx} 0 0 {fy} {tx} {ty})"))
+            .add(element)
+    }
+
+    /// Convert a Region's scanlines into device-space corner pairs.
+    fn region_to_rects(&mut self, region: &Region) -> Vec<(PointS, PointS)> {
+        let mut rects = vec![];
 
         for scan in &region.a_scans {
             // Scan coordinates are u16; clamp to i16 range
@@ -138,12 +780,7 @@ impl SVGPlayer {
                 let tl = self.convert_point(left, top);
                 let br = self.convert_point(right, bottom);
 
-                data = data
-                    .move_to(format!("{} {}", tl.x, tl.y))
-                    .line_to(format!("{} {}", br.x, tl.y))
-                    .line_to(format!("{} {}", br.x, br.y))
-                    .line_to(format!("{} {}", tl.x, br.y))
-                    .close();
+                rects.push((tl, br));
             }
         }
 
@@ -153,6 +790,19 @@ impl SVGPlayer {
             let tl = self.convert_point(r.left, r.top);
             let br = self.convert_point(r.right, r.bottom);
 
+            rects.push((tl, br));
+        }
+
+        rects
+    }
+
+    /// Build an SVG path from a Region's scanlines and record the painted
+    /// area on the scratch canvas.
+    fn region_to_path(&mut self, region: &Region, fill: Option<u32>) -> Data {
+        let rects = self.region_to_rects(region);
+        let mut data = Data::new();
+
+        for (tl, br) in &rects {
             data = data
                 .move_to(format!("{} {}", tl.x, tl.y))
                 .line_to(format!("{} {}", br.x, tl.y))
@@ -161,6 +811,10 @@ impl SVGPlayer {
                 .close();
         }
 
+        if let Some(color) = fill {
+            self.scratch_rects(&rects, color);
+        }
+
         data
     }
 
@@ -169,44 +823,9 @@ impl SVGPlayer {
         &mut self,
         brush_index: u16,
     ) -> Result<String, PlayError> {
-        let object =
-            self.context_current.object_table.get(brush_index as usize).clone();
+        let brush = self.state.brush_at(brush_index)?;
 
-        let GraphicsObject::Brush(brush) = object else {
-            return Err(PlayError::InvalidBrush {
-                cause: format!("object at index {brush_index} is not a Brush"),
-            });
-        };
-
-        match Fill::from(&brush) {
-            Fill::Pattern { pattern } => {
-                let id = self.issue_definition_id();
-                self.definitions.push(pattern.set("id", id.as_str()));
-                Ok(url_string(format!("#{id}").as_str()))
-            }
-            Fill::Value { value } => Ok(value),
-        }
-    }
-
-    /// Look up a Region from the object table by index.
-    fn get_region_from_object_table(
-        &self,
-        region_index: u16,
-    ) -> Result<Region, PlayError> {
-        let object = self
-            .context_current
-            .object_table
-            .get(region_index as usize)
-            .clone();
-
-        match object {
-            GraphicsObject::Region(r) => Ok(r),
-            _ => Err(PlayError::InvalidRecord {
-                cause: format!(
-                    "object at index {region_index} is not a Region"
-                ),
-            }),
-        }
+        Ok(self.fill_for(&brush))
     }
 }
 
@@ -217,23 +836,23 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn generate(self) -> Result<Vec<u8>, PlayError> {
-        let Self { context_current, definitions, elements, .. } = self;
+        let Self { state, definitions, groups, .. } = self;
 
-        let (x, y, width, height) = context_current.window.as_view_box();
+        let (x, y, width, height) = state.window().as_view_box();
         let mut document = Node::new("svg")
             .set("xmlns", "http://www.w3.org/2000/svg")
             .set("viewBox", format!("{x} {y} {width} {height}"));
 
         if !definitions.is_empty() {
             let mut defs = Node::new("defs");
-            for v in definitions {
+            for v in definitions.into_nodes() {
                 defs = defs.add(v);
             }
 
             document = document.add(defs);
         }
 
-        for v in elements {
+        for v in groups.into_nodes() {
             document = document.add(v);
         }
 
@@ -265,16 +884,18 @@ impl crate::converter::Player for SVGPlayer {
                 target,
                 ..
             } => {
+                let (x, y, width, height) =
+                    self.blit_destination(x_dest, y_dest, width, height);
                 let mut operator = TernaryRasterOperator::new(
                     raster_operation,
-                    x_dest,
-                    y_dest,
+                    x,
+                    y,
                     height,
                     width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 if raster_operation.use_source() {
@@ -291,23 +912,29 @@ impl crate::converter::Player for SVGPlayer {
                 x_dest,
                 ..
             } => {
+                let (x, y, width, height) =
+                    self.blit_destination(x_dest, y_dest, width, height);
                 let mut operator = TernaryRasterOperator::new(
                     raster_operation,
-                    x_dest,
-                    y_dest,
+                    x,
+                    y,
                     height,
                     width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 operator
             }
         };
 
-        self.run_raster_operator(record_number, operator)?;
+        self.run_raster_operator(
+            record_number,
+            RecordType::META_BITBLT,
+            operator,
+        )?;
         Ok(self)
     }
 
@@ -331,16 +958,18 @@ impl crate::converter::Player for SVGPlayer {
                 target,
                 ..
             } => {
+                let (x, y, width, height) =
+                    self.blit_destination(x_dest, y_dest, width, height);
                 let mut operator = TernaryRasterOperator::new(
                     raster_operation,
-                    x_dest,
-                    y_dest,
+                    x,
+                    y,
                     height,
                     width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 if raster_operation.use_source() {
@@ -357,23 +986,29 @@ impl crate::converter::Player for SVGPlayer {
                 x_dest,
                 ..
             } => {
+                let (x, y, width, height) =
+                    self.blit_destination(x_dest, y_dest, width, height);
                 let mut operator = TernaryRasterOperator::new(
                     raster_operation,
-                    x_dest,
-                    y_dest,
+                    x,
+                    y,
                     height,
                     width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 operator
             }
         };
 
-        self.run_raster_operator(record_number, operator)?;
+        self.run_raster_operator(
+            record_number,
+            RecordType::META_DIBBITBLT,
+            operator,
+        )?;
         Ok(self)
     }
 
@@ -390,6 +1025,10 @@ impl crate::converter::Player for SVGPlayer {
         let operator = match record {
             META_DIBSTRETCHBLT::WithBitmap {
                 raster_operation,
+                src_height,
+                src_width,
+                y_src,
+                x_src,
                 dest_height,
                 dest_width,
                 y_dest,
@@ -397,20 +1036,32 @@ impl crate::converter::Player for SVGPlayer {
                 target,
                 ..
             } => {
-                let mut operator = TernaryRasterOperator::new(
-                    raster_operation,
+                let (x, y, width, height) = self.blit_destination(
                     x_dest,
                     y_dest,
-                    dest_height,
                     dest_width,
+                    dest_height,
+                );
+                let mut operator = TernaryRasterOperator::new(
+                    raster_operation,
+                    x,
+                    y,
+                    height,
+                    width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 if raster_operation.use_source() {
-                    operator = operator.source_bitmap(*target);
+                    operator = self
+                        .stretch_source(
+                            operator,
+                            (x_src, y_src, src_width, src_height),
+                            (width, height),
+                        )
+                        .source_bitmap(*target);
                 }
 
                 operator
@@ -423,23 +1074,33 @@ impl crate::converter::Player for SVGPlayer {
                 x_dest,
                 ..
             } => {
-                let mut operator = TernaryRasterOperator::new(
-                    raster_operation,
+                let (x, y, width, height) = self.blit_destination(
                     x_dest,
                     y_dest,
-                    dest_height,
                     dest_width,
+                    dest_height,
+                );
+                let mut operator = TernaryRasterOperator::new(
+                    raster_operation,
+                    x,
+                    y,
+                    height,
+                    width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 operator
             }
         };
 
-        self.run_raster_operator(record_number, operator)?;
+        self.run_raster_operator(
+            record_number,
+            RecordType::META_DIBSTRETCHBLT,
+            operator,
+        )?;
         Ok(self)
     }
 
@@ -453,18 +1114,33 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETDIBTODEV,
     ) -> Result<Self, PlayError> {
+        let (bitmap_width, bitmap_height) = (
+            record.dib.dib_header_info.width(),
+            record.dib.dib_header_info.height(),
+        );
         let bitmap = crate::converter::Bitmap::from(record.dib);
         // Clamp to i16::MAX when u16 exceeds i16 range
         let x = i16::try_from(record.x_dest).unwrap_or(i16::MAX);
         let y = i16::try_from(record.y_dest).unwrap_or(i16::MAX);
+        let width = i16::try_from(record.width).unwrap_or(i16::MAX);
+        let height = i16::try_from(record.height).unwrap_or(i16::MAX);
         let point = self.convert_point(x, y);
 
-        let image = Node::new("image")
-            .set("x", point.x)
-            .set("y", point.y)
-            .set("width", record.width)
-            .set("height", record.height)
-            .set("href", bitmap.as_data_url());
+        let id = self.definitions.image(
+            bitmap.as_data_url(),
+            bitmap_width,
+            bitmap_height,
+        );
+        let image = image_placement(
+            &id,
+            (
+                i32::from(point.x),
+                i32::from(point.y),
+                i32::from(width),
+                i32::from(height),
+            ),
+            &format!("0 0 {bitmap_width} {bitmap_height}"),
+        );
 
         self.push_element(record_number, image);
 
@@ -484,6 +1160,10 @@ impl crate::converter::Player for SVGPlayer {
         let operator = match record {
             META_STRETCHBLT::WithBitmap {
                 raster_operation,
+                src_height,
+                src_width,
+                y_src,
+                x_src,
                 dest_height,
                 dest_width,
                 y_dest,
@@ -491,20 +1171,32 @@ impl crate::converter::Player for SVGPlayer {
                 target,
                 ..
             } => {
-                let mut operator = TernaryRasterOperator::new(
-                    raster_operation,
+                let (x, y, width, height) = self.blit_destination(
                     x_dest,
                     y_dest,
-                    dest_height,
                     dest_width,
+                    dest_height,
+                );
+                let mut operator = TernaryRasterOperator::new(
+                    raster_operation,
+                    x,
+                    y,
+                    height,
+                    width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 if raster_operation.use_source() {
-                    operator = operator.source_bitmap16(target);
+                    operator = self
+                        .stretch_source(
+                            operator,
+                            (x_src, y_src, src_width, src_height),
+                            (width, height),
+                        )
+                        .source_bitmap16(target);
                 }
 
                 operator
@@ -517,23 +1209,33 @@ impl crate::converter::Player for SVGPlayer {
                 x_dest,
                 ..
             } => {
-                let mut operator = TernaryRasterOperator::new(
-                    raster_operation,
+                let (x, y, width, height) = self.blit_destination(
                     x_dest,
                     y_dest,
-                    dest_height,
                     dest_width,
+                    dest_height,
+                );
+                let mut operator = TernaryRasterOperator::new(
+                    raster_operation,
+                    x,
+                    y,
+                    height,
+                    width,
                 );
 
                 if raster_operation.use_selected_brush() {
-                    operator = operator.brush(self.selected_brush().clone());
+                    operator = operator.brush(self.resolve_fill());
                 }
 
                 operator
             }
         };
 
-        self.run_raster_operator(record_number, operator)?;
+        self.run_raster_operator(
+            record_number,
+            RecordType::META_STRETCHBLT,
+            operator,
+        )?;
         Ok(self)
     }
 
@@ -549,6 +1251,10 @@ impl crate::converter::Player for SVGPlayer {
     ) -> Result<Self, PlayError> {
         let META_STRETCHDIB {
             raster_operation,
+            src_height,
+            src_width,
+            y_src,
+            x_src,
             dest_height,
             dest_width,
             y_dst,
@@ -557,23 +1263,30 @@ impl crate::converter::Player for SVGPlayer {
             ..
         } = record;
 
-        let mut operator = TernaryRasterOperator::new(
-            raster_operation,
-            x_dst,
-            y_dst,
-            dest_height,
-            dest_width,
-        );
+        let (x, y, width, height) =
+            self.blit_destination(x_dst, y_dst, dest_width, dest_height);
+        let mut operator =
+            TernaryRasterOperator::new(raster_operation, x, y, height, width);
 
         if raster_operation.use_selected_brush() {
-            operator = operator.brush(self.selected_brush().clone());
+            operator = operator.brush(self.resolve_fill());
         }
 
         if raster_operation.use_source() {
-            operator = operator.source_bitmap(dib);
+            operator = self
+                .stretch_source(
+                    operator,
+                    (x_src, y_src, src_width, src_height),
+                    (width, height),
+                )
+                .source_bitmap(dib);
         }
 
-        self.run_raster_operator(record_number, operator)?;
+        self.run_raster_operator(
+            record_number,
+            RecordType::META_STRETCHDIB,
+            operator,
+        )?;
         Ok(self)
     }
 
@@ -601,21 +1314,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         header: MetafileHeader,
     ) -> Result<Self, PlayError> {
-        let (placeable, header) = match header {
-            MetafileHeader::StartsWithHeader(header) => (None, header),
-            MetafileHeader::StartsWithPlaceable(placeable, header) => {
-                (Some(placeable), header)
-            }
-        };
-
-        if let Some(placeable) = placeable {
-            let Rect { left, top, right, bottom } = placeable.bounding_box;
-
-            self.context_current.window_origin(left, top);
-            self.context_current.window_ext(right - left, bottom - top);
-        }
-
-        self.context_current.create_object_table(header.number_of_objects);
+        self.state.header(&header);
 
         Ok(self)
     }
@@ -636,49 +1335,31 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_ARC,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
-        let start = self.convert_point(record.x_start_arc, record.y_start_arc);
-        let end = self.convert_point(record.x_end_arc, record.y_end_arc);
-        // Use f32 to avoid precision loss from integer division.
-        // Take abs() because SVG requires non-negative radii,
-        // while WMF may have inverted bounding rectangles.
-        let rx = (f32::from(record.right_rect - record.left_rect) / 2.0).abs();
-        let ry = (f32::from(record.bottom_rect - record.top_rect) / 2.0).abs();
-        let center_x = f32::midpoint(
-            f32::from(record.left_rect),
-            f32::from(record.right_rect),
-        );
-        let center_y = f32::midpoint(
-            f32::from(record.top_rect),
-            f32::from(record.bottom_rect),
-        );
-        let center = self
-            .convert_point(center_x.round() as i16, center_y.round() as i16);
-        // Start and end vectors relative to the center of the ellipse
-        let start_dx = f32::from(start.x - center.x);
-        let start_dy = f32::from(start.y - center.y);
-        let end_dx = f32::from(end.x - center.x);
-        let end_dy = f32::from(end.y - center.y);
-
-        // Calculate cross product to determine if the arc is larger than 180
-        // degrees. Invert the sign because upper-left is origin.
-        let cross = -(start_dx * end_dy - start_dy * end_dx);
-        // If the arc is less than 180 degrees (equivalent to the cross product
-        // is positive), it is not the larger arc.
-        let large_arc = i16::from(cross < 0.0);
-
-        // sweep is always 0 (equivalent to "counter-clockwise" in SVG)
-        let data = Data::new()
-            .move_to(format!("{} {}", start.x, start.y))
-            .elliptical_arc_to(format!(
-                "{rx} {ry} {} {large_arc} {} {} {}",
-                0, 0, end.x, end.y,
-            ));
+        let Some(arc) = self.elliptical_arc(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            (record.x_start_arc, record.y_start_arc),
+            (record.x_end_arc, record.y_end_arc),
+            0.0,
+        ) else {
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_ARC,
+                DiagnosticKind::Skipped,
+                "rx or ry is zero".to_owned(),
+            );
+
+            return Ok(self);
+        };
+
+        let stroke = self.resolve_stroke();
+        let (x, y) = arc.start();
+        let data = arc.arc_to(Data::new().move_to(format!("{x} {y}")));
         let path = Node::new("path").set("fill", "none").set("d", data);
         let path = stroke.set_props(path);
 
-        self.context_current.drawing_position(end);
-        self.push_element(record_number, path);
+        self.scratch_polyline(arc.points());
+        self.push_drawing(record_number, RecordType::META_ARC, path);
 
         Ok(self)
     }
@@ -693,64 +1374,37 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CHORD,
     ) -> Result<Self, PlayError> {
-        // Calculate ellipse center and radii from bounding rectangle.
-        // Use f32 to avoid precision loss from integer division.
-        let rx = (f32::from(record.right_rect - record.left_rect) / 2.0).abs();
-        let ry = (f32::from(record.bottom_rect - record.top_rect) / 2.0).abs();
-        if rx == 0.0 || ry == 0.0 {
-            info!("META_CHORD is skipped because rx or ry is zero.");
-            return Ok(self);
-        }
-        let center_x = f32::midpoint(
-            f32::from(record.left_rect),
-            f32::from(record.right_rect),
-        );
-        let center_y = f32::midpoint(
-            f32::from(record.top_rect),
-            f32::from(record.bottom_rect),
-        );
-        let center = self.context_current.point_s_to_absolute_point(&PointS {
-            x: center_x.round() as i16,
-            y: center_y.round() as i16,
-        });
+        let stroke = self.resolve_stroke();
+        let Some(arc) = self.elliptical_arc(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            (record.x_radial1, record.y_radial1),
+            (record.x_radial2, record.y_radial2),
+            stroke.inside_frame_inset(),
+        ) else {
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_CHORD,
+                DiagnosticKind::Skipped,
+                "rx or ry is zero".to_owned(),
+            );
 
-        // Convert radial endpoints from WMF coordinates to SVG absolute
-        // coordinates
-        let p1 = self.context_current.point_s_to_absolute_point(&PointS {
-            x: record.x_radial1,
-            y: record.y_radial1,
-        });
-        let p2 = self.context_current.point_s_to_absolute_point(&PointS {
-            x: record.x_radial2,
-            y: record.y_radial2,
-        });
+            return Ok(self);
+        };
 
-        // Build SVG path for chord: move to first radial, draw arc, line to
-        // center, close path
         let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
-        let stroke = Stroke::from(self.selected_pen());
-
-        // SVG arc parameters:
-        // - always small arc (large_arc=0)
-        // - always clockwise (sweep=1)
-        let large_arc = 0;
-        let sweep = 1;
-        let data = Data::new()
-            .move_to(format!("{} {}", p1.x, p1.y))
-            .elliptical_arc_to(format!(
-                "{} {} 0 {} {} {} {}",
-                rx, ry, large_arc, sweep, p2.x, p2.y
-            ))
-            .line_to(format!("{} {}", center.x, center.y))
-            .close();
+        let fill_rule = self.state.poly_fill_rule();
+        // Closing the path draws the chord back to the start point.
+        let (x, y) = arc.start();
+        let data = arc.arc_to(Data::new().move_to(format!("{x} {y}"))).close();
         let path = Node::new("path")
             .set("fill", fill.as_str())
             .set("fill-rule", fill_rule)
             .set("d", data);
         let path = stroke.set_props(path);
 
-        self.push_element(record_number, path);
+        self.scratch_polygon(arc.points());
+        self.push_drawing(record_number, RecordType::META_CHORD, path);
 
         Ok(self)
     }
@@ -772,17 +1426,19 @@ impl crate::converter::Player for SVGPlayer {
         let ry = (f32::from(record.bottom_rect - record.top_rect) / 2.0).abs();
 
         if rx == 0.0 || ry == 0.0 {
-            info!(
-                %rx, %ry,
-                "META_ELLIPSE is skipped because rx or ry is zero.",
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_ELLIPSE,
+                DiagnosticKind::Skipped,
+                "rx or ry is zero".to_owned(),
             );
 
             return Ok(self);
         }
 
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
+        let fill_rule = self.state.poly_fill_rule();
         let center_x = f32::midpoint(
             f32::from(record.left_rect),
             f32::from(record.right_rect),
@@ -794,16 +1450,22 @@ impl crate::converter::Player for SVGPlayer {
         let point = self
             .convert_point(center_x.round() as i16, center_y.round() as i16);
 
+        let inset = stroke.inside_frame_inset();
         let ellipse = Node::new("ellipse")
             .set("fill", fill.as_str())
             .set("fill-rule", fill_rule)
             .set("cx", point.x)
             .set("cy", point.y)
-            .set("rx", rx)
-            .set("ry", ry);
+            .set("rx", (rx - inset).max(0.0))
+            .set("ry", (ry - inset).max(0.0));
         let ellipse = stroke.set_props(ellipse);
 
-        self.push_element(record_number, ellipse);
+        self.scratch_polygon(Shape::ellipse_points(
+            (f32::from(point.x), f32::from(point.y)),
+            rx,
+            ry,
+        ));
+        self.push_drawing(record_number, RecordType::META_ELLIPSE, ellipse);
 
         Ok(self)
     }
@@ -814,14 +1476,18 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn ext_flood_fill(
-        self,
+        mut self,
         record_number: usize,
         record: META_EXTFLOODFILL,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_EXTFLOODFILL: skipped (pixel-based flood fill cannot be \
-             represented in SVG)",
+        self.flood_fill_from(
+            record_number,
+            RecordType::META_EXTFLOODFILL,
+            (record.x, record.y),
+            record.mode,
+            &record.color_ref,
         );
+
         Ok(self)
     }
 
@@ -838,79 +1504,99 @@ impl crate::converter::Player for SVGPlayer {
         use unicode_segmentation::UnicodeSegmentation;
         use unicode_width::UnicodeWidthStr;
 
-        let font_charset = self.object_selected.font.charset;
-        let font_height = self.object_selected.font.height;
+        let font_charset = self.state.font().charset;
+        let font_height = self.state.font().height;
         let text_content = record.into_utf8(font_charset).map_err(|err| {
             PlayError::InvalidRecord { cause: err.to_string() }
         })?;
-        let point = {
-            let point = PointS {
-                x: if self.context_current.text_align_update_cp {
-                    self.context_current.drawing_position.x
-                } else {
-                    record.x
-                },
-                y: if self.context_current.text_align_update_cp {
-                    self.context_current.drawing_position.y
-                } else {
-                    record.y
-                },
-            };
-
-            // update_cp == true: already in SVG space, no conversion
-            let point = if self.context_current.text_align_update_cp {
-                point
-            } else {
-                self.context_current.point_s_to_absolute_point(&point)
-            };
-
-            // Translate the WMF reference y into the SVG alphabetic
-            // baseline using script-aware ascent/descent ratios.
-            // Emitting baseline-aligned coordinates (and omitting
-            // `dominant-baseline`) avoids the unreliable cross-
-            // renderer support of the SVG/CSS baseline keywords,
-            // which otherwise let the text escape its WMF bounding
-            // rectangle.
-            let baseline_y_offset = self
-                .context_current
-                .text_baseline_y_offset(font_height, font_charset);
-            let point = PointS {
-                x: point.x,
-                y: point.y.saturating_add(baseline_y_offset),
-            };
+        let anchor = if self.state.text_align_update_cp() {
+            self.output_point(&self.state.drawing_position().clone())
+        } else {
+            self.output_point(&PointS { x: record.x, y: record.y })
+        };
+        // Translate the WMF reference point into the SVG baseline using
+        // script-aware ascent/descent ratios. Emitting baseline-aligned
+        // coordinates (and omitting `dominant-baseline`) avoids the
+        // unreliable cross-renderer support of the SVG/CSS baseline
+        // keywords, which otherwise let the text escape its WMF bounding
+        // rectangle.
+        let point = self.text_point(&anchor);
+        let is_vertical = self.state.font().is_vertical();
+
+        self.extend_window(&point);
+
+        let text_align = self.state.as_css_text_align();
+        // Both ETO_OPAQUE and ETO_CLIPPED refer to the rectangle, which is
+        // always given in absolute logical coordinates.
+        let rectangle = record.rectangle.as_ref().map(|rect| {
+            (
+                self.output_point(&PointS { x: rect.left, y: rect.top }),
+                self.output_point(&PointS { x: rect.right, y: rect.bottom }),
+            )
+        });
+        let rectangle_node = |(p1, p2): &(PointS, PointS)| {
+            Node::new("rect")
+                .set("x", p1.x.min(p2.x))
+                .set("y", p1.y.min(p2.y))
+                .set("width", p1.x.abs_diff(p2.x))
+                .set("height", p1.y.abs_diff(p2.y))
+        };
+        let opaque = match &rectangle {
+            Some(corners)
+                if record.fw_opts.contains(&ExtTextOutOptions::ETO_OPAQUE) =>
+            {
+                let brush =
+                    Brush::Solid { color_ref: self.state.bk_color().clone() };
+
+                self.extend_window(&corners.0);
+                self.extend_window(&corners.1);
+
+                if let Some(color) = Self::scratch_fill(&brush) {
+                    self.scratch_rects(&[corners.clone()], color);
+                }
 
-            self.context_current.extend_window(&point);
-            point
+                Some(
+                    rectangle_node(corners)
+                        .set("fill", self.fill_for(&brush))
+                        .set("stroke", "none"),
+                )
+            }
+            _ => None,
         };
-        let text_align = self.context_current.as_css_text_align();
-        let shape_inside = if let (true, Some(rect)) = (
-            record.fw_opts.contains(&ExtTextOutOptions::ETO_CLIPPED),
-            record.rectangle,
-        ) {
-            let tl = self.convert_point_for_text(rect.left, rect.top);
-            let tr = self.convert_point_for_text(rect.right, rect.top);
-            let bl = self.convert_point_for_text(rect.left, rect.bottom);
-            let br = self.convert_point_for_text(rect.right, rect.bottom);
-
-            Some(format!(
-                "shape-inside: polygon({} {} {} {});",
-                as_point_string(&tl),
-                as_point_string(&bl),
-                as_point_string(&br),
-                as_point_string(&tr),
-            ))
-        } else {
-            None
+        let clip_path = match &rectangle {
+            Some(corners)
+                if record.fw_opts.contains(&ExtTextOutOptions::ETO_CLIPPED) =>
+            {
+                Some(
+                    self.definitions.define(
+                        Node::new("clipPath").add(rectangle_node(corners)),
+                    ),
+                )
+            }
+            _ => None,
         };
 
         let mut text = Node::new("text")
             .set("x", point.x)
             .set("y", point.y)
             .set("text-anchor", text_align)
-            .set("fill", self.context_current.text_color_as_css_color());
+            .set("fill", self.state.text_color_as_css_color());
+
+        // Character and break extra are added to each glyph as GDI does,
+        // instead of CSS spacing which would be in the wrong units and
+        // spread the break extra evenly.
+        let graphemes: Vec<&str> = text_content.graphemes(true).collect();
+        let extras = self.state.glyph_extra_advances(&graphemes);
+        let has_extra = extras.iter().any(|extra| *extra != 0);
+        let extra_width = f32::from(
+            i16::try_from(extras.iter().sum::<i32>()).unwrap_or(i16::MAX),
+        );
 
-        if record.dx.len() <= 1 {
+        if record.dx.len() <= 1 && !has_extra {
             text = text.add(Node::new_text(&text_content));
+        } else if record.dx.len() <= 1 {
+            text =
+                Self::add_spaced_glyphs(text, &graphemes, &extras, is_vertical);
         } else {
             // The tspan dx attribute is an additional offset on top of the
             // natural advance computed by the SVG renderer, which accumulates
@@ -925,7 +1611,6 @@ impl crate::converter::Player for SVGPlayer {
             // first byte in the original byte sequence. Therefore re-encode
             // each grapheme with the original charset to obtain its byte
             // length, and use the running total as the index into dx.
-            let graphemes: Vec<&str> = text_content.graphemes(true).collect();
             let encoding: &'static encoding_rs::Encoding = font_charset.into();
             let is_symbol =
                 font_charset == crate::parser::CharacterSet::SYMBOL_CHARSET;
@@ -968,106 +1653,122 @@ impl crate::converter::Player for SVGPlayer {
                         .iter()
                         .map(|d| i32::from(*d))
                         .sum();
-                    cumulative_dx = cumulative_dx.saturating_add(dx_value);
-                    let abs_x =
-                        i32::from(point.x).saturating_add(cumulative_dx);
-                    tspan = tspan.set("x", abs_x);
+                    cumulative_dx = cumulative_dx
+                        .saturating_add(dx_value)
+                        .saturating_add(extras[i - 1]);
+
+                    // Vertical text advances down the y-axis.
+                    tspan = if is_vertical {
+                        tspan.set(
+                            "y",
+                            i32::from(point.y).saturating_add(cumulative_dx),
+                        )
+                    } else {
+                        tspan.set(
+                            "x",
+                            i32::from(point.x).saturating_add(cumulative_dx),
+                        )
+                    };
                 }
 
                 text = text.add(tspan);
             }
         }
 
-        let (mut text, mut styles) =
-            self.object_selected.font.set_props(text, &point);
-
-        if let Some(shape_inside) = shape_inside {
-            styles.push(shape_inside);
-        }
-
-        if self.context_current.text_char_extra > 0 {
-            styles.push(format!(
-                "letter-spacing: {}px;",
-                self.context_current.text_char_extra,
-            ));
-        }
-
-        if self.context_current.text_break_count > 0
-            && self.context_current.text_break_extra > 0
-        {
-            let word_spacing = f32::from(self.context_current.text_break_extra)
-                / f32::from(self.context_current.text_break_count);
-            styles.push(format!("word-spacing: {word_spacing}px;"));
-        }
+        let (mut text, styles) = self.state.font().set_props(text, &point);
 
         if !styles.is_empty() {
             text = text.set("style", styles.join(""));
         }
 
-        if self.context_current.text_align_update_cp {
+        if self.state.text_align_update_cp() {
             let half_height = f32::from(font_height.abs()) / 2.0;
             let text_width =
                 f32::from(i16::try_from(text_content.width()).unwrap_or(1));
-            let dx = (half_height * text_width).round() as i16;
-            let point = PointS { x: point.x + dx, y: point.y };
-            self.context_current.drawing_position(point);
+            let dx = (half_height * text_width + extra_width).round() as i16;
+            let PointS { x, y } = self.state.drawing_position().clone();
+            let position = if is_vertical {
+                PointS { x, y: y.saturating_add(dx) }
+            } else {
+                PointS { x: x.saturating_add(dx), y }
+            };
+            self.state.set_drawing_position(position);
         }
 
         // Draw background rectangle for OPAQUE text mode.
         // Insert the rect before the text so it renders behind.
-        if self.context_current.bk_mode == MixMode::OPAQUE {
+        if self.state.bk_mode() == MixMode::OPAQUE {
             let brush = if matches!(self.selected_brush(), Brush::Null) {
-                Brush::Solid {
-                    color_ref: self.context_current.text_bk_color.clone(),
-                }
+                Brush::Solid { color_ref: self.state.bk_color().clone() }
             } else {
                 self.selected_brush().clone()
             };
 
-            let bg_fill = match Fill::from(&brush) {
-                Fill::Pattern { pattern } => {
-                    let id = self.issue_definition_id();
-                    self.definitions.push(pattern.set("id", id.as_str()));
-                    url_string(format!("#{id}").as_str())
-                }
-                Fill::Value { value } => value,
-            };
+            let bg_fill = self.fill_for(&brush);
 
             // Estimate text bounding box from font metrics
             let abs_height = f32::from(font_height.abs());
             let half_height = abs_height / 2.0;
             let text_width =
                 f32::from(i16::try_from(text_content.width()).unwrap_or(1));
-            let estimated_width = half_height * text_width;
+            let estimated_width = half_height * text_width + extra_width;
 
-            // Adjust rect x based on text-anchor alignment
-            let rect_x = match self.context_current.text_align_horizontal {
+            // Adjust the start of the rect along the text based on
+            // text-anchor alignment
+            let start = if is_vertical { point.y } else { point.x };
+            let start = match self.state.text_align_horizontal() {
                 TextAlignmentMode::TA_CENTER => {
-                    f32::from(point.x) - estimated_width / 2.0
+                    f32::from(start) - estimated_width / 2.0
                 }
                 TextAlignmentMode::TA_RIGHT => {
-                    f32::from(point.x) - estimated_width
+                    f32::from(start) - estimated_width
                 }
-                _ => f32::from(point.x),
+                _ => f32::from(start),
+            };
+
+            // Vertical text is centred on its baseline.
+            let (rect_x, rect_y, rect_width, rect_height) = if is_vertical {
+                let x = f32::from(point.x) - half_height;
+
+                (x, start, abs_height, estimated_width)
+            } else {
+                let y = f32::from(point.y) - abs_height;
+
+                (start, y, estimated_width, abs_height)
             };
-            let rect_y = f32::from(point.y) - abs_height;
 
             let bg_rect = Node::new("rect")
                 .set("x", rect_x)
                 .set("y", rect_y)
-                .set("width", estimated_width)
-                .set("height", abs_height)
+                .set("width", rect_width)
+                .set("height", rect_height)
                 .set("fill", bg_fill)
                 .set("stroke", "none");
 
             // Wrap background rect and text in a group so
             // that only the group receives the element id
-            let group = Node::new("g").add(bg_rect).add(text);
-            self.push_element(record_number, group);
-        } else {
-            self.push_element(record_number, text);
+            text = Node::new("g").add(bg_rect).add(text);
         }
 
+        let mut element = self.upright_text(text, &anchor);
+
+        // ETO_OPAQUE fills the rectangle before the text is drawn.
+        if let Some(opaque) = opaque {
+            element = Node::new("g").add(opaque).add(element);
+        }
+
+        if let Some(id) = clip_path {
+            element = if element.get("transform").is_none() {
+                element.set("clip-path", url_string(&format!("#{id}")))
+            } else {
+                Node::new("g")
+                    .set("clip-path", url_string(&format!("#{id}")))
+                    .add(element)
+            };
+        }
+
+        self.push_element(record_number, element);
+
         Ok(self)
     }
 
@@ -1081,15 +1782,16 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_FILLREGION,
     ) -> Result<Self, PlayError> {
-        let region = self.get_region_from_object_table(record.region)?;
+        let region = self.state.region_at(record.region)?;
+        let brush = self.state.brush_at(record.brush)?;
         let fill = self.resolve_fill_from_object_table(record.brush)?;
-        let data = self.region_to_path(&region);
+        let data = self.region_to_path(&region, Self::scratch_fill(&brush));
         let path = Node::new("path")
             .set("d", data)
             .set("fill", fill)
             .set("stroke", "none");
 
-        self.push_element(record_number, path);
+        self.push_drawing(record_number, RecordType::META_FILLREGION, path);
 
         Ok(self)
     }
@@ -1100,14 +1802,20 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn flood_fill(
-        self,
+        mut self,
         record_number: usize,
         record: META_FLOODFILL,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_FLOODFILL: skipped (pixel-based flood fill cannot be \
-             represented in SVG)",
+        // META_FLOODFILL behaves like META_EXTFLOODFILL with
+        // FLOODFILLBORDER.
+        self.flood_fill_from(
+            record_number,
+            RecordType::META_FLOODFILL,
+            (record.x_start, record.y_start),
+            FloodFill::FLOODFILLBORDER,
+            &record.color_ref,
         );
+
         Ok(self)
     }
 
@@ -1121,7 +1829,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_FRAMEREGION,
     ) -> Result<Self, PlayError> {
-        let region = self.get_region_from_object_table(record.region)?;
+        let region = self.state.region_at(record.region)?;
         let fill = self.resolve_fill_from_object_table(record.brush)?;
         let r = &region.bounding_rectangle;
         let tl = self.convert_point(r.left, r.top);
@@ -1143,7 +1851,28 @@ impl crate::converter::Player for SVGPlayer {
             .set("stroke", fill)
             .set("stroke-width", record.width.abs().max(record.height.abs()));
 
-        self.push_element(record_number, rect);
+        let brush = self.state.brush_at(record.brush)?;
+        if let Some(color) = Self::scratch_fill(&brush) {
+            let (left, top) = (f32::from(x), f32::from(y));
+            let (right, bottom) =
+                (left + f32::from(width), top + f32::from(height));
+
+            self.push_shape(Shape::Polyline {
+                points: vec![
+                    (left, top),
+                    (right, top),
+                    (right, bottom),
+                    (left, bottom),
+                    (left, top),
+                ],
+                color,
+                width: f32::from(
+                    record.width.abs().max(record.height.abs()).max(1),
+                ),
+            });
+        }
+
+        self.push_drawing(record_number, RecordType::META_FRAMEREGION, rect);
 
         Ok(self)
     }
@@ -1154,14 +1883,18 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn invert_region(
-        self,
+        mut self,
         record_number: usize,
         record: META_INVERTREGION,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_INVERTREGION: skipped (pixel-level color inversion cannot \
-             be represented in SVG)",
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_INVERTREGION,
+            DiagnosticKind::Skipped,
+            "pixel-level color inversion cannot be represented in SVG"
+                .to_owned(),
         );
+
         Ok(self)
     }
 
@@ -1175,21 +1908,22 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_LINETO,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let point = self.convert_point(record.x, record.y);
-
+        let position = self.state.drawing_position().clone();
+        let from = self.convert_point(position.x, position.y);
         let data = Data::new()
-            .move_to(format!(
-                "{} {}",
-                self.context_current.drawing_position.x,
-                self.context_current.drawing_position.y
-            ))
+            .move_to(format!("{} {}", from.x, from.y))
             .line_to(format!("{} {}", point.x, point.y));
         let path = Node::new("path").set("fill", "none").set("d", data);
         let path = stroke.set_props(path);
 
-        self.context_current.drawing_position(point);
-        self.push_element(record_number, path);
+        self.scratch_polyline(vec![
+            (f32::from(from.x), f32::from(from.y)),
+            (f32::from(point.x), f32::from(point.y)),
+        ]);
+        self.state.set_drawing_position(PointS { x: record.x, y: record.y });
+        self.push_drawing(record_number, RecordType::META_LINETO, path);
 
         Ok(self)
     }
@@ -1204,15 +1938,16 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_PAINTREGION,
     ) -> Result<Self, PlayError> {
-        let region = self.get_region_from_object_table(record.region)?;
+        let region = self.state.region_at(record.region)?;
         let fill = self.resolve_fill();
-        let data = self.region_to_path(&region);
+        let data = self
+            .region_to_path(&region, Self::scratch_fill(self.selected_brush()));
         let path = Node::new("path")
             .set("d", data)
             .set("fill", fill)
             .set("stroke", "none");
 
-        self.push_element(record_number, path);
+        self.push_drawing(record_number, RecordType::META_PAINTREGION, path);
 
         Ok(self)
     }
@@ -1228,33 +1963,68 @@ impl crate::converter::Player for SVGPlayer {
         record: META_PATBLT,
     ) -> Result<Self, PlayError> {
         if record.width == 0 || record.height == 0 {
-            info!(
-                %record.width,
-                %record.height,
-                "META_PATBLT is skipped because width or height is zero.",
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_PATBLT,
+                DiagnosticKind::Skipped,
+                "width or height is zero".to_owned(),
             );
 
             return Ok(self);
         }
 
-        let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
         let p1 = self.convert_point(record.x_left, record.y_left);
         let p2 = self.convert_point(
             record.x_left + record.width,
             record.y_left + record.height,
         );
 
-        let rect = Node::new("rect")
-            .set("fill", fill.as_str())
-            .set("fill-rule", fill_rule)
-            .set("stroke", "none")
-            .set("x", p1.x.min(p2.x))
-            .set("y", p1.y.min(p2.y))
-            .set("height", (p2.y - p1.y).abs())
-            .set("width", (p2.x - p1.x).abs());
+        if record.raster_operation.use_source() {
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_PATBLT,
+                DiagnosticKind::Skipped,
+                format!(
+                    "{:?}: raster operation requires a source bitmap",
+                    record.raster_operation,
+                ),
+            );
 
-        self.push_element(record_number, rect);
+            return Ok(self);
+        }
+
+        let (left, top) = (p1.x.min(p2.x), p1.y.min(p2.y));
+        let (right, bottom) = (p1.x.max(p2.x), p1.y.max(p2.y));
+        let mut operator = TernaryRasterOperator::new(
+            record.raster_operation,
+            left,
+            top,
+            bottom - top,
+            right - left,
+        );
+
+        if record.raster_operation.use_selected_brush() {
+            operator = operator.brush(self.resolve_fill());
+        }
+
+        let color = match record.raster_operation {
+            TernaryRasterOperation::PATCOPY => {
+                Self::scratch_fill(self.selected_brush())
+            }
+            TernaryRasterOperation::BLACKNESS => Some(0x000000),
+            TernaryRasterOperation::WHITENESS => Some(0xFFFFFF),
+            _ => None,
+        };
+
+        if let Some(color) = color {
+            self.scratch_rects(&[(p1, p2)], color);
+        }
+
+        self.run_raster_operator(
+            record_number,
+            RecordType::META_PATBLT,
+            operator,
+        )?;
 
         Ok(self)
     }
@@ -1269,48 +2039,43 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_PIE,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
-        let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
-        // Use f32 to avoid precision loss from integer division.
-        // Take abs() because SVG requires non-negative radii,
-        // while WMF may have inverted bounding rectangles.
-        let rx = (f32::from(record.right_rect - record.left_rect) / 2.0).abs();
-        let ry = (f32::from(record.bottom_rect - record.top_rect) / 2.0).abs();
-        let center_x = f32::midpoint(
-            f32::from(record.left_rect),
-            f32::from(record.right_rect),
-        );
-        let center_y = f32::midpoint(
-            f32::from(record.top_rect),
-            f32::from(record.bottom_rect),
-        );
-        let center_xi = center_x.round() as i16;
-        let center_yi = center_y.round() as i16;
-
-        let ellipse = Node::new("ellipse")
-            .set("fill", fill.as_str())
-            .set("fill-rule", fill_rule)
-            .set("cx", center_x)
-            .set("cy", center_y)
-            .set("rx", rx)
-            .set("ry", ry);
-        let ellipse = stroke.set_props(ellipse);
+        let stroke = self.resolve_stroke();
+        let Some(arc) = self.elliptical_arc(
+            (record.left_rect, record.top_rect),
+            (record.right_rect, record.bottom_rect),
+            (record.x_radial1, record.y_radial1),
+            (record.x_radial2, record.y_radial2),
+            stroke.inside_frame_inset(),
+        ) else {
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_PIE,
+                DiagnosticKind::Skipped,
+                "rx or ry is zero".to_owned(),
+            );
 
-        let p1 = self.convert_point(record.x_radial1, record.y_radial1);
-        let center = self.convert_point(center_xi, center_yi);
-        let p2 = self.convert_point(record.x_radial2, record.y_radial2);
+            return Ok(self);
+        };
 
+        let fill = self.resolve_fill();
+        let fill_rule = self.state.poly_fill_rule();
+        let (cx, cy) = arc.center();
+        let (x, y) = arc.start();
         let data = Data::new()
-            .move_to(format!("{} {}", p1.x, p1.y))
-            .line_to(format!("{} {}", center.x, center.y))
-            .line_to(format!("{} {}", p2.x, p2.y));
-        let path = Node::new("path").set("fill", "none").set("d", data);
+            .move_to(format!("{cx} {cy}"))
+            .line_to(format!("{x} {y}"));
+        let data = arc.arc_to(data).close();
+        let path = Node::new("path")
+            .set("fill", fill.as_str())
+            .set("fill-rule", fill_rule)
+            .set("d", data);
         let path = stroke.set_props(path);
 
-        self.context_current.drawing_position(p2);
-        self.push_element(record_number, ellipse);
-        self.push_element(record_number, path);
+        let mut points = arc.points();
+        points.push((cx, cy));
+
+        self.scratch_polygon(points);
+        self.push_drawing(record_number, RecordType::META_PIE, path);
 
         Ok(self)
     }
@@ -1325,14 +2090,17 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_POLYLINE,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let Some(point) = record.a_points.first() else {
             return Err(PlayError::InvalidRecord {
                 cause: "aPoints[0] is not defined".to_owned(),
             });
         };
 
+        let mut last = point;
         let mut coordinate = self.convert_point(point.x, point.y);
+        let mut scratch =
+            vec![(f32::from(coordinate.x), f32::from(coordinate.y))];
 
         let mut data =
             Data::new().move_to(format!("{} {}", coordinate.x, coordinate.y));
@@ -1344,7 +2112,9 @@ impl crate::converter::Player for SVGPlayer {
                 });
             };
 
+            last = point;
             coordinate = self.convert_point(point.x, point.y);
+            scratch.push((f32::from(coordinate.x), f32::from(coordinate.y)));
 
             data = data.line_to(format!("{} {}", coordinate.x, coordinate.y));
         }
@@ -1352,8 +2122,9 @@ impl crate::converter::Player for SVGPlayer {
         let path = Node::new("path").set("fill", "none").set("d", data);
         let path = stroke.set_props(path);
 
-        self.context_current.drawing_position(coordinate);
-        self.push_element(record_number, path);
+        self.scratch_polyline(scratch);
+        self.state.set_drawing_position(last.clone());
+        self.push_drawing(record_number, RecordType::META_POLYLINE, path);
 
         Ok(self)
     }
@@ -1369,15 +2140,22 @@ impl crate::converter::Player for SVGPlayer {
         record: META_POLYGON,
     ) -> Result<Self, PlayError> {
         if record.number_of_points == 0 {
-            info!(%record.number_of_points, "polygon has no points");
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_POLYGON,
+                DiagnosticKind::Skipped,
+                "polygon has no points".to_owned(),
+            );
+
             return Ok(self);
         }
 
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
+        let fill_rule = self.state.poly_fill_rule();
 
         let mut points = Vec::with_capacity(record.number_of_points as usize);
+        let mut scratch = Vec::with_capacity(points.capacity());
 
         for i in 0..record.number_of_points {
             let Some(point) = record.a_points.get(i as usize) else {
@@ -1388,6 +2166,7 @@ impl crate::converter::Player for SVGPlayer {
 
             let point = self.convert_point(point.x, point.y);
             points.push(as_point_string(&point));
+            scratch.push((f32::from(point.x), f32::from(point.y)));
         }
 
         let polygon = Node::new("polygon")
@@ -1396,7 +2175,8 @@ impl crate::converter::Player for SVGPlayer {
             .set("points", points.join(" "));
         let polygon = stroke.set_props(polygon);
 
-        self.push_element(record_number, polygon);
+        self.scratch_polygon(scratch);
+        self.push_drawing(record_number, RecordType::META_POLYGON, polygon);
 
         Ok(self)
     }
@@ -1411,9 +2191,9 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_POLYPOLYGON,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
+        let fill_rule = self.state.poly_fill_rule();
 
         let mut a_point: VecDeque<_> = record.poly_polygon.a_points.into();
         let total_points = a_point.len();
@@ -1428,6 +2208,7 @@ impl crate::converter::Player for SVGPlayer {
             };
 
             let mut points = Vec::with_capacity(*points_of_polygon as usize);
+            let mut scratch = Vec::with_capacity(points.capacity());
 
             for _ in 0..*points_of_polygon {
                 let Some(point) = a_point.pop_front() else {
@@ -1441,6 +2222,7 @@ impl crate::converter::Player for SVGPlayer {
 
                 let point = self.convert_point(point.x, point.y);
                 points.push(as_point_string(&point));
+                scratch.push((f32::from(point.x), f32::from(point.y)));
             }
 
             let polygon = Node::new("polygon")
@@ -1449,7 +2231,12 @@ impl crate::converter::Player for SVGPlayer {
                 .set("points", points.join(" "));
             let polygon = stroke.set_props(polygon);
 
-            self.push_element(record_number, polygon);
+            self.scratch_polygon(scratch);
+            self.push_drawing(
+                record_number,
+                RecordType::META_POLYPOLYGON,
+                polygon,
+            );
         }
 
         Ok(self)
@@ -1465,22 +2252,35 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_RECTANGLE,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
+        let fill_rule = self.state.poly_fill_rule();
         let p1 = self.convert_point(record.left_rect, record.top_rect);
         let p2 = self.convert_point(record.right_rect, record.bottom_rect);
 
+        let inset = stroke.inside_frame_inset();
         let rect = Node::new("rect")
             .set("fill", fill.as_str())
             .set("fill-rule", fill_rule)
-            .set("x", p1.x.min(p2.x))
-            .set("y", p1.y.min(p2.y))
-            .set("height", (p2.y - p1.y).abs())
-            .set("width", (p2.x - p1.x).abs());
+            .set("x", f32::from(p1.x.min(p2.x)) + inset)
+            .set("y", f32::from(p1.y.min(p2.y)) + inset)
+            .set(
+                "height",
+                (f32::from((p2.y - p1.y).abs()) - inset * 2.0).max(0.0),
+            )
+            .set(
+                "width",
+                (f32::from((p2.x - p1.x).abs()) - inset * 2.0).max(0.0),
+            );
         let rect = stroke.set_props(rect);
 
-        self.push_element(record_number, rect);
+        self.scratch_polygon(vec![
+            (f32::from(p1.x), f32::from(p1.y)),
+            (f32::from(p2.x), f32::from(p1.y)),
+            (f32::from(p2.x), f32::from(p2.y)),
+            (f32::from(p1.x), f32::from(p2.y)),
+        ]);
+        self.push_drawing(record_number, RecordType::META_RECTANGLE, rect);
 
         Ok(self)
     }
@@ -1495,9 +2295,9 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_ROUNDRECT,
     ) -> Result<Self, PlayError> {
-        let stroke = Stroke::from(self.selected_pen());
+        let stroke = self.resolve_stroke();
         let fill = self.resolve_fill();
-        let fill_rule = self.context_current.poly_fill_rule();
+        let fill_rule = self.state.poly_fill_rule();
         let p1 = self.convert_point(record.left_rect, record.top_rect);
         let p2 = self.convert_point(record.right_rect, record.bottom_rect);
 
@@ -1505,26 +2305,36 @@ impl crate::converter::Player for SVGPlayer {
         let height = (p2.y - p1.y).abs();
 
         if width == 0 || height == 0 {
-            info!(
-                %width, %height,
-                "META_ROUNDRECT is skipped because width or height is zero.",
+            self.diagnostics.report(
+                record_number,
+                RecordType::META_ROUNDRECT,
+                DiagnosticKind::Skipped,
+                "width or height is zero".to_owned(),
             );
 
             return Ok(self);
         }
 
+        let inset = stroke.inside_frame_inset();
         let rect = Node::new("rect")
             .set("fill", fill.as_str())
             .set("fill-rule", fill_rule)
-            .set("x", p1.x.min(p2.x))
-            .set("y", p1.y.min(p2.y))
-            .set("height", height)
-            .set("width", width)
+            .set("x", f32::from(p1.x.min(p2.x)) + inset)
+            .set("y", f32::from(p1.y.min(p2.y)) + inset)
+            .set("height", (f32::from(height) - inset * 2.0).max(0.0))
+            .set("width", (f32::from(width) - inset * 2.0).max(0.0))
             .set("rx", record.width)
             .set("ry", record.height);
         let rect = stroke.set_props(rect);
 
-        self.push_element(record_number, rect);
+        // Rounded corners are negligible for the scratch canvas.
+        self.scratch_polygon(vec![
+            (f32::from(p1.x), f32::from(p1.y)),
+            (f32::from(p2.x), f32::from(p1.y)),
+            (f32::from(p2.x), f32::from(p2.y)),
+            (f32::from(p1.x), f32::from(p2.y)),
+        ]);
+        self.push_drawing(record_number, RecordType::META_ROUNDRECT, rect);
 
         Ok(self)
     }
@@ -1550,6 +2360,13 @@ impl crate::converter::Player for SVGPlayer {
             .set("fill", color.as_str())
             .set("stroke", "none");
 
+        self.scratch_rects(
+            &[(point.clone(), PointS {
+                x: point.x.saturating_add(1),
+                y: point.y.saturating_add(1),
+            })],
+            rgb(&record.color_ref),
+        );
         self.push_element(record_number, rect);
 
         Ok(self)
@@ -1565,72 +2382,76 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_TEXTOUT,
     ) -> Result<Self, PlayError> {
+        use unicode_segmentation::UnicodeSegmentation;
         use unicode_width::UnicodeWidthStr;
 
-        let font_charset = self.object_selected.font.charset;
-        let font_height = self.object_selected.font.height;
+        let font_charset = self.state.font().charset;
+        let font_height = self.state.font().height;
         let text_content = record.into_utf8(font_charset).map_err(|err| {
             PlayError::InvalidRecord { cause: err.to_string() }
         })?;
-        let point = self.convert_point_for_text(record.x_start, record.y_start);
-        // Same baseline shift as ext_text_out: pre-place y on the
-        // alphabetic baseline so the text stays inside the WMF
-        // bounding rectangle regardless of renderer baseline support.
-        let baseline_y_offset = self
-            .context_current
-            .text_baseline_y_offset(font_height, font_charset);
-        let point =
-            PointS { x: point.x, y: point.y.saturating_add(baseline_y_offset) };
+        let anchor =
+            self.convert_point_for_text(record.x_start, record.y_start);
+        // Same baseline shift as ext_text_out: pre-place the text on its
+        // baseline so it stays inside the WMF bounding rectangle
+        // regardless of renderer baseline support.
+        let point = self.text_point(&anchor);
+        let is_vertical = self.state.font().is_vertical();
+        let graphemes: Vec<&str> = text_content.graphemes(true).collect();
+        let extras = self.state.glyph_extra_advances(&graphemes);
+        let has_extra = extras.iter().any(|extra| *extra != 0);
+        let extra_width = f32::from(
+            i16::try_from(extras.iter().sum::<i32>()).unwrap_or(i16::MAX),
+        );
 
         let text = Node::new("text")
             .set("x", point.x)
             .set("y", point.y)
-            .set("fill", self.context_current.text_color_as_css_color())
-            .add(Node::new_text(&text_content));
-        let (text, mut styles) =
-            self.object_selected.font.set_props(text, &point);
-
-        if self.context_current.text_char_extra > 0 {
-            styles.push(format!(
-                "letter-spacing: {}px;",
-                self.context_current.text_char_extra,
-            ));
-        }
-
-        if self.context_current.text_break_count > 0
-            && self.context_current.text_break_extra > 0
-        {
-            let word_spacing = f32::from(self.context_current.text_break_extra)
-                / f32::from(self.context_current.text_break_count);
-            styles.push(format!("word-spacing: {word_spacing}px;"));
-        }
+            .set("fill", self.state.text_color_as_css_color());
+        let text = if has_extra {
+            Self::add_spaced_glyphs(text, &graphemes, &extras, is_vertical)
+        } else {
+            text.add(Node::new_text(&text_content))
+        };
+        let (text, styles) = self.state.font().set_props(text, &point);
 
         let text = text.set("style", styles.join(""));
 
         // Draw background rectangle for OPAQUE text mode
-        if self.context_current.bk_mode == MixMode::OPAQUE {
-            let bg_color =
-                css_color_from_color_ref(&self.context_current.text_bk_color);
+        if self.state.bk_mode() == MixMode::OPAQUE {
+            let bg_color = css_color_from_color_ref(self.state.bk_color());
             let abs_height = f32::from(font_height.abs());
             let half_height = abs_height / 2.0;
             let text_width =
                 f32::from(i16::try_from(text_content.width()).unwrap_or(1));
-            let estimated_width = half_height * text_width;
-            let rect_y = f32::from(point.y) - abs_height;
+            let estimated_width = half_height * text_width + extra_width;
+
+            // Vertical text is centred on its baseline.
+            let (rect_x, rect_y, rect_width, rect_height) = if is_vertical {
+                let x = f32::from(point.x) - half_height;
+
+                (x, f32::from(point.y), abs_height, estimated_width)
+            } else {
+                let y = f32::from(point.y) - abs_height;
+
+                (f32::from(point.x), y, estimated_width, abs_height)
+            };
 
             let bg_rect = Node::new("rect")
-                .set("x", point.x)
+                .set("x", rect_x)
                 .set("y", rect_y)
-                .set("width", estimated_width)
-                .set("height", abs_height)
+                .set("width", rect_width)
+                .set("height", rect_height)
                 .set("fill", bg_color)
                 .set("stroke", "none");
 
             // Wrap background rect and text in a group so
             // that only the group receives the element id
             let group = Node::new("g").add(bg_rect).add(text);
+            let group = self.upright_text(group, &anchor);
             self.push_element(record_number, group);
         } else {
+            let text = self.upright_text(text, &anchor);
             self.push_element(record_number, text);
         }
 
@@ -1652,9 +2473,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CREATEBRUSHINDIRECT,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .object_table
-            .push(GraphicsObject::Brush(record.create_brush()));
+        self.state.create_brush_indirect(&record);
 
         Ok(self)
     }
@@ -1669,9 +2488,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CREATEFONTINDIRECT,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .object_table
-            .push(GraphicsObject::Font(record.font));
+        self.state.create_font_indirect(&record);
 
         Ok(self)
     }
@@ -1686,9 +2503,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CREATEPALETTE,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .object_table
-            .push(GraphicsObject::Palette(record.palette));
+        self.state.create_palette(&record);
 
         Ok(self)
     }
@@ -1703,9 +2518,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CREATEPATTERNBRUSH,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .object_table
-            .push(GraphicsObject::Brush(record.create_brush()));
+        self.state.create_pattern_brush(&record);
 
         Ok(self)
     }
@@ -1720,7 +2533,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CREATEPENINDIRECT,
     ) -> Result<Self, PlayError> {
-        self.context_current.object_table.push(GraphicsObject::Pen(record.pen));
+        self.state.create_pen_indirect(&record);
 
         Ok(self)
     }
@@ -1735,9 +2548,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_CREATEREGION,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .object_table
-            .push(GraphicsObject::Region(record.region));
+        self.state.create_region(&record);
 
         Ok(self)
     }
@@ -1752,7 +2563,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_DELETEOBJECT,
     ) -> Result<Self, PlayError> {
-        self.context_current.object_table.delete(record.object_index as usize);
+        self.state.delete_object(&record);
 
         Ok(self)
     }
@@ -1767,9 +2578,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_DIBCREATEPATTERNBRUSH,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .object_table
-            .push(GraphicsObject::Brush(record.create_brush()));
+        self.state.create_device_independent_bitmap_pattern_brush(&record);
 
         Ok(self)
     }
@@ -1784,24 +2593,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SELECTCLIPREGION,
     ) -> Result<Self, PlayError> {
-        let object =
-            self.context_current.object_table.get(record.region as usize);
-
-        if let GraphicsObject::Region(region) = object {
-            let rect = &region.bounding_rectangle;
-            let id = format!("clip{record_number}");
-            let mut clip = Node::new("clipPath").set("id", &id);
-            let rect_node = Node::new("rect")
-                .set("x", rect.left)
-                .set("y", rect.top)
-                .set("width", rect.right - rect.left)
-                .set("height", rect.bottom - rect.top);
-            clip = clip.add(rect_node);
-            self.definitions.push(clip);
-            self.current_clip_id = Some(id);
-        } else {
-            self.current_clip_id = None;
-        }
+        self.state.select_clip_region(&record);
 
         Ok(self)
     }
@@ -1816,31 +2608,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SELECTOBJECT,
     ) -> Result<Self, PlayError> {
-        let object =
-            self.context_current.object_table.get(record.object_index as usize);
-
-        match object {
-            GraphicsObject::Brush(v) => {
-                self.object_selected.set_brush(v.clone());
-            }
-            GraphicsObject::Font(v) => {
-                self.object_selected.set_font(v.clone());
-            }
-            GraphicsObject::Palette(v) => {
-                self.object_selected.set_palette(v.clone());
-            }
-            GraphicsObject::Pen(v) => {
-                self.object_selected.set_pen(v.clone());
-            }
-            GraphicsObject::Region(v) => {
-                self.object_selected.set_region(v.clone());
-            }
-            GraphicsObject::Null => {
-                return Err(PlayError::UnexpectedGraphicsObject {
-                    cause: "Graphics Object is null".to_owned(),
-                });
-            }
-        }
+        self.state.select_object(&record)?;
 
         Ok(self)
     }
@@ -1855,16 +2623,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SELECTPALETTE,
     ) -> Result<Self, PlayError> {
-        let object =
-            self.context_current.object_table.get(record.palette as usize);
-
-        let GraphicsObject::Palette(palette) = object else {
-            return Err(PlayError::UnexpectedGraphicsObject {
-                cause: "Graphics Object is not palette object".to_owned(),
-            });
-        };
-
-        self.object_selected.set_palette(palette.clone());
+        self.state.select_palette(&record)?;
 
         Ok(self)
     }
@@ -1880,14 +2639,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn animate_palette(
-        self,
+        mut self,
         record_number: usize,
         record: META_ANIMATEPALETTE,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_ANIMATEPALETTE: skipped (palette animation is not \
-             applicable to SVG)",
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_ANIMATEPALETTE,
+            DiagnosticKind::Skipped,
+            "palette animation is not applicable to SVG".to_owned(),
         );
+
         Ok(self)
     }
 
@@ -1901,32 +2663,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_EXCLUDECLIPRECT,
     ) -> Result<Self, PlayError> {
-        // Convert logical coordinates to SVG coordinates so
-        // clipping matches rendered elements. Use
-        // point_s_to_absolute_point directly to avoid
-        // expanding the viewBox for non-drawing geometry.
-        let to_abs = |x, y| {
-            self.context_current.point_s_to_absolute_point(&PointS { x, y })
-        };
-        let tl = to_abs(record.left, record.top);
-        let tr = to_abs(record.right, record.top);
-        let br = to_abs(record.right, record.bottom);
-        let bl = to_abs(record.left, record.bottom);
-
-        // Build a clip path that punches the excluded rect as
-        // a hole using the even-odd fill rule
-        let id = format!("clip_excl{record_number}");
-        let path_data = format!(
-            "M-32768,-32768 H32767 V32767 H-32768 Z M{},{} L{},{} L{},{} \
-             L{},{} Z",
-            tl.x, tl.y, tr.x, tr.y, br.x, br.y, bl.x, bl.y,
-        );
-        let path =
-            Node::new("path").set("d", path_data).set("clip-rule", "evenodd");
-        let clip = Node::new("clipPath").set("id", &id).add(path);
-
-        self.definitions.push(clip);
-        self.current_clip_id = Some(id);
+        self.state.exclude_clip_rect(&record);
 
         Ok(self)
     }
@@ -1941,9 +2678,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_INTERSECTCLIPRECT,
     ) -> Result<Self, PlayError> {
-        let META_INTERSECTCLIPRECT { bottom, right, top, left, .. } = record;
-
-        self.context_current.clipping_region(Rect { left, top, right, bottom });
+        self.state.intersect_clip_rect(&record);
 
         Ok(self)
     }
@@ -1958,8 +2693,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_MOVETO,
     ) -> Result<Self, PlayError> {
-        let point = self.convert_point(record.x, record.y);
-        self.context_current.drawing_position(point);
+        self.state.move_to(&record);
 
         Ok(self)
     }
@@ -1974,44 +2708,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_OFFSETCLIPRGN,
     ) -> Result<Self, PlayError> {
-        if let Some(ref mut region) = self.context_current.clipping_region {
-            region.left = region.left.saturating_add(record.x_offset);
-            region.right = region.right.saturating_add(record.x_offset);
-            region.top = region.top.saturating_add(record.y_offset);
-            region.bottom = region.bottom.saturating_add(record.y_offset);
-
-            // Copy values before conversion to avoid borrow
-            // conflict with self.context_current
-            let (left, top, right, bottom) =
-                (region.left, region.top, region.right, region.bottom);
-
-            // Convert logical coordinates to SVG coordinates
-            // so clipping matches rendered elements. Use
-            // point_s_to_absolute_point directly to avoid
-            // expanding the viewBox for non-drawing geometry.
-            let tl = self
-                .context_current
-                .point_s_to_absolute_point(&PointS { x: left, y: top });
-            let br = self
-                .context_current
-                .point_s_to_absolute_point(&PointS { x: right, y: bottom });
-
-            let x = tl.x.min(br.x);
-            let y = tl.y.min(br.y);
-            let width = (br.x - tl.x).abs();
-            let height = (br.y - tl.y).abs();
-
-            let id = format!("clip{record_number}");
-            let rect = Node::new("rect")
-                .set("x", x)
-                .set("y", y)
-                .set("width", width)
-                .set("height", height);
-            let clip = Node::new("clipPath").set("id", &id).add(rect);
-
-            self.definitions.push(clip);
-            self.current_clip_id = Some(id);
-        }
+        self.state.offset_clip_region(&record);
 
         Ok(self)
     }
@@ -2026,8 +2723,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_OFFSETVIEWPORTORG,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .offset_viewport_origin(record.x_offset, record.y_offset);
+        self.state.offset_viewport_origin(&record);
 
         Ok(self)
     }
@@ -2042,8 +2738,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_OFFSETWINDOWORG,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .offset_window_origin(record.x_offset, record.y_offset);
+        self.state.offset_window_origin(&record);
 
         Ok(self)
     }
@@ -2054,14 +2749,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn realize_palette(
-        self,
+        mut self,
         record_number: usize,
         record: META_REALIZEPALETTE,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_REALIZEPALETTE: skipped (system palette mapping is not \
-             applicable to SVG)",
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_REALIZEPALETTE,
+            DiagnosticKind::Skipped,
+            "system palette mapping is not applicable to SVG".to_owned(),
         );
+
         Ok(self)
     }
 
@@ -2071,14 +2769,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn resize_palette(
-        self,
+        mut self,
         record_number: usize,
         record: META_RESIZEPALETTE,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_RESIZEPALETTE: skipped (palette management is not \
-             applicable to SVG)",
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_RESIZEPALETTE,
+            DiagnosticKind::Skipped,
+            "palette management is not applicable to SVG".to_owned(),
         );
+
         Ok(self)
     }
 
@@ -2092,49 +2793,8 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_RESTOREDC,
     ) -> Result<Self, PlayError> {
-        // MS-WMF spec:
-        // Negative: relative offset from the top of the stack
-        //   (-1 = most recent, -2 = one before that, ...)
-        // Positive: 1-based absolute index
-        // In both cases, all entries saved after the target are discarded
-        let index = match record.n_saved_dc.cmp(&0) {
-            core::cmp::Ordering::Less => {
-                let offset = record.n_saved_dc.unsigned_abs() as usize;
-
-                self.context_stack.len().checked_sub(offset)
-            }
-            core::cmp::Ordering::Greater => {
-                let idx = (record.n_saved_dc as usize) - 1;
-
-                if idx < self.context_stack.len() { Some(idx) } else { None }
-            }
-            core::cmp::Ordering::Equal => None,
-        };
-
-        if let Some(idx) = index {
-            // Object Table is not subject to save/restore
-            let object_table = core::mem::replace(
-                &mut self.context_current.object_table,
-                GraphicsObjects::new(0),
-            );
-            // viewBox tracking values are not subject to save/restore
-            // (preserve the visible bounds of already-rendered elements)
-            let window_min_x = self.context_current.window.min_x;
-            let window_min_y = self.context_current.window.min_y;
-            let window_max_x = self.context_current.window.x;
-            let window_max_y = self.context_current.window.y;
-
-            self.context_current = self.context_stack[idx].clone();
-            self.context_current.object_table = object_table;
-            self.context_current.window.min_x =
-                window_min_x.min(self.context_current.window.min_x);
-            self.context_current.window.min_y =
-                window_min_y.min(self.context_current.window.min_y);
-            self.context_current.window.x =
-                window_max_x.max(self.context_current.window.x);
-            self.context_current.window.y =
-                window_max_y.max(self.context_current.window.y);
-            self.context_stack.truncate(idx);
+        if let Some(idx) = self.state.restore_device_context(&record) {
+            self.groups.restore(idx);
         }
 
         Ok(self)
@@ -2150,7 +2810,8 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SAVEDC,
     ) -> Result<Self, PlayError> {
-        self.context_stack.push(self.context_current.clone());
+        self.state.save_device_context(&record);
+        self.groups.save();
 
         Ok(self)
     }
@@ -2165,22 +2826,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SCALEVIEWPORTEXT,
     ) -> Result<Self, PlayError> {
-        if record.x_denom == 0 || record.y_denom == 0 {
-            return Err(PlayError::InvalidRecord {
-                cause: format!(
-                    "META_SCALEVIEWPORTEXT has zero denominator: x_denom={}, \
-                     y_denom={}",
-                    record.x_denom, record.y_denom,
-                ),
-            });
-        }
-
-        self.context_current.scale_viewport_ext(
-            record.x_num,
-            record.x_denom,
-            record.y_num,
-            record.y_denom,
-        );
+        self.state.scale_viewport_ext(&record)?;
 
         Ok(self)
     }
@@ -2195,24 +2841,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SCALEWINDOWEXT,
     ) -> Result<Self, PlayError> {
-        if record.x_denom == 0 || record.y_denom == 0 {
-            return Err(PlayError::InvalidRecord {
-                cause: format!(
-                    "META_SCALEWINDOWEXT has zero denominator: x_denom={}, \
-                     y_denom={}",
-                    record.x_denom, record.y_denom,
-                ),
-            });
-        }
-
-        let scale_x = (self.context_current.window.scale_x
-            * f32::from(record.x_num))
-            / f32::from(record.x_denom);
-        let scale_y = (self.context_current.window.scale_y
-            * f32::from(record.y_num))
-            / f32::from(record.y_denom);
-
-        self.context_current.window_scale(scale_x, scale_y);
+        self.state.scale_window_ext(&record)?;
 
         Ok(self)
     }
@@ -2227,7 +2856,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETBKCOLOR,
     ) -> Result<Self, PlayError> {
-        self.context_current.text_bk_color(record.color_ref);
+        self.state.set_bk_color(&record);
 
         Ok(self)
     }
@@ -2242,7 +2871,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETBKMODE,
     ) -> Result<Self, PlayError> {
-        self.context_current.bk_mode(record.bk_mode);
+        self.state.set_bk_mode(&record);
 
         Ok(self)
     }
@@ -2257,7 +2886,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETLAYOUT,
     ) -> Result<Self, PlayError> {
-        self.context_current.layout(record.layout);
+        self.state.set_layout(&record);
 
         Ok(self)
     }
@@ -2272,7 +2901,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETMAPMODE,
     ) -> Result<Self, PlayError> {
-        self.context_current.map_mode(record.map_mode);
+        self.state.set_map_mode(&record);
 
         Ok(self)
     }
@@ -2283,14 +2912,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_mapper_flags(
-        self,
+        mut self,
         record_number: usize,
         record: META_SETMAPPERFLAGS,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_SETMAPPERFLAGS: skipped (font mapper algorithm control is \
-             not applicable to SVG)",
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_SETMAPPERFLAGS,
+            DiagnosticKind::Skipped,
+            "font mapper algorithm control is not applicable to SVG".to_owned(),
         );
+
         Ok(self)
     }
 
@@ -2300,14 +2932,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_pal_entries(
-        self,
+        mut self,
         record_number: usize,
         record: META_SETPALENTRIES,
     ) -> Result<Self, PlayError> {
-        info!(
-            "META_SETPALENTRIES: skipped (palette entry management is not \
-             applicable to SVG)",
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_SETPALENTRIES,
+            DiagnosticKind::Skipped,
+            "palette entry management is not applicable to SVG".to_owned(),
         );
+
         Ok(self)
     }
 
@@ -2321,7 +2956,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETPOLYFILLMODE,
     ) -> Result<Self, PlayError> {
-        self.context_current.poly_fill_mode(record.poly_fill_mode);
+        self.state.set_polyfill_mode(&record);
 
         Ok(self)
     }
@@ -2332,11 +2967,17 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn set_relabs(
-        self,
+        mut self,
         record_number: usize,
         record: META_SETRELABS,
     ) -> Result<Self, PlayError> {
-        info!("META_SETRELABS: reserved record and not supported");
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_SETRELABS,
+            DiagnosticKind::Skipped,
+            "reserved record and not supported".to_owned(),
+        );
+
         Ok(self)
     }
 
@@ -2350,7 +2991,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETROP2,
     ) -> Result<Self, PlayError> {
-        self.context_current.draw_mode(record.draw_mode);
+        self.state.set_raster_operation(&record);
 
         Ok(self)
     }
@@ -2365,7 +3006,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETSTRETCHBLTMODE,
     ) -> Result<Self, PlayError> {
-        self.context_current.stretch_mode(record.stretch_mode);
+        self.state.set_stretch_blt_mode(&record);
 
         Ok(self)
     }
@@ -2380,39 +3021,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETTEXTALIGN,
     ) -> Result<Self, PlayError> {
-        let update_cp = record.text_alignment_mode
-            & (TextAlignmentMode::TA_UPDATECP as u16)
-            == TextAlignmentMode::TA_UPDATECP as u16;
-        let align_horizontal =
-            [TextAlignmentMode::TA_CENTER, TextAlignmentMode::TA_RIGHT]
-                .into_iter()
-                .find(|a| record.text_alignment_mode & (*a as u16) == *a as u16)
-                .unwrap_or(TextAlignmentMode::TA_LEFT);
-        // Per MS-WMF section 2.3.5.24, the value is interpreted as
-        // TextAlignmentMode for fonts with a horizontal default
-        // baseline (the common case), and VerticalTextAlignmentMode
-        // only when the font has a vertical default baseline (e.g.,
-        // CJK vertical layout). Map the horizontal-baseline values
-        // (TA_TOP / TA_BOTTOM / TA_BASELINE) to the internal vertical
-        // alignment representation.
-        //
-        // Test TA_BASELINE first because it shares the TA_BOTTOM bit
-        // (0x18 vs 0x08); a plain bit-AND against TA_BOTTOM would
-        // incorrectly match TA_BASELINE as well.
-        let ta_baseline = TextAlignmentMode::TA_BASELINE as u16;
-        let ta_bottom = TextAlignmentMode::TA_BOTTOM as u16;
-        let align_vertical =
-            if (record.text_alignment_mode & ta_baseline) == ta_baseline {
-                VerticalTextAlignmentMode::VTA_BASELINE
-            } else if (record.text_alignment_mode & ta_bottom) == ta_bottom {
-                VerticalTextAlignmentMode::VTA_BOTTOM
-            } else {
-                VerticalTextAlignmentMode::VTA_TOP
-            };
-
-        self.context_current.text_align_update_cp(update_cp);
-        self.context_current.text_align_horizontal(align_horizontal);
-        self.context_current.text_align_vertical(align_vertical);
+        self.state.set_text_align(&record);
 
         Ok(self)
     }
@@ -2427,7 +3036,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETTEXTCHAREXTRA,
     ) -> Result<Self, PlayError> {
-        self.context_current.text_char_extra(record.char_extra);
+        self.state.set_text_char_extra(&record);
 
         Ok(self)
     }
@@ -2442,7 +3051,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETTEXTCOLOR,
     ) -> Result<Self, PlayError> {
-        self.context_current.text_color(record.color_ref);
+        self.state.set_text_color(&record);
 
         Ok(self)
     }
@@ -2457,8 +3066,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETTEXTJUSTIFICATION,
     ) -> Result<Self, PlayError> {
-        self.context_current
-            .text_justification(record.break_count, record.break_extra);
+        self.state.set_text_justification(&record);
 
         Ok(self)
     }
@@ -2473,7 +3081,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETVIEWPORTEXT,
     ) -> Result<Self, PlayError> {
-        self.context_current.viewport_ext(record.x, record.y);
+        self.state.set_viewport_ext(&record);
 
         Ok(self)
     }
@@ -2488,7 +3096,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETVIEWPORTORG,
     ) -> Result<Self, PlayError> {
-        self.context_current.viewport_origin(record.x, record.y);
+        self.state.set_viewport_origin(&record);
 
         Ok(self)
     }
@@ -2503,29 +3111,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETWINDOWEXT,
     ) -> Result<Self, PlayError> {
-        // Inside a SAVEDC scope, a META_SETWINDOWEXT call usually
-        // defines a sub-region (e.g. a chart panel) that should
-        // remap the saved (outer) device area to a smaller logical
-        // window. Capture the prior window extent as an implicit
-        // viewport extent so the new logical coordinates rescale to
-        // fit the outer area; otherwise the sub-region would render
-        // at unscaled logical size and collapse to a fraction of
-        // its intended bounds. RESTOREDC reverts the captured
-        // viewport extent along with the rest of the saved state.
-        // Only triggered inside a SAVEDC scope to avoid regressing
-        // metafiles whose only META_SETWINDOWEXT establishes the
-        // overall logical coordinate system in MM_TEXT mode.
-        if !self.context_stack.is_empty()
-            && self.context_current.window.viewport_ext_x.is_none()
-            && self.context_current.window.viewport_ext_y.is_none()
-        {
-            let prev_ext_x = self.context_current.window.current_ext_x;
-            let prev_ext_y = self.context_current.window.current_ext_y;
-            self.context_current.window.viewport_ext_x = Some(prev_ext_x);
-            self.context_current.window.viewport_ext_y = Some(prev_ext_y);
-        }
-
-        self.context_current.window_ext(record.x, record.y);
+        self.state.set_window_ext(&record);
 
         Ok(self)
     }
@@ -2540,7 +3126,7 @@ impl crate::converter::Player for SVGPlayer {
         record_number: usize,
         record: META_SETWINDOWORG,
     ) -> Result<Self, PlayError> {
-        self.context_current.window_origin(record.x, record.y);
+        self.state.set_window_origin(&record);
 
         Ok(self)
     }
@@ -2557,10 +3143,20 @@ impl crate::converter::Player for SVGPlayer {
         err(level = tracing::Level::ERROR, Display),
     ))]
     fn escape(
-        self,
+        mut self,
         record_number: usize,
         record: META_ESCAPE,
     ) -> Result<Self, PlayError> {
+        self.diagnostics.report(
+            record_number,
+            RecordType::META_ESCAPE,
+            DiagnosticKind::Skipped,
+            format!(
+                "{:?}: escapes are not applicable to SVG",
+                record.escape_function(),
+            ),
+        );
+
         Ok(self)
     }
 }
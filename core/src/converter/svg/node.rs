@@ -1,13 +1,13 @@
 use crate::imports::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Node {
     typ: NodeType,
     inner: Vec<Node>,
     attrs: BTreeMap<String, String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 enum NodeType {
     Node(String),
     Text(String),
@@ -47,6 +47,20 @@ impl Node {
         self
     }
 
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(String::as_str)
+    }
+
+    /// Whether the node or one of its descendants sets the CSS `property`
+    /// in its `style` attribute.
+    pub fn has_style(&self, property: &str) -> bool {
+        self.get("style").is_some_and(|style| {
+            style.split(';').any(|declaration| {
+                declaration.trim_start().starts_with(property)
+            })
+        }) || self.inner.iter().any(|node| node.has_style(property))
+    }
+
     fn escape_text(value: impl ToString) -> String {
         let s = value.to_string();
         let mut out = String::with_capacity(s.len());
@@ -1,5 +1,9 @@
 use crate::converter::{
-    svg::{Fill, node::Node, util::url_string},
+    svg::{
+        definitions::Definitions,
+        node::Node,
+        util::{image_placement, url_string},
+    },
     *,
 };
 
@@ -17,8 +21,10 @@ pub struct TernaryRasterOperator {
     y: i16,
     height: i16,
     width: i16,
-    brush: Option<Brush>,
+    brush: Option<String>,
     source: Option<Source>,
+    source_rect: Option<(i16, i16, i16, i16)>,
+    image_rendering: Option<&'static str>,
 }
 
 enum Source {
@@ -37,6 +43,28 @@ impl Source {
             Source::Bitmap(data) => Bitmap::from(data),
         }
     }
+
+    /// Whether the first row of the source bitmap is its bottom one.
+    /// Device-dependent bitmaps always run from the top down.
+    fn is_bottom_up(&self) -> bool {
+        match self {
+            Source::Bitmap16(_) => false,
+            Source::Bitmap(data) => data.dib_header_info.is_bottom_up(),
+        }
+    }
+
+    /// Width and height of the source bitmap in pixels.
+    fn dimensions(&self) -> (usize, usize) {
+        match self {
+            Source::Bitmap16(data) => (
+                usize::from(data.width.unsigned_abs()),
+                usize::from(data.height.unsigned_abs()),
+            ),
+            Source::Bitmap(data) => {
+                (data.dib_header_info.width(), data.dib_header_info.height())
+            }
+        }
+    }
 }
 
 impl TernaryRasterOperator {
@@ -47,11 +75,43 @@ impl TernaryRasterOperator {
         height: i16,
         width: i16,
     ) -> Self {
-        Self { operation, x, y, height, width, brush: None, source: None }
+        Self {
+            operation,
+            x,
+            y,
+            height,
+            width,
+            brush: None,
+            source: None,
+            source_rect: None,
+            image_rendering: None,
+        }
+    }
+
+    pub fn operation(&self) -> TernaryRasterOperation {
+        self.operation
     }
 
-    pub fn brush(mut self, brush: Brush) -> Self {
-        self.brush = brush.into();
+    /// Describe how rendering the operation deviates from GDI, or `None`
+    /// when it is drawn exactly or not at all.
+    pub fn approximation(&self) -> Option<&'static str> {
+        match self.operation {
+            TernaryRasterOperation::SRCERASE => Some(
+                "S & ~D approximated as source copy (destination masking not \
+                 available)",
+            ),
+            TernaryRasterOperation::NOTSRCERASE => Some(
+                "~(S | D) approximated as inverted source (destination not \
+                 available)",
+            ),
+            _ => None,
+        }
+    }
+
+    /// Paint the pattern operand with `fill`, the SVG paint resolved from
+    /// the selected brush.
+    pub fn brush(mut self, fill: String) -> Self {
+        self.brush = fill.into();
         self
     }
 
@@ -65,9 +125,27 @@ impl TernaryRasterOperator {
         self
     }
 
+    /// Copy only the given rectangle of the source bitmap, in pixels.
+    pub fn source_rect(
+        mut self,
+        x: i16,
+        y: i16,
+        width: i16,
+        height: i16,
+    ) -> Self {
+        self.source_rect = (x, y, width, height).into();
+        self
+    }
+
+    /// Set the `image-rendering` used when the source bitmap is scaled.
+    pub fn image_rendering(mut self, image_rendering: &'static str) -> Self {
+        self.image_rendering = image_rendering.into();
+        self
+    }
+
     pub fn run(
         self,
-        definitions: &mut Vec<Node>,
+        definitions: &mut Definitions,
     ) -> Result<Option<Node>, TernaryRasterOperationError> {
         if self.operation.use_selected_brush() && self.brush.is_none() {
             return Err(TernaryRasterOperationError::NoBrush {
@@ -90,36 +168,28 @@ impl TernaryRasterOperator {
         let result: Node = match self.operation {
             TernaryRasterOperation::BLACKNESS => self.black_rect(),
             TernaryRasterOperation::WHITENESS => self.white_rect(),
-            TernaryRasterOperation::SRCCOPY => self.source_image(),
-            TernaryRasterOperation::PATCOPY => self.pattern_rect(definitions),
+            TernaryRasterOperation::SRCCOPY => self.source_image(definitions),
+            TernaryRasterOperation::PATCOPY => self.pattern_rect(),
             TernaryRasterOperation::NOTSRCCOPY => {
                 self.source_image_inverted(definitions)
             }
             TernaryRasterOperation::SRCPAINT => {
-                self.source_image_blended("screen")
+                self.source_image_blended(definitions, "screen")
             }
             TernaryRasterOperation::SRCAND => {
-                self.source_image_blended("multiply")
+                self.source_image_blended(definitions, "multiply")
             }
             TernaryRasterOperation::SRCINVERT => {
-                self.source_image_blended("difference")
+                self.source_image_blended(definitions, "difference")
             }
             TernaryRasterOperation::SRCERASE => {
                 // S & ~D: no exact SVG equivalent; render
                 // source as approximation
-                info!(
-                    "SRCERASE (S & ~D): approximated as source copy \
-                     (destination masking not available)",
-                );
-                self.source_image()
+                self.source_image(definitions)
             }
             TernaryRasterOperation::NOTSRCERASE => {
                 // ~(S | D): no exact SVG equivalent; render
                 // inverted source as approximation
-                info!(
-                    "NOTSRCERASE ~(S | D): approximated as inverted source \
-                     (destination not available)",
-                );
                 self.source_image_inverted(definitions)
             }
             TernaryRasterOperation::DSTINVERT => {
@@ -129,8 +199,7 @@ impl TernaryRasterOperator {
             }
             TernaryRasterOperation::PATINVERT => {
                 // P ^ D: pattern + difference blend
-                self.pattern_rect(definitions)
-                    .set("style", "mix-blend-mode: difference;")
+                self.pattern_rect().set("style", "mix-blend-mode: difference;")
             }
             TernaryRasterOperation::MERGECOPY => {
                 // P & S: overlay pattern on source with
@@ -149,8 +218,8 @@ impl TernaryRasterOperator {
             }
             TernaryRasterOperation::PN => {
                 // ~P: inverted pattern
-                let filter_id = Self::create_invert_filter(definitions);
-                self.pattern_rect(definitions)
+                let filter_id = definitions.invert_filter();
+                self.pattern_rect()
                     .set("filter", url_string(format!("#{filter_id}").as_str()))
             }
             operation => {
@@ -186,41 +255,90 @@ impl TernaryRasterOperator {
     }
 
     /// Render the source bitmap as an image
-    fn source_image(self) -> Node {
-        let bitmap = self.source.unwrap().into_bitmap();
+    ///
+    /// A negative extent of the source or the destination mirrors the image
+    /// along that axis, and two negative extents cancel out.
+    fn source_image(mut self, definitions: &mut Definitions) -> Node {
+        let source = self.source.take().unwrap();
+        let (bitmap_width, bitmap_height) = source.dimensions();
+        let bottom_up = source.is_bottom_up();
+        let id = definitions.image(
+            source.into_bitmap().as_data_url(),
+            bitmap_width,
+            bitmap_height,
+        );
+        let (x, width, mut mirror_x) =
+            normalize(i32::from(self.x), i32::from(self.width));
+        let (y, height, mut mirror_y) =
+            normalize(i32::from(self.y), i32::from(self.height));
+        // Show only the source rectangle when one is given.
+        let view_box = match self.source_rect {
+            Some((x_src, y_src, width_src, height_src))
+                if width_src != 0 && height_src != 0 =>
+            {
+                let (x_src, width_src, flip_x) =
+                    normalize(i32::from(x_src), i32::from(width_src));
+                let (y_src, height_src, flip_y) =
+                    normalize(i32::from(y_src), i32::from(height_src));
+                // The source y of a bottom-up DIB counts from its bottom
+                // row.
+                let y_src = if bottom_up {
+                    i32::try_from(bitmap_height).unwrap_or(i32::MAX)
+                        - y_src
+                        - height_src
+                } else {
+                    y_src
+                };
 
-        Node::new("image")
-            .set("x", self.x)
-            .set("y", self.y)
-            .set("width", self.width)
-            .set("height", self.height)
-            .set("href", bitmap.as_data_url())
+                mirror_x ^= flip_x;
+                mirror_y ^= flip_y;
+
+                format!("{x_src} {y_src} {width_src} {height_src}")
+            }
+            _ => format!("0 0 {bitmap_width} {bitmap_height}"),
+        };
+        let mut image = image_placement(&id, (x, y, width, height), &view_box);
+
+        if let Some(image_rendering) = self.image_rendering {
+            image = image.set("image-rendering", image_rendering);
+        }
+
+        if !mirror_x && !mirror_y {
+            return image;
+        }
+
+        // Reflect the image across the centre of its placement.
+        let (scale_x, translate_x) =
+            if mirror_x { (-1, 2 * x + width) } else { (1, 0) };
+        let (scale_y, translate_y) =
+            if mirror_y { (-1, 2 * y + height) } else { (1, 0) };
+
+        Node::new("g")
+            .set(
+                "transform",
+                format!(
+                    "matrix({scale_x} 0 0 {scale_y} {translate_x} \
+                     {translate_y})"
+                ),
+            )
+            .add(image)
     }
 
     /// Render the source bitmap with color inversion
-    fn source_image_inverted(self, definitions: &mut Vec<Node>) -> Node {
-        let filter_id = Self::create_invert_filter(definitions);
-        let bitmap = self.source.unwrap().into_bitmap();
+    fn source_image_inverted(self, definitions: &mut Definitions) -> Node {
+        let filter_id = definitions.invert_filter();
 
-        Node::new("image")
-            .set("x", self.x)
-            .set("y", self.y)
-            .set("width", self.width)
-            .set("height", self.height)
-            .set("href", bitmap.as_data_url())
+        self.source_image(definitions)
             .set("filter", url_string(format!("#{filter_id}").as_str()))
     }
 
     /// Render the source bitmap with a blend mode
-    fn source_image_blended(self, blend_mode: &str) -> Node {
-        let bitmap = self.source.unwrap().into_bitmap();
-
-        Node::new("image")
-            .set("x", self.x)
-            .set("y", self.y)
-            .set("width", self.width)
-            .set("height", self.height)
-            .set("href", bitmap.as_data_url())
+    fn source_image_blended(
+        self,
+        definitions: &mut Definitions,
+        blend_mode: &str,
+    ) -> Node {
+        self.source_image(definitions)
             .set("style", format!("mix-blend-mode: {blend_mode};"))
     }
 
@@ -229,18 +347,11 @@ impl TernaryRasterOperator {
     /// (P & S) where both source and pattern interact.
     fn source_with_pattern(
         self,
-        definitions: &mut Vec<Node>,
+        definitions: &mut Definitions,
         blend_mode: &str,
     ) -> Node {
-        let pattern = self.pattern_rect(definitions);
-        let bitmap = self.source.unwrap().into_bitmap();
-        let source = Node::new("image")
-            .set("x", self.x)
-            .set("y", self.y)
-            .set("width", self.width)
-            .set("height", self.height)
-            .set("href", bitmap.as_data_url())
-            .set("style", format!("mix-blend-mode: {blend_mode};"));
+        let pattern = self.pattern_rect();
+        let source = self.source_image_blended(definitions, blend_mode);
 
         // Isolate the group so the blend mode only applies
         // between the source and the pattern, not the
@@ -256,20 +367,15 @@ impl TernaryRasterOperator {
     /// PATPAINT (P | ~S | D).
     fn inverted_source_with_pattern(
         self,
-        definitions: &mut Vec<Node>,
+        definitions: &mut Definitions,
         blend_mode: &str,
     ) -> Node {
-        let filter_id = Self::create_invert_filter(definitions);
+        let filter_id = definitions.invert_filter();
         let pattern = self
-            .pattern_rect(definitions)
+            .pattern_rect()
             .set("style", format!("mix-blend-mode: {blend_mode};"));
-        let bitmap = self.source.unwrap().into_bitmap();
-        let source = Node::new("image")
-            .set("x", self.x)
-            .set("y", self.y)
-            .set("width", self.width)
-            .set("height", self.height)
-            .set("href", bitmap.as_data_url())
+        let source = self
+            .source_image(definitions)
             .set("filter", url_string(format!("#{filter_id}").as_str()))
             .set("style", format!("mix-blend-mode: {blend_mode};"));
 
@@ -277,52 +383,22 @@ impl TernaryRasterOperator {
     }
 
     /// Generate a rectangle filled with the pattern brush
-    fn pattern_rect(&self, definitions: &mut Vec<Node>) -> Node {
-        let fill = match Fill::from(self.brush.as_ref().unwrap()) {
-            Fill::Pattern { pattern } => {
-                let id = Self::issue_id(definitions);
-                definitions.push(pattern.set("id", id.as_str()));
-                url_string(format!("#{id}").as_str())
-            }
-            Fill::Value { value } => value,
-        };
-
+    fn pattern_rect(&self) -> Node {
         Node::new("rect")
             .set("x", self.x)
             .set("y", self.y)
             .set("width", self.width)
             .set("height", self.height)
-            .set("fill", fill.as_str())
-    }
-
-    /// Add a color-inversion SVG filter to definitions and return its ID
-    fn create_invert_filter(definitions: &mut Vec<Node>) -> String {
-        let id = format!("rop_inv{}", definitions.len());
-        let filter = Node::new("filter").set("id", id.as_str()).add(
-            Node::new("feComponentTransfer")
-                .add(
-                    Node::new("feFuncR")
-                        .set("type", "table")
-                        .set("tableValues", "1 0"),
-                )
-                .add(
-                    Node::new("feFuncG")
-                        .set("type", "table")
-                        .set("tableValues", "1 0"),
-                )
-                .add(
-                    Node::new("feFuncB")
-                        .set("type", "table")
-                        .set("tableValues", "1 0"),
-                ),
-        );
-
-        definitions.push(filter);
-        id
+            .set("fill", self.brush.as_deref().unwrap())
     }
+}
 
-    #[inline]
-    fn issue_id(definitions: &[Node]) -> String {
-        format!("rop_pat{}", definitions.len())
+/// Turn a start and a signed extent into a start and a positive extent, and
+/// whether the extent was negative.
+fn normalize(start: i32, extent: i32) -> (i32, i32, bool) {
+    if extent < 0 {
+        (start + extent, -extent, true)
+    } else {
+        (start, extent, false)
     }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    converter::svg::node::{Data, Node},
+    converter::{
+        LineCap, LineJoin, LineStyle,
+        svg::node::{Data, Node},
+    },
     imports::*,
     parser::*,
 };
@@ -8,10 +11,35 @@ pub fn css_color_from_color_ref(c: &ColorRef) -> String {
     format!("#{:02X}{:02X}{:02X}", c.red, c.green, c.blue)
 }
 
+/// Invert a `#RRGGBB` colour, as GDI does for `Pn` operations.
+pub fn invert_css_color(value: &str) -> Option<String> {
+    let hex = value.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+
+    Some(format!("#{:06X}", !rgb & 0x00FF_FFFF))
+}
+
 pub fn url_string(link: &str) -> String {
     format!("url({link})")
 }
 
+/// Place the image definition `id` on the destination rectangle, showing
+/// the part of it selected by `view_box` stretched to fit.
+pub fn image_placement(
+    id: &str,
+    (x, y, width, height): (i32, i32, i32, i32),
+    view_box: &str,
+) -> Node {
+    Node::new("svg")
+        .set("x", x)
+        .set("y", y)
+        .set("width", width)
+        .set("height", height)
+        .set("viewBox", view_box)
+        .set("preserveAspectRatio", "none")
+        .add(Node::new("use").set("href", format!("#{id}")))
+}
+
 pub fn as_point_string(point: &PointS) -> String {
     format!("{},{}", point.x, point.y)
 }
@@ -29,9 +57,19 @@ pub enum Fill {
     Value { value: String },
 }
 
-impl From<&Brush> for Fill {
-    fn from(v: &Brush) -> Self {
-        match v {
+impl Fill {
+    /// Build the fill for `brush`.
+    ///
+    /// Hatched brushes are drawn on an 8x8 device pixel tile, where
+    /// `pixel_size` is the size of a device pixel in output units, and the
+    /// gaps are painted with `background` when it is given (OPAQUE
+    /// background mode).
+    pub fn new(
+        brush: &Brush,
+        background: Option<&ColorRef>,
+        pixel_size: f32,
+    ) -> Self {
+        match brush {
             Brush::DIBPatternPT { brush_hatch, .. } => {
                 let data = crate::converter::Bitmap::from(brush_hatch.clone())
                     .as_data_url();
@@ -53,69 +91,7 @@ impl From<&Brush> for Fill {
                 Fill::Pattern { pattern }
             }
             Brush::Hatched { color_ref, brush_hatch } => {
-                let path = match brush_hatch {
-                    HatchStyle::HS_HORIZONTAL => {
-                        let data = Data::new().move_to("0 0").line_to("10 0");
-
-                        Node::new("path")
-                            .set("stroke", css_color_from_color_ref(color_ref))
-                            .set("d", data)
-                    }
-                    HatchStyle::HS_VERTICAL => {
-                        let data = Data::new().move_to("0 0").line_to("0 10");
-
-                        Node::new("path")
-                            .set("stroke", css_color_from_color_ref(color_ref))
-                            .set("d", data)
-                    }
-                    HatchStyle::HS_FDIAGONAL => {
-                        let data = Data::new().move_to("0 10").line_to("10 0");
-
-                        Node::new("path")
-                            .set("stroke", css_color_from_color_ref(color_ref))
-                            .set("d", data)
-                    }
-                    HatchStyle::HS_BDIAGONAL => {
-                        let data = Data::new().move_to("0 0").line_to("10 10");
-
-                        Node::new("path")
-                            .set("stroke", css_color_from_color_ref(color_ref))
-                            .set("d", data)
-                    }
-                    HatchStyle::HS_CROSS => {
-                        let data = Data::new()
-                            .move_to("0 0")
-                            .line_to("10 0")
-                            .move_to("0 0")
-                            .line_to("0 10");
-
-                        Node::new("path")
-                            .set("stroke", css_color_from_color_ref(color_ref))
-                            .set("d", data)
-                    }
-                    HatchStyle::HS_DIAGCROSS => {
-                        let data = Data::new()
-                            .move_to("0 0")
-                            .line_to("10 10")
-                            .move_to("10 0")
-                            .line_to("0 10");
-
-                        Node::new("path")
-                            .set("stroke", css_color_from_color_ref(color_ref))
-                            .set("d", data)
-                    }
-                };
-
-                let pattern = Node::new("pattern")
-                    .set("patternUnits", "userSpaceOnUse")
-                    .set("patternContentUnits", "userSpaceOnUse")
-                    .set("x", "0")
-                    .set("y", "0")
-                    .set("width", "10")
-                    .set("height", "10")
-                    .add(path);
-
-                Fill::Pattern { pattern }
+                Self::hatch(color_ref, *brush_hatch, background, pixel_size)
             }
             Brush::Pattern { brush_hatch } => {
                 let bitmap = crate::parser::DeviceIndependentBitmap::from(
@@ -145,6 +121,72 @@ impl From<&Brush> for Fill {
             Brush::Null => Fill::Value { value: "none".to_owned() },
         }
     }
+
+    fn hatch(
+        color_ref: &ColorRef,
+        hatch_style: HatchStyle,
+        background: Option<&ColorRef>,
+        pixel_size: f32,
+    ) -> Self {
+        // Lines run past the tile so that diagonals join across tiles;
+        // the pattern viewport clips the overhang.
+        let data = match hatch_style {
+            HatchStyle::HS_HORIZONTAL => {
+                Data::new().move_to("-1 0.5").line_to("9 0.5")
+            }
+            HatchStyle::HS_VERTICAL => {
+                Data::new().move_to("0.5 -1").line_to("0.5 9")
+            }
+            // 45-degree downward, left to right
+            HatchStyle::HS_FDIAGONAL => {
+                Data::new().move_to("-1 -1").line_to("9 9")
+            }
+            // 45-degree upward, left to right
+            HatchStyle::HS_BDIAGONAL => {
+                Data::new().move_to("-1 9").line_to("9 -1")
+            }
+            HatchStyle::HS_CROSS => Data::new()
+                .move_to("-1 0.5")
+                .line_to("9 0.5")
+                .move_to("0.5 -1")
+                .line_to("0.5 9"),
+            HatchStyle::HS_DIAGCROSS => Data::new()
+                .move_to("-1 -1")
+                .line_to("9 9")
+                .move_to("-1 9")
+                .line_to("9 -1"),
+        };
+
+        let mut pattern = Node::new("pattern")
+            .set("patternUnits", "userSpaceOnUse")
+            .set("patternContentUnits", "userSpaceOnUse")
+            .set("x", "0")
+            .set("y", "0")
+            .set("width", "8")
+            .set("height", "8");
+
+        if (pixel_size - 1.0).abs() > f32::EPSILON {
+            pattern =
+                pattern.set("patternTransform", format!("scale({pixel_size})"));
+        }
+
+        if let Some(background) = background {
+            pattern = pattern.add(
+                Node::new("rect")
+                    .set("width", "8")
+                    .set("height", "8")
+                    .set("fill", css_color_from_color_ref(background)),
+            );
+        }
+
+        let path = Node::new("path")
+            .set("d", data)
+            .set("fill", "none")
+            .set("stroke", css_color_from_color_ref(color_ref))
+            .set("stroke-width", "1");
+
+        Fill::Pattern { pattern: pattern.add(path) }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -154,7 +196,7 @@ pub struct Stroke {
     /// sets the color of the line around an element
     color: ColorRef,
     /// sets the width of the line around an element
-    width: i16,
+    width: f32,
     /// sets the opacity of the line around an element
     opacity: f32,
     /// sets the shape of the end-lines for a line or open path
@@ -163,6 +205,12 @@ pub struct Stroke {
     dash_array: String,
     /// sets the shape of the corners where two lines meet
     line_join: String,
+    /// set true if the stroke of closed figures is drawn inside the
+    /// bounding rectangle (PS_INSIDEFRAME)
+    inside_frame: bool,
+    /// set true if the width is in device pixels regardless of the
+    /// transform of the element
+    non_scaling: bool,
 }
 
 impl Default for Stroke {
@@ -170,102 +218,110 @@ impl Default for Stroke {
         Self {
             none: false,
             color: ColorRef::black(),
-            width: 1,
+            width: 1.0,
             opacity: 1_f32,
             line_cap: "butt".to_owned(),
             dash_array: "none".to_owned(),
             line_join: "miter".to_owned(),
+            inside_frame: false,
+            non_scaling: false,
         }
     }
 }
 
-impl From<&Pen> for Stroke {
-    fn from(v: &Pen) -> Self {
-        if v.style.style == PenStyle::PS_NULL {
-            return Self { none: true, ..Default::default() };
-        }
+impl Stroke {
+    /// Build the stroke for `pen`, where `scale` is the number of output
+    /// units per logical unit along the x-axis.
+    ///
+    /// A pen whose width maps to at most one output unit is drawn like a
+    /// cosmetic pen: one unit wide, with GDI's fixed dash patterns and no
+    /// end caps or joins. Wider pens are geometric: the end cap and join
+    /// bits of the style apply and dash lengths scale with the width.
+    pub fn new(pen: &Pen, scale: f32) -> Self {
+        Self::with_output_scale(pen, scale, scale)
+    }
 
-        let mut stroke = Self::default();
+    /// Build the stroke for `pen` in logical units, for elements mapped to
+    /// the device by a transform. `scale` is the number of device units
+    /// per logical unit along the x-axis.
+    ///
+    /// Cosmetic pens keep their one-pixel width through
+    /// `vector-effect: non-scaling-stroke`.
+    pub fn new_logical(pen: &Pen, scale: f32) -> Self {
+        Self::with_output_scale(pen, scale, 1.0)
+    }
 
-        match v.style.end_cap {
-            PenStyle::PS_SOLID => {
-                "round".clone_into(&mut stroke.line_cap);
-            }
-            PenStyle::PS_ENDCAP_SQUARE => {
-                "square".clone_into(&mut stroke.line_cap);
-            }
-            _ => {
-                "butt".clone_into(&mut stroke.line_cap);
-            }
+    fn with_output_scale(pen: &Pen, scale: f32, output_scale: f32) -> Self {
+        if pen.style.style == PenStyle::PS_NULL {
+            return Self { none: true, ..Default::default() };
         }
 
-        match v.style.line_join {
-            PenStyle::PS_SOLID => {
-                "round".clone_into(&mut stroke.line_join);
-            }
-            PenStyle::PS_JOIN_BEVEL => {
-                "bevel".clone_into(&mut stroke.line_join);
+        let logical_width = f32::from(pen.width.x).abs();
+        let cosmetic = logical_width * scale.abs() <= 1.0;
+        let line = if cosmetic {
+            LineStyle::cosmetic(pen, 1.0)
+        } else {
+            LineStyle::geometric(pen, logical_width * output_scale.abs())
+        };
+        let dashes: Vec<_> =
+            line.dashes.iter().map(ToString::to_string).collect();
+
+        Self {
+            color: pen.color_ref.clone(),
+            width: line.width,
+            line_cap: match line.cap {
+                LineCap::Flat => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
             }
-            _ => {
-                "miter".clone_into(&mut stroke.line_join);
+            .to_owned(),
+            dash_array: if dashes.is_empty() {
+                "none".to_owned()
+            } else {
+                dashes.join(" ")
+            },
+            line_join: match line.join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
             }
+            .to_owned(),
+            inside_frame: !cosmetic
+                && pen.style.style == PenStyle::PS_INSIDEFRAME,
+            non_scaling: cosmetic
+                && (scale - output_scale).abs() > f32::EPSILON,
+            ..Default::default()
         }
+    }
 
-        // Dash pattern ratios relative to pen width, based on
-        // typical Windows GDI behavior:
-        //   dash = 4w, dot = w, gap = 2w
-        // Use at least 1 for the effective width so that
-        // cosmetic pens (width 0) produce valid patterns.
-        let w = i16::max(v.width.x, 1);
-
-        match v.style.style {
-            PenStyle::PS_DASH => {
-                let dash = w.saturating_mul(4);
-                let gap = w.saturating_mul(2);
-                stroke.dash_array = format!("{dash} {gap}");
-            }
-            PenStyle::PS_DOT => {
-                let dot = w;
-                let gap = w.saturating_mul(2);
-                stroke.dash_array = format!("{dot} {gap}");
-            }
-            PenStyle::PS_ALTERNATE => {
-                "1 1".clone_into(&mut stroke.dash_array);
-            }
-            PenStyle::PS_DASHDOT => {
-                let dash = w.saturating_mul(4);
-                let dot = w;
-                let gap = w.saturating_mul(2);
-                stroke.dash_array = format!("{dash} {gap} {dot} {gap}");
-            }
-            PenStyle::PS_DASHDOTDOT => {
-                let dash = w.saturating_mul(4);
-                let dot = w;
-                let gap = w.saturating_mul(2);
-                stroke.dash_array =
-                    format!("{dash} {gap} {dot} {gap} {dot} {gap}");
-            }
-            _ => {}
+    /// Distance to move the edges of a closed figure inwards so that the
+    /// stroke stays inside its bounding rectangle.
+    pub fn inside_frame_inset(&self) -> f32 {
+        if self.none || !self.inside_frame {
+            return 0.0;
         }
 
-        stroke.color = v.color_ref.clone();
-        stroke.width = if v.width.x == 0 { 1 } else { v.width.x };
-        stroke
+        self.width / 2.0
     }
-}
 
-impl Stroke {
     pub fn set_props(&self, elem: Node) -> Node {
         if self.none {
             return elem.set("stroke", "none");
         }
 
-        elem.set("stroke", css_color_from_color_ref(&self.color))
+        let elem = elem
+            .set("stroke", css_color_from_color_ref(&self.color))
             .set("stroke-dasharray", &self.dash_array)
             .set("stroke-linecap", &self.line_cap)
             .set("stroke-linejoin", &self.line_join)
             .set("stroke-opacity", format!("{:.02}", self.opacity))
-            .set("stroke-width", self.width)
+            .set("stroke-width", self.width);
+
+        if self.non_scaling {
+            elem.set("vector-effect", "non-scaling-stroke")
+        } else {
+            elem
+        }
     }
 }
 
@@ -311,8 +367,18 @@ impl Font {
             }
         }
 
-        if self.escapement != 0 {
-            let esc_deg = f32::from(self.escapement) / 10.0;
+        // Vertical fonts run the text down the page at an escapement of
+        // 270 degrees, which `writing-mode` already provides. It also keeps
+        // CJK glyphs upright while turning Latin ones sideways, as GDI does.
+        let escapement = if self.is_vertical() {
+            styles.push("writing-mode: vertical-rl;".to_owned());
+            (f32::from(self.escapement) - 2700.0).rem_euclid(3600.0)
+        } else {
+            f32::from(self.escapement)
+        };
+
+        if escapement.abs() > f32::EPSILON {
+            let esc_deg = escapement / 10.0;
 
             elem = elem.set(
                 "transform",
@@ -320,12 +386,11 @@ impl Font {
             );
         }
 
-        let mut font_family: Vec<&str> = vec![];
-
-        font_family.push(self.facename.as_str());
-        self.fallback_facename.iter().for_each(|f| {
-            font_family.push(f.as_str());
-        });
+        // The '@' prefix only selects the vertical variant of the font.
+        let font_family: Vec<&str> = core::iter::once(&self.facename)
+            .chain(&self.fallback_facename)
+            .map(|f| f.strip_prefix('@').unwrap_or(f))
+            .collect();
 
         elem = elem
             .set("font-family", format!("'{}'", font_family.join("','")))
@@ -335,6 +400,12 @@ impl Font {
         (elem, styles)
     }
 
+    /// Whether the font lays text out vertically, as GDI does for facenames
+    /// prefixed with '@' such as "@MS Mincho".
+    pub fn is_vertical(&self) -> bool {
+        self.facename.starts_with('@')
+    }
+
     /// Convert a WMF `Font.weight` (0..=1000, where 0 is FW_DONTCARE) into a
     /// value accepted by SVG 1.1. SVG 1.1 only allows numeric weights at
     /// multiples of 100 in 100..=900 or keywords such as `normal`, so map
@@ -354,6 +425,95 @@ impl Font {
 mod tests {
     use super::*;
 
+    fn pen(style: u16, width: i16) -> Pen {
+        Pen {
+            style: PenStyleSubsection {
+                end_cap: PenStyleSubsection::end_cap(style),
+                line_join: PenStyleSubsection::line_join(style),
+                style: PenStyleSubsection::style(style),
+                typ: PenStyle::PS_SOLID,
+            },
+            width: PointS { x: width, y: 0 },
+            color_ref: ColorRef::black(),
+        }
+    }
+
+    fn stroke_attrs(stroke: &Stroke) -> String {
+        stroke.set_props(Node::new("path")).to_string()
+    }
+
+    #[test]
+    fn stroke_cosmetic_pen_uses_fixed_dash_pattern() {
+        let stroke = Stroke::new(&pen(PenStyle::PS_DASH as u16, 0), 10.0);
+
+        assert_eq!(
+            stroke_attrs(&stroke),
+            r##"<path stroke="#000000" stroke-dasharray="18 6" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1"></path>"##,
+        );
+    }
+
+    #[test]
+    fn stroke_geometric_pen_defaults_to_round_cap_and_join() {
+        let stroke = Stroke::new(&pen(PenStyle::PS_SOLID as u16, 4), 1.0);
+
+        assert_eq!(
+            stroke_attrs(&stroke),
+            r##"<path stroke="#000000" stroke-dasharray="none" stroke-linecap="round" stroke-linejoin="round" stroke-opacity="1.00" stroke-width="4"></path>"##,
+        );
+    }
+
+    #[test]
+    fn stroke_geometric_pen_decodes_cap_and_join_bits() {
+        let style = PenStyle::PS_DASH as u16
+            | PenStyle::PS_ENDCAP_SQUARE as u16
+            | PenStyle::PS_JOIN_BEVEL as u16;
+        let stroke = Stroke::new(&pen(style, 2), 1.5);
+
+        assert_eq!(
+            stroke_attrs(&stroke),
+            r##"<path stroke="#000000" stroke-dasharray="12 6" stroke-linecap="square" stroke-linejoin="bevel" stroke-opacity="1.00" stroke-width="3"></path>"##,
+        );
+    }
+
+    #[test]
+    fn stroke_logical_keeps_pen_width_in_logical_units() {
+        let geometric =
+            Stroke::new_logical(&pen(PenStyle::PS_SOLID as u16, 4), 0.5);
+        let cosmetic =
+            Stroke::new_logical(&pen(PenStyle::PS_SOLID as u16, 1), 0.5);
+
+        assert_eq!(
+            stroke_attrs(&geometric),
+            r##"<path stroke="#000000" stroke-dasharray="none" stroke-linecap="round" stroke-linejoin="round" stroke-opacity="1.00" stroke-width="4"></path>"##,
+        );
+        assert_eq!(
+            stroke_attrs(&cosmetic),
+            r##"<path stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" vector-effect="non-scaling-stroke"></path>"##,
+        );
+    }
+
+    #[test]
+    fn stroke_inside_frame_insets_by_half_width() {
+        let inside_frame = PenStyle::PS_INSIDEFRAME as u16;
+
+        assert!(
+            (Stroke::new(&pen(inside_frame, 3), 2.0).inside_frame_inset()
+                - 3.0)
+                .abs()
+                < f32::EPSILON
+        );
+        assert!(
+            Stroke::new(&pen(inside_frame, 0), 2.0).inside_frame_inset().abs()
+                < f32::EPSILON
+        );
+        assert!(
+            Stroke::new(&pen(PenStyle::PS_SOLID as u16, 3), 2.0)
+                .inside_frame_inset()
+                .abs()
+                < f32::EPSILON
+        );
+    }
+
     #[test]
     fn svg_font_weight_maps_dontcare_to_normal() {
         assert_eq!(Font::svg_font_weight(0), "normal");
@@ -378,4 +538,23 @@ mod tests {
         assert_eq!(Font::svg_font_weight(1000), "900");
         assert_eq!(Font::svg_font_weight(-1), "100");
     }
+
+    #[test]
+    fn font_vertical_runs_down_without_rotation() {
+        use crate::converter::graphics_object::SelectedGraphicsObject;
+
+        let font = Font {
+            escapement: 2700,
+            facename: "@MS Mincho".to_owned(),
+            fallback_facename: vec!["@ＭＳ 明朝".to_owned()],
+            ..SelectedGraphicsObject::default().font
+        };
+        let (elem, styles) =
+            font.set_props(Node::new("text"), &PointS { x: 10, y: 20 });
+
+        assert!(font.is_vertical());
+        assert_eq!(styles, vec!["writing-mode: vertical-rl;".to_owned()]);
+        assert_eq!(elem.get("transform"), None);
+        assert_eq!(elem.get("font-family"), Some("'MS Mincho','ＭＳ 明朝'"));
+    }
 }
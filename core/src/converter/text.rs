@@ -0,0 +1,222 @@
+use crate::{
+    converter::{
+        GdiState, PlayError,
+        drawing::{self, Context, Device, Drawing, Image, Paint},
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Player collecting the text of the metafile, for search indexing.
+///
+/// The strings of META_TEXTOUT and META_EXTTEXTOUT records are decoded in
+/// the character set of the selected font and kept as [`TextRun`]s, with
+/// their position in device units, font and colour. [`TextExtractor::lines`]
+/// reassembles the runs into lines by the proximity of their baselines, and
+/// the output of the player is the text of those lines.
+///
+/// The metafile does not give the metrics of its fonts, so the width of a
+/// run is the sum of its Dx array when the record has one, and otherwise
+/// estimated from the standard font resembling the selected font.
+///
+/// ```
+/// use wmf_core::converter::{TextExtractor, WMFConverter};
+///
+/// # fn extract(buffer: &[u8]) -> Result<(), wmf_core::converter::ConvertError> {
+/// let extractor = WMFConverter::new(buffer, TextExtractor::new()).play()?;
+///
+/// for line in extractor.lines() {
+///     println!("{:.1}: {}", line.baseline, line.text());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub type TextExtractor = Drawing<Text>;
+
+/// Runs of a [`TextExtractor`], which draws nothing but text.
+#[derive(Default)]
+pub struct Text {
+    runs: Vec<TextRun>,
+}
+
+/// String drawn by a single text record.
+#[derive(Clone, Debug)]
+pub struct TextRun {
+    /// Number of the record drawing the string.
+    pub record_number: usize,
+    pub text: String,
+    /// Start of the string on its baseline, in device units.
+    pub x: f32,
+    pub y: f32,
+    /// Estimated advance of the string along its baseline, in device
+    /// units.
+    pub width: f32,
+    /// Em size of the font in device units.
+    pub size: f32,
+    /// Angle of the baseline in degrees counter-clockwise from the x-axis.
+    pub angle: f32,
+    /// Logical font selected when the string was drawn.
+    pub font: Font,
+    pub color: ColorRef,
+}
+
+/// Runs sharing a baseline, ordered from left to right.
+#[derive(Clone, Debug)]
+pub struct TextLine {
+    /// Vertical position of the baseline in device units.
+    pub baseline: f32,
+    pub runs: Vec<TextRun>,
+}
+
+impl TextLine {
+    /// Text of the runs, separated by a space where the gap between two
+    /// runs is wider than a quarter of an em.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        let mut end: Option<f32> = None;
+
+        for run in &self.runs {
+            if let Some(end) = end {
+                let separated = text.ends_with(char::is_whitespace)
+                    || run.text.starts_with(char::is_whitespace);
+
+                if !separated && run.x - end > run.size / 4.0 {
+                    text.push(' ');
+                }
+            }
+
+            text.push_str(&run.text);
+            end = Some(run.x + run.width);
+        }
+
+        text
+    }
+}
+
+impl TextExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs in the order they are drawn.
+    pub fn runs(&self) -> &[TextRun] {
+        &self.device().runs
+    }
+
+    /// Runs grouped into lines, from top to bottom.
+    ///
+    /// Horizontal runs belong to the same line when their baselines are
+    /// less than half an em apart. Rotated runs make up lines of their own.
+    pub fn lines(&self) -> Vec<TextLine> {
+        self.device().lines()
+    }
+}
+
+impl Text {
+    fn lines(&self) -> Vec<TextLine> {
+        let mut runs: Vec<&TextRun> = self.runs.iter().collect();
+        let mut lines: Vec<TextLine> = vec![];
+
+        runs.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+        for run in runs {
+            let line = lines.iter_mut().rev().find(|line| {
+                let tolerance = line
+                    .runs
+                    .iter()
+                    .map(|run| run.size)
+                    .fold(run.size, f32::min)
+                    / 2.0;
+
+                run.angle == 0.0
+                    && line.runs.iter().all(|run| run.angle == 0.0)
+                    && (line.baseline - run.y).abs() < tolerance
+            });
+
+            if let Some(line) = line {
+                line.runs.push(run.clone());
+            } else {
+                lines.push(TextLine {
+                    baseline: run.y,
+                    runs: vec![run.clone()],
+                });
+            }
+        }
+
+        for line in &mut lines {
+            line.runs.sort_by(|a, b| a.x.total_cmp(&b.x));
+        }
+
+        lines.sort_by(|a, b| {
+            let x = |line: &TextLine| line.runs[0].x;
+
+            a.baseline.total_cmp(&b.baseline).then(x(a).total_cmp(&x(b)))
+        });
+
+        lines
+    }
+}
+
+impl Device for Text {
+    type Path = ();
+
+    fn path(&self, _: &GdiState) {}
+
+    fn draw(&mut self, _: &mut Context<'_>, (): (), _: &Paint) {}
+
+    fn pattern_blt(
+        &mut self,
+        _: &mut Context<'_>,
+        _: ((f32, f32), (f32, f32)),
+        _: TernaryRasterOperation,
+    ) {
+    }
+
+    fn image(
+        &mut self,
+        _: &mut Context<'_>,
+        _: Image,
+        _: ((f32, f32), (f32, f32)),
+        _: (f32, f32, f32, f32),
+        _: TernaryRasterOperation,
+    ) {
+    }
+
+    /// Keep the text of `run`, unless it is blank.
+    fn text(&mut self, context: &mut Context<'_>, run: &drawing::TextRun) {
+        let text: String =
+            run.glyphs.iter().map(|glyph| glyph.text.as_str()).collect();
+
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let (x, y) = run.start();
+
+        self.runs.push(TextRun {
+            record_number: context.record_number(),
+            text,
+            x,
+            y,
+            width: run.width,
+            size: run.size,
+            // Escapement is in tenths of degrees.
+            angle: f32::from(run.font.escapement) / 10.0,
+            font: run.font.clone(),
+            color: context.state.text_color().clone(),
+        });
+    }
+
+    fn flood_fill(&mut self, _: &mut Context<'_>, _: (f32, f32)) {}
+
+    fn generate(self, _: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let mut output = String::new();
+
+        for line in self.lines() {
+            output.push_str(&line.text());
+            output.push('\n');
+        }
+
+        Ok(output.into_bytes())
+    }
+}
@@ -0,0 +1,499 @@
+use crate::{
+    converter::{
+        Clip, DiagnosticKind, GdiState, LineCap, LineStyle, PlayError,
+        drawing::{self, Context, Device, TextRun},
+        page::{Image, Path},
+        thumbnail::{
+            Thumbnail,
+            surface::{Cap, Outline, PATCOPY, Paint, Point, Surface},
+        },
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Share of the box between the baseline and the x-height of a word that
+/// its glyphs cover, which text is painted with.
+const TEXT_DENSITY: f32 = 0.5;
+
+/// Surface of a [`ThumbnailPlayer`](crate::converter::ThumbnailPlayer) and
+/// the mapping of device units to its pixels.
+pub struct Raster {
+    surface: Surface,
+    /// Pixels per device unit along the axes.
+    scale: (f32, f32),
+    /// Pixel of the device origin.
+    offset: (f32, f32),
+    /// Whether running out of work has been reported.
+    exhausted: bool,
+}
+
+impl Raster {
+    /// Create a surface painting `area`, in device units, over `width` by
+    /// `height` pixels, or fewer when that exceeds 2048 by 2048.
+    pub fn new(area: &RectL, width: u32, height: u32) -> Self {
+        let surface = Surface::new(width, height);
+        let size = |v: usize| f32::from(u16::try_from(v).unwrap_or(u16::MAX));
+        // Areas span up to twice the range of `i32`, so the sides are
+        // subtracted in f64.
+        let units = |v: i32| f64::from(v) as f32;
+        let extent = |from: i32, to: i32| {
+            ((f64::from(to) - f64::from(from)) as f32).max(1.0)
+        };
+        let scale = (
+            size(surface.width()) / extent(area.left, area.right),
+            size(surface.height()) / extent(area.top, area.bottom),
+        );
+
+        Self {
+            surface,
+            scale,
+            offset: (-units(area.left) * scale.0, -units(area.top) * scale.1),
+            exhausted: false,
+        }
+    }
+
+    /// The image painted so far.
+    pub fn thumbnail(&self) -> Thumbnail {
+        let size = |v: usize| u32::try_from(v).unwrap_or(u32::MAX);
+
+        Thumbnail {
+            width: size(self.surface.width()),
+            height: size(self.surface.height()),
+            rgba: self.surface.rgba(),
+        }
+    }
+
+    /// Position of the device point `(x, y)` in pixels.
+    fn pixel(&self, (x, y): (f32, f32)) -> Point {
+        (x * self.scale.0 + self.offset.0, y * self.scale.1 + self.offset.1)
+    }
+
+    /// The clipping region in pixels.
+    fn clips(&self, state: &GdiState) -> Vec<Clip> {
+        let pixels = |(left, top, right, bottom): &(f32, f32, f32, f32)| {
+            let (left, top) = self.pixel((*left, *top));
+            let (right, bottom) = self.pixel((*right, *bottom));
+
+            (left, top, right, bottom)
+        };
+
+        state
+            .clips()
+            .iter()
+            .map(|clip| match clip {
+                Clip::Rects(rects) => {
+                    Clip::Rects(rects.iter().map(pixels).collect())
+                }
+                Clip::Exclude(rect) => Clip::Exclude(pixels(rect)),
+            })
+            .collect()
+    }
+
+    /// Fill `polygons` in pixels with each of `paints` in turn, clipped to
+    /// `clips`, reporting once when the work runs out.
+    fn fill(
+        &mut self,
+        context: &mut Context<'_>,
+        polygons: &[Vec<Point>],
+        even_odd: bool,
+        paints: &[Paint],
+        clips: &[Clip],
+    ) {
+        for paint in paints {
+            if !self.surface.fill(polygons, even_odd, *paint, clips) {
+                self.out_of_work(context);
+                return;
+            }
+        }
+    }
+
+    fn out_of_work(&mut self, context: &mut Context<'_>) {
+        if !self.exhausted {
+            self.exhausted = true;
+            context.report(
+                DiagnosticKind::Skipped,
+                "the work allowed for a thumbnail is spent, so this and the \
+                 following records are not painted"
+                    .to_owned(),
+            );
+        }
+    }
+
+    /// Polygons covering the stroke of `outline` with the selected pen and
+    /// its colour, or `None` for PS_NULL.
+    ///
+    /// Cosmetic pens are a pixel wide, with their dash patterns in pixels,
+    /// and geometric pens at least a pixel wide. Joins are not drawn.
+    fn stroke(
+        &self,
+        state: &GdiState,
+        outline: &Outline,
+    ) -> Option<(Vec<Vec<Point>>, [u8; 3])> {
+        let pen = state.pen();
+
+        if pen.style.style == PenStyle::PS_NULL {
+            return None;
+        }
+
+        let color =
+            [pen.color_ref.red, pen.color_ref.green, pen.color_ref.blue];
+        let width = state.pen_width();
+        let line = if width <= state.window().device_pixel_size() {
+            LineStyle::cosmetic(pen, 1.0)
+        } else {
+            LineStyle::geometric(pen, (width * self.scale.0).max(1.0))
+        };
+        let cap = match line.cap {
+            LineCap::Flat => Cap::Flat,
+            LineCap::Round => Cap::Round,
+            LineCap::Square => Cap::Square,
+        };
+
+        Some((outline.stroke(line.width, &line.dashes, cap), color))
+    }
+}
+
+/// Truth table of the ROP2 mode `operation` as a ternary raster operation.
+fn ternary(operation: BinaryRasterOperation) -> u8 {
+    // The codes of the ROP2 modes are one more than their truth tables over
+    // the bits of the pen and the destination.
+    let table = (operation as u16).wrapping_sub(1) as u8;
+
+    (0..8).fold(0, |operation, index| {
+        let bit = ((index >> 2) << 1) | (index & 1);
+
+        operation | (((table >> bit) & 1) << index)
+    })
+}
+
+/// Paints standing in for `brush`, painted in turn; empty for a null
+/// brush.
+fn brush_paints(
+    context: &mut Context<'_>,
+    brush: &Brush,
+    operation: u8,
+) -> Vec<Paint> {
+    let paint = |color: &ColorRef, density| Paint {
+        color: [color.red, color.green, color.blue],
+        operation,
+        density,
+    };
+
+    match brush {
+        Brush::Null => vec![],
+        Brush::Solid { color_ref } => vec![paint(color_ref, 1.0)],
+        Brush::Hatched { color_ref, brush_hatch } => {
+            let state = context.state;
+            let mut paints = vec![];
+
+            if state.bk_mode() == MixMode::OPAQUE {
+                paints.push(paint(state.bk_color(), 1.0));
+            }
+
+            paints.push(paint(color_ref, hatch_density(*brush_hatch)));
+            context.report(
+                DiagnosticKind::Approximated,
+                format!(
+                    "{brush_hatch:?} hatched brush is painted with its \
+                     average colour"
+                ),
+            );
+
+            paints
+        }
+        Brush::DIBPatternPT { brush_hatch, .. } => {
+            let image = Image::from_dib(brush_hatch);
+
+            pattern_paint(context, image, operation).into_iter().collect()
+        }
+        Brush::Pattern { brush_hatch } => {
+            let image = Image::from_bitmap16(brush_hatch);
+
+            pattern_paint(context, image, operation).into_iter().collect()
+        }
+    }
+}
+
+/// Paint of the average colour of the pattern `image`.
+fn pattern_paint(
+    context: &mut Context<'_>,
+    image: Result<Image, String>,
+    operation: u8,
+) -> Option<Paint> {
+    match image.and_then(|image| image.average_color()) {
+        Ok(color) => {
+            context.report(
+                DiagnosticKind::Approximated,
+                "pattern brush is painted with the average colour of its \
+                 bitmap"
+                    .to_owned(),
+            );
+
+            Some(Paint { color, operation, density: 1.0 })
+        }
+        Err(message) => {
+            context.report(
+                DiagnosticKind::Skipped,
+                format!("pattern brush is not painted: {message}"),
+            );
+
+            None
+        }
+    }
+}
+
+/// Paints of the selected brush combined by the ternary raster operation
+/// `operation`, or black when the operation does not use it.
+fn blit_paints(
+    context: &mut Context<'_>,
+    operation: TernaryRasterOperation,
+) -> Vec<Paint> {
+    let code = ((operation as u32) >> 16) as u8;
+
+    if operation.use_selected_brush() {
+        let brush = context.state.brush().clone();
+
+        brush_paints(context, &brush, code)
+    } else {
+        vec![Paint { color: [0; 3], operation: code, density: 1.0 }]
+    }
+}
+
+impl Device for Raster {
+    type Path = Outline;
+
+    fn path(&self, _: &GdiState) -> Outline {
+        Outline::new(self.scale, self.offset)
+    }
+
+    fn draw(
+        &mut self,
+        context: &mut Context<'_>,
+        outline: Outline,
+        paint: &drawing::Paint,
+    ) {
+        let operation = ternary(paint.operation);
+        let clips = self.clips(context.state);
+
+        if let Some(brush) = &paint.brush {
+            let paints = brush_paints(context, brush, operation);
+            let even_odd = context.state.fill_even_odd();
+
+            self.fill(context, &outline.polygons(), even_odd, &paints, &clips);
+        }
+
+        if let Some((polygons, color)) =
+            paint.stroke.then(|| self.stroke(context.state, &outline)).flatten()
+        {
+            let paint = Paint { color, operation, density: 1.0 };
+
+            self.fill(context, &polygons, false, &[paint], &clips);
+        }
+    }
+
+    /// Raster operations combine the brush with the surface exactly.
+    fn pattern_blt(
+        &mut self,
+        context: &mut Context<'_>,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        operation: TernaryRasterOperation,
+    ) {
+        let paints = blit_paints(context, operation);
+        let clips = self.clips(context.state);
+        let outline = self.path(context.state).rect(
+            (p1.0.min(p2.0), p1.1.min(p2.1)),
+            (p1.0.max(p2.0), p1.1.max(p2.1)),
+        );
+
+        self.fill(context, &outline.polygons(), false, &paints, &clips);
+    }
+
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Image,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        source: (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    ) {
+        let paints = blit_paints(context, operation);
+        let clips = self.clips(context.state);
+        let pixels = match image.rgba() {
+            Ok(pixels) => pixels,
+            Err(message) => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("bitmap is not drawn: {message}"),
+                );
+
+                return;
+            }
+        };
+        // A brush painted as more than one colour leaves the last for the
+        // pattern.
+        let paint = paints.last().copied().unwrap_or(Paint {
+            color: [0; 3],
+            operation: ((operation as u32) >> 16) as u8,
+            density: 1.0,
+        });
+
+        if !self.surface.image(
+            (image.width(), image.height(), &pixels),
+            source,
+            (self.pixel(p1), self.pixel(p2)),
+            paint,
+            &clips,
+        ) {
+            self.out_of_work(context);
+        }
+    }
+
+    /// Each word becomes a bar between the baseline and the x-height,
+    /// painted lighter than the text colour as glyphs only cover part of
+    /// it.
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let state = context.state;
+        let (start, size, width) = (run.start(), run.size, run.width);
+        let band = |from: f32, to: f32, above: f32, below: f32| {
+            [
+                run.at(start, from, above),
+                run.at(start, to, above),
+                run.at(start, to, below),
+                run.at(start, from, below),
+            ]
+        };
+        let color = |color: &ColorRef| [color.red, color.green, color.blue];
+        let background = Paint {
+            color: color(state.bk_color()),
+            operation: PATCOPY,
+            density: 1.0,
+        };
+        let foreground = color(state.text_color());
+        let bk_opaque = state.bk_mode() == MixMode::OPAQUE;
+        let mut clips = self.clips(state);
+
+        // ETO_OPAQUE fills the rectangle before the text is drawn, and
+        // outside of the ETO_CLIPPED rectangle.
+        if let Some((left, top, right, bottom)) = run.opaque {
+            let outline = self.path(state).rect((left, top), (right, bottom));
+
+            self.fill(
+                context,
+                &outline.polygons(),
+                false,
+                &[background],
+                &clips,
+            );
+        }
+
+        if let Some((left, top, right, bottom)) = run.clip {
+            let (x1, y1) = self.pixel((left, top));
+            let (x2, y2) = self.pixel((right, bottom));
+
+            clips.push(Clip::Rects(vec![(x1, y1, x2, y2)]));
+        }
+
+        if bk_opaque {
+            let outline = self.path(state).polygon(&band(
+                0.0,
+                width,
+                -0.8 * size,
+                0.2 * size,
+            ));
+
+            self.fill(
+                context,
+                &outline.polygons(),
+                false,
+                &[background],
+                &clips,
+            );
+        }
+
+        let mut words = self.path(state);
+        let mut along = 0.0;
+        let mut word: Option<f32> = None;
+
+        for glyph in &run.glyphs {
+            if glyph.text.trim().is_empty() {
+                if let Some(from) = word.take() {
+                    words = words.polygon(&band(from, along, -0.5 * size, 0.0));
+                }
+            } else if word.is_none() {
+                word = Some(along);
+            }
+
+            along += glyph.advance;
+        }
+
+        if let Some(from) = word {
+            words = words.polygon(&band(from, along, -0.5 * size, 0.0));
+        }
+
+        let mut lines = self.path(state);
+
+        for below in run.lines() {
+            lines = lines.polygon(&band(
+                0.0,
+                width,
+                below - 0.025 * size,
+                below + 0.025 * size,
+            ));
+        }
+
+        let paint =
+            |density| Paint { color: foreground, operation: PATCOPY, density };
+
+        self.fill(
+            context,
+            &words.polygons(),
+            false,
+            &[paint(TEXT_DENSITY)],
+            &clips,
+        );
+        self.fill(context, &lines.polygons(), false, &[paint(1.0)], &clips);
+
+        if run.glyphs.iter().any(|glyph| !glyph.text.trim().is_empty()) {
+            context.report(
+                DiagnosticKind::Approximated,
+                "text is painted as bars over its words, as fonts are not \
+                 available"
+                    .to_owned(),
+            );
+        }
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+        context.report(
+            DiagnosticKind::Skipped,
+            "flood fill needs exact colours at the boundary, which \
+             antialiasing does not keep"
+                .to_owned(),
+        );
+    }
+
+    fn escape(&mut self, _: &mut Context<'_>, _: &META_ESCAPE) {
+        info!("META_ESCAPE: skipped (not applicable to thumbnails)");
+    }
+
+    fn generate(self, _: &GdiState) -> Result<Vec<u8>, PlayError> {
+        Ok(self.thumbnail().png())
+    }
+}
+
+/// Share of the 8 by 8 tile of a hatched brush its lines cover.
+fn hatch_density(style: HatchStyle) -> f32 {
+    let lines = match style {
+        HatchStyle::HS_HORIZONTAL
+        | HatchStyle::HS_VERTICAL
+        | HatchStyle::HS_FDIAGONAL
+        | HatchStyle::HS_BDIAGONAL => 8.0,
+        // Horizontal and vertical lines cross on a pixel, diagonal ones
+        // between pixels.
+        HatchStyle::HS_CROSS => 15.0,
+        HatchStyle::HS_DIAGCROSS => 16.0,
+    };
+
+    lines / 64.0
+}
@@ -0,0 +1,141 @@
+mod device;
+mod png;
+mod surface;
+
+pub use self::device::Raster;
+use crate::{
+    converter::{
+        ConvertError, ExtentPlayer, PlayError, WMFConverter, drawing::Drawing,
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Player painting the metafile into a small antialiased raster image,
+/// for previews.
+///
+/// The image shows `area`, a rectangle in device units such as the
+/// META_PLACEABLE bounding box or the [`ExtentPlayer`] extent, stretched
+/// over its pixels, on a white background. Pens without a geometric width
+/// are a pixel of the image wide, as on any device GDI plays a metafile
+/// on. Raster operations combine the colours exactly; brushes with a
+/// hatch or a bitmap pattern are painted with the average colour of the
+/// pattern, and text with bars over its words, as fonts are not
+/// available.
+///
+/// The image has at most 2048 by 2048 pixels, and the work spent on
+/// drawing is limited, so hostile metafiles cannot exhaust memory or
+/// time; what remains to draw once the work runs out is skipped.
+///
+/// ```
+/// use wmf_core::converter::render_thumbnail;
+///
+/// # fn preview(buffer: &[u8]) -> Result<(), wmf_core::converter::ConvertError> {
+/// let thumbnail = render_thumbnail(buffer, 128, 128)?;
+/// let png = thumbnail.png();
+/// # Ok(())
+/// # }
+/// ```
+pub type ThumbnailPlayer = Drawing<Raster>;
+
+/// Raster image of a metafile.
+#[derive(Clone, Debug)]
+pub struct Thumbnail {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Thumbnail {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pixels as RGBA, four bytes each in rows from top to bottom.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// The image as a PNG file.
+    pub fn png(&self) -> Vec<u8> {
+        png::encode(self.width, self.height, &self.rgba)
+    }
+}
+
+/// Render a thumbnail of the metafile in `buffer` no larger than
+/// `max_width` by `max_height` pixels, keeping its aspect ratio.
+///
+/// The thumbnail shows the META_PLACEABLE bounding box, or the extent of
+/// what the metafile paints when it has none.
+pub fn render_thumbnail(
+    buffer: &[u8],
+    max_width: u32,
+    max_height: u32,
+) -> Result<Thumbnail, ConvertError> {
+    if max_width == 0 || max_height == 0 {
+        return Err(PlayError::FailedGenerate {
+            cause: format!("thumbnail size is empty: {max_width}x{max_height}"),
+        }
+        .into());
+    }
+
+    let (header, _) = MetafileHeader::parse(&mut &buffer[..])?;
+    let page = match header {
+        MetafileHeader::StartsWithPlaceable(placeable, _) => {
+            let Rect { left, top, right, bottom } = placeable.bounding_box;
+            let width = (i32::from(right) - i32::from(left)).abs();
+            let height = (i32::from(bottom) - i32::from(top)).abs();
+
+            (width > 0 && height > 0).then_some(RectL {
+                left: 0,
+                top: 0,
+                right: width,
+                bottom: height,
+            })
+        }
+        MetafileHeader::StartsWithHeader(_) => None,
+    };
+    let area = match page {
+        Some(page) => page,
+        None => WMFConverter::new(buffer, ExtentPlayer::new())
+            .play()?
+            .extent()
+            .ok_or_else(|| PlayError::FailedGenerate {
+                cause: "nothing is painted".to_owned(),
+            })?,
+    };
+
+    // A saturated extent spans more than `i32` holds, so the sides are
+    // subtracted in f64, where every `i32` is exact.
+    let (width, height) = (
+        (f64::from(area.right) - f64::from(area.left)).max(1.0),
+        (f64::from(area.bottom) - f64::from(area.top)).max(1.0),
+    );
+    let scale =
+        (f64::from(max_width) / width).min(f64::from(max_height) / height);
+    let size = |v: f64, max: u32| ((v * scale).round() as u32).clamp(1, max);
+    let player = ThumbnailPlayer::new(
+        &area,
+        size(width, max_width),
+        size(height, max_height),
+    );
+
+    Ok(WMFConverter::new(buffer, player).play()?.thumbnail())
+}
+
+impl ThumbnailPlayer {
+    /// Create a player painting `area`, in device units, over `width` by
+    /// `height` pixels, or fewer when that exceeds 2048 by 2048.
+    pub fn new(area: &RectL, width: u32, height: u32) -> Self {
+        Self::with_device(Raster::new(area, width, height))
+    }
+
+    /// The image painted so far.
+    pub fn thumbnail(&self) -> Thumbnail {
+        self.device().thumbnail()
+    }
+}
@@ -0,0 +1,249 @@
+//! Encoder of 8-bit RGBA PNG images, compressing with a single deflate
+//! block of fixed Huffman codes and greedy matches of repeated bytes,
+//! which is enough for the large runs of equal pixels of a drawing.
+
+use crate::imports::*;
+
+/// Bytes back from the current position in which matches are searched.
+const WINDOW: usize = 32768;
+
+/// Longest match deflate can encode.
+const MAX_MATCH: usize = 258;
+
+/// Shortest match deflate can encode.
+const MIN_MATCH: usize = 3;
+
+/// Number of bits of the hash of the next three bytes.
+const HASH_BITS: u32 = 15;
+
+/// Base lengths of the length codes 257 to 285.
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59,
+    67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+
+/// Extra bits of the length codes 257 to 285.
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5,
+    5, 5, 5, 0,
+];
+
+/// Base distances of the distance codes 0 to 29.
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513,
+    769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Extra bits of the distance codes 0 to 29.
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10,
+    11, 11, 12, 12, 13, 13,
+];
+
+/// Encode `pixels`, `width` by `height` RGBA pixels in rows from top to
+/// bottom, as a PNG file.
+pub fn encode(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let stride = width as usize * 4;
+    // Every row starts with its filter type, 0 for none.
+    let raw: Vec<u8> = pixels
+        .chunks_exact(stride.max(1))
+        .take(height as usize)
+        .flat_map(|row| core::iter::once(0).chain(row.iter().copied()))
+        .collect();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bits per sample, RGBA, deflate, adaptive filtering, no interlace.
+    header.extend([8, 6, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+
+    chunk(&mut png, *b"IHDR", &header);
+    chunk(&mut png, *b"IDAT", &zlib(&raw));
+    chunk(&mut png, *b"IEND", &[]);
+
+    png
+}
+
+/// Append a chunk of type `typ` holding `data`.
+fn chunk(png: &mut Vec<u8>, typ: [u8; 4], data: &[u8]) {
+    let length = u32::try_from(data.len()).unwrap_or(u32::MAX);
+    let start = png.len() + 4;
+
+    png.extend(length.to_be_bytes());
+    png.extend(typ);
+    png.extend(data);
+
+    let crc = crc32(&png[start..]);
+
+    png.extend(crc.to_be_bytes());
+}
+
+/// CRC-32 of PNG chunks, over the type and the data.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 }
+        })
+    })
+}
+
+/// Adler-32 checksum ending a zlib stream.
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1_u32, 0_u32), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % 65521;
+
+        (a, (b + a) % 65521)
+    });
+
+    (b << 16) | a
+}
+
+/// Compress `data` as a zlib stream.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window and the default compression level.
+    let mut writer = BitWriter { bytes: vec![0x78, 0x9C], bits: 0, count: 0 };
+
+    // A final block with fixed Huffman codes.
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut position = 0;
+
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &head);
+
+        if length >= MIN_MATCH {
+            writer.length(length);
+            writer.distance(distance);
+
+            for at in position..position + length {
+                insert(&mut head, data, at);
+            }
+
+            position += length;
+        } else {
+            writer.literal(u16::from(data[position]));
+            insert(&mut head, data, position);
+            position += 1;
+        }
+    }
+
+    writer.literal(256);
+    writer.flush();
+    writer.bytes.extend(adler32(data).to_be_bytes());
+
+    writer.bytes
+}
+
+/// Hash of the three bytes at `position`, or `None` near the end.
+fn hash(data: &[u8], position: usize) -> Option<usize> {
+    let bytes = data.get(position..position + MIN_MATCH)?;
+    let value = (u32::from(bytes[0]) << 16)
+        | (u32::from(bytes[1]) << 8)
+        | u32::from(bytes[2]);
+
+    Some((value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize)
+}
+
+fn insert(head: &mut [usize], data: &[u8], position: usize) {
+    if let Some(hash) = hash(data, position) {
+        head[hash] = position;
+    }
+}
+
+/// Length and distance of the match of the bytes at `position` with the
+/// last bytes hashed alike, whose length is zero when there is none.
+fn longest_match(
+    data: &[u8],
+    position: usize,
+    head: &[usize],
+) -> (usize, usize) {
+    let Some(candidate) = hash(data, position).map(|hash| head[hash]) else {
+        return (0, 0);
+    };
+
+    if candidate == usize::MAX || position - candidate > WINDOW {
+        return (0, 0);
+    }
+
+    let limit = (data.len() - position).min(MAX_MATCH);
+    let length = (0..limit)
+        .take_while(|i| data[candidate + i] == data[position + i])
+        .count();
+
+    (length, position - candidate)
+}
+
+/// Writer of the bits of a deflate stream, least significant first.
+struct BitWriter {
+    bytes: Vec<u8>,
+    bits: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= value << self.count;
+        self.count += count;
+
+        while self.count >= 8 {
+            self.bytes.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Write a Huffman code, which deflate packs most significant bit
+    /// first.
+    fn code(&mut self, code: u32, count: u32) {
+        self.write(code.reverse_bits() >> (32 - count), count);
+    }
+
+    /// Write the fixed Huffman code of a literal/length symbol.
+    fn literal(&mut self, symbol: u16) {
+        let symbol = u32::from(symbol);
+
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, length: usize) {
+        let length = u16::try_from(length).unwrap_or(258);
+        let index = LENGTH_BASES.iter().rposition(|base| *base <= length);
+        let index = index.unwrap_or_default();
+
+        self.literal(257 + index as u16);
+        self.write(
+            u32::from(length - LENGTH_BASES[index]),
+            u32::from(LENGTH_EXTRA_BITS[index]),
+        );
+    }
+
+    fn distance(&mut self, distance: usize) {
+        let distance = u16::try_from(distance).unwrap_or(32768);
+        let index = DISTANCE_BASES.iter().rposition(|base| *base <= distance);
+        let index = index.unwrap_or_default();
+
+        // Distance codes are five bits long.
+        self.code(index as u32, 5);
+        self.write(
+            u32::from(distance - DISTANCE_BASES[index]),
+            u32::from(DISTANCE_EXTRA_BITS[index]),
+        );
+    }
+
+    fn flush(&mut self) {
+        if self.count > 0 {
+            self.bytes.push(self.bits as u8);
+            self.bits = 0;
+            self.count = 0;
+        }
+    }
+}
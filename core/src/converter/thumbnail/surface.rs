@@ -0,0 +1,885 @@
+//! Antialiased raster surface of the thumbnail, with a budget of work that
+//! bounds the time spent on hostile files.
+
+// Pixel coordinates stay far below 2^23, so usize to f32 casts are exact.
+#![allow(clippy::cast_precision_loss)]
+
+use crate::{
+    converter::{Clip, page::Path},
+    imports::*,
+};
+
+/// Point in pixels of the thumbnail.
+pub type Point = (f32, f32);
+
+/// Upper bound on the number of pixels of a thumbnail, which keeps the
+/// memory taken by the surface to a few tens of megabytes however large
+/// the requested size.
+pub const MAX_PIXELS: usize = 2048 * 2048;
+
+/// Units of work a thumbnail may spend on drawing: an edge crossed by a
+/// scanline, a pixel blended or a bitmap sample read. It takes well below
+/// a second to spend.
+const MAX_WORK: usize = 1 << 28;
+
+/// Scanlines sampled per row of pixels. Coverage along the rows is
+/// computed exactly.
+const SAMPLES: usize = 4;
+
+/// Number of sides of the polygons standing in for round caps and joins.
+const ROUND_SIDES: u16 = 12;
+
+/// Ternary raster operation code of PATCOPY, painting the colour as it is.
+pub const PATCOPY: u8 = 0xF0;
+
+/// Colour painted into the surface and the way it combines with what is
+/// already there.
+#[derive(Clone, Copy, Debug)]
+pub struct Paint {
+    /// Colour of the pattern, the selected pen or brush.
+    pub color: [u8; 3],
+    /// Truth table of the ternary raster operation, indexed by the bits of
+    /// the pattern, the source and the destination, most significant
+    /// first.
+    pub operation: u8,
+    /// Share of the covered pixels painted, below 1 for brushes whose
+    /// pattern is painted as its average.
+    pub density: f32,
+}
+
+/// Shape of the ends of open strokes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cap {
+    Flat,
+    Round,
+    Square,
+}
+
+/// Outline of a figure in pixels, built with [`Path`] from device units
+/// mapped by `scale` and `offset`.
+#[derive(Clone, Debug)]
+pub struct Outline {
+    scale: (f32, f32),
+    offset: (f32, f32),
+    contours: Vec<Contour>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Contour {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl Outline {
+    pub fn new(scale: (f32, f32), offset: (f32, f32)) -> Self {
+        Self { scale, offset, contours: vec![] }
+    }
+
+    fn pixel(&self, (x, y): (f32, f32)) -> Point {
+        (x * self.scale.0 + self.offset.0, y * self.scale.1 + self.offset.1)
+    }
+
+    fn last(&self) -> Option<Point> {
+        self.contours.last().and_then(|contour| contour.points.last()).copied()
+    }
+
+    /// Closed polygons of the contours, as they are filled.
+    pub fn polygons(&self) -> Vec<Vec<Point>> {
+        self.contours
+            .iter()
+            .filter(|contour| contour.points.len() > 2)
+            .map(|contour| contour.points.clone())
+            .collect()
+    }
+
+    /// Polygons covering the stroke of the contours `width` pixels wide,
+    /// interrupted by the alternating lengths of `dashes` when given.
+    ///
+    /// The polygons all turn the same way, so filling them with the
+    /// nonzero rule paints their union. Joins are round.
+    pub fn stroke(
+        &self,
+        width: f32,
+        dashes: &[f32],
+        cap: Cap,
+    ) -> Vec<Vec<Point>> {
+        let half = width / 2.0;
+        let mut polygons = vec![];
+
+        for contour in &self.contours {
+            let mut points = contour.points.clone();
+            let first = points.first().copied();
+
+            if let Some(first) = first.filter(|_| contour.closed) {
+                points.push(first);
+
+                // The join where the contour closes.
+                if dashes.is_empty() {
+                    polygons.push(circle(first, half));
+                }
+            }
+
+            for (run, open) in dash(&points, dashes, contour.closed) {
+                stroke_run(
+                    &run,
+                    half,
+                    if open { cap } else { Cap::Flat },
+                    &mut polygons,
+                );
+            }
+        }
+
+        for polygon in &mut polygons {
+            if signed_area(polygon) < 0.0 {
+                polygon.reverse();
+            }
+        }
+
+        polygons
+    }
+}
+
+impl Path for Outline {
+    fn move_to(mut self, point: (f32, f32)) -> Self {
+        let point = self.pixel(point);
+
+        self.contours.push(Contour { points: vec![point], closed: false });
+        self
+    }
+
+    fn line_to(mut self, point: (f32, f32)) -> Self {
+        let point = self.pixel(point);
+
+        match self.contours.last_mut() {
+            Some(contour) if !contour.closed => contour.points.push(point),
+            _ => self
+                .contours
+                .push(Contour { points: vec![point], closed: false }),
+        }
+
+        self
+    }
+
+    fn curve_to(
+        mut self,
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+    ) -> Self {
+        let Some(from) = self.last() else {
+            return self.move_to(to);
+        };
+        let (c1, c2, to) =
+            (self.pixel(control1), self.pixel(control2), self.pixel(to));
+        // Enough segments to keep within a fraction of a pixel of the
+        // curve, from the length of its control polygon.
+        let length = distance(from, c1) + distance(c1, c2) + distance(c2, to);
+        let segments = length.sqrt().ceil().clamp(1.0, 64.0) as u8;
+
+        for i in 1..=segments {
+            let t = f32::from(i) / f32::from(segments);
+            let u = 1.0 - t;
+            let weights =
+                [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+            let point = [from, c1, c2, to].iter().zip(weights).fold(
+                (0.0, 0.0),
+                |(x, y), (point, weight)| {
+                    (x + weight * point.0, y + weight * point.1)
+                },
+            );
+
+            if let Some(contour) = self.contours.last_mut() {
+                contour.points.push(point);
+            }
+        }
+
+        self
+    }
+
+    fn close(mut self) -> Self {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.closed = true;
+        }
+
+        self
+    }
+}
+
+/// Opaque RGB pixels of the thumbnail, on a white background.
+#[derive(Clone, Debug)]
+pub struct Surface {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+    /// Units of work left to spend.
+    work: usize,
+}
+
+impl Surface {
+    /// Create a surface of at most `width` by `height` pixels, reduced in
+    /// proportion when it would exceed [`MAX_PIXELS`].
+    pub fn new(width: u32, height: u32) -> Self {
+        let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+        let (width, height) = if width.saturating_mul(height) > MAX_PIXELS {
+            let scale =
+                (MAX_PIXELS as f64 / (width as f64 * height as f64)).sqrt();
+            let fit = |size: usize| ((size as f64 * scale) as usize).max(1);
+
+            (fit(width).min(MAX_PIXELS), fit(height).min(MAX_PIXELS))
+        } else {
+            (width, height)
+        };
+
+        Self {
+            width,
+            height,
+            pixels: vec![[0xFF; 3]; width * height],
+            work: MAX_WORK,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixels as RGBA, four bytes each in rows from top to bottom.
+    pub fn rgba(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|[r, g, b]| [*r, *g, *b, 0xFF]).collect()
+    }
+
+    /// Spend `cost` units of work, or return `false` when the budget does
+    /// not cover it, which ends all drawing.
+    fn spend(&mut self, cost: usize) -> bool {
+        if let Some(work) = self.work.checked_sub(cost) {
+            self.work = work;
+            true
+        } else {
+            self.work = 0;
+            false
+        }
+    }
+
+    /// Paint the area of `polygons` with `paint` where `clips`, in pixels,
+    /// leave it visible, using the even-odd rule when `even_odd` is set
+    /// and the nonzero rule otherwise.
+    ///
+    /// Returns `false` when the budget of work ran out before the end.
+    pub fn fill(
+        &mut self,
+        polygons: &[Vec<Point>],
+        even_odd: bool,
+        paint: Paint,
+        clips: &[Clip],
+    ) -> bool {
+        let mut edges: Vec<Edge> = polygons
+            .iter()
+            .flat_map(|polygon| {
+                polygon
+                    .iter()
+                    .zip(polygon.iter().cycle().skip(1))
+                    .filter_map(|(a, b)| Edge::new(*a, *b))
+            })
+            .collect();
+
+        if edges.is_empty() {
+            return true;
+        }
+
+        edges.sort_by(|a, b| a.top.total_cmp(&b.top));
+
+        let (left, right) = polygons.iter().flatten().fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(left, right), (x, _)| (left.min(*x), right.max(*x)),
+        );
+        let bottom = edges
+            .iter()
+            .map(|edge| edge.bottom)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let (first_column, last_column) = (
+            left.floor().clamp(0.0, self.width as f32) as usize,
+            right.ceil().clamp(0.0, self.width as f32) as usize,
+        );
+        let (first_row, last_row) = (
+            edges[0].top.floor().clamp(0.0, self.height as f32) as usize,
+            bottom.ceil().clamp(0.0, self.height as f32) as usize,
+        );
+
+        if first_column >= last_column {
+            return true;
+        }
+
+        let clip_cost = clip_cost(clips);
+        let mut cover = vec![0.0_f32; last_column - first_column];
+        let mut active: Vec<usize> = vec![];
+        let mut crossings: Vec<(f32, i32)> = vec![];
+        let mut next = 0;
+
+        for row in first_row..last_row {
+            for sample in 0..SAMPLES {
+                let y = row as f32 + (sample as f32 + 0.5) / SAMPLES as f32;
+
+                while next < edges.len() && edges[next].top <= y {
+                    active.push(next);
+                    next += 1;
+                }
+
+                active.retain(|i| edges[*i].bottom > y);
+
+                if !self.spend(active.len()) {
+                    return false;
+                }
+
+                crossings.clear();
+                crossings.extend(
+                    active
+                        .iter()
+                        .map(|i| &edges[*i])
+                        .filter(|edge| edge.top <= y)
+                        .map(|edge| (edge.x(y), edge.direction)),
+                );
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+
+                    let inside =
+                        if even_odd { winding % 2 != 0 } else { winding != 0 };
+
+                    if inside {
+                        add_span(
+                            &mut cover,
+                            pair[0].0 - first_column as f32,
+                            pair[1].0 - first_column as f32,
+                            1.0 / SAMPLES as f32,
+                        );
+                    }
+                }
+            }
+
+            if !self.spend(cover.len() * (1 + clip_cost)) {
+                return false;
+            }
+
+            for (i, coverage) in cover.iter_mut().enumerate() {
+                let amount = coverage.min(1.0) * paint.density;
+                let x = first_column + i;
+
+                *coverage = 0.0;
+
+                if amount > 0.0
+                    && visible(clips, (x as f32 + 0.5, row as f32 + 0.5))
+                {
+                    let pixel = &mut self.pixels[row * self.width + x];
+
+                    *pixel = blend(
+                        *pixel,
+                        combine(paint.operation, paint.color, [0; 3], *pixel),
+                        amount,
+                    );
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Combine the part `source` of `image`, its size and RGBA pixels in
+    /// rows from top to bottom, stretched over the pixels from `p1` to
+    /// `p2`, with the surface as `paint` says.
+    ///
+    /// The source rectangle is `(x, y, width, height)` in pixels of the
+    /// image. Each pixel averages up to four by four samples of the image
+    /// it covers. Returns `false` when the budget of work ran out.
+    pub fn image(
+        &mut self,
+        (width, height, pixels): (usize, usize, &[u8]),
+        (x, y, source_width, source_height): (f32, f32, f32, f32),
+        (p1, p2): (Point, Point),
+        paint: Paint,
+        clips: &[Clip],
+    ) -> bool {
+        let (left, right) = (p1.0.min(p2.0), p1.0.max(p2.0));
+        let (top, bottom) = (p1.1.min(p2.1), p1.1.max(p2.1));
+
+        if width == 0
+            || height == 0
+            || right - left <= 0.0
+            || bottom - top <= 0.0
+        {
+            return true;
+        }
+
+        // Source pixels per thumbnail pixel, negative when mirrored.
+        let kx = source_width / (p2.0 - p1.0);
+        let ky = source_height / (p2.1 - p1.1);
+        let samples = |k: f32| k.abs().ceil().clamp(1.0, 4.0) as u8;
+        let (samples_x, samples_y) = (samples(kx), samples(ky));
+        let clip_cost = clip_cost(clips);
+        let cost = usize::from(samples_x) * usize::from(samples_y) + clip_cost;
+        let sample = |sx: f32, sy: f32| {
+            let column =
+                (x + (sx - p1.0) * kx).floor().clamp(0.0, (width - 1) as f32)
+                    as usize;
+            let row = (y + (sy - p1.1) * ky)
+                .floor()
+                .clamp(0.0, (height - 1) as f32) as usize;
+            let at = (row * width + column) * 4;
+
+            pixels
+                .get(at..at + 3)
+                .map_or([0; 3], |rgb| [rgb[0], rgb[1], rgb[2]])
+        };
+
+        let columns = left.floor().max(0.0) as usize
+            ..(right.ceil().min(self.width as f32).max(0.0) as usize);
+        let rows = top.floor().max(0.0) as usize
+            ..(bottom.ceil().min(self.height as f32).max(0.0) as usize);
+
+        for row in rows {
+            if !self.spend(columns.len() * cost) {
+                return false;
+            }
+
+            let row_coverage = overlap(row as f32, top, bottom);
+
+            for column in columns.clone() {
+                let amount = overlap(column as f32, left, right) * row_coverage;
+                let center = (column as f32 + 0.5, row as f32 + 0.5);
+
+                if amount <= 0.0 || !visible(clips, center) {
+                    continue;
+                }
+
+                let mut sum = [0_u32; 3];
+
+                for i in 0..samples_y {
+                    for j in 0..samples_x {
+                        let rgb = sample(
+                            column as f32
+                                + (f32::from(j) + 0.5) / f32::from(samples_x),
+                            row as f32
+                                + (f32::from(i) + 0.5) / f32::from(samples_y),
+                        );
+
+                        for (sum, value) in sum.iter_mut().zip(rgb) {
+                            *sum += u32::from(value);
+                        }
+                    }
+                }
+
+                let count = u32::from(samples_x) * u32::from(samples_y);
+                let source = sum.map(|sum| (sum / count) as u8);
+                let pixel = &mut self.pixels[row * self.width + column];
+
+                *pixel = blend(
+                    *pixel,
+                    combine(paint.operation, paint.color, source, *pixel),
+                    amount,
+                );
+            }
+        }
+
+        true
+    }
+}
+
+/// Edge of a polygon from its top to its bottom.
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    top: f32,
+    bottom: f32,
+    /// x at the top.
+    x: f32,
+    /// Change of x per unit of y.
+    slope: f32,
+    /// 1 when the polygon runs downwards along the edge, -1 otherwise.
+    direction: i32,
+}
+
+impl Edge {
+    /// Edge from `a` to `b`, or `None` when it is horizontal or not
+    /// finite.
+    fn new(a: Point, b: Point) -> Option<Self> {
+        if !(a.0.is_finite()
+            && a.1.is_finite()
+            && b.0.is_finite()
+            && b.1.is_finite())
+            || (a.1 - b.1).abs() < f32::EPSILON
+        {
+            return None;
+        }
+
+        let (upper, lower, direction) =
+            if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+
+        Some(Self {
+            top: upper.1,
+            bottom: lower.1,
+            x: upper.0,
+            slope: (lower.0 - upper.0) / (lower.1 - upper.1),
+            direction,
+        })
+    }
+
+    fn x(&self, y: f32) -> f32 {
+        self.x + (y - self.top) * self.slope
+    }
+}
+
+/// Add `weight` times the share of each pixel between `x0` and `x1` to
+/// `cover`.
+fn add_span(cover: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let end = cover.len() as f32;
+    let (x0, x1) = (x0.clamp(0.0, end), x1.clamp(0.0, end));
+
+    if x1 <= x0 {
+        return;
+    }
+
+    let (first, last) = (x0.floor() as usize, x1.floor() as usize);
+
+    if first == last {
+        cover[first] += (x1 - x0) * weight;
+        return;
+    }
+
+    cover[first] += (first as f32 + 1.0 - x0) * weight;
+
+    for value in &mut cover[first + 1..last] {
+        *value += weight;
+    }
+
+    if let Some(value) = cover.get_mut(last) {
+        *value += (x1 - last as f32) * weight;
+    }
+}
+
+/// Share of the pixel starting at `at` between `start` and `end`.
+fn overlap(at: f32, start: f32, end: f32) -> f32 {
+    (end.min(at + 1.0) - start.max(at)).clamp(0.0, 1.0)
+}
+
+/// Work of testing a pixel against `clips`.
+fn clip_cost(clips: &[Clip]) -> usize {
+    clips
+        .iter()
+        .map(|clip| match clip {
+            Clip::Rects(rects) => rects.len(),
+            Clip::Exclude(_) => 1,
+        })
+        .sum()
+}
+
+/// Whether the clipping region of `clips` contains `point`.
+fn visible(clips: &[Clip], (x, y): Point) -> bool {
+    let inside = |(left, top, right, bottom): &(f32, f32, f32, f32)| {
+        *left <= x && x < *right && *top <= y && y < *bottom
+    };
+
+    clips.iter().all(|clip| match clip {
+        Clip::Rects(rects) => rects.iter().any(inside),
+        Clip::Exclude(rect) => !inside(rect),
+    })
+}
+
+/// Result of the ternary raster operation `operation` on each bit of the
+/// pattern, source and destination colours.
+fn combine(
+    operation: u8,
+    pattern: [u8; 3],
+    source: [u8; 3],
+    destination: [u8; 3],
+) -> [u8; 3] {
+    let mut result = [0; 3];
+
+    for (i, value) in result.iter_mut().enumerate() {
+        let (p, s, d) = (pattern[i], source[i], destination[i]);
+
+        for index in 0..8 {
+            if operation >> index & 1 == 1 {
+                let p = if index & 4 == 0 { !p } else { p };
+                let s = if index & 2 == 0 { !s } else { s };
+                let d = if index & 1 == 0 { !d } else { d };
+
+                *value |= p & s & d;
+            }
+        }
+    }
+
+    result
+}
+
+/// Move `from` towards `to` by `amount` between 0 and 1.
+fn blend(from: [u8; 3], to: [u8; 3], amount: f32) -> [u8; 3] {
+    let mut result = from;
+
+    for (value, to) in result.iter_mut().zip(to) {
+        let mixed =
+            f32::from(*value) + (f32::from(to) - f32::from(*value)) * amount;
+
+        *value = mixed.round().clamp(0.0, 255.0) as u8;
+    }
+
+    result
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Twice the area of `polygon`, positive when it turns clockwise on the
+/// page.
+fn signed_area(polygon: &[Point]) -> f32 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum()
+}
+
+/// Split `points` into the runs `dashes` draws, each with whether its ends
+/// are the ends of an open line, which take caps.
+fn dash(
+    points: &[Point],
+    dashes: &[f32],
+    closed: bool,
+) -> Vec<(Vec<Point>, bool)> {
+    let period: f32 = dashes.iter().sum();
+
+    if dashes.is_empty() || period <= 0.0 || !period.is_finite() {
+        return vec![(points.to_vec(), !closed)];
+    }
+
+    let mut runs = vec![];
+    let mut run = vec![];
+    let mut index = 0;
+    let mut left = dashes[0];
+
+    if let Some(first) = points.first() {
+        run.push(*first);
+    }
+
+    for pair in points.windows(2) {
+        let (mut from, to) = (pair[0], pair[1]);
+        let mut length = distance(from, to);
+
+        while length > left {
+            let t = left / length;
+            let point =
+                (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+
+            if index % 2 == 0 {
+                run.push(point);
+                runs.push((core::mem::take(&mut run), true));
+            } else {
+                run = vec![point];
+            }
+
+            length -= left;
+            from = point;
+            index = (index + 1) % dashes.len();
+            left = dashes[index];
+        }
+
+        left -= length;
+
+        if index % 2 == 0 {
+            run.push(to);
+        }
+    }
+
+    if index % 2 == 0 && run.len() > 1 {
+        runs.push((run, true));
+    }
+
+    runs
+}
+
+/// Append polygons covering the stroke of the polyline `points`, `half`
+/// pixels either side of it, to `polygons`.
+fn stroke_run(
+    points: &[Point],
+    half: f32,
+    cap: Cap,
+    polygons: &mut Vec<Vec<Point>>,
+) {
+    let points: Vec<Point> =
+        points.iter().copied().fold(vec![], |mut points: Vec<Point>, point| {
+            if points.last().is_none_or(|last| distance(*last, point) > 1e-4) {
+                points.push(point);
+            }
+            points
+        });
+
+    let Some(first) = points.first().copied() else {
+        return;
+    };
+
+    if points.len() == 1 {
+        // A zero-length line shows its caps only.
+        if cap == Cap::Round {
+            polygons.push(circle(first, half));
+        } else if cap == Cap::Square {
+            polygons.push(vec![
+                (first.0 - half, first.1 - half),
+                (first.0 + half, first.1 - half),
+                (first.0 + half, first.1 + half),
+                (first.0 - half, first.1 + half),
+            ]);
+        }
+
+        return;
+    }
+
+    let last = points.len() - 1;
+
+    for (i, pair) in points.windows(2).enumerate() {
+        let (mut a, mut b) = (pair[0], pair[1]);
+        let length = distance(a, b);
+        let (ux, uy) = ((b.0 - a.0) / length, (b.1 - a.1) / length);
+
+        if cap == Cap::Square {
+            if i == 0 {
+                a = (a.0 - ux * half, a.1 - uy * half);
+            }
+
+            if i + 1 == last {
+                b = (b.0 + ux * half, b.1 + uy * half);
+            }
+        }
+
+        let (nx, ny) = (-uy * half, ux * half);
+
+        polygons.push(vec![
+            (a.0 + nx, a.1 + ny),
+            (b.0 + nx, b.1 + ny),
+            (b.0 - nx, b.1 - ny),
+            (a.0 - nx, a.1 - ny),
+        ]);
+    }
+
+    for (i, point) in points.iter().enumerate() {
+        let end = i == 0 || i == last;
+
+        if !end || cap == Cap::Round {
+            polygons.push(circle(*point, half));
+        }
+    }
+}
+
+/// Polygon standing in for the circle of radius `radius` around `center`.
+fn circle(center: Point, radius: f32) -> Vec<Point> {
+    (0..ROUND_SIDES)
+        .map(|i| {
+            let angle =
+                core::f32::consts::TAU * f32::from(i) / f32::from(ROUND_SIDES);
+
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COPY: Paint =
+        Paint { color: [0; 3], operation: PATCOPY, density: 1.0 };
+
+    fn square(left: f32, top: f32, right: f32, bottom: f32) -> Vec<Point> {
+        vec![(left, top), (right, top), (right, bottom), (left, bottom)]
+    }
+
+    #[test]
+    fn fill_antialiases_partly_covered_pixels() {
+        let mut surface = Surface::new(4, 4);
+
+        assert!(surface.fill(&[square(1.0, 1.0, 2.5, 3.0)], false, COPY, &[]));
+
+        let pixels = surface.rgba();
+        let at = |x: usize, y: usize| pixels[(y * 4 + x) * 4];
+
+        assert_eq!(at(0, 1), 0xFF);
+        assert_eq!(at(1, 1), 0x00);
+        assert_eq!(at(2, 2), 0x80);
+        assert_eq!(at(1, 3), 0xFF);
+    }
+
+    #[test]
+    fn fill_applies_raster_operation_and_clip() {
+        let mut surface = Surface::new(2, 1);
+        // DSTINVERT turns the white background black.
+        let invert = Paint { color: [0; 3], operation: 0x55, density: 1.0 };
+        let clips = [Clip::Exclude((1.0, 0.0, 2.0, 1.0))];
+
+        assert!(surface.fill(
+            &[square(0.0, 0.0, 2.0, 1.0)],
+            false,
+            invert,
+            &clips
+        ));
+        assert_eq!(surface.rgba(), [0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn stroke_union_is_painted_once() {
+        let outline = Outline::new((1.0, 1.0), (0.0, 0.0))
+            .move_to((1.0, 2.0))
+            .line_to((7.0, 2.0))
+            .line_to((7.0, 6.0));
+        let paint = Paint { color: [0; 3], operation: PATCOPY, density: 0.5 };
+        let mut surface = Surface::new(8, 8);
+
+        assert!(surface.fill(
+            &outline.stroke(2.0, &[], Cap::Flat),
+            false,
+            paint,
+            &[]
+        ));
+
+        // The corner, covered by two segments and a join, is as light as
+        // the middle of a segment.
+        let pixels = surface.rgba();
+        let at = |x: usize, y: usize| pixels[(y * 8 + x) * 4];
+
+        assert_eq!(at(6, 2), at(3, 2));
+        assert_eq!(at(3, 2), 0x80);
+    }
+
+    #[test]
+    fn dashes_split_lines() {
+        let runs = dash(&[(0.0, 0.0), (10.0, 0.0)], &[3.0, 2.0], false);
+        let starts: Vec<_> =
+            runs.iter().map(|(run, _)| run[0].0.round() as i32).collect();
+
+        assert_eq!(starts, [0, 5]);
+    }
+
+    #[test]
+    fn surface_is_reduced_to_the_pixel_limit() {
+        let surface = Surface::new(8192, 4096);
+
+        assert!(surface.width() * surface.height() <= MAX_PIXELS);
+        assert_eq!(surface.width(), 2 * surface.height());
+    }
+
+    #[test]
+    fn work_budget_ends_drawing() {
+        let mut surface = Surface::new(16, 16);
+
+        surface.work = 10;
+        assert!(!surface.fill(
+            &[square(0.0, 0.0, 16.0, 16.0)],
+            false,
+            COPY,
+            &[]
+        ));
+        assert!(!surface.fill(&[square(0.0, 0.0, 1.0, 1.0)], false, COPY, &[]));
+    }
+}
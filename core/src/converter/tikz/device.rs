@@ -0,0 +1,571 @@
+use crate::{
+    converter::{
+        Clip, DiagnosticKind, GdiState, LineCap, LineJoin, LineStyle,
+        PlayError,
+        drawing::{Context, Device, Paint, TextRun},
+        page::{Image, Path, Placeable, StandardFont, number},
+        tikz::picture::{Figure, Picture, color, escape},
+    },
+    imports::*,
+    parser::*,
+};
+
+/// Picture of a [`TikzPlayer`](crate::converter::TikzPlayer): its
+/// statements and the bitmap placeholders placed so far.
+#[derive(Default)]
+pub struct Tikz {
+    picture: Picture,
+    // Number of the bitmaps placed so far, which names the next one.
+    images: usize,
+    // META_PLACEABLE bounding box, which becomes the bounding box.
+    placeable: Placeable,
+}
+
+/// Colour change standing in for a raster operation. TikZ paints
+/// opaquely, so operations combining with the pixels already painted are
+/// approximated by one of these.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operation {
+    Copy,
+    Invert,
+    Black,
+    White,
+}
+
+impl Operation {
+    /// Operation standing in for the ROP2 mode `operation`, or `None` when
+    /// nothing is drawn.
+    fn from_rop2(
+        context: &mut Context<'_>,
+        operation: BinaryRasterOperation,
+    ) -> Option<Self> {
+        use BinaryRasterOperation as Rop2;
+
+        let approximated = match operation {
+            Rop2::R2_NOP => return None,
+            Rop2::R2_COPYPEN => return Some(Self::Copy),
+            Rop2::R2_NOTCOPYPEN => return Some(Self::Invert),
+            Rop2::R2_BLACK => return Some(Self::Black),
+            Rop2::R2_WHITE => return Some(Self::White),
+            Rop2::R2_NOT => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!(
+                        "{operation:?}: inverting the picture is not possible \
+                         in TikZ"
+                    ),
+                );
+
+                return None;
+            }
+            Rop2::R2_NOTXORPEN | Rop2::R2_MASKNOTPEN | Rop2::R2_MERGENOTPEN => {
+                Self::Invert
+            }
+            Rop2::R2_XORPEN
+            | Rop2::R2_MASKPEN
+            | Rop2::R2_MERGEPEN
+            | Rop2::R2_NOTMERGEPEN
+            | Rop2::R2_MASKPENNOT
+            | Rop2::R2_NOTMASKPEN
+            | Rop2::R2_MERGEPENNOT => Self::Copy,
+        };
+
+        context.report(
+            DiagnosticKind::Approximated,
+            format!(
+                "{operation:?}: drawn as {}",
+                if approximated == Self::Invert {
+                    "R2_NOTCOPYPEN"
+                } else {
+                    "R2_COPYPEN"
+                },
+            ),
+        );
+
+        Some(approximated)
+    }
+
+    fn color(self, color: &ColorRef) -> ColorRef {
+        match self {
+            Self::Copy => color.clone(),
+            Self::Invert => ColorRef {
+                red: !color.red,
+                green: !color.green,
+                blue: !color.blue,
+                ..color.clone()
+            },
+            Self::Black => ColorRef::black(),
+            Self::White => ColorRef::white(),
+        }
+    }
+}
+
+impl Tikz {
+    /// Points per device unit.
+    fn scale(&self, state: &GdiState) -> f32 {
+        self.placeable.scale(state.window(), 72.0)
+    }
+
+    /// Append `body`, in a scope clipped to the clipping region when there
+    /// is one.
+    fn paint(&mut self, state: &GdiState, body: &Picture) {
+        let clips = clip(state.clips(), self.scale(state));
+        let mut picture = core::mem::take(&mut self.picture);
+
+        picture = if clips.is_empty() {
+            picture.append(body)
+        } else {
+            picture
+                .raw("\\begin{scope}")
+                .append(&clips)
+                .append(body)
+                .raw("\\end{scope}")
+        };
+
+        self.picture = picture;
+    }
+
+    /// Options stroking a path with the selected pen, or `None` for
+    /// PS_NULL.
+    fn stroke_paint(
+        &self,
+        state: &GdiState,
+        operation: Operation,
+    ) -> Option<Vec<String>> {
+        let line = LineStyle::selected(state)?;
+        let scale = self.scale(state);
+        let cap = match line.cap {
+            LineCap::Flat => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "rect",
+        };
+        let join = match line.join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        };
+        let dashes: Vec<_> =
+            line.dashes.iter().map(|dash| dash * scale).collect();
+
+        Some(
+            [
+                format!(
+                    "draw={}",
+                    color(&operation.color(&state.pen().color_ref))
+                ),
+                format!("line width={}pt", number(line.width * scale)),
+                format!("line cap={cap}"),
+                format!("line join={join}"),
+            ]
+            .into_iter()
+            .chain(dash_pattern(&dashes))
+            .collect(),
+        )
+    }
+}
+
+/// Options filling a path with `brush`, or `None` when it paints nothing.
+///
+/// Hatched brushes become the line patterns of the `patterns` library,
+/// whose spacing is fixed. Pattern brushes are filled with the average
+/// colour of their bitmap.
+fn fill_paint(
+    context: &mut Context<'_>,
+    brush: &Brush,
+    operation: Operation,
+) -> Option<Vec<String>> {
+    let state = context.state;
+    let image = match brush {
+        Brush::Null => return None,
+        _ if matches!(operation, Operation::Black | Operation::White) => {
+            let paint = operation.color(&ColorRef::black());
+
+            return Some(vec![format!("fill={}", color(&paint))]);
+        }
+        Brush::Solid { color_ref } => {
+            let paint = operation.color(color_ref);
+
+            return Some(vec![format!("fill={}", color(&paint))]);
+        }
+        Brush::Hatched { color_ref, brush_hatch } => {
+            let pattern = match brush_hatch {
+                HatchStyle::HS_HORIZONTAL => "horizontal lines",
+                HatchStyle::HS_VERTICAL => "vertical lines",
+                HatchStyle::HS_FDIAGONAL => "north west lines",
+                HatchStyle::HS_BDIAGONAL => "north east lines",
+                HatchStyle::HS_CROSS => "grid",
+                HatchStyle::HS_DIAGCROSS => "crosshatch",
+            };
+            let mut options = vec![
+                format!("pattern={pattern}"),
+                format!("pattern color={}", color(&operation.color(color_ref))),
+            ];
+
+            // The background of the hatch is painted before the pattern,
+            // which leaves it transparent.
+            if state.bk_mode() == MixMode::OPAQUE {
+                let background = operation.color(state.bk_color());
+
+                options
+                    .push(format!("preaction={{fill={}}}", color(&background)));
+            }
+
+            return Some(options);
+        }
+        Brush::DIBPatternPT { brush_hatch, .. } => Image::from_dib(brush_hatch),
+        Brush::Pattern { brush_hatch } => Image::from_bitmap16(brush_hatch),
+    };
+
+    match image.and_then(|image| image.average_color()) {
+        Ok([red, green, blue]) => {
+            context.report(
+                DiagnosticKind::Approximated,
+                "pattern brush is painted with the average colour of its \
+                 bitmap"
+                    .to_owned(),
+            );
+
+            let average = ColorRef { red, green, blue, ..ColorRef::black() };
+
+            Some(vec![format!("fill={}", color(&operation.color(&average)))])
+        }
+        Err(message) => {
+            context.report(
+                DiagnosticKind::Skipped,
+                format!("pattern brush is not painted: {message}"),
+            );
+
+            None
+        }
+    }
+}
+
+/// `\clip` statements intersecting the clipping path with each of `clips`,
+/// at `scale` points per device unit.
+fn clip(clips: &[Clip], scale: f32) -> Picture {
+    clips.iter().fold(Picture::new(), |picture, clip| match clip {
+        Clip::Rects(rects) if rects.is_empty() => picture.path(
+            "\\clip",
+            &[],
+            &Figure::new(scale).rect((0.0, 0.0), (0.0, 0.0)),
+        ),
+        Clip::Rects(rects) => {
+            picture.path("\\clip", &[], &rects_path(scale, rects))
+        }
+        Clip::Exclude((left, top, right, bottom)) => picture.path(
+            "\\clip",
+            &["even odd rule".to_owned()],
+            &Figure::new(scale)
+                .rect((-32768.0, -32768.0), (32767.0, 32767.0))
+                .rect((*left, *top), (*right, *bottom)),
+        ),
+    })
+}
+
+/// `dash pattern` option of `dashes` in points, alternately on and off,
+/// or nothing for a solid line.
+fn dash_pattern(dashes: &[f32]) -> Option<String> {
+    if dashes.is_empty() {
+        return None;
+    }
+
+    let pattern: Vec<_> = dashes
+        .chunks(2)
+        .flat_map(|pair| {
+            let on = format!("on {}pt", number(pair[0]));
+            let off = pair.get(1).map(|v| format!("off {}pt", number(*v)));
+
+            core::iter::once(on).chain(off)
+        })
+        .collect();
+
+    Some(format!("dash pattern={}", pattern.join(" ")))
+}
+
+impl Device for Tikz {
+    type Path = Figure;
+
+    fn path(&self, state: &GdiState) -> Figure {
+        Figure::new(self.scale(state))
+    }
+
+    fn header(&mut self, header: &MetafileHeader) {
+        self.placeable = Placeable::new(header);
+    }
+
+    fn draw(
+        &mut self,
+        context: &mut Context<'_>,
+        figure: Figure,
+        paint: &Paint,
+    ) {
+        let Some(operation) = Operation::from_rop2(context, paint.operation)
+        else {
+            return;
+        };
+        let fill = paint
+            .brush
+            .as_ref()
+            .and_then(|brush| fill_paint(context, brush, operation));
+        let stroke = if paint.stroke {
+            self.stroke_paint(context.state, operation)
+        } else {
+            None
+        };
+        let command = match (&fill, &stroke) {
+            (None, None) => return,
+            (Some(_), None) => "\\fill",
+            (_, Some(_)) => "\\draw",
+        };
+        let mut options = vec![];
+
+        if let Some(fill) = fill {
+            if context.state.fill_even_odd() {
+                options.push("even odd rule".to_owned());
+            }
+
+            options.extend(fill);
+        }
+
+        options.extend(stroke.into_iter().flatten());
+        self.paint(
+            context.state,
+            &Picture::new().path(command, &options, &figure),
+        );
+    }
+
+    /// Operations combining the source with the picture are approximated
+    /// by SRCCOPY, and the placeholder of the bitmap is shown as it is.
+    ///
+    /// The placeholder is the next `wmf-image-N`, whose whole image is
+    /// placed around its centre and clipped to the part shown.
+    fn image(
+        &mut self,
+        context: &mut Context<'_>,
+        image: Image,
+        (p1, p2): ((f32, f32), (f32, f32)),
+        (x, y, source_width, source_height): (f32, f32, f32, f32),
+        operation: TernaryRasterOperation,
+    ) {
+        use TernaryRasterOperation as Rop3;
+
+        match operation {
+            Rop3::SRCCOPY => {}
+            Rop3::NOTSRCCOPY
+            | Rop3::SRCAND
+            | Rop3::SRCPAINT
+            | Rop3::SRCINVERT
+            | Rop3::MERGEPAINT
+            | Rop3::SRCERASE
+            | Rop3::NOTSRCERASE => {
+                context.report(
+                    DiagnosticKind::Approximated,
+                    format!("{operation:?}: drawn as SRCCOPY"),
+                );
+            }
+            _ => {
+                context.report(
+                    DiagnosticKind::Skipped,
+                    format!("{operation:?}: raster operation is not supported"),
+                );
+
+                return;
+            }
+        }
+
+        let size = |v: usize| f32::from(u16::try_from(v).unwrap_or(u16::MAX));
+        let (width, height) = (size(image.width()), size(image.height()));
+        // Device units per source pixel, negative when mirrored.
+        let kx = (p2.0 - p1.0) / source_width;
+        let ky = (p2.1 - p1.1) / source_height;
+        let center =
+            (p1.0 + (width / 2.0 - x) * kx, p1.1 + (height / 2.0 - y) * ky);
+        let scale = self.scale(context.state);
+        let mut options = vec!["inner sep=0pt".to_owned()];
+
+        if kx < 0.0 {
+            options.push("xscale=-1".to_owned());
+        }
+
+        if ky < 0.0 {
+            options.push("yscale=-1".to_owned());
+        }
+
+        self.images += 1;
+
+        let name = format!("wmf-image-{}", self.images);
+        let body = Picture::new()
+            .raw(format!(
+                "% {name}: {}x{} pixels",
+                image.width(),
+                image.height()
+            ))
+            .raw("\\begin{scope}")
+            .path("\\clip", &[], &Figure::new(scale).rect(p1, p2))
+            .raw(format!(
+                "\\node[{}] at {} \
+                 {{\\pgfimage[width={}pt,height={}pt]{{{name}}}}};",
+                options.join(","),
+                Figure::new(scale).coordinate(center),
+                number((width * kx * scale).abs()),
+                number((height * ky * scale).abs()),
+            ))
+            .raw("\\end{scope}");
+
+        self.paint(context.state, &body);
+    }
+
+    /// Text placed as a whole is anchored by its alignment, so that the
+    /// document fonts line up as the metafile does; the standard font
+    /// metrics only give the extent of the background and the lines.
+    fn text(&mut self, context: &mut Context<'_>, run: &TextRun) {
+        let state = context.state;
+        let standard_font = StandardFont::new(&run.font);
+        let scale = self.scale(state);
+        let (start, size, width) = (run.start(), run.size, run.width);
+        // Escapement is in tenths of degrees.
+        let degrees = f32::from(run.font.escapement) / 10.0;
+        let anchor = match run.align {
+            0.5 => "base",
+            1.0 => "base east",
+            _ => "base west",
+        };
+        let text_color = state.text_color();
+        let bk_color = state.bk_color();
+        let figure = || Figure::new(scale);
+
+        // ETO_OPAQUE fills the rectangle before the text is drawn.
+        if let Some((left, top, right, bottom)) = run.opaque {
+            self.paint(
+                state,
+                &Picture::new().path(
+                    "\\fill",
+                    &[format!("fill={}", color(bk_color))],
+                    &figure().rect((left, top), (right, bottom)),
+                ),
+            );
+        }
+
+        let mut body = Picture::new();
+
+        if let Some((left, top, right, bottom)) = run.clip {
+            body = body.raw("\\begin{scope}").path(
+                "\\clip",
+                &[],
+                &figure().rect((left, top), (right, bottom)),
+            );
+        }
+
+        if state.bk_mode() == MixMode::OPAQUE {
+            body = body.path(
+                "\\fill",
+                &[format!("fill={}", color(bk_color))],
+                &figure().polygon(&run.corners()),
+            );
+        }
+
+        let font_options = format!(
+            "font={{\\fontsize{{{}}}{{{}}}\\selectfont{}}}",
+            number(size * scale),
+            number(size * scale * 1.2),
+            standard_font.latex_declarations(),
+        );
+        let node = |body: Picture, anchor, origin, text: &str| {
+            let mut options = vec![
+                format!("anchor={anchor}"),
+                "inner sep=0pt".to_owned(),
+                font_options.clone(),
+                format!("text={}", color(text_color)),
+            ];
+
+            if degrees != 0.0 {
+                options.push(format!("rotate={}", number(degrees)));
+            }
+
+            body.raw(format!(
+                "\\node[{}] at {} {{{}}};",
+                options.join(","),
+                figure().coordinate(origin),
+                escape(text),
+            ))
+        };
+
+        if run.custom {
+            for (glyph, origin) in run.glyphs.iter().zip(run.glyph_origins()) {
+                body = node(body, "base west", origin, &glyph.text);
+            }
+        } else {
+            let text: String =
+                run.glyphs.iter().map(|glyph| glyph.text.as_str()).collect();
+
+            body = node(body, anchor, run.origin, &text);
+        }
+
+        for below in run.lines() {
+            body = body.path(
+                "\\draw",
+                &[
+                    format!("draw={}", color(text_color)),
+                    format!("line width={}pt", number(0.05 * size * scale)),
+                ],
+                &figure()
+                    .move_to(run.at(start, 0.0, below))
+                    .line_to(run.at(start, width, below)),
+            );
+        }
+
+        if run.clip.is_some() {
+            body = body.raw("\\end{scope}");
+        }
+
+        self.paint(state, &body);
+    }
+
+    fn flood_fill(&mut self, context: &mut Context<'_>, _: (f32, f32)) {
+        context.report(
+            DiagnosticKind::Skipped,
+            "flood fill needs the pixels of the drawing, which TikZ output \
+             does not keep"
+                .to_owned(),
+        );
+    }
+
+    fn escape(&mut self, _: &mut Context<'_>, _: &META_ESCAPE) {
+        info!("META_ESCAPE: skipped (not applicable to TikZ)");
+    }
+
+    fn generate(self, state: &GdiState) -> Result<Vec<u8>, PlayError> {
+        let scale = self.scale(state);
+        let Self { picture, placeable, .. } = self;
+        let (x, y, width, height) = placeable.area(state.window());
+
+        if width <= 0.0 || height <= 0.0 {
+            return Err(PlayError::FailedGenerate {
+                cause: format!("picture size is empty: {width}x{height}"),
+            });
+        }
+
+        // Device space has the y-axis pointing down from the top left of the
+        // drawing, while TikZ points up.
+        let area = Figure::new(scale).rect((x, y), (x + width, y + height));
+
+        Ok(Picture::new()
+            .raw("% Needs \\usepackage{tikz} and \\usetikzlibrary{patterns}.")
+            .raw("\\begin{tikzpicture}[x=1pt,y=-1pt]")
+            .path("\\useasboundingbox", &[], &area)
+            .append(&picture)
+            .raw("\\end{tikzpicture}")
+            .into_bytes())
+    }
+}
+
+/// Path of the rectangles `(left, top, right, bottom)`, at `scale` points
+/// per device unit.
+fn rects_path(scale: f32, rects: &[(f32, f32, f32, f32)]) -> Figure {
+    rects.iter().fold(
+        Figure::new(scale),
+        |figure, (left, top, right, bottom)| {
+            figure.rect((*left, *top), (*right, *bottom))
+        },
+    )
+}
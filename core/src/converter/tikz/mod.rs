@@ -0,0 +1,28 @@
+mod device;
+mod picture;
+
+pub use self::device::Tikz;
+use crate::converter::drawing::Drawing;
+
+/// Player writing the metafile as a TikZ picture for LaTeX documents.
+///
+/// Drawing records become `\draw` and `\fill` paths and text becomes
+/// `\node`s set in the document fonts closest to the logical font, at its
+/// size. Coordinates, line widths and font sizes are in points: the
+/// picture is the META_PLACEABLE bounding box at its resolution, or the
+/// area of the drawing at 96 DPI without one.
+///
+/// Bitmaps are not embedded. Each becomes a `\pgfimage` of the file
+/// `wmf-image-N`, numbered from 1 in the order of the records and
+/// preceded by a comment giving its size in pixels, which the document
+/// provides in its place.
+///
+/// The picture needs `\usepackage{tikz}` and `\usetikzlibrary{patterns}`
+/// for the hatched brushes.
+pub type TikzPlayer = Drawing<Tikz>;
+
+impl TikzPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
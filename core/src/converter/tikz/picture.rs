@@ -0,0 +1,193 @@
+use crate::{
+    converter::page::{Path, number},
+    imports::*,
+    parser::*,
+};
+
+/// Path of a TikZ statement.
+///
+/// Points are given in device units and written in points, `scale` of
+/// them per device unit, with the y-axis pointing down as in device space;
+/// the options of the picture turn it up.
+#[derive(Clone, Debug)]
+pub struct Figure {
+    scale: f32,
+    path: String,
+}
+
+impl Figure {
+    pub fn new(scale: f32) -> Self {
+        Self { scale, path: String::new() }
+    }
+
+    /// Coordinate of the device point `(x, y)`.
+    pub fn coordinate(&self, (x, y): (f32, f32)) -> String {
+        format!("({},{})", number(x * self.scale), number(y * self.scale))
+    }
+
+    fn push(mut self, operation: &str, point: (f32, f32)) -> Self {
+        let coordinate = self.coordinate(point);
+
+        if !self.path.is_empty() {
+            self.path.push(' ');
+        }
+
+        self.path.push_str(operation);
+        self.path.push_str(&coordinate);
+        self
+    }
+}
+
+impl Path for Figure {
+    fn move_to(self, point: (f32, f32)) -> Self {
+        self.push("", point)
+    }
+
+    fn line_to(self, point: (f32, f32)) -> Self {
+        self.push("-- ", point)
+    }
+
+    fn curve_to(
+        self,
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+    ) -> Self {
+        let controls = format!(
+            ".. controls {} and {} .. ",
+            self.coordinate(control1),
+            self.coordinate(control2),
+        );
+
+        self.push(&controls, to)
+    }
+
+    fn close(mut self) -> Self {
+        self.path.push_str(" -- cycle");
+        self
+    }
+}
+
+impl core::fmt::Display for Figure {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+/// Statements of a TikZ picture.
+#[derive(Clone, Debug, Default)]
+pub struct Picture(String);
+
+impl Picture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the path statement `command` with `options` along `figure`.
+    pub fn path(
+        self,
+        command: &str,
+        options: &[String],
+        figure: &Figure,
+    ) -> Self {
+        if options.is_empty() {
+            return self.raw(format!("{command} {figure};"));
+        }
+
+        self.raw(format!("{command}[{}] {figure};", options.join(",")))
+    }
+
+    /// Append statements written by another builder.
+    pub fn append(mut self, picture: &Picture) -> Self {
+        self.0.push_str(&picture.0);
+        self
+    }
+
+    /// Append a statement line as it is.
+    pub fn raw(mut self, line: impl core::fmt::Display) -> Self {
+        use core::fmt::Write;
+
+        let _ = writeln!(self.0, "{line}");
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0.into_bytes()
+    }
+}
+
+impl core::fmt::Display for Picture {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Colour in the extended syntax of xcolor, which TikZ options take.
+pub fn color(color: &ColorRef) -> String {
+    format!(
+        "{{rgb,255:red,{};green,{};blue,{}}}",
+        color.red, color.green, color.blue
+    )
+}
+
+/// Escape the characters LaTeX treats specially, so that `text` is set as
+/// it is.
+pub fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            // Control characters have no glyph.
+            _ if c.is_control() => {}
+            _ => escaped.push(c),
+        }
+
+        escaped
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_is_a_closed_subpath_in_points() {
+        let figure = Figure::new(0.5).rect((10.0, 20.0), (0.0, 5.0));
+
+        assert_eq!(
+            figure.to_string(),
+            "(5,10) -- (0,10) -- (0,2.5) -- (5,2.5) -- cycle",
+        );
+    }
+
+    #[test]
+    fn curves_have_both_controls() {
+        let figure = Figure::new(1.0).move_to((0.0, 0.0)).curve_to(
+            (1.0, 0.0),
+            (2.0, 1.0),
+            (2.0, 2.0),
+        );
+
+        assert_eq!(
+            figure.to_string(),
+            "(0,0) .. controls (1,0) and (2,1) .. (2,2)",
+        );
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        assert_eq!(
+            escape("50% of {a_b} & \\c~"),
+            "50\\% of \\{a\\_b\\} \\& \\textbackslash{}c\\textasciitilde{}",
+        );
+    }
+}
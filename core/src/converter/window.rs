@@ -0,0 +1,295 @@
+use crate::parser::*;
+
+/// Window and viewport of a playback device context, mapping logical
+/// coordinates to the device space of the output.
+#[derive(Clone, Debug)]
+pub struct Window {
+    pub x: i16,
+    pub y: i16,
+    pub origin_x: i16,
+    pub origin_y: i16,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    /// Minimum rendered coordinates for viewBox expansion
+    pub min_x: i16,
+    pub min_y: i16,
+    /// Flip the axis when the extent is negative
+    /// (in WMF, a negative extent reverses the axis direction)
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Current window extent (set by META_SETWINDOWEXT)
+    pub current_ext_x: f32,
+    pub current_ext_y: f32,
+    /// Viewport origin
+    pub viewport_origin_x: f32,
+    pub viewport_origin_y: f32,
+    /// Viewport extent (None = not explicitly set)
+    pub viewport_ext_x: Option<f32>,
+    pub viewport_ext_y: Option<f32>,
+    /// Current mapping mode
+    pub map_mode: MapMode,
+    /// Logical units per inch from META_PLACEABLE
+    pub units_per_inch: Option<u16>,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            origin_x: 0,
+            origin_y: 0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            min_x: 0,
+            min_y: 0,
+            flip_x: false,
+            flip_y: false,
+            current_ext_x: 1.0,
+            current_ext_y: 1.0,
+            viewport_origin_x: 0.0,
+            viewport_origin_y: 0.0,
+            viewport_ext_x: None,
+            viewport_ext_y: None,
+            map_mode: MapMode::MM_TEXT,
+            units_per_inch: None,
+        }
+    }
+}
+
+impl Window {
+    /// Returns whether the current mapping mode is a fixed mode
+    /// (MM_LOMETRIC through MM_TWIPS). In fixed modes,
+    /// META_SETWINDOWEXT / META_SETVIEWPORTEXT do not affect
+    /// coordinate conversion.
+    fn is_fixed_map_mode(&self) -> bool {
+        matches!(
+            self.map_mode,
+            MapMode::MM_LOMETRIC
+                | MapMode::MM_HIMETRIC
+                | MapMode::MM_LOENGLISH
+                | MapMode::MM_HIENGLISH
+                | MapMode::MM_TWIPS
+        )
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ext(&mut self, x: i16, y: i16) {
+        // In fixed mapping modes, window extent does not affect
+        // coordinate conversion (per MS-WMF spec). ViewBox
+        // tracking is delegated to extend_window.
+        if self.is_fixed_map_mode() {
+            return;
+        }
+
+        self.flip_x = x < 0;
+        self.flip_y = y < 0;
+
+        let mag_x = i16::try_from(x.unsigned_abs()).unwrap_or(i16::MAX);
+        let mag_y = i16::try_from(y.unsigned_abs()).unwrap_or(i16::MAX);
+
+        // Preserve any previously tracked larger extents to
+        // avoid shrinking the viewBox and clipping already
+        // rendered content.
+        self.x = self.x.max(mag_x);
+        self.y = self.y.max(mag_y);
+
+        // Track window extent for viewport calculations
+        self.current_ext_x = f32::from(mag_x);
+        self.current_ext_y = f32::from(mag_y);
+
+        // META_SETWINDOWEXT sets an absolute extent, so reset
+        // the scale accumulated by META_SCALEWINDOWEXT
+        self.scale_x = 1.0;
+        self.scale_y = 1.0;
+    }
+
+    pub fn origin(&mut self, origin_x: i16, origin_y: i16) {
+        self.origin_x = origin_x;
+        self.origin_y = origin_y;
+    }
+
+    pub fn scale(&mut self, scale_x: f32, scale_y: f32) {
+        // Ignore scale changes in fixed mapping modes
+        if self.is_fixed_map_mode() {
+            return;
+        }
+
+        self.scale_x = scale_x;
+        self.scale_y = scale_y;
+    }
+
+    pub fn offset_origin(&mut self, x: i16, y: i16) {
+        self.origin_x = self.origin_x.saturating_add(x);
+        self.origin_y = self.origin_y.saturating_add(y);
+    }
+
+    pub fn viewport_origin(&mut self, x: i16, y: i16) {
+        self.viewport_origin_x = f32::from(x);
+        self.viewport_origin_y = f32::from(y);
+    }
+
+    pub fn offset_viewport_origin(&mut self, x: i16, y: i16) {
+        self.viewport_origin_x += f32::from(x);
+        self.viewport_origin_y += f32::from(y);
+    }
+
+    pub fn viewport_ext(&mut self, x: i16, y: i16) {
+        // Ignore viewport extent in fixed mapping modes
+        if self.is_fixed_map_mode() {
+            return;
+        }
+
+        self.viewport_ext_x = Some(f32::from(x));
+        self.viewport_ext_y = Some(f32::from(y));
+    }
+
+    pub fn scale_viewport_ext(
+        &mut self,
+        x_num: f32,
+        x_denom: f32,
+        y_num: f32,
+        y_denom: f32,
+    ) {
+        // Ignore viewport extent scaling in fixed mapping modes
+        if self.is_fixed_map_mode() {
+            return;
+        }
+
+        if let Some(ref mut ext_x) = self.viewport_ext_x {
+            *ext_x = *ext_x * x_num / x_denom;
+        }
+
+        if let Some(ref mut ext_y) = self.viewport_ext_y {
+            *ext_y = *ext_y * y_num / y_denom;
+        }
+    }
+
+    /// Convert logical coordinates to device coordinates.
+    /// The conversion method varies depending on the MapMode.
+    pub fn logical_to_device(&self, lx: f32, ly: f32) -> (f32, f32) {
+        // Fixed mapping modes (MM_LOMETRIC through MM_TWIPS):
+        // positive Y points up in logical space, so flip it to
+        // match SVG coordinate system (positive Y points down).
+        // No scaling by window/viewport extent.
+        if self.is_fixed_map_mode() {
+            let dx = lx + self.viewport_origin_x;
+            let dy = -ly + self.viewport_origin_y;
+            return (dx, dy);
+        }
+
+        let (dx, dy) = match (self.viewport_ext_x, self.viewport_ext_y) {
+            (Some(vp_ext_x), Some(vp_ext_y)) => {
+                // When viewport extent is set, apply the WMF
+                // formula: Dx = Lx * VEx / WEx
+                let eff_win_x = self.current_ext_x * self.scale_x;
+                let eff_win_y = self.current_ext_y * self.scale_y;
+                let rx = if eff_win_x.abs() > f32::EPSILON {
+                    vp_ext_x / eff_win_x
+                } else {
+                    1.0
+                };
+                let ry = if eff_win_y.abs() > f32::EPSILON {
+                    vp_ext_y / eff_win_y
+                } else {
+                    1.0
+                };
+
+                // MM_ISOTROPIC: preserve aspect ratio by applying
+                // the smaller absolute scale to both axes. The
+                // direction (sign) of each axis is preserved.
+                if self.map_mode == MapMode::MM_ISOTROPIC {
+                    let abs_min = rx.abs().min(ry.abs());
+                    (lx * abs_min.copysign(rx), ly * abs_min.copysign(ry))
+                } else {
+                    (lx * rx, ly * ry)
+                }
+            }
+            _ => {
+                // Default: preserve existing behavior
+                (lx / self.scale_x, ly / self.scale_y)
+            }
+        };
+
+        // Add viewport origin after axis flip
+        let dx = if self.flip_x { -dx } else { dx } + self.viewport_origin_x;
+        let dy = if self.flip_y { -dy } else { dy } + self.viewport_origin_y;
+
+        (dx, dy)
+    }
+
+    /// Convert a point relative to the logical origin, i.e. before
+    /// subtracting the window origin, to device coordinates.
+    pub fn point_to_device(&self, x: f32, y: f32) -> (f32, f32) {
+        self.logical_to_device(
+            x - f32::from(self.origin_x),
+            y - f32::from(self.origin_y),
+        )
+    }
+
+    /// The mapping of [`Self::point_to_device`] as the scale and
+    /// translation of an SVG `matrix(sx 0 0 sy tx ty)`.
+    pub fn transform(&self) -> (f32, f32, f32, f32) {
+        let (x0, y0) = self.logical_to_device(0.0, 0.0);
+        let (x1, y1) = self.logical_to_device(1.0, 1.0);
+        let (tx, ty) = self.point_to_device(0.0, 0.0);
+
+        (x1 - x0, y1 - y0, tx, ty)
+    }
+
+    /// Whether arcs run clockwise in device space. WMF has no record to
+    /// change the arc direction, so arcs always run counter-clockwise in
+    /// logical space, and a mapping that mirrors one axis reverses them.
+    pub fn arcs_clockwise(&self) -> bool {
+        let (sx, sy, ..) = self.transform();
+
+        sx * sy < 0.0
+    }
+
+    /// Number of device units covered by one logical unit along the
+    /// x-axis, which GDI uses to transform geometric pen widths.
+    pub fn device_units_per_logical_x(&self) -> f32 {
+        let (x0, _) = self.logical_to_device(0.0, 0.0);
+        let (x1, _) = self.logical_to_device(1.0, 0.0);
+
+        (x1 - x0).abs()
+    }
+
+    /// Size of one device pixel in output units, assuming a 96 DPI device.
+    ///
+    /// Output units are device units once a viewport extent is set.
+    /// Otherwise the physical size of a logical unit comes from the fixed
+    /// mapping mode or the META_PLACEABLE resolution; without either, a
+    /// logical unit is taken as a pixel.
+    pub fn device_pixel_size(&self) -> f32 {
+        let logical_units_per_inch = match self.map_mode {
+            MapMode::MM_LOMETRIC => 254.0,
+            MapMode::MM_HIMETRIC => 2540.0,
+            MapMode::MM_LOENGLISH => 100.0,
+            MapMode::MM_HIENGLISH => 1000.0,
+            MapMode::MM_TWIPS => 1440.0,
+            _ if self.viewport_ext_x.is_some() => return 1.0,
+            _ => match self.units_per_inch {
+                Some(inch) if inch > 0 => f32::from(inch),
+                _ => return 1.0,
+            },
+        };
+
+        logical_units_per_inch / 96.0 * self.device_units_per_logical_x()
+    }
+
+    pub fn as_view_box(&self) -> (i32, i32, i32, i32) {
+        // Expand viewBox to include negative coordinates if any
+        let min_x = i32::from(self.min_x).min(0);
+        let min_y = i32::from(self.min_y).min(0);
+        let max_x = i32::from(self.x);
+        let max_y = i32::from(self.y);
+
+        (min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+}
@@ -16,6 +16,9 @@
 #[macro_use]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(feature = "tracing")]
 #[macro_use]
 extern crate tracing;
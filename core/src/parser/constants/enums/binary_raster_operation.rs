@@ -13,6 +13,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum BinaryRasterOperation {
     /// 0, Pixel is always 0.
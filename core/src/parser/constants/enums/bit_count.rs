@@ -11,6 +11,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum BitCount {
     /// The number of bits per pixel is undefined.
@@ -12,6 +12,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum BrushStyle {
     /// A brush that paints a single, constant color, either solid or dithered.
@@ -11,6 +11,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum CharacterSet {
     /// Specifies the English character set.
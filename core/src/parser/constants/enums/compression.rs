@@ -11,6 +11,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum Compression {
     /// The bitmap is in uncompressed red green blue (RGB) format that is not
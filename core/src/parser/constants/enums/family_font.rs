@@ -12,6 +12,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum FamilyFont {
     /// The default font is specified, which is implementation-dependent.
@@ -11,6 +11,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum FloodFill {
     /// The fill area is bounded by the color specified by the Color member.
@@ -12,6 +12,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum GamutMappingIntent {
     /// Specifies that saturation SHOULD be maintained. Typically used for
@@ -16,6 +16,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum LogicalColorSpace {
     /// Color values are calibrated red green blue (RGB) values.
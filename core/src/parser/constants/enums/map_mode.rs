@@ -23,6 +23,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum MapMode {
     /// Each logical unit is mapped to one device pixel. Positive x is to the
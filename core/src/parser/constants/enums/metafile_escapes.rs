@@ -14,6 +14,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum MetafileEscapes {
     /// Notifies the printer driver that the application has finished writing
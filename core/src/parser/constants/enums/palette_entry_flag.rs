@@ -10,6 +10,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum PaletteEntryFlag {
     /// Specifies that the logical palette entry be used for palette animation.
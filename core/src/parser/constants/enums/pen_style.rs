@@ -13,6 +13,7 @@ use crate::imports::*;
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum PenStyle {
     /// This value 0x0000 has multiple meanings:
@@ -15,6 +15,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum PitchFont {
     /// The default pitch, which is implementation-dependent.
@@ -13,6 +13,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum PostScriptFeatureSetting {
     /// Specifies the n-up printing (page layout) setting.
@@ -11,6 +11,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(i32)]
 pub enum PostScriptJoin {
     /// Specifies that the line-joining style has not been set and that a
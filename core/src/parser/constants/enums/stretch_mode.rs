@@ -12,6 +12,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum StretchMode {
     /// Performs a Boolean AND operation by using the color values for the
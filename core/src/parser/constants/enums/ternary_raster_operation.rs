@@ -14,6 +14,7 @@ use crate::imports::*;
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u32)]
 pub enum TernaryRasterOperation {
     // cSpell:disable
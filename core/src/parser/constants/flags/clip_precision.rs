@@ -12,6 +12,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u8)]
 pub enum ClipPrecision {
     /// Specifies that default clipping MUST be used.
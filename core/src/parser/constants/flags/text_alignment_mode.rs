@@ -16,6 +16,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum TextAlignmentMode {
     /// The drawing position in the playback device context MUST NOT be updated
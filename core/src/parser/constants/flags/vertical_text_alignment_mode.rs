@@ -17,6 +17,7 @@
     strum::FromRepr,
     strum::EnumIter,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(u16)]
 pub enum VerticalTextAlignmentMode {
     /// The reference point MUST be on the top edge of the bounding rectangle.
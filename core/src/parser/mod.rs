@@ -394,6 +394,31 @@ fn bytes_into_utf8(
     }
 }
 
+/// Serializes a buffer of opaque data, such as bitmap bits or an embedded
+/// file, as a whole rather than as a sequence of integers: a byte string,
+/// or `{"length": ..., "base64": "..."}` for human-readable formats.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_bytes<S: serde::Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use base64::Engine;
+    use serde::ser::SerializeStruct;
+
+    if !serializer.is_human_readable() {
+        return serializer.serialize_bytes(bytes);
+    }
+
+    let mut state = serializer.serialize_struct("Bytes", 2)?;
+
+    state.serialize_field("length", &bytes.len())?;
+    state.serialize_field(
+        "base64",
+        &base64::engine::general_purpose::STANDARD.encode(bytes),
+    )?;
+    state.end()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ParseError, ReadLeField};
@@ -2,6 +2,7 @@
 /// Objects are created by the META_CREATEBRUSHINDIRECT, META_CREATEPATTERNBRUSH
 /// and META_DIBCREATEPATTERNBRUSH records.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Brush {
     DIBPatternPT {
         color_usage: crate::parser::ColorUsage,
@@ -2,6 +2,7 @@ use crate::imports::*;
 
 /// The Font object specifies the attributes of a logical font.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Font {
     /// Height (2 bytes): A 16-bit signed integer that specifies the height, in
     /// logical units, of the font's character cell. The character height is
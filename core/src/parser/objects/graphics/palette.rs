@@ -2,6 +2,7 @@ use crate::imports::*;
 
 /// The Palette Object specifies the colors in a logical palette.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Palette {
     /// Start (2 bytes): A 16-bit unsigned integer that defines the offset into
     /// the Palette Object when used with the META_SETPALENTRIES and
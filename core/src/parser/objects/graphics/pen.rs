@@ -1,5 +1,6 @@
 /// The Pen Object specifies the style, width, and color of a pen.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Pen {
     /// PenStyle (2 bytes): A 16-bit unsigned integer that specifies the pen
     /// style. The value MUST be defined from the PenStyle Enumeration table.
@@ -40,6 +41,7 @@ impl Pen {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PenStyleSubsection {
     pub end_cap: crate::parser::PenStyle,
     pub line_join: crate::parser::PenStyle,
@@ -73,7 +75,7 @@ impl PenStyleSubsection {
         ))
     }
 
-    fn end_cap(v: u16) -> crate::parser::PenStyle {
+    pub fn end_cap(v: u16) -> crate::parser::PenStyle {
         const MASK: u16 = 0x0F00;
 
         for s in crate::parser::PenStyle::end_cap() {
@@ -3,6 +3,7 @@ use crate::imports::*;
 /// The Region Object defines a potentially non-rectilinear shape defined by an
 /// array of scanlines.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Region {
     /// nextInChain (2 bytes): A value that MUST be ignored. (Windows sets this
     /// field to `0x0000` .)
@@ -5,6 +5,7 @@ use crate::imports::*;
 ///
 /// Bitmap16 object seems to be Windows DDB.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Bitmap16 {
     /// Type (2 bytes): A 16-bit signed integer that defines the bitmap type.
     pub typ: i16,
@@ -30,6 +31,10 @@ pub struct Bitmap16 {
     /// ```text
     /// (((Width * BitsPixel + 15) >> 4) << 1) * Height
     /// ```
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::parser::serialize_bytes")
+    )]
     pub bits: Vec<u8>,
 }
 
@@ -5,6 +5,7 @@
 ///
 /// A DIB is specified by a DeviceIndependentBitmap Object.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BitmapInfoHeaderCore {
     /// HeaderSize (4 bytes): A 32-bit unsigned integer that defines the
     /// size of this object, in bytes.
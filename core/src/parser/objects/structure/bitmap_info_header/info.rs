@@ -1,6 +1,7 @@
 /// The BitmapInfoHeader Object contains information about the dimensions
 /// and color format of a device-independent bitmap (DIB).
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BitmapInfoHeaderInfo {
     /// HeaderSize (4 bytes): A 32-bit unsigned integer that defines the
     /// size of this object, in bytes.
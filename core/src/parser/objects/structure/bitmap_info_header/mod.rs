@@ -6,6 +6,7 @@ mod v5;
 pub use self::{core::*, info::*, v4::*, v5::*};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BitmapInfoHeader {
     Core(BitmapInfoHeaderCore),
     Info(BitmapInfoHeaderInfo),
@@ -77,6 +78,17 @@ impl BitmapInfoHeader {
         }
     }
 
+    /// Compression of the bitmap, which is BI_RGB for a
+    /// BitmapCoreHeader.
+    pub fn compression(&self) -> crate::parser::Compression {
+        match self {
+            Self::Core(_) => crate::parser::Compression::BI_RGB,
+            Self::Info(BitmapInfoHeaderInfo { compression, .. })
+            | Self::V4(BitmapInfoHeaderV4 { compression, .. })
+            | Self::V5(BitmapInfoHeaderV5 { compression, .. }) => *compression,
+        }
+    }
+
     pub fn size(&self) -> usize {
         let size = match self {
             Self::Core(BitmapInfoHeaderCore {
@@ -178,6 +190,17 @@ impl BitmapInfoHeader {
         }
     }
 
+    /// Whether the rows run from the bottom up, with the origin at the
+    /// lower-left corner.
+    pub fn is_bottom_up(&self) -> bool {
+        match self {
+            Self::Core(_) => true,
+            Self::Info(BitmapInfoHeaderInfo { height, .. })
+            | Self::V4(BitmapInfoHeaderV4 { height, .. })
+            | Self::V5(BitmapInfoHeaderV5 { height, .. }) => *height > 0,
+        }
+    }
+
     pub fn width(&self) -> usize {
         match self {
             Self::Core(BitmapInfoHeaderCore { width, .. }) => {
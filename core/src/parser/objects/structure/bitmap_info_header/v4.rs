@@ -3,6 +3,7 @@
 /// of the BitmapInfoHeader Object. (Windows NT 3.1, Windows NT 3.5, and
 /// Windows NT 3.51: This structure is not supported.)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BitmapInfoHeaderV4 {
     /// HeaderSize (4 bytes): A 32-bit unsigned integer that defines the
     /// size of this object, in bytes.
@@ -4,6 +4,7 @@
 /// NT 3.51, Windows 95, and Windows NT 4.0: This structure is not
 /// supported.)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BitmapInfoHeaderV5 {
     /// HeaderSize (4 bytes): A 32-bit unsigned integer that defines the
     /// size of this object, in bytes.
@@ -1,5 +1,6 @@
 /// The CIEXYZ Object defines information about the CIEXYZ chromaticity object.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CIEXYZ {
     /// ciexyzX (4 bytes): A 32-bit 2.30 fixed point type that defines the x
     /// chromaticity value.
@@ -1,6 +1,7 @@
 /// The CIEXYZTriple Object defines information about the CIEXYZTriple color
 /// object.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CIEXYZTriple {
     /// ciexyzRed (12 bytes): A 96-bit CIEXYZ Object (section 2.2.2.6) that
     /// defines the red chromaticity values.
@@ -1,5 +1,6 @@
 /// The ColorRef Object defines the RGB color.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ColorRef {
     /// Red (1 byte): An 8-bit unsigned integer that defines the relative
     /// intensity of red.
@@ -3,6 +3,7 @@ use crate::imports::*;
 /// The DeviceIndependentBitmap (DIB) Object defines an image in
 /// device-independent bitmap (DIB) format.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DeviceIndependentBitmap {
     /// DIBHeaderInfo (variable): Either a BitmapCoreHeader Object or a
     /// BitmapInfoHeader Object that specifies information about the image.
@@ -65,6 +66,7 @@ impl DeviceIndependentBitmap {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Colors {
     PaletteIndices(Vec<u16>),
     RGBQuad(Vec<crate::parser::RGBQuad>),
@@ -307,6 +309,7 @@ impl Colors {
 /// reintroduce them as `Option<Vec<u8>>` to make the "not parsed" state
 /// explicit instead of conflating it with an empty buffer.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BitmapBuffer {
     // /// UndefinedSpace (variable): An optional field that MUST be ignored.
     // /// If this DIB is a packed bitmap, this field MUST NOT be present.
@@ -328,6 +331,10 @@ pub struct BitmapBuffer {
     ///
     /// Otherwise, the size of aData MUST be the BitmapInfoHeader Object value
     /// ImageSize.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::parser::serialize_bytes")
+    )]
     pub a_data: Vec<u8>,
 }
 
@@ -2,6 +2,7 @@
 /// object is used only in the META_CREATEBRUSHINDIRECT Record to create a Brush
 /// Object.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LogBrush {
     DIBPattern,
     DIBPatternPT,
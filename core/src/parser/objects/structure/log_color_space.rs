@@ -4,6 +4,7 @@ use crate::imports::*;
 /// device context, which can be the name of a color profile in ASCII
 /// characters.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LogColorSpace {
     /// Signature (4 bytes): A 32-bit unsigned integer that specifies the
     /// signature of color space objects; it MUST be set to the value
@@ -4,6 +4,7 @@ use crate::imports::*;
 /// defined by a color profile file with a name consisting of Unicode 16-bit
 /// characters.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LogColorSpaceW {
     /// A 32-bit unsigned integer that specifies the signature of color space
     /// objects. This MUST be set to the value 0x50534F43, which is the ASCII
@@ -1,6 +1,7 @@
 /// The PaletteEntry Object defines the color and usage of an entry in a
 /// palette.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PaletteEntry {
     /// Red (1 byte): An 8-bit unsigned integer that defines the red intensity
     /// value for the palette entry.
@@ -2,6 +2,7 @@
 /// Font Object. Pitch refers to the width of the characters, and family refers
 /// to the general appearance of a font.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PitchAndFamily {
     /// Family (4 bits): A property of a font that describes its general
     /// appearance. This MUST be a value in the FamilyFont Enumeration.
@@ -1,5 +1,6 @@
 /// The PointL Object defines the coordinates of a point.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PointL {
     /// x (4 bytes): A 32-bit signed integer that defines the horizontal (x)
     /// coordinate of the point.
@@ -1,5 +1,6 @@
 /// The PointS Object defines the x- and y-coordinates of a point.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PointS {
     /// x (2 bytes): A 16-bit signed integer that defines the horizontal (x)
     /// coordinate of the point.
@@ -2,6 +2,7 @@ use crate::imports::*;
 
 /// The PolyPolygon Object defines a series of closed polygons.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PolyPolygon {
     /// NumberOfPolygons (2 bytes): A 16-bit unsigned integer that defines the
     /// number of polygons in the object.
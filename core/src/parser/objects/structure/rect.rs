@@ -1,5 +1,6 @@
 /// The Rect Object defines a rectangle.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Rect {
     /// Left (2 bytes): A 16-bit signed integer that defines the x-coordinate,
     /// in logical coordinates, of the upper-left corner of the rectangle
@@ -3,6 +3,7 @@
 /// A rectangle defined with a RectL Object is filled up to— but not
 /// including—the right column and bottom row of pixels.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RectL {
     /// Left (4 bytes): A 32-bit signed integer that defines the x coordinate,
     /// in logical coordinates, of the upper-left corner of the rectangle.
@@ -1,6 +1,7 @@
 /// The RGBTriple Object defines the pixel color values in an uncompressed DIB
 /// Object.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RGBTriple {
     /// Red (1 byte): An 8-bit unsigned integer that defines the relative
     /// intensity of red.
@@ -2,6 +2,7 @@ use crate::imports::*;
 
 /// The Scan Object specifies a collection of scanlines.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(clippy::struct_field_names)]
 pub struct Scan {
     /// Count (2 bytes): A 16-bit unsigned integer that specifies the number of
@@ -59,6 +60,7 @@ impl Scan {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ScanLine {
     /// Left (2 bytes): A 16-bit unsigned integer that defines the horizontal
     /// (x-axis) coordinate, in logical units, of the left endpoint of the
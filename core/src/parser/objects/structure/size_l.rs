@@ -1,5 +1,6 @@
 /// The SizeL Object defines the x- and y-extents of a rectangle.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SizeL {
     /// cx (4 bytes): A 32-bit unsigned integer that defines the x-coordinate
     /// of the point.
@@ -16,6 +16,7 @@
 /// RecordSize == ((RecordFunction >> 8) + 3)
 /// ```
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum META_BITBLT {
     WithBitmap {
         /// RecordSize: A 32-bit unsigned integer that defines the number of
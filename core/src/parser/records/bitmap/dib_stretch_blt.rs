@@ -26,6 +26,7 @@ use crate::imports::*;
 /// RecordSize == ((RecordFunction >> 8) + 3)
 /// ```
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum META_DIBSTRETCHBLT {
     WithBitmap {
         /// RecordSize: A 32-bit unsigned integer that defines the number of
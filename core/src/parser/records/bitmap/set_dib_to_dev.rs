@@ -3,6 +3,7 @@
 ///
 /// The source of the color data is a DIB.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SETDIBTODEV {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the record.
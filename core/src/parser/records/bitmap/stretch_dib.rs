@@ -5,6 +5,7 @@
 /// The source of the color data is a DIB, and the destination of the transfer
 /// is the current output region in the playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_STRETCHDIB {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the record.
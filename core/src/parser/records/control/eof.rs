@@ -1,5 +1,6 @@
 /// The META_EOF Record indicates the end of the WMF metafile.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_EOF {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of 16-bit WORD structures, defined in [MS-DTYP] section 2.2.61, in the
@@ -1,6 +1,7 @@
 /// The META_HEADER Record is the first record in a standard (nonplaceable) WMF
 /// metafile.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_HEADER {
     /// Type (2 bytes): A 16-bit unsigned integer that defines the type of
     /// metafile. It MUST be a value in the MetafileType Enumeration.
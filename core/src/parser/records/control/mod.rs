@@ -8,6 +8,7 @@ mod placeable;
 pub use self::{eof::*, header::*, placeable::*};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MetafileHeader {
     StartsWithPlaceable(
         crate::parser::META_PLACEABLE,
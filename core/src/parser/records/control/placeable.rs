@@ -8,6 +8,7 @@
 /// The META_PLACEABLE MUST be the first record of the metafile, located
 /// immediately before the META_HEADER Record
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_PLACEABLE {
     /// Key (4 bytes): Identification value that indicates the presence of a
     /// placeable metafile header. This value MUST be 0x9AC6CDD7.
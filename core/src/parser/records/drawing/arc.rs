@@ -1,5 +1,6 @@
 /// The META_ARC Record draws an elliptical arc.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_ARC {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
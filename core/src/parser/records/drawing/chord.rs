@@ -3,6 +3,7 @@
 /// using the pen and filled using the brush that are defined in the playback
 /// device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_CHORD {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -1,6 +1,7 @@
 /// The META_EXTFLOODFILL Record fills an area with the brush that is defined in
 /// the playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_EXTFLOODFILL {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -16,7 +17,7 @@ pub struct META_EXTFLOODFILL {
     pub mode: crate::parser::FloodFill,
     /// ColorRef (4 bytes): A 32-bit ColorRef Object that defines the color
     /// value.
-    pub color_ref: i16,
+    pub color_ref: crate::parser::ColorRef,
     /// Y (2 bytes): A 16-bit signed integer that defines the y-coordinate, in
     /// logical units, of the point to be set.
     pub y: i16,
@@ -49,7 +50,8 @@ impl META_EXTFLOODFILL {
 
         let mode =
             read_with(buf, &mut record_size, crate::parser::FloodFill::parse)?;
-        let color_ref = read_field(buf, &mut record_size)?;
+        let color_ref =
+            read_with(buf, &mut record_size, crate::parser::ColorRef::parse)?;
         let y = read_field(buf, &mut record_size)?;
         let x = read_field(buf, &mut record_size)?;
 
@@ -58,3 +60,32 @@ impl META_EXTFLOODFILL {
         Ok(Self { record_size, record_function, mode, color_ref, y, x })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{imports::*, parser::records::test_helpers::*};
+
+    #[test]
+    fn parse_ok() {
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&1_u16.to_le_bytes());
+        payload.extend_from_slice(&[0x12, 0x34, 0x56, 0x00]);
+        payload.extend_from_slice(&20_i16.to_le_bytes());
+        payload.extend_from_slice(&10_i16.to_le_bytes());
+        let data = build_record(
+            8,
+            crate::parser::RecordType::META_EXTFLOODFILL as u16,
+            &payload,
+        );
+        let (rs, rf, mut reader) = parse_record_header(&data);
+        let record = META_EXTFLOODFILL::parse(&mut reader, rs, rf).unwrap();
+        assert_eq!(record.mode, crate::parser::FloodFill::FLOODFILLSURFACE);
+        assert_eq!(record.color_ref.red, 0x12);
+        assert_eq!(record.color_ref.green, 0x34);
+        assert_eq!(record.color_ref.blue, 0x56);
+        assert_eq!(record.y, 20);
+        assert_eq!(record.x, 10);
+        assert!(reader.is_empty());
+    }
+}
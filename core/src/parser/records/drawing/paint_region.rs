@@ -1,6 +1,7 @@
 /// The META_PAINTREGION Record paints the specified region by using the brush
 /// that is defined in the playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_PAINTREGION {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
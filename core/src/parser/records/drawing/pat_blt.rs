@@ -2,6 +2,7 @@
 /// defined in the playback device context. The brush color and the surface
 /// color or colors are combined using the specified raster operation.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_PATBLT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -2,6 +2,7 @@
 /// an ellipse and two radials. The pie is outlined by using the pen and filled
 /// by using the brush that are defined in the playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_PIE {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -3,6 +3,7 @@ use crate::imports::*;
 /// The META_POLYLINE Record draws a series of line segments by connecting the
 /// points in the specified array.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_POLYLINE {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -3,6 +3,7 @@
 /// mode; these are defined in the playback device context. The polygons drawn
 /// by this function can overlap.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_POLYPOLYGON {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -5,6 +5,7 @@ use crate::imports::*;
 /// filled by using the brush and polygon fill mode that are defined in the
 /// playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_POLYGON {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -2,6 +2,7 @@
 /// rectangle is outlined using the pen and filled using the brush, as defined
 /// in the playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_ROUNDRECT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -4,6 +4,7 @@ use crate::imports::*;
 /// by using the font, background color, and text color that are defined in the
 /// playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_TEXTOUT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -53,6 +53,7 @@ use crate::imports::*;
 /// not directly available through other records defined in the RecordType
 /// Enumeration. The MetafileEscapes Enumeration lists these extensions.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum META_ESCAPE {
     /// The ABORTDOC Record stops processing the current document and erases
     /// everything drawn since the last STARTDOC Record was processed.
@@ -96,6 +97,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the JPEGBuffer field.
         byte_count: u16,
         /// JPEGBuffer (variable): A buffer of JPEG image data.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         jpeg_buffer: Vec<u8>,
     },
     /// The CHECKPNGFORMAT Record queries the driver to see if it can handle
@@ -114,6 +119,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the PNGBuffer field.
         byte_count: u16,
         /// PNGBuffer (variable): A buffer of PNG image data.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         png_buffer: Vec<u8>,
     },
     /// The CLIP_TO_PATH Record applies a function to the current PostScript
@@ -235,6 +244,10 @@ pub enum META_ESCAPE {
         /// output parallelogram in 28.4 FIX device coordinates.
         points: crate::parser::PointL,
         /// Data (variable): The PostScript data.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         data: Vec<u8>,
     },
     /// The END_PATH Record specifies the end of a path.
@@ -319,6 +332,10 @@ pub enum META_ESCAPE {
         /// ColorTableBuffer (variable): A buffer containing the color table
         /// that is obtained from the printer driver, which is not required to
         /// be contiguous with the static part of the record.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         color_table_buffer: Vec<u8>,
     },
     /// The GETDEVICEUNITS Record gets the current device units.
@@ -494,6 +511,10 @@ pub enum META_ESCAPE {
         /// EnhancedMetafileData (variable): A segment of an EMF file. The
         /// bytes in consecutive META_ESCAPE_ENHANCED_METAFILE records MUST be
         /// concatenated to represent the entire embedded EMF file.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         enhanced_metafile_data: Vec<u8>,
     },
     /// The METAFILE_DRIVER Record queries the printer driver about its support
@@ -553,6 +574,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the Data field.
         byte_count: u16,
         /// Data (variable): An array of bytes of size ByteCount.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         data: Vec<u8>,
     },
     /// The POSTSCRIPT_DATA Record sends arbitrary PostScript data to the
@@ -570,6 +595,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the Data field.
         byte_count: u16,
         /// Data (variable): An array of bytes of size ByteCount.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         data: Vec<u8>,
     },
     /// The POSTSCRIPT_IDENTIFY Record sets the printer driver to either
@@ -589,6 +618,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the Data field.
         byte_count: u16,
         /// Data (variable): An array of bytes of size ByteCount.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         data: Vec<u8>,
     },
     /// The POSTSCRIPT_IGNORE Record informs the device to ignore the
@@ -625,6 +658,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the Data field.
         byte_count: u16,
         /// Data (variable): An array of bytes of size ByteCount.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         data: Vec<u8>,
     },
     /// The POSTSCRIPT_PASSTHROUGH Record sends arbitrary data directly to the
@@ -644,6 +681,10 @@ pub enum META_ESCAPE {
         /// size, in bytes, of the Data field.
         byte_count: u16,
         /// Data (variable): An array of bytes of size ByteCount.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         data: Vec<u8>,
     },
     /// The OPENCHANNEL RECORD notifies the printer driver that a new print job
@@ -711,6 +752,10 @@ pub enum META_ESCAPE {
         byte_count: u16,
         /// ColorTable (variable): A ByteCount length byte array containing the
         /// color table.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         color_table: Vec<u8>,
     },
     /// The SETCOPYCOUNT Record sets the number of copies.
@@ -808,6 +853,10 @@ pub enum META_ESCAPE {
         size: u16,
         /// RawData (variable): The Size-length byte array of unprocessed
         /// private data to send to the printer driver.
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::parser::serialize_bytes")
+        )]
         raw_data: Vec<u8>,
     },
     /// The STARTDOC Record informs the printer driver that a new print job is
@@ -1088,4 +1137,127 @@ impl META_ESCAPE {
 
         Ok(record)
     }
+
+    /// Escape function of the record.
+    pub fn escape_function(&self) -> crate::parser::MetafileEscapes {
+        match self {
+            Self::ABORTDOC { .. } => crate::parser::MetafileEscapes::ABORTDOC,
+            Self::BEGIN_PATH { .. } => {
+                crate::parser::MetafileEscapes::BEGIN_PATH
+            }
+            Self::CHECKJPEGFORMAT { .. } => {
+                crate::parser::MetafileEscapes::CHECKJPEGFORMAT
+            }
+            Self::CHECKPNGFORMAT { .. } => {
+                crate::parser::MetafileEscapes::CHECKPNGFORMAT
+            }
+            Self::CLIP_TO_PATH { .. } => {
+                crate::parser::MetafileEscapes::CLIP_TO_PATH
+            }
+            Self::CLOSECHANNEL { .. } => {
+                crate::parser::MetafileEscapes::CLOSECHANNEL
+            }
+            Self::DOWNLOADFACE { .. } => {
+                crate::parser::MetafileEscapes::DOWNLOADFACE
+            }
+            Self::DOWNLOADHEADER { .. } => {
+                crate::parser::MetafileEscapes::DOWNLOADHEADER
+            }
+            Self::DRAWPATTERNRECT { .. } => {
+                crate::parser::MetafileEscapes::DRAWPATTERNRECT
+            }
+            Self::ENCAPSULATED_POSTSCRIPT { .. } => {
+                crate::parser::MetafileEscapes::ENCAPSULATED_POSTSCRIPT
+            }
+            Self::ENDDOC { .. } => crate::parser::MetafileEscapes::ENDDOC,
+            Self::END_PATH { .. } => crate::parser::MetafileEscapes::END_PATH,
+            Self::EPSPRINTING { .. } => {
+                crate::parser::MetafileEscapes::EPSPRINTING
+            }
+            Self::EXTTEXTOUT { .. } => {
+                crate::parser::MetafileEscapes::EXTTEXTOUT
+            }
+            Self::GETCOLORTABLE { .. } => {
+                crate::parser::MetafileEscapes::GETCOLORTABLE
+            }
+            Self::GETDEVICEUNITS { .. } => {
+                crate::parser::MetafileEscapes::GETDEVICEUNITS
+            }
+            Self::GETEXTENDEDTEXTMETRICS { .. } => {
+                crate::parser::MetafileEscapes::GETEXTENDEDTEXTMETRICS
+            }
+            Self::GETFACENAME { .. } => {
+                crate::parser::MetafileEscapes::GETFACENAME
+            }
+            Self::GETPAIRKERNTABLE { .. } => {
+                crate::parser::MetafileEscapes::GETPAIRKERNTABLE
+            }
+            Self::GETPHYSPAGESIZE { .. } => {
+                crate::parser::MetafileEscapes::GETPHYSPAGESIZE
+            }
+            Self::GETPRINTINGOFFSET { .. } => {
+                crate::parser::MetafileEscapes::GETPRINTINGOFFSET
+            }
+            Self::GETSCALINGFACTOR { .. } => {
+                crate::parser::MetafileEscapes::GETSCALINGFACTOR
+            }
+            Self::GET_PS_FEATURESETTING { .. } => {
+                crate::parser::MetafileEscapes::GET_PS_FEATURESETTING
+            }
+            Self::METAFILE_DRIVER { .. } => {
+                crate::parser::MetafileEscapes::METAFILE_DRIVER
+            }
+            Self::META_ESCAPE_ENHANCED_METAFILE { .. } => {
+                crate::parser::MetafileEscapes::META_ESCAPE_ENHANCED_METAFILE
+            }
+            Self::NEWFRAME { .. } => crate::parser::MetafileEscapes::NEWFRAME,
+            Self::NEXTBAND { .. } => crate::parser::MetafileEscapes::NEXTBAND,
+            Self::OPENCHANNEL { .. } => {
+                crate::parser::MetafileEscapes::OPENCHANNEL
+            }
+            Self::PASSTHROUGH { .. } => {
+                crate::parser::MetafileEscapes::PASSTHROUGH
+            }
+            Self::POSTSCRIPT_DATA { .. } => {
+                crate::parser::MetafileEscapes::POSTSCRIPT_DATA
+            }
+            Self::POSTSCRIPT_IDENTIFY { .. } => {
+                crate::parser::MetafileEscapes::POSTSCRIPT_IDENTIFY
+            }
+            Self::POSTSCRIPT_IGNORE { .. } => {
+                crate::parser::MetafileEscapes::POSTSCRIPT_IGNORE
+            }
+            Self::POSTSCRIPT_INJECTION { .. } => {
+                crate::parser::MetafileEscapes::POSTSCRIPT_INJECTION
+            }
+            Self::POSTSCRIPT_PASSTHROUGH { .. } => {
+                crate::parser::MetafileEscapes::POSTSCRIPT_PASSTHROUGH
+            }
+            Self::QUERYDIBSUPPORT { .. } => {
+                crate::parser::MetafileEscapes::QUERYDIBSUPPORT
+            }
+            Self::QUERYESCSUPPORT { .. } => {
+                crate::parser::MetafileEscapes::QUERYESCSUPPORT
+            }
+            Self::SETCOLORTABLE { .. } => {
+                crate::parser::MetafileEscapes::SETCOLORTABLE
+            }
+            Self::SETCOPYCOUNT { .. } => {
+                crate::parser::MetafileEscapes::SETCOPYCOUNT
+            }
+            Self::SETLINECAP { .. } => {
+                crate::parser::MetafileEscapes::SETLINECAP
+            }
+            Self::SETLINEJOIN { .. } => {
+                crate::parser::MetafileEscapes::SETLINEJOIN
+            }
+            Self::SETMITERLIMIT { .. } => {
+                crate::parser::MetafileEscapes::SETMITERLIMIT
+            }
+            Self::SPCLPASSTHROUGH2 { .. } => {
+                crate::parser::MetafileEscapes::SPCLPASSTHROUGH2
+            }
+            Self::STARTDOC { .. } => crate::parser::MetafileEscapes::STARTDOC,
+        }
+    }
 }
@@ -137,6 +137,18 @@ impl core::fmt::Debug for RecordSize {
     }
 }
 
+/// Serialized as the word count of the record only, as the consumed bytes
+/// depend on how far the record has been parsed.
+#[cfg(feature = "serde")]
+impl serde::Serialize for RecordSize {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.size_words)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Display-only helpers used by the per-record `tracing::instrument`
 // ---------------------------------------------------------------------------
@@ -6,6 +6,7 @@ use crate::imports::*;
 /// of this record in Windows is intended solely for compatibility purposes so
 /// that Windows metafiles containing this record can be rendered.)
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_CREATEPATTERNBRUSH {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -27,6 +28,10 @@ pub struct META_CREATEPATTERNBRUSH {
     /// ```text
     /// (((Width * BitsPixel + 15) >> 4) << 1) * Height
     /// ```
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::parser::serialize_bytes")
+    )]
     pub pattern: Vec<u8>,
 }
 
@@ -4,6 +4,7 @@
 /// index in the WMF Object Table is no longer valid but is available to be
 /// reused.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_DELETEOBJECT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -1,6 +1,7 @@
 /// The META_DIBCREATEPATTERNBRUSH Record creates a Brush Object with a pattern
 /// specified by a DeviceIndependentBitmap (DIB) Object.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_DIBCREATEPATTERNBRUSH {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
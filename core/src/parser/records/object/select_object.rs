@@ -7,6 +7,7 @@
 /// META_SELECTOBJECT Record, because the META_SELECTOBJECT Record does not
 /// support replacing the Palette Object type.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SELECTOBJECT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
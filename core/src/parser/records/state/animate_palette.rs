@@ -10,6 +10,7 @@
 /// palette have the PC_RESERVED flag set, then this record SHOULD have no
 /// effect.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_ANIMATEPALETTE {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -2,6 +2,7 @@
 /// device context to the intersection of the existing clipping region and the
 /// specified rectangle.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_INTERSECTCLIPRECT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
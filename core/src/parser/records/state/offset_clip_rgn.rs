@@ -1,6 +1,7 @@
 /// The META_OFFSETCLIPRGN Record moves the clipping region in the playback
 /// device context by the specified offsets.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_OFFSETCLIPRGN {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
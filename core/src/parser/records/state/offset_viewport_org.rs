@@ -1,6 +1,7 @@
 /// The META_OFFSETVIEWPORTORG Record moves the viewport origin in the playback
 /// device context by specified horizontal and vertical offsets.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_OFFSETVIEWPORTORG {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -1,6 +1,7 @@
 /// The META_OFFSETWINDOWORG Record moves the output window origin in the
 /// playback device context by specified horizontal and vertical offsets.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_OFFSETWINDOWORG {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -1,6 +1,7 @@
 /// The META_REALIZEPALETTE Record maps entries from the logical palette that is
 /// defined in the playback device context to the system palette.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_REALIZEPALETTE {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
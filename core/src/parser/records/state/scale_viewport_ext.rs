@@ -2,6 +2,7 @@
 /// of the viewport that is defined in the playback device context by using the
 /// ratios formed by the specified multiplicands and divisors.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SCALEVIEWPORTEXT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -2,6 +2,7 @@
 /// the output window that is defined in the playback device context by using
 /// the ratios formed by specified multiplicands and divisors.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SCALEWINDOWEXT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -2,6 +2,7 @@
 /// context to a specified color, or to the nearest physical color if the device
 /// cannot represent the specified color.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SETBKCOLOR {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
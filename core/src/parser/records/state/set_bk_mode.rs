@@ -3,6 +3,7 @@
 /// combining pens, text, hatched brushes, and interiors of filled objects with
 /// background colors on the output surface.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SETBKMODE {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -3,6 +3,7 @@
 /// Windows NT 4.0 implementations do not support this record type.) The layout
 /// orientation determines the direction in which text and graphics are drawn.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SETLAYOUT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -1,6 +1,7 @@
 /// The META_SETMAPPERFLAGS Record defines the algorithm that the font mapper
 /// uses when it maps logical fonts to physical fonts.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SETMAPPERFLAGS {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -1,6 +1,7 @@
 /// The META_SETVIEWPORTEXT Record sets the horizontal and vertical extents of
 /// the viewport in the playback device context.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct META_SETVIEWPORTEXT {
     /// RecordSize (4 bytes): A 32-bit unsigned integer that defines the number
     /// of WORD structures, defined in [MS-DTYP] section 2.2.61, in the WMF
@@ -0,0 +1,2 @@
+mod stretch_blt;
+mod stretch_dib;
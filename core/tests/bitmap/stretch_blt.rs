@@ -0,0 +1,166 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        BitCount, Bitmap16, META_SETSTRETCHBLTMODE, META_STRETCHBLT,
+        RecordSize, StretchMode, TernaryRasterOperation,
+    },
+};
+
+/// An all-black 8x8 monochrome bitmap.
+fn bitmap() -> Bitmap16 {
+    Bitmap16 {
+        typ: 0,
+        width: 8,
+        height: 8,
+        width_bytes: 2,
+        planes: 1,
+        bits_pixel: BitCount::BI_BITCOUNT_1,
+        bits: vec![0; 16],
+    }
+}
+
+fn stretch_blt(
+    record_number: usize,
+    source: (i16, i16, i16, i16),
+    destination: (i16, i16),
+) -> META_STRETCHBLT {
+    let (x_src, y_src, src_width, src_height) = source;
+    let (dest_width, dest_height) = destination;
+
+    META_STRETCHBLT::WithBitmap {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        raster_operation: TernaryRasterOperation::SRCCOPY,
+        src_height,
+        src_width,
+        y_src,
+        x_src,
+        dest_height,
+        dest_width,
+        y_dest: 10,
+        x_dest: i16::try_from(record_number).unwrap() * 10,
+        target: bitmap(),
+    }
+}
+
+/// Split the SVG body into the `<defs>` content and the elements.
+fn split_defs(svg: &str) -> (&str, &str) {
+    let defs_start = svg.find("<defs>").expect("defs is missing") + 6;
+    let defs_end = svg.find("</defs>").expect("defs is not closed");
+
+    (&svg[defs_start..defs_end], &svg[defs_end + 7..svg.len() - 6])
+}
+
+#[test]
+fn meta_stretchblt_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        stretch_mode: StretchMode,
+        source: (i16, i16, i16, i16),
+        destination: (i16, i16),
+        expected: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Whole bitmap at its own size is drawn as is",
+            stretch_mode: StretchMode::HALFTONE,
+            source: (0, 0, 8, 8),
+            destination: (8, 8),
+            expected: r##"<svg height="8" id="elem1" preserveAspectRatio="none" viewBox="0 0 8 8" width="8" x="10" y="10"><use href="#defs0"></use></svg>"##,
+        },
+        TestCase {
+            desc: "Scaling with COLORONCOLOR does not smooth pixels",
+            stretch_mode: StretchMode::COLORONCOLOR,
+            source: (0, 0, 8, 8),
+            destination: (32, 32),
+            expected: r##"<svg height="32" id="elem1" image-rendering="pixelated" preserveAspectRatio="none" viewBox="0 0 8 8" width="32" x="10" y="10"><use href="#defs0"></use></svg>"##,
+        },
+        TestCase {
+            desc: "Scaling with HALFTONE smooths pixels",
+            stretch_mode: StretchMode::HALFTONE,
+            source: (0, 0, 8, 8),
+            destination: (32, 32),
+            expected: r##"<svg height="32" id="elem1" image-rendering="smooth" preserveAspectRatio="none" viewBox="0 0 8 8" width="32" x="10" y="10"><use href="#defs0"></use></svg>"##,
+        },
+        TestCase {
+            desc: "Source rectangle is cropped through the viewBox",
+            stretch_mode: StretchMode::HALFTONE,
+            source: (2, 4, 4, 2),
+            destination: (40, 20),
+            expected: r##"<svg height="20" id="elem1" image-rendering="smooth" preserveAspectRatio="none" viewBox="2 4 4 2" width="40" x="10" y="10"><use href="#defs0"></use></svg>"##,
+        },
+        TestCase {
+            desc: "Negative destination width mirrors the image",
+            stretch_mode: StretchMode::HALFTONE,
+            source: (0, 0, 8, 8),
+            destination: (-8, 8),
+            expected: r##"<g id="elem1" transform="matrix(-1 0 0 1 12 0)"><svg height="8" preserveAspectRatio="none" viewBox="0 0 8 8" width="8" x="2" y="10"><use href="#defs0"></use></svg></g>"##,
+        },
+        TestCase {
+            desc: "Negative source width mirrors the image",
+            stretch_mode: StretchMode::HALFTONE,
+            source: (8, 0, -8, 8),
+            destination: (8, 8),
+            expected: r##"<g id="elem1" transform="matrix(-1 0 0 1 28 0)"><svg height="8" preserveAspectRatio="none" viewBox="0 0 8 8" width="8" x="10" y="10"><use href="#defs0"></use></svg></g>"##,
+        },
+        TestCase {
+            desc: "Negative source and destination heights cancel out",
+            stretch_mode: StretchMode::HALFTONE,
+            source: (0, 8, 8, -8),
+            destination: (8, -8),
+            expected: r##"<svg height="8" id="elem1" preserveAspectRatio="none" viewBox="0 0 8 8" width="8" x="10" y="2"><use href="#defs0"></use></svg>"##,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let result = SVGPlayer::new()
+            .set_stretch_blt_mode(0, META_SETSTRETCHBLTMODE {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                stretch_mode: case.stretch_mode,
+                reserved: None,
+            })
+            .and_then(|player| {
+                player.stretch_blt(
+                    1,
+                    stretch_blt(1, case.source, case.destination),
+                )
+            });
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg = result.unwrap().generate().expect("SVG generation failed");
+        let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+        let (defs, elements) = split_defs(&svg_str);
+
+        assert!(
+            defs.starts_with(
+                r#"<image height="8" href="data:image/bmp;base64,"#
+            ),
+            "case {i}: {}: image definition does not match expected: {defs}",
+            case.desc,
+        );
+        assert_eq!(
+            elements, case.expected,
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_stretchblt_shares_image_data() {
+    let svg = SVGPlayer::new()
+        .stretch_blt(1, stretch_blt(1, (0, 0, 8, 8), (8, 8)))
+        .and_then(|player| {
+            player.stretch_blt(2, stretch_blt(2, (0, 0, 4, 4), (16, 16)))
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+    let (defs, elements) = split_defs(&svg_str);
+
+    assert_eq!(defs.matches("<image ").count(), 1);
+    assert_eq!(elements.matches(r##"<use href="#defs0">"##).count(), 2);
+}
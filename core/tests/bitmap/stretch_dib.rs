@@ -0,0 +1,142 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        BitCount, BitmapBuffer, BitmapInfoHeader, BitmapInfoHeaderInfo,
+        ColorUsage, Colors, Compression, DeviceIndependentBitmap,
+        META_SETMAPMODE, META_SETVIEWPORTEXT, META_SETWINDOWEXT,
+        META_SETWINDOWORG, META_STRETCHDIB, MapMode, RecordSize,
+        TernaryRasterOperation,
+    },
+};
+
+/// A black 2x4 24-bit DIB, stored bottom-up for a positive `height` and
+/// top-down for a negative one.
+fn dib(height: i32) -> DeviceIndependentBitmap {
+    DeviceIndependentBitmap {
+        dib_header_info: BitmapInfoHeader::Info(BitmapInfoHeaderInfo {
+            header_size: 40,
+            width: 2,
+            height,
+            planes: 1,
+            bit_count: BitCount::BI_BITCOUNT_5,
+            compression: Compression::BI_RGB,
+            image_size: 0,
+            x_pels_per_meter: 0,
+            y_pels_per_meter: 0,
+            color_used: 0,
+            color_important: 0,
+        }),
+        colors: Colors::Null,
+        bitmap_buffer: BitmapBuffer { a_data: vec![0; 32] },
+    }
+}
+
+#[test]
+fn meta_stretchdib_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        height: i32,
+        expected_view_box: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Source y of a bottom-up DIB counts from the bottom row",
+            height: 4,
+            expected_view_box: r#"viewBox="0 2 2 1""#,
+        },
+        TestCase {
+            desc: "Source y of a top-down DIB counts from the top row",
+            height: -4,
+            expected_view_box: r#"viewBox="0 1 2 1""#,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let svg = SVGPlayer::new()
+            .stretch_device_independent_bitmap(1, META_STRETCHDIB {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                raster_operation: TernaryRasterOperation::SRCCOPY,
+                color_usage: ColorUsage::DIB_RGB_COLORS,
+                src_height: 1,
+                src_width: 2,
+                y_src: 1,
+                x_src: 0,
+                dest_height: 1,
+                dest_width: 2,
+                y_dst: 10,
+                x_dst: 10,
+                dib: dib(case.height),
+            })
+            .and_then(Player::generate)
+            .expect("rendering failed");
+        let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+        assert!(
+            svg.contains(case.expected_view_box),
+            "case {i}: {}: {svg}",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_stretchdib_svg_maps_the_destination() {
+    let svg = SVGPlayer::new()
+        .set_map_mode(1, META_SETMAPMODE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            map_mode: MapMode::MM_ANISOTROPIC,
+        })
+        .and_then(|player| {
+            player.set_window_origin(2, META_SETWINDOWORG {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 5,
+                x: 5,
+            })
+        })
+        .and_then(|player| {
+            player.set_window_ext(3, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+        })
+        .and_then(|player| {
+            player.set_viewport_ext(4, META_SETVIEWPORTEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 200,
+                x: 200,
+            })
+        })
+        .and_then(|player| {
+            player.stretch_device_independent_bitmap(5, META_STRETCHDIB {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                raster_operation: TernaryRasterOperation::SRCCOPY,
+                color_usage: ColorUsage::DIB_RGB_COLORS,
+                src_height: 4,
+                src_width: 2,
+                y_src: 0,
+                x_src: 0,
+                dest_height: 4,
+                dest_width: 2,
+                y_dst: 10,
+                x_dst: 10,
+                dib: dib(4),
+            })
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    // The destination is mapped like the corners of a shape.
+    assert!(
+        svg.contains(r#"viewBox="0 0 2 4" width="4" x="10" y="10""#),
+        "{svg}"
+    );
+}
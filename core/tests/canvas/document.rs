@@ -0,0 +1,67 @@
+use wmf_core::{
+    converter::{CanvasPlayer, Player},
+    parser::{
+        META_PLACEABLE, META_SETWINDOWEXT, MetafileHeader, RecordSize, Rect,
+    },
+};
+
+use crate::fixtures::{header, rectangle};
+
+#[test]
+fn canvas_is_sized_by_window_extent() {
+    let script = CanvasPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(0)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 200,
+            })
+        })
+        .and_then(|player| player.rectangle(2, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("canvas generation failed");
+    let script = String::from_utf8_lossy(&script);
+
+    // Without META_PLACEABLE, a logical unit is a canvas pixel.
+    assert!(
+        script.starts_with("ctx.canvas.width=200;\nctx.canvas.height=100;\n"),
+        "{script}"
+    );
+    assert!(script.contains("ctx.transform(1,0,0,1,0,0);\n"), "{script}");
+    assert!(script.contains("ctx.rect(10,10,40,40);\n"), "{script}");
+    assert!(script.ends_with("ctx.restore();\n"), "{script}");
+}
+
+#[test]
+fn canvas_scale_follows_placeable_header() {
+    let placeable = META_PLACEABLE {
+        key: 0x9AC6_CDD7,
+        hwmf: 0,
+        bounding_box: Rect { left: 0, top: 0, right: 2010, bottom: 1000 },
+        inch: 1440,
+        reserved: 0,
+        checksum: [0, 0],
+    };
+    let script = CanvasPlayer::new()
+        .header(0, MetafileHeader::StartsWithPlaceable(placeable, header(0)))
+        .and_then(|player| player.rectangle(1, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("canvas generation failed");
+    let script = String::from_utf8_lossy(&script);
+
+    // 134 by 66.67 pixels at 96 DPI, rounded up for the canvas.
+    assert!(
+        script.starts_with("ctx.canvas.width=134;\nctx.canvas.height=67;\n"),
+        "{script}"
+    );
+    assert!(
+        script.contains("ctx.transform(0.06666667,0,0,0.06666667,0,0);\n"),
+        "{script}"
+    );
+    // A cosmetic pen is a pixel wide.
+    assert!(script.contains("ctx.lineWidth=15;\n"), "{script}");
+}
@@ -0,0 +1,134 @@
+use std::sync::{Arc, Mutex};
+
+use wmf_core::{
+    converter::{CanvasPlayer, Diagnostic, DiagnosticKind},
+    parser::{BinaryRasterOperation, RecordType},
+};
+
+use crate::fixtures;
+
+fn draw(
+    draw_mode: BinaryRasterOperation,
+    diagnostics: &Arc<Mutex<Vec<Diagnostic>>>,
+) -> String {
+    let sink = Arc::clone(diagnostics);
+    let script = fixtures::draw(
+        CanvasPlayer::new()
+            .diagnostic_sink(move |d| sink.lock().unwrap().push(d)),
+        draw_mode,
+    );
+
+    String::from_utf8_lossy(&script).into_owned()
+}
+
+#[test]
+fn canvas_drawing_records_are_paths() {
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let script = draw(BinaryRasterOperation::R2_COPYPEN, &diagnostics);
+
+    // The default pen is a black cosmetic pen and there is no brush.
+    assert!(
+        script.contains(concat!(
+            "ctx.save();\n",
+            "ctx.beginPath();\n",
+            "ctx.rect(10,10,40,40);\n",
+            "ctx.strokeStyle=\"#000000\";\n",
+            "ctx.lineWidth=1;\n",
+            "ctx.lineCap=\"butt\";\n",
+            "ctx.lineJoin=\"miter\";\n",
+            "ctx.setLineDash([]);\n",
+            "ctx.stroke();\n",
+            "ctx.restore();\n",
+        )),
+        "{script}"
+    );
+    assert!(
+        script.contains("ctx.moveTo(0,0);\nctx.lineTo(60,80);\n"),
+        "{script}"
+    );
+    assert!(!script.contains("globalCompositeOperation"), "{script}");
+    assert!(diagnostics.lock().unwrap().is_empty());
+}
+
+#[test]
+fn canvas_raster_operations_are_composite_operations() {
+    struct TestCase {
+        desc: &'static str,
+        draw_mode: BinaryRasterOperation,
+        composite: Option<&'static str>,
+        color: &'static str,
+        kind: Option<DiagnosticKind>,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "R2_NOT is exact with white and difference",
+            draw_mode: BinaryRasterOperation::R2_NOT,
+            composite: Some("difference"),
+            color: "#FFFFFF",
+            kind: None,
+        },
+        TestCase {
+            desc: "R2_XORPEN is approximated with difference",
+            draw_mode: BinaryRasterOperation::R2_XORPEN,
+            composite: Some("difference"),
+            color: "#000000",
+            kind: Some(DiagnosticKind::Approximated),
+        },
+        TestCase {
+            desc: "R2_WHITE is drawn with white",
+            draw_mode: BinaryRasterOperation::R2_WHITE,
+            composite: None,
+            color: "#FFFFFF",
+            kind: None,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let script = draw(case.draw_mode, &diagnostics);
+        let diagnostics = diagnostics.lock().unwrap();
+
+        let expected = format!(
+            "ctx.rect(10,10,40,40);\nctx.strokeStyle=\"{}\";\n",
+            case.color
+        );
+
+        assert!(
+            script.contains(&expected),
+            "case {i}: {}: {script}",
+            case.desc
+        );
+
+        match case.composite {
+            Some(composite) => assert!(
+                script.contains(&format!(
+                    "ctx.globalCompositeOperation=\"{composite}\";\n"
+                )),
+                "case {i}: {}: {script}",
+                case.desc
+            ),
+            None => assert!(
+                !script.contains("globalCompositeOperation"),
+                "case {i}: {}: {script}",
+                case.desc
+            ),
+        }
+
+        match case.kind {
+            Some(kind) => assert!(
+                diagnostics
+                    .iter()
+                    .any(|d| d.record_type == RecordType::META_RECTANGLE
+                        && d.kind == kind),
+                "case {i}: {}: {diagnostics:?}",
+                case.desc,
+            ),
+            None => assert!(
+                diagnostics.is_empty(),
+                "case {i}: {}: {diagnostics:?}",
+                case.desc
+            ),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{META_ARC, META_LINETO, META_SETWINDOWEXT, RecordSize},
+};
+
+const STROKE: &str = r##"stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1""##;
+
+fn arc(
+    (left_rect, top_rect, right_rect, bottom_rect): (i16, i16, i16, i16),
+    (x_start_arc, y_start_arc): (i16, i16),
+    (x_end_arc, y_end_arc): (i16, i16),
+) -> META_ARC {
+    META_ARC {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y_end_arc,
+        x_end_arc,
+        y_start_arc,
+        x_start_arc,
+        bottom_rect,
+        right_rect,
+        top_rect,
+        left_rect,
+    }
+}
+
+#[test]
+fn meta_arc_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        window_ext_y: i16,
+        record: META_ARC,
+        expected_d: Option<&'static str>,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Counter-clockwise from the left to the right passes the \
+                   bottom",
+            window_ext_y: 1024,
+            record: arc((40, 40, 120, 120), (40, 80), (120, 80)),
+            expected_d: Some("M 40 80 A 40 40 0 0 0 120 80"),
+        },
+        TestCase {
+            desc: "Counter-clockwise from the top to the bottom passes the \
+                   left",
+            window_ext_y: 1024,
+            record: arc((40, 40, 120, 120), (80, 40), (80, 120)),
+            expected_d: Some("M 80 40 A 40 40 0 0 0 80 120"),
+        },
+        TestCase {
+            desc: "Radial points are projected onto the ellipse",
+            window_ext_y: 1024,
+            record: arc((40, 40, 120, 120), (180, -20), (0, 80)),
+            expected_d: Some("M 108.28427 51.71573 A 40 40 0 0 0 40 80"),
+        },
+        TestCase {
+            desc: "Arcs over 180 degrees set the large-arc flag",
+            window_ext_y: 1024,
+            record: arc((40, 40, 120, 120), (40, 80), (80, 40)),
+            expected_d: Some("M 40 80 A 40 40 0 1 0 80 40"),
+        },
+        TestCase {
+            desc: "Radial points in the same direction draw the whole ellipse",
+            window_ext_y: 1024,
+            record: arc((40, 40, 120, 120), (200, 80), (120, 80)),
+            expected_d: Some(
+                "M 120 80 A 40 40 0 0 0 40 80 A 40 40 0 0 0 120 80",
+            ),
+        },
+        TestCase {
+            desc: "Inverted bounding rectangles give the same ellipse",
+            window_ext_y: 1024,
+            record: arc((120, 120, 40, 40), (40, 80), (120, 80)),
+            expected_d: Some("M 40 80 A 40 40 0 0 0 120 80"),
+        },
+        TestCase {
+            desc: "A mirrored y-axis reverses the direction in device units",
+            window_ext_y: -1024,
+            record: arc((40, 40, 120, 120), (40, 80), (120, 80)),
+            expected_d: Some("M 40 -80 A 40 40 0 0 1 120 -80"),
+        },
+        TestCase {
+            desc: "Skip when rx/ry is zero",
+            window_ext_y: 1024,
+            record: arc((40, 40, 40, 120), (40, 80), (120, 80)),
+            expected_d: None,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let result = SVGPlayer::new()
+            .set_window_ext(0, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: case.window_ext_y,
+                x: 1024,
+            })
+            .and_then(|player| player.arc(1, case.record.clone()));
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg = result.unwrap().generate().expect("SVG generation failed");
+        let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+        let body = &svg_str[svg_str.find('>').unwrap() + 1..svg_str.len() - 6];
+        let expected = case.expected_d.map_or_else(String::new, |d| {
+            format!(r#"<path d="{d}" fill="none" id="elem1" {STROKE}></path>"#)
+        });
+
+        assert_eq!(
+            body, expected,
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_arc_svg_keeps_the_drawing_position() {
+    let svg = SVGPlayer::new()
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 1024,
+            x: 1024,
+        })
+        .and_then(|player| {
+            player.arc(1, arc((40, 40, 120, 120), (40, 80), (120, 80)))
+        })
+        .and_then(|player| {
+            player.line_to(2, META_LINETO {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 10,
+                x: 10,
+            })
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    // GDI Arc leaves the current position where it was.
+    assert!(svg.contains(r#"d="M 0 0 L 10 10""#), "{svg}");
+}
@@ -1,88 +1,114 @@
 use wmf_core::{
     converter::{Player, SVGPlayer},
-    parser::{META_CHORD, META_SETWINDOWEXT},
+    parser::{META_CHORD, META_SETWINDOWEXT, RecordSize},
 };
 
+const STROKE: &str = r##"stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1""##;
+
+fn chord(
+    (left_rect, top_rect, right_rect, bottom_rect): (i16, i16, i16, i16),
+    (x_radial1, y_radial1): (i16, i16),
+    (x_radial2, y_radial2): (i16, i16),
+) -> META_CHORD {
+    META_CHORD {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y_radial2,
+        x_radial2,
+        y_radial1,
+        x_radial1,
+        bottom_rect,
+        right_rect,
+        top_rect,
+        left_rect,
+    }
+}
+
 #[test]
 fn meta_chord_svg_table_test() {
     struct TestCase {
         desc: &'static str,
+        window_ext_y: i16,
         record: META_CHORD,
-        expected_svg: &'static str,
+        expected_d: Option<&'static str>,
     }
 
     let cases = [
         TestCase {
-            desc: "Standard horizontal ellipse chord",
-            record: META_CHORD {
-                record_size: wmf_core::parser::RecordSize::from_raw(0),
-                record_function: 0,
-                y_radial2: 80,
-                x_radial2: 120,
-                y_radial1: 80,
-                x_radial1: 40,
-                bottom_rect: 120,
-                right_rect: 120,
-                top_rect: 40,
-                left_rect: 40,
-            },
-            expected_svg: r##"<svg viewBox="0 0 1024 1024" xmlns="http://www.w3.org/2000/svg"><path d="M 40 80 A 40 40 0 0 1 120 80 L 80 80 Z" fill="none" fill-rule="evenodd" id="elem1" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1"></path></svg>"##,
+            desc: "Counter-clockwise from the left to the right passes the \
+                   bottom",
+            window_ext_y: 1024,
+            record: chord((40, 40, 120, 120), (40, 80), (120, 80)),
+            expected_d: Some("M 40 80 A 40 40 0 0 0 120 80 Z"),
         },
         TestCase {
-            desc: "Vertical ellipse chord",
-            record: META_CHORD {
-                record_size: wmf_core::parser::RecordSize::from_raw(0),
-                record_function: 0,
-                y_radial2: 120,
-                x_radial2: 80,
-                y_radial1: 40,
-                x_radial1: 80,
-                bottom_rect: 120,
-                right_rect: 120,
-                top_rect: 40,
-                left_rect: 40,
-            },
-            expected_svg: r##"<svg viewBox="0 0 1024 1024" xmlns="http://www.w3.org/2000/svg"><path d="M 80 40 A 40 40 0 0 1 80 120 L 80 80 Z" fill="none" fill-rule="evenodd" id="elem1" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1"></path></svg>"##,
+            desc: "Counter-clockwise from the top to the bottom passes the \
+                   left",
+            window_ext_y: 1024,
+            record: chord((40, 40, 120, 120), (80, 40), (80, 120)),
+            expected_d: Some("M 80 40 A 40 40 0 0 0 80 120 Z"),
+        },
+        TestCase {
+            desc: "Radial points are projected onto the ellipse",
+            window_ext_y: 1024,
+            record: chord((40, 40, 120, 120), (180, -20), (0, 80)),
+            expected_d: Some("M 108.28427 51.71573 A 40 40 0 0 0 40 80 Z"),
+        },
+        TestCase {
+            desc: "Arcs over 180 degrees set the large-arc flag",
+            window_ext_y: 1024,
+            record: chord((40, 40, 120, 120), (40, 80), (80, 40)),
+            expected_d: Some("M 40 80 A 40 40 0 1 0 80 40 Z"),
+        },
+        TestCase {
+            desc: "Radial points in the same direction draw the whole ellipse",
+            window_ext_y: 1024,
+            record: chord((40, 40, 120, 120), (200, 80), (120, 80)),
+            expected_d: Some(
+                "M 120 80 A 40 40 0 0 0 40 80 A 40 40 0 0 0 120 80 Z",
+            ),
+        },
+        TestCase {
+            desc: "Inverted bounding rectangles give the same ellipse",
+            window_ext_y: 1024,
+            record: chord((120, 120, 40, 40), (40, 80), (120, 80)),
+            expected_d: Some("M 40 80 A 40 40 0 0 0 120 80 Z"),
+        },
+        TestCase {
+            desc: "A mirrored y-axis reverses the direction in device units",
+            window_ext_y: -1024,
+            record: chord((40, 40, 120, 120), (40, 80), (120, 80)),
+            expected_d: Some("M 40 -80 A 40 40 0 0 1 120 -80 Z"),
         },
         TestCase {
             desc: "Skip when rx/ry is zero",
-            record: META_CHORD {
-                record_size: wmf_core::parser::RecordSize::from_raw(0),
-                record_function: 0,
-                y_radial2: 80,
-                x_radial2: 120,
-                y_radial1: 80,
-                x_radial1: 40,
-                bottom_rect: 40,
-                right_rect: 40,
-                top_rect: 40,
-                left_rect: 40,
-            },
-            expected_svg: r#"<svg viewBox="0 0 1024 1024" xmlns="http://www.w3.org/2000/svg"></svg>"#,
+            window_ext_y: 1024,
+            record: chord((40, 40, 40, 120), (40, 80), (120, 80)),
+            expected_d: None,
         },
     ];
 
     for (i, case) in cases.iter().enumerate() {
-        let player = SVGPlayer::new();
-        let player = player
+        let result = SVGPlayer::new()
             .set_window_ext(0, META_SETWINDOWEXT {
-                record_size: wmf_core::parser::RecordSize::from_raw(0),
+                record_size: RecordSize::from_raw(0),
                 record_function: 0,
-                y: 1024,
+                y: case.window_ext_y,
                 x: 1024,
             })
-            .expect("set_window_ext failed");
-        let result = player.chord(1, case.record.clone());
+            .and_then(|player| player.chord(1, case.record.clone()));
 
         assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
 
-        let player = result.unwrap();
-        let svg = player.generate().expect("SVG generation failed");
+        let svg = result.unwrap().generate().expect("SVG generation failed");
         let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+        let body = &svg_str[svg_str.find('>').unwrap() + 1..svg_str.len() - 6];
+        let expected = case.expected_d.map_or_else(String::new, |d| {
+            format!(r#"<path d="{d}" fill="none" fill-rule="evenodd" id="elem1" {STROKE}></path>"#)
+        });
 
         assert_eq!(
-            svg_str.trim(),
-            case.expected_svg.trim(),
+            body, expected,
             "case {i}: {}: SVG output does not match expected",
             case.desc,
         );
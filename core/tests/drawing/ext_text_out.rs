@@ -0,0 +1,173 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        CharacterSet, ClipPrecision, ExtTextOutOptions, FamilyFont, Font,
+        FontQuality, META_CREATEFONTINDIRECT, META_EXTTEXTOUT,
+        META_SELECTOBJECT, META_SETWINDOWEXT, MetafileHeader, OutPrecision,
+        PitchAndFamily, PitchFont, RecordSize, Rect,
+    },
+};
+
+use crate::fixtures::header;
+
+fn ext_text_out(fw_opts: &[ExtTextOutOptions]) -> META_EXTTEXTOUT {
+    META_EXTTEXTOUT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 20,
+        x: 10,
+        string_length: 2,
+        fw_opts: fw_opts.iter().copied().collect(),
+        rectangle: (!fw_opts.is_empty()).then_some(Rect {
+            left: 5,
+            top: 10,
+            right: 45,
+            bottom: 30,
+        }),
+        string: b"AB".to_vec(),
+        dx: vec![],
+    }
+}
+
+#[test]
+fn meta_exttextout_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        record: META_EXTTEXTOUT,
+        expected: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Neither ETO_OPAQUE nor ETO_CLIPPED draws the text only",
+            record: ext_text_out(&[]),
+            expected: r##"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg"><text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem1" text-anchor="start" x="10" y="30">AB</text></svg>"##,
+        },
+        TestCase {
+            desc: "ETO_OPAQUE fills the rectangle with the background color",
+            record: ext_text_out(&[ExtTextOutOptions::ETO_OPAQUE]),
+            expected: r##"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg"><g id="elem1"><rect fill="#FFFFFF" height="20" stroke="none" width="40" x="5" y="10"></rect><text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" text-anchor="start" x="10" y="30">AB</text></g></svg>"##,
+        },
+        TestCase {
+            desc: "ETO_CLIPPED clips the text to the rectangle",
+            record: ext_text_out(&[ExtTextOutOptions::ETO_CLIPPED]),
+            expected: r##"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg"><defs><clipPath id="defs0"><rect height="20" width="40" x="5" y="10"></rect></clipPath></defs><text clip-path="url(#defs0)" fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem1" text-anchor="start" x="10" y="30">AB</text></svg>"##,
+        },
+        TestCase {
+            desc: "ETO_OPAQUE and ETO_CLIPPED combine",
+            record: ext_text_out(&[
+                ExtTextOutOptions::ETO_OPAQUE,
+                ExtTextOutOptions::ETO_CLIPPED,
+            ]),
+            expected: r##"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg"><defs><clipPath id="defs0"><rect height="20" width="40" x="5" y="10"></rect></clipPath></defs><g clip-path="url(#defs0)" id="elem1"><rect fill="#FFFFFF" height="20" stroke="none" width="40" x="5" y="10"></rect><text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" text-anchor="start" x="10" y="30">AB</text></g></svg>"##,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let result = SVGPlayer::new()
+            .set_window_ext(0, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+            .and_then(|player| player.ext_text_out(1, case.record.clone()))
+            .and_then(Player::generate);
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg_str = String::from_utf8(result.unwrap())
+            .expect("SVG output is not UTF-8");
+
+        assert_eq!(
+            svg_str, case.expected,
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_exttextout_svg_vertical_font() {
+    let header = MetafileHeader::StartsWithHeader(header(1));
+    let font = Font {
+        height: 20,
+        width: 0,
+        escapement: 2700,
+        orientation: 2700,
+        weight: 400,
+        italic: false,
+        underline: false,
+        strike_out: false,
+        charset: CharacterSet::SHIFTJIS_CHARSET,
+        out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+        clip_precision: ClipPrecision::CLIP_DEFAULT_PRECIS,
+        quality: FontQuality::DEFAULT_QUALITY,
+        pitch_and_family: PitchAndFamily {
+            family: FamilyFont::FF_DONTCARE,
+            pitch: PitchFont::DEFAULT_PITCH,
+        },
+        facename: "@MS Mincho".to_owned(),
+        fallback_facename: vec![],
+    };
+    let svg = SVGPlayer::new()
+        .header(0, header)
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+        })
+        .and_then(|player| {
+            player.create_font_indirect(2, META_CREATEFONTINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                font,
+            })
+        })
+        .and_then(|player| {
+            player.select_object(3, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|player| {
+            player.ext_text_out(4, META_EXTTEXTOUT {
+                dx: vec![20, 20],
+                ..ext_text_out(&[])
+            })
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    // The reference point is the top right of the column, so the central
+    // baseline is half an em to its left and the dx array advances y.
+    assert_eq!(
+        svg_str,
+        r##"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg"><text fill="#000000" font-family="&apos;MS Mincho&apos;" font-size="20" font-weight="400" id="elem4" style="writing-mode: vertical-rl;" text-anchor="start" x="0" y="20"><tspan>A</tspan><tspan y="40">B</tspan></text></svg>"##,
+    );
+}
+
+#[test]
+fn meta_exttextout_svg_rectangle_wider_than_i16() {
+    let mut record = ext_text_out(&[ExtTextOutOptions::ETO_CLIPPED]);
+    record.rectangle =
+        Some(Rect { left: -20000, top: -20000, right: 20000, bottom: 20000 });
+
+    let svg = SVGPlayer::new()
+        .ext_text_out(1, record)
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    assert!(
+        svg.contains(
+            r#"<rect height="40000" width="40000" x="-20000" y="-20000">"#
+        ),
+        "{svg}"
+    );
+}
@@ -0,0 +1,320 @@
+use std::sync::{Arc, Mutex};
+
+use wmf_core::{
+    converter::{Diagnostic, DiagnosticKind, Player, SVGPlayer},
+    parser::{
+        ColorRef, FloodFill, LogBrush, META_CREATEBRUSHINDIRECT,
+        META_EXTFLOODFILL, META_FLOODFILL, META_RECTANGLE, META_SELECTOBJECT,
+        META_SETPIXEL, META_SETWINDOWEXT, MetafileHeader, RecordSize,
+        RecordType,
+    },
+};
+
+use crate::fixtures::header;
+
+fn red() -> ColorRef {
+    ColorRef { red: 0xFF, green: 0, blue: 0, reserved: 0 }
+}
+
+/// Prepare a 100x100 drawing with a black rectangle outline from (10, 10)
+/// to (50, 50) and, optionally, a red solid brush selected.
+fn outlined_rectangle(select_red_brush: bool) -> SVGPlayer {
+    let player = SVGPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .expect("header failed")
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .expect("set_window_ext failed")
+        .rectangle(1, META_RECTANGLE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom_rect: 50,
+            right_rect: 50,
+            top_rect: 10,
+            left_rect: 10,
+        })
+        .expect("rectangle failed");
+
+    if !select_red_brush {
+        return player;
+    }
+
+    player
+        .create_brush_indirect(2, META_CREATEBRUSHINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            log_brush: LogBrush::Solid { color_ref: red() },
+        })
+        .expect("create_brush_indirect failed")
+        .select_object(3, META_SELECTOBJECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            object_index: 0,
+        })
+        .expect("select_object failed")
+}
+
+const RECTANGLE_SVG: &str = r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem1" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="40" x="10" y="10"></rect>"##;
+
+#[test]
+fn meta_floodfill_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        select_red_brush: bool,
+        record: META_FLOODFILL,
+        expected_fill: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Fill the inside of the outline up to the black border",
+            select_red_brush: true,
+            record: META_FLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                color_ref: ColorRef::black(),
+                y_start: 30,
+                x_start: 30,
+            },
+            expected_fill: r##"<path d="M 10 10 L 49 10 L 49 49 L 10 49 Z" fill="#FF0000" id="elem4" stroke="none"></path>"##,
+        },
+        TestCase {
+            desc: "Start on the border color paints nothing",
+            select_red_brush: true,
+            record: META_FLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                color_ref: ColorRef::black(),
+                y_start: 10,
+                x_start: 9,
+            },
+            expected_fill: "",
+        },
+        TestCase {
+            desc: "Null brush paints nothing",
+            select_red_brush: false,
+            record: META_FLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                color_ref: ColorRef::black(),
+                y_start: 30,
+                x_start: 30,
+            },
+            expected_fill: "",
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let player = outlined_rectangle(case.select_red_brush);
+        let result = player.flood_fill(4, case.record.clone());
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg = result.unwrap().generate().expect("SVG generation failed");
+        let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+        assert_eq!(
+            svg_str,
+            format!(
+                r#"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">{RECTANGLE_SVG}{}</svg>"#,
+                case.expected_fill,
+            ),
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_extfloodfill_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        record: META_EXTFLOODFILL,
+        expected_fill: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "FLOODFILLBORDER behaves like META_FLOODFILL",
+            record: META_EXTFLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                mode: FloodFill::FLOODFILLBORDER,
+                color_ref: ColorRef::black(),
+                y: 30,
+                x: 30,
+            },
+            expected_fill: r##"<path d="M 10 10 L 49 10 L 49 49 L 10 49 Z" fill="#FF0000" id="elem4" stroke="none"></path>"##,
+        },
+        TestCase {
+            desc: "FLOODFILLSURFACE spreads over the matching white surface",
+            record: META_EXTFLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                mode: FloodFill::FLOODFILLSURFACE,
+                color_ref: ColorRef::white(),
+                y: 30,
+                x: 30,
+            },
+            expected_fill: r##"<path d="M 10 10 L 49 10 L 49 49 L 10 49 Z" fill="#FF0000" id="elem4" stroke="none"></path>"##,
+        },
+        TestCase {
+            desc: "FLOODFILLSURFACE does not start on a different color",
+            record: META_EXTFLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                mode: FloodFill::FLOODFILLSURFACE,
+                color_ref: red(),
+                y: 30,
+                x: 30,
+            },
+            expected_fill: "",
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let player = outlined_rectangle(true);
+        let result = player.ext_flood_fill(4, case.record.clone());
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg = result.unwrap().generate().expect("SVG generation failed");
+        let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+        assert_eq!(
+            svg_str,
+            format!(
+                r#"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">{RECTANGLE_SVG}{}</svg>"#,
+                case.expected_fill,
+            ),
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_floodfill_svg_fills_over_earlier_fills() {
+    let diagnostics: Arc<Mutex<Vec<Diagnostic>>> = Arc::default();
+    let sink = Arc::clone(&diagnostics);
+    let svg = outlined_rectangle(true)
+        .diagnostic_sink(move |d| sink.lock().unwrap().push(d))
+        .flood_fill(4, META_FLOODFILL {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            color_ref: ColorRef::black(),
+            y_start: 30,
+            x_start: 30,
+        })
+        .and_then(|player| {
+            // The first fill is painted on the scratch canvas, so the
+            // surface fill spreads over the red it left.
+            player.ext_flood_fill(5, META_EXTFLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                mode: FloodFill::FLOODFILLSURFACE,
+                color_ref: red(),
+                y: 30,
+                x: 30,
+            })
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    assert!(
+        svg.contains(r##"<path d="M 10 10 L 49 10 L 49 49 L 10 49 Z" fill="#FF0000" id="elem5" stroke="none"></path>"##),
+        "{svg}"
+    );
+
+    let diagnostics = diagnostics.lock().unwrap();
+    let reported: Vec<_> =
+        diagnostics.iter().map(|d| (d.record_type, d.kind)).collect();
+
+    assert_eq!(reported, [
+        (RecordType::META_FLOODFILL, DiagnosticKind::Approximated),
+        (RecordType::META_EXTFLOODFILL, DiagnosticKind::Approximated),
+    ]);
+}
+
+#[test]
+fn meta_floodfill_svg_skips_fills_past_the_work_budget() {
+    let diagnostics: Arc<Mutex<Vec<Diagnostic>>> = Arc::default();
+    let sink = Arc::clone(&diagnostics);
+    let mut player = SVGPlayer::new()
+        .diagnostic_sink(move |d| sink.lock().unwrap().push(d))
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .and_then(|player| {
+            player.set_window_ext(0, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 30000,
+                x: 30000,
+            })
+        })
+        .expect("set_window_ext failed")
+        .create_brush_indirect(1, META_CREATEBRUSHINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            log_brush: LogBrush::Solid { color_ref: red() },
+        })
+        .and_then(|player| {
+            player.select_object(2, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .expect("select_object failed");
+
+    // Every fill is charged a scan of the whole scratch canvas, even one
+    // that stops at its start on the blank canvas.
+    for record_number in 3..103 {
+        player = player
+            .ext_flood_fill(record_number, META_EXTFLOODFILL {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                mode: FloodFill::FLOODFILLSURFACE,
+                color_ref: ColorRef::black(),
+                y: 100,
+                x: 100,
+            })
+            .expect("ext_flood_fill failed");
+    }
+
+    let diagnostics = diagnostics.lock().unwrap();
+    let skipped = diagnostics
+        .iter()
+        .position(|d| d.kind == DiagnosticKind::Skipped)
+        .expect("no fill was skipped");
+
+    assert!(skipped > 0, "the first fill was skipped");
+    assert_eq!(diagnostics.len(), 100);
+    assert!(
+        diagnostics[skipped..]
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::Skipped),
+        "a fill ran past the budget"
+    );
+}
+
+#[test]
+fn set_pixel_svg_on_the_last_coordinate() {
+    let svg = SVGPlayer::new()
+        .set_pixel(1, META_SETPIXEL {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            color_ref: red(),
+            y: i16::MAX,
+            x: i16::MAX,
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    assert!(svg.contains(r#"x="32767" y="32767""#), "{svg}");
+}
@@ -1 +1,6 @@
+mod arc;
 mod chord;
+mod ext_text_out;
+mod flood_fill;
+mod pat_blt;
+mod pie;
@@ -0,0 +1,127 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        ColorRef, LogBrush, META_CREATEBRUSHINDIRECT, META_PATBLT,
+        META_SELECTOBJECT, META_SETWINDOWEXT, MetafileHeader, RecordSize,
+        TernaryRasterOperation,
+    },
+};
+
+use crate::fixtures::header;
+
+/// Prepare a 100x100 drawing with a red solid brush selected.
+fn red_brush_selected() -> SVGPlayer {
+    let header = MetafileHeader::StartsWithHeader(header(1));
+
+    SVGPlayer::new()
+        .header(0, header)
+        .expect("header failed")
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .expect("set_window_ext failed")
+        .create_brush_indirect(1, META_CREATEBRUSHINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            log_brush: LogBrush::Solid {
+                color_ref: ColorRef {
+                    red: 0xFF,
+                    green: 0,
+                    blue: 0,
+                    reserved: 0,
+                },
+            },
+        })
+        .expect("create_brush_indirect failed")
+        .select_object(2, META_SELECTOBJECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            object_index: 0,
+        })
+        .expect("select_object failed")
+}
+
+#[test]
+fn meta_patblt_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        raster_operation: TernaryRasterOperation,
+        width: i16,
+        expected: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "PATCOPY paints the selected brush",
+            raster_operation: TernaryRasterOperation::PATCOPY,
+            width: 30,
+            expected: r##"<rect fill="#FF0000" height="20" id="elem3" width="30" x="10" y="20"></rect>"##,
+        },
+        TestCase {
+            desc: "Negative width is normalized",
+            raster_operation: TernaryRasterOperation::PATCOPY,
+            width: -5,
+            expected: r##"<rect fill="#FF0000" height="20" id="elem3" width="5" x="5" y="20"></rect>"##,
+        },
+        TestCase {
+            desc: "PATINVERT blends the brush with difference",
+            raster_operation: TernaryRasterOperation::PATINVERT,
+            width: 30,
+            expected: r##"<rect fill="#FF0000" height="20" id="elem3" style="mix-blend-mode: difference;" width="30" x="10" y="20"></rect>"##,
+        },
+        TestCase {
+            desc: "DSTINVERT inverts the destination",
+            raster_operation: TernaryRasterOperation::DSTINVERT,
+            width: 30,
+            expected: r#"<rect fill="white" height="20" id="elem3" stroke="none" style="mix-blend-mode: difference;" width="30" x="10" y="20"></rect>"#,
+        },
+        TestCase {
+            desc: "BLACKNESS ignores the brush",
+            raster_operation: TernaryRasterOperation::BLACKNESS,
+            width: 30,
+            expected: r#"<rect fill="black" height="20" id="elem3" stroke="none" width="30" x="10" y="20"></rect>"#,
+        },
+        TestCase {
+            desc: "WHITENESS ignores the brush",
+            raster_operation: TernaryRasterOperation::WHITENESS,
+            width: 30,
+            expected: r#"<rect fill="white" height="20" id="elem3" stroke="none" width="30" x="10" y="20"></rect>"#,
+        },
+        TestCase {
+            desc: "Operations reading a source bitmap are skipped",
+            raster_operation: TernaryRasterOperation::SRCCOPY,
+            width: 30,
+            expected: "",
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let result = red_brush_selected().pat_blt(3, META_PATBLT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            raster_operation: case.raster_operation,
+            height: 20,
+            width: case.width,
+            y_left: 20,
+            x_left: 10,
+        });
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg = result.unwrap().generate().expect("SVG generation failed");
+        let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+        assert_eq!(
+            svg_str,
+            format!(
+                r#"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">{}</svg>"#,
+                case.expected,
+            ),
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        ColorRef, META_CREATEPENINDIRECT, META_PIE, META_SELECTOBJECT,
+        META_SETWINDOWEXT, MetafileHeader, Pen, PenStyle, PenStyleSubsection,
+        PointS, RecordSize,
+    },
+};
+
+use crate::fixtures::header;
+
+const STROKE: &str = r##"stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1""##;
+
+fn pie(
+    (left_rect, top_rect, right_rect, bottom_rect): (i16, i16, i16, i16),
+    (x_radial1, y_radial1): (i16, i16),
+    (x_radial2, y_radial2): (i16, i16),
+) -> META_PIE {
+    META_PIE {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y_radial2,
+        x_radial2,
+        y_radial1,
+        x_radial1,
+        bottom_rect,
+        right_rect,
+        top_rect,
+        left_rect,
+    }
+}
+
+#[test]
+fn meta_pie_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        window_ext_y: i16,
+        record: META_PIE,
+        expected_d: Option<&'static str>,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Counter-clockwise from the left to the right passes the \
+                   bottom",
+            window_ext_y: 1024,
+            record: pie((40, 40, 120, 120), (40, 80), (120, 80)),
+            expected_d: Some("M 80 80 L 40 80 A 40 40 0 0 0 120 80 Z"),
+        },
+        TestCase {
+            desc: "Counter-clockwise from the top to the bottom passes the \
+                   left",
+            window_ext_y: 1024,
+            record: pie((40, 40, 120, 120), (80, 40), (80, 120)),
+            expected_d: Some("M 80 80 L 80 40 A 40 40 0 0 0 80 120 Z"),
+        },
+        TestCase {
+            desc: "Radial points are projected onto the ellipse",
+            window_ext_y: 1024,
+            record: pie((40, 40, 120, 120), (180, -20), (0, 80)),
+            expected_d: Some(
+                "M 80 80 L 108.28427 51.71573 A 40 40 0 0 0 40 80 Z",
+            ),
+        },
+        TestCase {
+            desc: "Arcs over 180 degrees set the large-arc flag",
+            window_ext_y: 1024,
+            record: pie((40, 40, 120, 120), (40, 80), (80, 40)),
+            expected_d: Some("M 80 80 L 40 80 A 40 40 0 1 0 80 40 Z"),
+        },
+        TestCase {
+            desc: "Radial points in the same direction draw the whole ellipse",
+            window_ext_y: 1024,
+            record: pie((40, 40, 120, 120), (200, 80), (120, 80)),
+            expected_d: Some(
+                "M 80 80 L 120 80 A 40 40 0 0 0 40 80 A 40 40 0 0 0 120 80 Z",
+            ),
+        },
+        TestCase {
+            desc: "Inverted bounding rectangles give the same ellipse",
+            window_ext_y: 1024,
+            record: pie((120, 120, 40, 40), (40, 80), (120, 80)),
+            expected_d: Some("M 80 80 L 40 80 A 40 40 0 0 0 120 80 Z"),
+        },
+        TestCase {
+            desc: "A mirrored y-axis reverses the direction in device units",
+            window_ext_y: -1024,
+            record: pie((40, 40, 120, 120), (40, 80), (120, 80)),
+            expected_d: Some("M 80 -80 L 40 -80 A 40 40 0 0 1 120 -80 Z"),
+        },
+        TestCase {
+            desc: "Skip when rx/ry is zero",
+            window_ext_y: 1024,
+            record: pie((40, 40, 40, 120), (40, 80), (120, 80)),
+            expected_d: None,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let result = SVGPlayer::new()
+            .set_window_ext(0, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: case.window_ext_y,
+                x: 1024,
+            })
+            .and_then(|player| player.pie(1, case.record.clone()));
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg = result.unwrap().generate().expect("SVG generation failed");
+        let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+        let body = &svg_str[svg_str.find('>').unwrap() + 1..svg_str.len() - 6];
+        let expected = case.expected_d.map_or_else(String::new, |d| {
+            format!(r#"<path d="{d}" fill="none" fill-rule="evenodd" id="elem1" {STROKE}></path>"#)
+        });
+
+        assert_eq!(
+            body, expected,
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn meta_pie_svg_inside_frame() {
+    let header = MetafileHeader::StartsWithHeader(header(1));
+    let svg = SVGPlayer::new()
+        .header(0, header)
+        .and_then(|player| {
+            player.set_window_ext(0, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 1024,
+                x: 1024,
+            })
+        })
+        .and_then(|player| {
+            player.create_pen_indirect(1, META_CREATEPENINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                pen: Pen {
+                    style: PenStyleSubsection {
+                        end_cap: PenStyle::PS_ENDCAP_FLAT,
+                        line_join: PenStyle::PS_JOIN_MITER,
+                        style: PenStyle::PS_INSIDEFRAME,
+                        typ: PenStyle::PS_SOLID,
+                    },
+                    width: PointS { x: 10, y: 0 },
+                    color_ref: ColorRef::black(),
+                },
+            })
+        })
+        .and_then(|player| {
+            player.select_object(2, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|player| {
+            player.pie(3, pie((40, 40, 120, 120), (40, 80), (120, 80)))
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    // The 10 units wide pen is drawn inside the bounding rectangle.
+    assert!(
+        svg_str.contains(r#"d="M 80 80 L 45 80 A 35 35 0 0 0 115 80 Z""#),
+        "pie is not inset by half the pen width: {svg_str}",
+    );
+}
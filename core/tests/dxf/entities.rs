@@ -0,0 +1,485 @@
+use wmf_core::{
+    converter::{DxfPlayer, Player},
+    parser::{
+        CharacterSet, ClipPrecision, ColorRef, FamilyFont, Font, FontQuality,
+        HatchStyle, LogBrush, META_ARC, META_CREATEBRUSHINDIRECT,
+        META_CREATEFONTINDIRECT, META_ELLIPSE, META_POLYPOLYGON,
+        META_RECTANGLE, META_ROUNDRECT, META_SELECTOBJECT,
+        META_SETPOLYFILLMODE, META_SETTEXTCOLOR, META_TEXTOUT, MetafileHeader,
+        OutPrecision, PitchAndFamily, PitchFont, PointS, PolyFillMode,
+        PolyPolygon, RecordSize,
+    },
+};
+
+use crate::fixtures::header;
+
+fn player() -> DxfPlayer {
+    DxfPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .expect("rendering failed")
+}
+
+fn select(player: DxfPlayer, object_index: u16) -> DxfPlayer {
+    player
+        .select_object(0, META_SELECTOBJECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            object_index,
+        })
+        .expect("rendering failed")
+}
+
+/// Groups of the whole file, as pairs of the code and the value.
+fn groups(player: DxfPlayer) -> Vec<(u16, String)> {
+    let output = player.generate().expect("DXF generation failed");
+    let output = String::from_utf8(output).expect("DXF is not UTF-8");
+    let lines: Vec<_> = output.lines().collect();
+
+    lines
+        .chunks(2)
+        .map(|pair| {
+            let code = pair[0].trim().parse::<u16>().expect("invalid code");
+
+            (code, pair[1].to_owned())
+        })
+        .collect()
+}
+
+/// Entities of the ENTITIES section in order, as their type and groups.
+fn all_entities(player: DxfPlayer) -> Vec<(String, Vec<(u16, String)>)> {
+    split_entities(&groups(player))
+}
+
+fn split_entities(
+    groups: &[(u16, String)],
+) -> Vec<(String, Vec<(u16, String)>)> {
+    let start = groups
+        .iter()
+        .position(|(code, value)| *code == 2 && value == "ENTITIES")
+        .expect("ENTITIES section is missing");
+    let mut entities: Vec<(String, Vec<_>)> = vec![];
+
+    for (code, value) in &groups[start + 1..] {
+        if *code == 0 && value == "ENDSEC" {
+            break;
+        } else if *code == 0 {
+            entities.push((value.clone(), vec![]));
+        } else if let Some((_, groups)) = entities.last_mut() {
+            groups.push((*code, value.clone()));
+        }
+    }
+
+    entities
+}
+
+/// Groups of the entities of type `typ` in the ENTITIES section, as pairs
+/// of the code and the value.
+fn entities(player: DxfPlayer, typ: &str) -> Vec<Vec<(u16, String)>> {
+    all_entities(player)
+        .into_iter()
+        .filter(|(entity, _)| entity == typ)
+        .map(|(_, groups)| groups)
+        .collect()
+}
+
+/// Drawing coordinates of the groups `x` and `x + 10` of `groups`.
+fn point(groups: &[(u16, String)], x: u16) -> (f32, f32) {
+    let coordinate = |code| {
+        value(groups, code)
+            .and_then(|value| value.parse().ok())
+            .expect("coordinate is missing")
+    };
+
+    (coordinate(x), coordinate(x + 10))
+}
+
+fn brush(log_brush: LogBrush) -> DxfPlayer {
+    let player = player()
+        .create_brush_indirect(0, META_CREATEBRUSHINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            log_brush,
+        })
+        .expect("rendering failed");
+
+    select(player, 0)
+}
+
+fn value(groups: &[(u16, String)], code: u16) -> Option<&str> {
+    groups.iter().find(|(c, _)| *c == code).map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn dxf_fills_with_solid_in_nearest_colour() {
+    let player = brush(LogBrush::Solid {
+        color_ref: ColorRef { red: 250, green: 10, blue: 0, reserved: 0 },
+    })
+    .rectangle(1, rectangle())
+    .expect("rendering failed");
+    let solids = entities(player, "SOLID");
+
+    assert_eq!(solids.len(), 1);
+    assert_eq!(value(&solids[0], 62), Some("1"));
+
+    let corners: Vec<_> =
+        [10, 11, 12, 13].into_iter().map(|x| point(&solids[0], x)).collect();
+
+    // Both lower corners, then both upper ones.
+    assert_eq!(corners, [
+        (10.0, -40.0),
+        (30.0, -40.0),
+        (10.0, -20.0),
+        (30.0, -20.0)
+    ]);
+}
+
+#[test]
+fn dxf_fills_overlaps_by_the_fill_mode() {
+    struct TestCase {
+        desc: &'static str,
+        poly_fill_mode: PolyFillMode,
+        expected_area: f32,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "ALTERNATE leaves the overlap out",
+            poly_fill_mode: PolyFillMode::ALTERNATE,
+            expected_area: 600.0,
+        },
+        TestCase {
+            desc: "WINDING fills the overlap",
+            poly_fill_mode: PolyFillMode::WINDING,
+            expected_area: 700.0,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let square = |at: i16| {
+            [(at, at), (at + 20, at), (at + 20, at + 20), (at, at + 20)]
+                .map(|(x, y)| PointS { x, y })
+        };
+        let player = brush(LogBrush::Solid { color_ref: ColorRef::black() })
+            .set_polyfill_mode(1, META_SETPOLYFILLMODE {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                poly_fill_mode: case.poly_fill_mode,
+                reserved: None,
+            })
+            .and_then(|player| {
+                player.poly_polygon(2, META_POLYPOLYGON {
+                    record_size: RecordSize::from_raw(0),
+                    record_function: 0,
+                    poly_polygon: PolyPolygon {
+                        number_of_polygons: 2,
+                        a_points_per_polygon: vec![4, 4],
+                        a_points: [square(0), square(10)].concat(),
+                    },
+                })
+            })
+            .expect("rendering failed");
+        let area: f32 = entities(player, "SOLID")
+            .iter()
+            .map(|solid| {
+                let [a, b, c, d] = [10, 11, 12, 13].map(|x| point(solid, x));
+
+                f32::midpoint(b.0 - a.0, d.0 - c.0) * (c.1 - a.1)
+            })
+            .sum();
+
+        assert!(
+            (area - case.expected_area).abs() < 1e-3,
+            "Test case #{i}: {}: {area}",
+            case.desc
+        );
+    }
+}
+
+#[test]
+fn dxf_hatches_with_lines_8_pixels_apart() {
+    let player = brush(LogBrush::Hatched {
+        color_ref: ColorRef::black(),
+        brush_hatch: HatchStyle::HS_HORIZONTAL,
+    })
+    .rectangle(1, rectangle())
+    .expect("rendering failed");
+    let lines: Vec<_> = entities(player, "LINE")
+        .iter()
+        .map(|line| (point(line, 10), point(line, 11)))
+        .collect();
+
+    assert_eq!(lines, [
+        ((10.0, -40.0), (30.0, -40.0)),
+        ((10.0, -32.0), (30.0, -32.0)),
+        ((10.0, -24.0), (30.0, -24.0)),
+    ]);
+}
+
+#[test]
+fn dxf_declares_layer_of_each_pen_colour() {
+    let output = DxfPlayer::new()
+        .rectangle(0, rectangle())
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let output = String::from_utf8(output).expect("DXF is not UTF-8");
+
+    assert!(output.contains("  0\nLAYER\n  2\nPEN_000000\n"), "{output}");
+    assert!(output.ends_with("  0\nENDSEC\n  0\nEOF\n"), "{output}");
+}
+
+fn rectangle() -> META_RECTANGLE {
+    META_RECTANGLE {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        bottom_rect: 40,
+        right_rect: 30,
+        top_rect: 20,
+        left_rect: 10,
+    }
+}
+
+#[test]
+fn dxf_keeps_logical_units_with_y_axis_up() {
+    let player =
+        DxfPlayer::new().rectangle(0, rectangle()).expect("rendering failed");
+    let entities = all_entities(player);
+    let polylines: Vec<_> =
+        entities.iter().filter(|(typ, _)| typ == "POLYLINE").collect();
+    let vertices: Vec<_> = entities
+        .iter()
+        .filter(|(typ, _)| typ == "VERTEX")
+        .map(|(_, groups)| point(groups, 10))
+        .collect();
+
+    assert_eq!(polylines.len(), 1);
+    assert_eq!(value(&polylines[0].1, 8), Some("PEN_000000"));
+    assert_eq!(value(&polylines[0].1, 70), Some("1"));
+    assert_eq!(vertices, [
+        (10.0, -20.0),
+        (30.0, -20.0),
+        (30.0, -40.0),
+        (10.0, -40.0)
+    ]);
+}
+
+#[test]
+fn dxf_writes_circular_arc_counter_clockwise() {
+    let player = DxfPlayer::new()
+        .arc(0, META_ARC {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y_end_arc: 0,
+            x_end_arc: 50,
+            y_start_arc: 50,
+            x_start_arc: 100,
+            bottom_rect: 100,
+            right_rect: 100,
+            top_rect: 0,
+            left_rect: 0,
+        })
+        .expect("rendering failed");
+    let arcs = entities(player, "ARC");
+
+    assert_eq!(arcs.len(), 1);
+
+    let groups: Vec<_> = [10, 20, 40, 50, 51]
+        .into_iter()
+        .map(|code| value(&arcs[0], code))
+        .collect();
+
+    // From the right of the circle to its top on the page.
+    assert_eq!(groups, [
+        Some("50"),
+        Some("-50"),
+        Some("50"),
+        Some("0"),
+        Some("90")
+    ]);
+}
+
+#[test]
+fn dxf_writes_ellipse_as_closed_polyline() {
+    let player = DxfPlayer::new()
+        .ellipse(0, META_ELLIPSE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom_rect: 100,
+            right_rect: 50,
+            top_rect: 0,
+            left_rect: 0,
+        })
+        .expect("rendering failed");
+    let entities = all_entities(player);
+    let polylines: Vec<_> =
+        entities.iter().filter(|(typ, _)| typ == "POLYLINE").collect();
+    let vertices: Vec<_> = entities
+        .iter()
+        .filter(|(typ, _)| typ == "VERTEX")
+        .map(|(_, groups)| point(groups, 10))
+        .collect();
+
+    assert_eq!(polylines.len(), 1);
+    assert_eq!(value(&polylines[0].1, 70), Some("1"));
+    assert_eq!(vertices.len(), 64);
+
+    for (x, y) in vertices {
+        let (dx, dy) = ((x - 25.0) / 25.0, (y + 50.0) / 50.0);
+
+        assert!((dx * dx + dy * dy - 1.0).abs() < 1e-2, "({x}, {y})");
+    }
+}
+
+#[test]
+fn dxf_is_well_formed_release_12() {
+    // Entity types of AutoCAD Release 12.
+    const ENTITIES: [&str; 16] = [
+        "LINE",
+        "POINT",
+        "CIRCLE",
+        "ARC",
+        "TRACE",
+        "SOLID",
+        "TEXT",
+        "SHAPE",
+        "INSERT",
+        "ATTDEF",
+        "ATTRIB",
+        "POLYLINE",
+        "VERTEX",
+        "SEQEND",
+        "3DFACE",
+        "DIMENSION",
+    ];
+
+    let player = brush(LogBrush::Hatched {
+        color_ref: ColorRef::black(),
+        brush_hatch: HatchStyle::HS_DIAGCROSS,
+    })
+    .round_rect(1, META_ROUNDRECT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        height: 20,
+        width: 10,
+        bottom_rect: 100,
+        right_rect: 100,
+        top_rect: 0,
+        left_rect: 0,
+    })
+    .and_then(|player| {
+        player.ellipse(2, META_ELLIPSE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom_rect: 100,
+            right_rect: 50,
+            top_rect: 0,
+            left_rect: 0,
+        })
+    })
+    .expect("rendering failed");
+    let groups = groups(player);
+
+    assert_eq!(&groups[..4], [
+        (0, "SECTION".to_owned()),
+        (2, "HEADER".to_owned()),
+        (9, "$ACADVER".to_owned()),
+        (1, "AC1009".to_owned()),
+    ]);
+    // Subclass markers, handles and owners came with Release 13.
+    assert!(groups.iter().all(|(code, _)| ![5, 100, 330].contains(code)));
+
+    assert_eq!(groups.last(), Some(&(0, "EOF".to_owned())));
+
+    let mut polylines = 0;
+    let mut vertices = None;
+
+    for (typ, _) in split_entities(&groups) {
+        assert!(ENTITIES.contains(&typ.as_str()), "{typ}");
+
+        // Every POLYLINE runs through VERTEX entities to a SEQEND.
+        vertices = match (typ.as_str(), vertices) {
+            ("POLYLINE", None) => Some(0),
+            ("VERTEX", Some(count)) => Some(count + 1),
+            ("SEQEND", Some(count)) if count > 0 => {
+                polylines += 1;
+                None
+            }
+            (typ, None) if typ != "VERTEX" && typ != "SEQEND" => None,
+            (typ, _) => panic!("{typ} out of a POLYLINE sequence"),
+        };
+    }
+
+    assert_eq!(vertices, None, "the last POLYLINE has no SEQEND");
+    assert_eq!(polylines, 2);
+}
+
+#[test]
+fn dxf_writes_text_with_escaped_characters() {
+    let player = player()
+        .create_font_indirect(0, META_CREATEFONTINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            font: Font {
+                height: -20,
+                width: 0,
+                escapement: 900,
+                orientation: 900,
+                weight: 400,
+                italic: false,
+                underline: false,
+                strike_out: false,
+                charset: CharacterSet::ANSI_CHARSET,
+                out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+                clip_precision: ClipPrecision::CLIP_DEFAULT_PRECIS,
+                quality: FontQuality::DEFAULT_QUALITY,
+                pitch_and_family: PitchAndFamily {
+                    family: FamilyFont::FF_DONTCARE,
+                    pitch: PitchFont::DEFAULT_PITCH,
+                },
+                facename: "Arial".to_owned(),
+                fallback_facename: vec![],
+            },
+        })
+        .and_then(|player| {
+            player.set_text_color(1, META_SETTEXTCOLOR {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                color_ref: ColorRef {
+                    red: 0,
+                    green: 0,
+                    blue: 250,
+                    reserved: 0,
+                },
+            })
+        })
+        .expect("rendering failed");
+    let player = select(player, 0)
+        .text_out(2, META_TEXTOUT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            string_length: 5,
+            string: b"Caf\xE9 ".to_vec(),
+            y_start: 20,
+            x_start: 10,
+        })
+        .expect("rendering failed");
+    let texts = entities(player, "TEXT");
+
+    assert_eq!(texts.len(), 1);
+
+    let groups: Vec<_> = [1, 10, 20, 40, 50, 62, 72, 73]
+        .into_iter()
+        .map(|code| value(&texts[0], code))
+        .collect();
+
+    // Aligned at the top left of the text, as the default of GDI.
+    assert_eq!(groups, [
+        Some("Caf\\U+00E9 "),
+        Some("10"),
+        Some("-20"),
+        Some("20"),
+        Some("90"),
+        Some("5"),
+        Some("0"),
+        Some("3")
+    ]);
+}
@@ -0,0 +1,74 @@
+use wmf_core::{
+    converter::{EmfPlayer, Player},
+    parser::{
+        META_PLACEABLE, META_SETWINDOWEXT, MetafileHeader, RecordSize, Rect,
+    },
+};
+
+use super::{i32_at, records, u32_at};
+use crate::fixtures::{header, rectangle};
+
+#[test]
+fn emf_starts_with_header_and_ends_with_eof() {
+    let emf = EmfPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(2)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 200,
+            })
+        })
+        .and_then(|player| player.rectangle(2, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("EMF generation failed");
+    let records = records(&emf);
+    let types: Vec<_> = records.iter().map(|(typ, _)| *typ).collect();
+    let (_, header) = records[0];
+
+    // EMR_HEADER, EMR_SETWINDOWEXTEX, EMR_RECTANGLE and EMR_EOF.
+    assert_eq!(types, [0x01, 0x09, 0x2B, 0x0E]);
+    assert_eq!(header.len(), 108);
+    // " EMF"
+    assert_eq!(u32_at(header, 40), 0x464D_4520);
+    assert_eq!(u32_at(header, 48) as usize, emf.len());
+    assert_eq!(u32_at(header, 52), 4);
+    // The object table and handle 0.
+    assert_eq!(u32_at(header, 56) & 0xFFFF, 3);
+    // Bounds cover the window extent in pixels.
+    assert_eq!([8, 12, 16, 20].map(|offset| i32_at(header, offset)), [
+        0, 0, 199, 99
+    ],);
+}
+
+#[test]
+fn emf_maps_placeable_bounding_box_to_pixels() {
+    let placeable = META_PLACEABLE {
+        key: 0x9AC6_CDD7,
+        hwmf: 0,
+        bounding_box: Rect { left: 0, top: 0, right: 2880, bottom: 1440 },
+        inch: 1440,
+        reserved: 0,
+        checksum: [0, 0],
+    };
+    let emf = EmfPlayer::new()
+        .header(0, MetafileHeader::StartsWithPlaceable(placeable, header(2)))
+        .and_then(|player| player.rectangle(1, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("EMF generation failed");
+    let records = records(&emf);
+    let (_, header) = records[0];
+    let (typ, viewport_ext) = records[5];
+
+    // Two by one inches at 96 DPI.
+    assert_eq!([8, 12, 16, 20].map(|offset| i32_at(header, offset)), [
+        0, 0, 191, 95
+    ],);
+    // Frame in 0.01 millimeters.
+    assert_eq!(i32_at(header, 32), 5080);
+    assert_eq!(typ, 0x0B);
+    assert_eq!([i32_at(viewport_ext, 8), i32_at(viewport_ext, 12)], [192, 96]);
+}
@@ -0,0 +1,28 @@
+mod document;
+mod object;
+
+/// Split an EMF into `(type, record)` pairs, where the record includes the
+/// type and size fields.
+fn records(emf: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut records = vec![];
+    let mut rest = emf;
+
+    while rest.len() >= 8 {
+        let typ = u32_at(rest, 0);
+        let size = u32_at(rest, 4) as usize;
+        let (record, next) = rest.split_at(size);
+
+        records.push((typ, record));
+        rest = next;
+    }
+
+    records
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn i32_at(data: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
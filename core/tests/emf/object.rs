@@ -0,0 +1,170 @@
+use wmf_core::{
+    converter::{EmfPlayer, Player},
+    parser::{
+        CharacterSet, ClipPrecision, ColorRef, FamilyFont, Font, FontQuality,
+        LogBrush, META_CREATEBRUSHINDIRECT, META_CREATEFONTINDIRECT,
+        META_CREATEPENINDIRECT, META_DELETEOBJECT, META_EXTTEXTOUT,
+        META_SELECTOBJECT, MetafileHeader, OutPrecision, Pen, PenStyle,
+        PenStyleSubsection, PitchAndFamily, PitchFont, PointS, RecordSize,
+    },
+};
+
+use super::{i32_at, records, u32_at};
+use crate::fixtures::header;
+
+fn font() -> Font {
+    Font {
+        height: 20,
+        width: 0,
+        escapement: 0,
+        orientation: 0,
+        weight: 400,
+        italic: false,
+        underline: false,
+        strike_out: false,
+        charset: CharacterSet::SHIFTJIS_CHARSET,
+        out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+        clip_precision: ClipPrecision::CLIP_DEFAULT_PRECIS,
+        quality: FontQuality::DEFAULT_QUALITY,
+        pitch_and_family: PitchAndFamily {
+            family: FamilyFont::FF_DONTCARE,
+            pitch: PitchFont::DEFAULT_PITCH,
+        },
+        facename: "MS Mincho".to_owned(),
+        fallback_facename: vec![],
+    }
+}
+
+#[test]
+fn emf_object_indexes_become_handles() {
+    let emf = EmfPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(2)))
+        .and_then(|player| {
+            player.create_brush_indirect(1, META_CREATEBRUSHINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                log_brush: LogBrush::Solid { color_ref: ColorRef::black() },
+            })
+        })
+        .and_then(|player| {
+            player.create_font_indirect(2, META_CREATEFONTINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                font: font(),
+            })
+        })
+        .and_then(|player| {
+            player.delete_object(3, META_DELETEOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|player| {
+            player.create_pen_indirect(4, META_CREATEPENINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                pen: Pen {
+                    style: PenStyleSubsection {
+                        end_cap: PenStyle::PS_ENDCAP_FLAT,
+                        line_join: PenStyle::PS_JOIN_MITER,
+                        style: PenStyle::PS_DASH,
+                        typ: PenStyle::PS_SOLID,
+                    },
+                    width: PointS { x: 3, y: 0 },
+                    color_ref: ColorRef::white(),
+                },
+            })
+        })
+        .and_then(|player| {
+            player.select_object(5, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 1,
+            })
+        })
+        .and_then(|player| {
+            player.select_object(6, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .expect("rendering failed")
+        .generate()
+        .expect("EMF generation failed");
+    let records = records(&emf);
+    // The type and the handle of each record between EMR_HEADER and
+    // EMR_EOF.
+    let handles: Vec<_> = records[1..records.len() - 1]
+        .iter()
+        .map(|(typ, record)| (*typ, u32_at(record, 8)))
+        .collect();
+
+    assert_eq!(handles, [
+        // EMR_CREATEBRUSHINDIRECT takes handle 1 and the font handle 2.
+        (0x27, 1),
+        (0x52, 2),
+        (0x28, 1),
+        // The pen reuses the index, and so the handle, of the brush.
+        (0x26, 1),
+        (0x25, 2),
+        (0x25, 1),
+    ]);
+
+    let (_, pen) = records[4];
+
+    assert_eq!(u32_at(pen, 12), 0x2201);
+    assert_eq!(i32_at(pen, 16), 3);
+}
+
+#[test]
+fn emf_text_is_converted_to_utf16_with_an_advance_per_unit() {
+    let emf = EmfPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(2)))
+        .and_then(|player| {
+            player.create_font_indirect(1, META_CREATEFONTINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                font: font(),
+            })
+        })
+        .and_then(|player| {
+            player.select_object(2, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|player| {
+            player.ext_text_out(3, META_EXTTEXTOUT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 20,
+                x: 10,
+                string_length: 3,
+                fw_opts: [].into(),
+                rectangle: None,
+                // "あA" in Shift_JIS
+                string: vec![0x82, 0xA0, 0x41],
+                dx: vec![10, 10, 5],
+            })
+        })
+        .expect("rendering failed")
+        .generate()
+        .expect("EMF generation failed");
+    let records = records(&emf);
+    let (typ, text) = records[3];
+    let offset = u32_at(text, 48) as usize;
+    let offset_dx = u32_at(text, 72) as usize;
+    let units: Vec<_> = text[offset..offset + 4]
+        .chunks(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect();
+
+    assert_eq!(typ, 0x54);
+    assert_eq!([i32_at(text, 36), i32_at(text, 40)], [10, 20]);
+    assert_eq!(u32_at(text, 44), 2);
+    assert_eq!(units, [0x3042, 0x41]);
+    assert_eq!([i32_at(text, offset_dx), i32_at(text, offset_dx + 4)], [20, 5]);
+}
@@ -0,0 +1,59 @@
+use wmf_core::{
+    converter::{EpsPlayer, Player},
+    parser::{
+        META_PLACEABLE, META_SETWINDOWEXT, MetafileHeader, RecordSize, Rect,
+    },
+};
+
+use crate::fixtures::{header, rectangle};
+
+#[test]
+fn eps_has_document_structure_comments() {
+    let eps = EpsPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(0)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 200,
+            })
+        })
+        .and_then(|player| player.rectangle(2, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("EPS generation failed");
+    let eps = String::from_utf8_lossy(&eps);
+
+    assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n"), "{eps}");
+    // The window extent at 96 DPI.
+    assert!(eps.contains("\n%%BoundingBox: 0 0 150 75\n"), "{eps}");
+    assert!(eps.contains("\n%%HiResBoundingBox: 0 0 150 75\n"), "{eps}");
+    assert!(eps.contains("\n%%EndProlog\n"), "{eps}");
+    assert!(eps.contains("[0.75 0 0 -0.75 0 75] concat\n"), "{eps}");
+    assert!(eps.ends_with("showpage\n%%Trailer\n%%EOF\n"), "{eps}");
+}
+
+#[test]
+fn eps_bounding_box_follows_placeable_header() {
+    let placeable = META_PLACEABLE {
+        key: 0x9AC6_CDD7,
+        hwmf: 0,
+        bounding_box: Rect { left: 0, top: 0, right: 2010, bottom: 1000 },
+        inch: 1440,
+        reserved: 0,
+        checksum: [0, 0],
+    };
+    let eps = EpsPlayer::new()
+        .header(0, MetafileHeader::StartsWithPlaceable(placeable, header(0)))
+        .and_then(|player| player.rectangle(1, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("EPS generation failed");
+    let eps = String::from_utf8_lossy(&eps);
+
+    // 100.5 by 50 points, rounded outwards for the integer box.
+    assert!(eps.contains("\n%%BoundingBox: 0 0 101 50\n"), "{eps}");
+    assert!(eps.contains("\n%%HiResBoundingBox: 0 0 100.5 50\n"), "{eps}");
+    assert!(eps.contains("[0.05 0 0 -0.05 0 50] concat\n"), "{eps}");
+}
@@ -0,0 +1,94 @@
+use std::sync::{Arc, Mutex};
+
+use wmf_core::{
+    converter::{Diagnostic, DiagnosticKind, EpsPlayer},
+    parser::{BinaryRasterOperation, RecordType},
+};
+
+use crate::fixtures;
+
+fn draw(
+    draw_mode: BinaryRasterOperation,
+    diagnostics: &Arc<Mutex<Vec<Diagnostic>>>,
+) -> String {
+    let sink = Arc::clone(diagnostics);
+    let eps = fixtures::draw(
+        EpsPlayer::new().diagnostic_sink(move |d| sink.lock().unwrap().push(d)),
+        draw_mode,
+    );
+
+    String::from_utf8_lossy(&eps).into_owned()
+}
+
+#[test]
+fn eps_drawing_records_are_postscript_paths() {
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let eps = draw(BinaryRasterOperation::R2_COPYPEN, &diagnostics);
+
+    // The default pen is a black cosmetic pen and there is no brush.
+    assert!(
+        eps.contains(
+            "gsave\nnewpath\n10 10 moveto\n50 10 lineto\n50 50 lineto\n10 50 \
+             lineto\nclosepath\n0 0 0 setrgbcolor\n1 setlinewidth\n0 \
+             setlinecap\n0 setlinejoin\n[] 0 setdash\nstroke\ngrestore\n"
+        ),
+        "{eps}"
+    );
+    assert!(eps.contains("0 0 moveto\n60 80 lineto\n"), "{eps}");
+    assert!(diagnostics.lock().unwrap().is_empty());
+}
+
+#[test]
+fn eps_raster_operations_are_approximated() {
+    struct TestCase {
+        desc: &'static str,
+        draw_mode: BinaryRasterOperation,
+        expected: Option<&'static str>,
+        kind: DiagnosticKind,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "R2_XORPEN is drawn with the pen colour",
+            draw_mode: BinaryRasterOperation::R2_XORPEN,
+            expected: Some("0 0 0 setrgbcolor\n"),
+            kind: DiagnosticKind::Approximated,
+        },
+        TestCase {
+            desc: "R2_NOTXORPEN is drawn with the inverted pen colour",
+            draw_mode: BinaryRasterOperation::R2_NOTXORPEN,
+            expected: Some("1 1 1 setrgbcolor\n"),
+            kind: DiagnosticKind::Approximated,
+        },
+        TestCase {
+            desc: "R2_NOT needs the page and is skipped",
+            draw_mode: BinaryRasterOperation::R2_NOT,
+            expected: None,
+            kind: DiagnosticKind::Skipped,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let eps = draw(case.draw_mode, &diagnostics);
+        let diagnostics = diagnostics.lock().unwrap();
+
+        match case.expected {
+            Some(expected) => {
+                assert!(eps.contains(expected), "case {i}: {}", case.desc);
+            }
+            None => {
+                assert!(!eps.contains("stroke\n"), "case {i}: {}", case.desc);
+            }
+        }
+
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.record_type == RecordType::META_RECTANGLE
+                    && d.kind == case.kind),
+            "case {i}: {}: {diagnostics:?}",
+            case.desc,
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use wmf_core::{
+    converter::{EpsPlayer, Player},
+    parser::{
+        META_ESCAPE, META_INTERSECTCLIPRECT, META_SETWINDOWEXT, RecordSize,
+    },
+};
+
+fn render(escapes: Vec<META_ESCAPE>) -> Vec<u8> {
+    let player = EpsPlayer::new()
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .and_then(|player| {
+            player.intersect_clip_rect(1, META_INTERSECTCLIPRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                bottom: 90,
+                right: 90,
+                top: 10,
+                left: 10,
+            })
+        })
+        .expect("rendering failed");
+
+    escapes
+        .into_iter()
+        .enumerate()
+        .try_fold(player, |player, (i, escape)| player.escape(i + 2, escape))
+        .expect("rendering failed")
+        .generate()
+        .expect("EPS generation failed")
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[test]
+fn eps_inserts_postscript_data_in_graphics_state_of_drawing() {
+    let data = b"% \xA9 app\n0 0 moveto 100 100 lineto stroke".to_vec();
+    let eps = render(vec![META_ESCAPE::POSTSCRIPT_DATA {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        byte_count: u16::try_from(data.len()).unwrap(),
+        data: data.clone(),
+    }]);
+    let at = find(&eps, &data).expect("payload is not inserted verbatim");
+    let before = String::from_utf8_lossy(&eps[..at]);
+
+    // Clipped to the clipping region, with a newline ending the payload.
+    assert!(before.ends_with("clip\nnewpath\n"), "{before}");
+    assert!(eps[at + data.len()..].starts_with(b"\ngrestore\n"));
+}
+
+#[test]
+fn eps_inserts_postscript_passthrough_as_it_is() {
+    let data = b"/WMFPassthrough true def\n".to_vec();
+    let eps = render(vec![META_ESCAPE::POSTSCRIPT_PASSTHROUGH {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        byte_count: u16::try_from(data.len()).unwrap(),
+        data: data.clone(),
+    }]);
+    let at = find(&eps, &data).expect("payload is not inserted verbatim");
+    let before = String::from_utf8_lossy(&eps[..at]);
+
+    assert!(before.ends_with("] concat\n"), "{before}");
+    assert!(eps[at + data.len()..].starts_with(b"grestore\nend\n"));
+}
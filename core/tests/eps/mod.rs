@@ -0,0 +1,3 @@
+mod document;
+mod drawing;
+mod escape;
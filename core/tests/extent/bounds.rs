@@ -0,0 +1,202 @@
+use wmf_core::{
+    converter::{ExtentPlayer, Player},
+    parser::{
+        ColorRef, META_ARC, META_CREATEPENINDIRECT, META_INTERSECTCLIPRECT,
+        META_PATBLT, META_RECTANGLE, META_SCALEVIEWPORTEXT, META_SELECTOBJECT,
+        META_SETMAPMODE, META_SETVIEWPORTEXT, META_SETWINDOWEXT, META_TEXTOUT,
+        MapMode, MetafileHeader, Pen, PenStyle, PenStyleSubsection, PointS,
+        RecordSize, TernaryRasterOperation,
+    },
+};
+
+use crate::fixtures::{header, rectangle};
+
+fn player() -> ExtentPlayer {
+    ExtentPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+        })
+        .expect("rendering failed")
+}
+
+fn pen(style: PenStyle, width: i16) -> ExtentPlayer {
+    player()
+        .create_pen_indirect(2, META_CREATEPENINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            pen: Pen {
+                style: PenStyleSubsection {
+                    end_cap: PenStyle::PS_ENDCAP_FLAT,
+                    line_join: PenStyle::PS_JOIN_MITER,
+                    style,
+                    typ: PenStyle::PS_SOLID,
+                },
+                width: PointS { x: width, y: 0 },
+                color_ref: ColorRef::black(),
+            },
+        })
+        .and_then(|player| {
+            player.select_object(3, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .expect("rendering failed")
+}
+
+fn extent(player: &ExtentPlayer) -> Option<(i32, i32, i32, i32)> {
+    player.extent().map(|rect| (rect.left, rect.top, rect.right, rect.bottom))
+}
+
+#[test]
+fn extent_includes_the_pen_width() {
+    let player = pen(PenStyle::PS_SOLID, 6)
+        .rectangle(4, rectangle())
+        .expect("rendering failed");
+
+    assert_eq!(extent(&player), Some((7, 7, 53, 53)));
+
+    // PS_INSIDEFRAME keeps the stroke inside the rectangle.
+    let player = pen(PenStyle::PS_INSIDEFRAME, 6)
+        .rectangle(4, rectangle())
+        .expect("rendering failed");
+
+    assert_eq!(extent(&player), Some((10, 10, 50, 50)));
+}
+
+#[test]
+fn extent_follows_arcs_rather_than_their_rectangle() {
+    // The quarter from the right of the ellipse counter-clockwise to its
+    // top.
+    let player = pen(PenStyle::PS_SOLID, 2)
+        .arc(4, META_ARC {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y_end_arc: 0,
+            x_end_arc: 50,
+            y_start_arc: 50,
+            x_start_arc: 100,
+            bottom_rect: 100,
+            right_rect: 100,
+            top_rect: 0,
+            left_rect: 0,
+        })
+        .expect("rendering failed");
+
+    assert_eq!(extent(&player), Some((49, -1, 101, 51)));
+}
+
+#[test]
+fn extent_is_clipped() {
+    let player = player()
+        .intersect_clip_rect(2, META_INTERSECTCLIPRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom: 30,
+            right: 30,
+            top: 0,
+            left: 0,
+        })
+        .and_then(|player| player.rectangle(3, rectangle()))
+        .expect("rendering failed");
+
+    assert_eq!(extent(&player), Some((9, 9, 30, 30)));
+}
+
+#[test]
+fn extent_leaves_out_what_paints_nothing() {
+    let player = pen(PenStyle::PS_NULL, 1)
+        .rectangle(4, rectangle())
+        .and_then(|player| {
+            player.pat_blt(5, META_PATBLT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                raster_operation: TernaryRasterOperation::D,
+                height: 10,
+                width: 10,
+                y_left: 0,
+                x_left: 0,
+            })
+        })
+        .expect("rendering failed");
+
+    assert_eq!(extent(&player), None);
+    assert!(player.generate().is_err());
+}
+
+#[test]
+fn extent_includes_text_boxes() {
+    // "Hi" is 11.3 units wide in 12 units of Helvetica, and TA_TOP hangs
+    // the text an em below the reference point.
+    let output = player()
+        .text_out(2, META_TEXTOUT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            string_length: 2,
+            string: b"Hi".to_vec(),
+            y_start: 20,
+            x_start: 10,
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    assert_eq!(String::from_utf8_lossy(&output), "10 20 22 32\n");
+}
+
+#[test]
+fn extent_saturates_past_i32() {
+    // A 1x1 window on a viewport scaled to 32767^2 device units per
+    // logical unit takes the right and bottom far past i32::MAX.
+    let player = player()
+        .set_map_mode(2, META_SETMAPMODE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            map_mode: MapMode::MM_ANISOTROPIC,
+        })
+        .and_then(|player| {
+            player.set_window_ext(3, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 1,
+                x: 1,
+            })
+        })
+        .and_then(|player| {
+            player.set_viewport_ext(4, META_SETVIEWPORTEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: i16::MAX,
+                x: i16::MAX,
+            })
+        })
+        .and_then(|player| {
+            player.scale_viewport_ext(5, META_SCALEVIEWPORTEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y_denom: 1,
+                y_num: i16::MAX,
+                x_denom: 1,
+                x_num: i16::MAX,
+            })
+        })
+        .and_then(|player| {
+            player.rectangle(6, META_RECTANGLE {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                bottom_rect: i16::MAX,
+                right_rect: i16::MAX,
+                top_rect: i16::MIN,
+                left_rect: i16::MIN,
+            })
+        })
+        .expect("rendering failed");
+
+    assert_eq!(extent(&player), Some((i32::MIN, i32::MIN, i32::MAX, i32::MAX)));
+}
@@ -0,0 +1,94 @@
+use wmf_core::parser::{META_HEADER, MetafileType, MetafileVersion};
+#[cfg(any(
+    feature = "canvas",
+    feature = "emf",
+    feature = "eps",
+    feature = "extent",
+    feature = "pdf",
+    feature = "tikz"
+))]
+use wmf_core::parser::{META_RECTANGLE, RecordSize};
+#[cfg(any(
+    feature = "canvas",
+    feature = "eps",
+    feature = "pdf",
+    feature = "tikz"
+))]
+use wmf_core::{
+    converter::Player,
+    parser::{
+        BinaryRasterOperation, META_LINETO, META_SETROP2, META_SETWINDOWEXT,
+    },
+};
+
+/// Header of a memory metafile with room for `number_of_objects` objects.
+pub fn header(number_of_objects: u16) -> META_HEADER {
+    META_HEADER {
+        typ: MetafileType::MEMORYMETAFILE,
+        header_size: 9,
+        version: MetafileVersion::METAVERSION300,
+        size_low: 0,
+        size_high: 0,
+        number_of_objects,
+        max_record: 0,
+        number_of_members: 0,
+    }
+}
+
+/// Rectangle from (10, 10) to (50, 50).
+#[cfg(any(
+    feature = "canvas",
+    feature = "emf",
+    feature = "eps",
+    feature = "extent",
+    feature = "pdf",
+    feature = "tikz"
+))]
+pub fn rectangle() -> META_RECTANGLE {
+    META_RECTANGLE {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        bottom_rect: 50,
+        right_rect: 50,
+        top_rect: 10,
+        left_rect: 10,
+    }
+}
+
+/// Play the rectangle and a line from the origin to (60, 80) in a 100x100
+/// window with `draw_mode`, and return the generated output.
+#[cfg(any(
+    feature = "canvas",
+    feature = "eps",
+    feature = "pdf",
+    feature = "tikz"
+))]
+pub fn draw<P: Player>(player: P, draw_mode: BinaryRasterOperation) -> Vec<u8> {
+    player
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .and_then(|player| {
+            player.set_raster_operation(1, META_SETROP2 {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                draw_mode,
+                reserved: None,
+            })
+        })
+        .and_then(|player| player.rectangle(2, rectangle()))
+        .and_then(|player| {
+            player.line_to(3, META_LINETO {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 80,
+                x: 60,
+            })
+        })
+        .expect("rendering failed")
+        .generate()
+        .expect("generation failed")
+}
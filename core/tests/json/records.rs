@@ -0,0 +1,91 @@
+use wmf_core::{
+    converter::{JsonPlayer, Player},
+    parser::{
+        BitCount, Bitmap16, META_SETWINDOWEXT, META_STRETCHBLT, MetafileHeader,
+        RecordSize, TernaryRasterOperation,
+    },
+};
+
+use crate::fixtures::header;
+
+fn stretch_blt() -> META_STRETCHBLT {
+    META_STRETCHBLT::WithBitmap {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0x0B41,
+        raster_operation: TernaryRasterOperation::SRCCOPY,
+        src_height: 2,
+        src_width: 2,
+        y_src: 0,
+        x_src: 0,
+        dest_height: 2,
+        dest_width: 2,
+        y_dest: 0,
+        x_dest: 0,
+        target: Bitmap16 {
+            typ: 0,
+            width: 2,
+            height: 2,
+            width_bytes: 2,
+            planes: 1,
+            bits_pixel: BitCount::BI_BITCOUNT_1,
+            bits: vec![0xFF, 0x00, 0x80, 0x00],
+        },
+    }
+}
+
+fn play(player: JsonPlayer) -> String {
+    let json = player
+        .header(0, MetafileHeader::StartsWithHeader(header(0)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(5),
+                record_function: 0x020C,
+                y: 100,
+                x: 200,
+            })
+        })
+        .and_then(|player| player.stretch_blt(2, stretch_blt()))
+        .expect("playing failed")
+        .generate()
+        .expect("JSON generation failed");
+
+    String::from_utf8(json).unwrap()
+}
+
+#[test]
+fn json_has_a_line_per_record() {
+    let json = play(JsonPlayer::new());
+    let lines: Vec<_> = json.lines().collect();
+
+    assert_eq!(lines.len(), 4, "{json}");
+    assert!(
+        lines[0].starts_with(
+            r#"{"header":{"StartsWithHeader":{"typ":"MEMORYMETAFILE","#
+        ),
+        "{json}"
+    );
+    assert!(lines[0].ends_with(r#""records":["#), "{json}");
+    // The record size is the word count, without the parsing progress.
+    assert_eq!(
+        lines[1],
+        r#"{"record_number":1,"record_type":"META_SETWINDOWEXT","record":{"record_size":5,"record_function":524,"y":100,"x":200}},"#
+    );
+    assert_eq!(lines[3], "]}");
+}
+
+#[test]
+fn json_summarises_bitmaps_unless_requested() {
+    let json = play(JsonPlayer::new());
+
+    assert!(
+        json.contains(r#""bits_pixel":"BI_BITCOUNT_1","bits":{"length":4}}"#),
+        "{json}"
+    );
+
+    let json = play(JsonPlayer::new().bitmap_data());
+
+    assert!(
+        json.contains(r#""bits":{"length":4,"base64":"/wCAAA=="}}"#),
+        "{json}"
+    );
+}
@@ -1 +1,27 @@
+mod bitmap;
+#[cfg(feature = "canvas")]
+mod canvas;
 mod drawing;
+#[cfg(feature = "dxf")]
+mod dxf;
+#[cfg(feature = "emf")]
+mod emf;
+#[cfg(feature = "eps")]
+mod eps;
+#[cfg(feature = "extent")]
+mod extent;
+mod fixtures;
+#[cfg(feature = "json")]
+mod json;
+mod object;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod state;
+#[cfg(feature = "statistics")]
+mod statistics;
+#[cfg(feature = "text")]
+mod text;
+#[cfg(feature = "thumbnail")]
+mod thumbnail;
+#[cfg(feature = "tikz")]
+mod tikz;
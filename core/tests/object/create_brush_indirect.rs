@@ -0,0 +1,134 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        ColorRef, HatchStyle, LogBrush, META_CREATEBRUSHINDIRECT, META_PATBLT,
+        META_SELECTOBJECT, META_SETBKMODE, META_SETMAPMODE, META_SETWINDOWEXT,
+        MapMode, MetafileHeader, MixMode, RecordSize, TernaryRasterOperation,
+    },
+};
+
+use crate::fixtures::header;
+
+/// Select a red hatched brush and paint it twice with META_PATBLT.
+fn paint_hatch_twice(
+    brush_hatch: HatchStyle,
+    bk_mode: MixMode,
+    map_mode: MapMode,
+) -> String {
+    let header = MetafileHeader::StartsWithHeader(header(1));
+    let pat_blt = |x_left| META_PATBLT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        raster_operation: TernaryRasterOperation::PATCOPY,
+        height: 10,
+        width: 10,
+        y_left: 0,
+        x_left,
+    };
+
+    let svg = SVGPlayer::new()
+        .header(0, header)
+        .and_then(|p| {
+            p.set_map_mode(1, META_SETMAPMODE {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                map_mode,
+            })
+        })
+        .and_then(|p| {
+            p.set_window_ext(2, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 20,
+                x: 40,
+            })
+        })
+        .and_then(|p| {
+            p.set_bk_mode(3, META_SETBKMODE {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                bk_mode,
+                reserved: None,
+            })
+        })
+        .and_then(|p| {
+            p.create_brush_indirect(4, META_CREATEBRUSHINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                log_brush: LogBrush::Hatched {
+                    color_ref: ColorRef {
+                        red: 0xFF,
+                        green: 0,
+                        blue: 0,
+                        reserved: 0,
+                    },
+                    brush_hatch,
+                },
+            })
+        })
+        .and_then(|p| {
+            p.select_object(5, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|p| p.pat_blt(6, pat_blt(0)))
+        .and_then(|p| p.pat_blt(7, pat_blt(20)))
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    String::from_utf8(svg).expect("SVG output is not UTF-8")
+}
+
+#[test]
+fn meta_createbrushindirect_hatched_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        brush_hatch: HatchStyle,
+        bk_mode: MixMode,
+        map_mode: MapMode,
+        expected_pattern: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Transparent horizontal hatch on an 8x8 tile",
+            brush_hatch: HatchStyle::HS_HORIZONTAL,
+            bk_mode: MixMode::TRANSPARENT,
+            map_mode: MapMode::MM_TEXT,
+            expected_pattern: r##"<pattern height="8" id="defs0" patternContentUnits="userSpaceOnUse" patternUnits="userSpaceOnUse" width="8" x="0" y="0"><path d="M -1 0.5 L 9 0.5" fill="none" stroke="#FF0000" stroke-width="1"></path></pattern>"##,
+        },
+        TestCase {
+            desc: "Opaque background fills the gaps with the background color",
+            brush_hatch: HatchStyle::HS_FDIAGONAL,
+            bk_mode: MixMode::OPAQUE,
+            map_mode: MapMode::MM_TEXT,
+            expected_pattern: r##"<pattern height="8" id="defs0" patternContentUnits="userSpaceOnUse" patternUnits="userSpaceOnUse" width="8" x="0" y="0"><rect fill="#FFFFFF" height="8" width="8"></rect><path d="M -1 -1 L 9 9" fill="none" stroke="#FF0000" stroke-width="1"></path></pattern>"##,
+        },
+        TestCase {
+            desc: "Hatch spacing stays 8 device pixels in MM_LOENGLISH",
+            brush_hatch: HatchStyle::HS_BDIAGONAL,
+            bk_mode: MixMode::TRANSPARENT,
+            map_mode: MapMode::MM_LOENGLISH,
+            expected_pattern: r##"<pattern height="8" id="defs0" patternContentUnits="userSpaceOnUse" patternTransform="scale(1.0416666)" patternUnits="userSpaceOnUse" width="8" x="0" y="0"><path d="M -1 9 L 9 -1" fill="none" stroke="#FF0000" stroke-width="1"></path></pattern>"##,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let svg =
+            paint_hatch_twice(case.brush_hatch, case.bk_mode, case.map_mode);
+
+        assert!(
+            svg.contains(&format!("<defs>{}</defs>", case.expected_pattern)),
+            "case {i}: {}: pattern does not match expected: {svg}",
+            case.desc,
+        );
+        assert_eq!(
+            svg.matches(r#"fill="url(#defs0)""#).count(),
+            2,
+            "case {i}: {}: both rectangles should share the pattern",
+            case.desc,
+        );
+    }
+}
@@ -0,0 +1 @@
+mod create_brush_indirect;
@@ -0,0 +1,67 @@
+use wmf_core::{
+    converter::{PdfPlayer, Player},
+    parser::{
+        META_PLACEABLE, META_SETWINDOWEXT, MetafileHeader, RecordSize, Rect,
+    },
+};
+
+use crate::fixtures::{header, rectangle};
+
+#[test]
+fn pdf_document_has_one_page_with_cross_reference_table() {
+    let pdf = PdfPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(0)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+        })
+        .and_then(|player| player.rectangle(2, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("PDF generation failed");
+    let text = String::from_utf8_lossy(&pdf);
+
+    assert!(text.starts_with("%PDF-1.4\n"));
+    assert!(text.ends_with("%%EOF\n"));
+    assert!(text.contains("/Type /Pages /Kids [3 0 R] /Count 1"));
+    // Object 0 and the catalog, page tree, page and content stream.
+    assert!(text.contains("xref\n0 5\n0000000000 65535 f \n"));
+
+    // The offset is in bytes, which the binary comment line after the
+    // header keeps apart from the lossy text.
+    let xref: usize = text
+        .lines()
+        .skip_while(|line| *line != "startxref")
+        .nth(1)
+        .and_then(|line| line.parse().ok())
+        .expect("startxref has no offset");
+
+    assert!(pdf[xref..].starts_with(b"xref\n"));
+}
+
+#[test]
+fn pdf_page_size_follows_placeable_header() {
+    let placeable = META_PLACEABLE {
+        key: 0x9AC6_CDD7,
+        hwmf: 0,
+        bounding_box: Rect { left: 0, top: 0, right: 2880, bottom: 1440 },
+        inch: 1440,
+        reserved: 0,
+        checksum: [0, 0],
+    };
+    let pdf = PdfPlayer::new()
+        .header(0, MetafileHeader::StartsWithPlaceable(placeable, header(0)))
+        .and_then(|player| player.rectangle(1, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("PDF generation failed");
+    let pdf = String::from_utf8_lossy(&pdf);
+
+    // Two by one inches.
+    assert!(pdf.contains("/MediaBox [0 0 144 72]"), "{pdf}");
+    assert!(pdf.contains("0.05 0 0 -0.05 0 72 cm\n"), "{pdf}");
+}
@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+
+use wmf_core::{
+    converter::{Diagnostic, DiagnosticKind, PdfPlayer},
+    parser::{BinaryRasterOperation, RecordType},
+};
+
+use crate::fixtures;
+
+fn draw(
+    draw_mode: BinaryRasterOperation,
+    diagnostics: &Arc<Mutex<Vec<Diagnostic>>>,
+) -> String {
+    let sink = Arc::clone(diagnostics);
+    let pdf = fixtures::draw(
+        PdfPlayer::new().diagnostic_sink(move |d| sink.lock().unwrap().push(d)),
+        draw_mode,
+    );
+
+    String::from_utf8_lossy(&pdf).into_owned()
+}
+
+#[test]
+fn pdf_drawing_records_are_vector_paths() {
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let pdf = draw(BinaryRasterOperation::R2_COPYPEN, &diagnostics);
+
+    // The default pen is a black cosmetic pen and there is no brush.
+    assert!(pdf.contains(
+        "q\n0 0 0 RG\n1 w\n0 J\n0 j\n[] 0 d\n10 10 40 40 re\nS\nQ\n"
+    ));
+    assert!(pdf.contains("0 0 m\n60 80 l\nS\nQ\n"));
+    assert!(diagnostics.lock().unwrap().is_empty());
+}
+
+#[test]
+fn pdf_raster_operations_use_blend_modes() {
+    struct TestCase {
+        desc: &'static str,
+        draw_mode: BinaryRasterOperation,
+        expected: &'static str,
+        approximated: bool,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "R2_XORPEN blends with the difference",
+            draw_mode: BinaryRasterOperation::R2_XORPEN,
+            expected: "/BM /Difference",
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_MASKPEN blends by multiplying",
+            draw_mode: BinaryRasterOperation::R2_MASKPEN,
+            expected: "/BM /Multiply",
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_MERGENOTPEN screens the inverted colour",
+            draw_mode: BinaryRasterOperation::R2_MERGENOTPEN,
+            expected: "/BM /Screen",
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_NOTMERGEPEN has no blend mode and is approximated",
+            draw_mode: BinaryRasterOperation::R2_NOTMERGEPEN,
+            expected: "0 0 0 RG\n1 w\n0 J\n0 j\n[] 0 d\n10 10 40 40 re\nS\n",
+            approximated: true,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let pdf = draw(case.draw_mode, &diagnostics);
+        let diagnostics = diagnostics.lock().unwrap();
+
+        assert!(pdf.contains(case.expected), "case {i}: {}: {pdf}", case.desc);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .any(|d| d.record_type == RecordType::META_RECTANGLE
+                    && d.kind == DiagnosticKind::Approximated),
+            case.approximated,
+            "case {i}: {}: {diagnostics:?}",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn pdf_r2_nop_draws_nothing() {
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let pdf = draw(BinaryRasterOperation::R2_NOP, &diagnostics);
+
+    assert!(!pdf.contains(" re\n"), "{pdf}");
+}
@@ -0,0 +1,217 @@
+use wmf_core::{
+    converter::{Clip, GdiState},
+    parser::{
+        META_EXCLUDECLIPRECT, META_INTERSECTCLIPRECT, META_MOVETO,
+        META_RESTOREDC, META_SAVEDC, META_SETMAPMODE, META_SETVIEWPORTEXT,
+        META_SETVIEWPORTORG, META_SETWINDOWEXT, META_SETWINDOWORG, MapMode,
+        PointS, RecordSize,
+    },
+};
+
+fn save(state: &mut GdiState) {
+    state.save_device_context(&META_SAVEDC {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+    });
+}
+
+fn restore(state: &mut GdiState, n_saved_dc: i16) -> Option<usize> {
+    state.restore_device_context(&META_RESTOREDC {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        n_saved_dc,
+    })
+}
+
+fn move_to(state: &mut GdiState, x: i16, y: i16) {
+    state.move_to(&META_MOVETO {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y,
+        x,
+    });
+}
+
+fn intersect(state: &mut GdiState, left: i16, top: i16, right: i16) {
+    state.intersect_clip_rect(&META_INTERSECTCLIPRECT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        bottom: 40,
+        right,
+        top,
+        left,
+    });
+}
+
+fn anisotropic(state: &mut GdiState) {
+    state.set_map_mode(&META_SETMAPMODE {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        map_mode: MapMode::MM_ANISOTROPIC,
+    });
+    state.set_window_origin(&META_SETWINDOWORG {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 100,
+        x: 100,
+    });
+    state.set_window_ext(&META_SETWINDOWEXT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 1000,
+        x: 1000,
+    });
+    state.set_viewport_origin(&META_SETVIEWPORTORG {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 20,
+        x: 10,
+    });
+    state.set_viewport_ext(&META_SETVIEWPORTEXT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: -100,
+        x: 100,
+    });
+}
+
+#[test]
+fn gdi_state_restore_dc_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        saves: i16,
+        n_saved_dc: i16,
+        expected_index: Option<usize>,
+        expected_position: (i16, i16),
+    }
+
+    // Each save records the current position (save number, 0) before
+    // moving on to the next one.
+    let cases = [
+        TestCase {
+            desc: "-1 restores the most recent save",
+            saves: 3,
+            n_saved_dc: -1,
+            expected_index: Some(2),
+            expected_position: (2, 0),
+        },
+        TestCase {
+            desc: "-3 discards the two saves after the target",
+            saves: 3,
+            n_saved_dc: -3,
+            expected_index: Some(0),
+            expected_position: (0, 0),
+        },
+        TestCase {
+            desc: "Positive values count from the bottom of the stack",
+            saves: 3,
+            n_saved_dc: 2,
+            expected_index: Some(1),
+            expected_position: (1, 0),
+        },
+        TestCase {
+            desc: "An offset past the stack leaves the context alone",
+            saves: 1,
+            n_saved_dc: -2,
+            expected_index: None,
+            expected_position: (1, 0),
+        },
+        TestCase {
+            desc: "Zero names no saved context",
+            saves: 2,
+            n_saved_dc: 0,
+            expected_index: None,
+            expected_position: (2, 0),
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let mut state = GdiState::new();
+
+        for x in 0..case.saves {
+            move_to(&mut state, x, 0);
+            save(&mut state);
+        }
+        move_to(&mut state, case.saves, 0);
+
+        let index = restore(&mut state, case.n_saved_dc);
+
+        assert_eq!(
+            index, case.expected_index,
+            "Test case #{i}: {}: index",
+            case.desc
+        );
+        let position = state.drawing_position();
+
+        assert_eq!(
+            (position.x, position.y),
+            case.expected_position,
+            "Test case #{i}: {}: position",
+            case.desc
+        );
+    }
+}
+
+#[test]
+fn gdi_state_restore_dc_keeps_the_drawing_bounds() {
+    let mut state = GdiState::new();
+
+    save(&mut state);
+    state.extend_bounds((-5.0, -6.0));
+    state.extend_bounds((70.0, 80.0));
+    restore(&mut state, -1);
+
+    let window = state.window();
+
+    assert_eq!(
+        (window.min_x, window.min_y, window.x, window.y),
+        (-5, -6, 70, 80)
+    );
+}
+
+#[test]
+fn gdi_state_maps_window_to_viewport() {
+    let mut state = GdiState::new();
+
+    anisotropic(&mut state);
+
+    assert_eq!(state.point_to_device(&PointS { x: 100, y: 100 }), (10.0, 20.0));
+    assert_eq!(state.point_to_device(&PointS { x: 600, y: 300 }), (60.0, 0.0));
+}
+
+#[test]
+fn gdi_state_intersects_clips_in_device_units() {
+    let mut state = GdiState::new();
+
+    intersect(&mut state, 10, 20, 30);
+    state.exclude_clip_rect(&META_EXCLUDECLIPRECT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        bottom: 30,
+        right: 25,
+        top: 25,
+        left: 15,
+    });
+    save(&mut state);
+    intersect(&mut state, 0, 0, 20);
+
+    assert_eq!(state.clips(), [
+        Clip::Rects(vec![(10.0, 20.0, 30.0, 40.0)]),
+        Clip::Exclude((15.0, 25.0, 25.0, 30.0)),
+        Clip::Rects(vec![(0.0, 0.0, 20.0, 40.0)]),
+    ]);
+
+    restore(&mut state, -1);
+
+    assert_eq!(state.clips().len(), 2, "RestoreDC restores the clips");
+}
+
+#[test]
+fn gdi_state_keeps_clips_across_mapping_changes() {
+    let mut state = GdiState::new();
+
+    intersect(&mut state, 100, 10, 600);
+    anisotropic(&mut state);
+
+    assert_eq!(state.clips(), [Clip::Rects(vec![(100.0, 10.0, 600.0, 40.0)])]);
+}
@@ -0,0 +1,217 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        BinaryRasterOperation, META_INTERSECTCLIPRECT, META_LINETO,
+        META_MOVETO, META_RECTANGLE, META_SETMAPMODE, META_SETROP2,
+        META_SETVIEWPORTEXT, META_SETWINDOWEXT, META_SETWINDOWORG,
+        META_TEXTOUT, MapMode, RecordSize,
+    },
+};
+
+fn set_map_mode(player: SVGPlayer, map_mode: MapMode) -> SVGPlayer {
+    player
+        .set_map_mode(1, META_SETMAPMODE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            map_mode,
+        })
+        .expect("set_map_mode failed")
+}
+
+fn rectangle(player: SVGPlayer) -> String {
+    let svg = player
+        .rectangle(5, META_RECTANGLE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom_rect: 700,
+            right_rect: 900,
+            top_rect: 500,
+            left_rect: 600,
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    String::from_utf8(svg).expect("SVG output is not UTF-8")
+}
+
+#[test]
+fn logical_coordinates_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        map_mode: MapMode,
+        logical_coordinates: bool,
+        expected: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Device output bakes the mapping into the coordinates",
+            map_mode: MapMode::MM_ANISOTROPIC,
+            logical_coordinates: false,
+            expected: r##"<svg viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg"><rect fill="none" fill-rule="evenodd" height="20" id="elem5" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="30" x="10" y="0"></rect></svg>"##,
+        },
+        TestCase {
+            desc: "Logical output maps the coordinates with a transform",
+            map_mode: MapMode::MM_ANISOTROPIC,
+            logical_coordinates: true,
+            expected: r##"<svg viewBox="0 0 1000 1000" xmlns="http://www.w3.org/2000/svg"><g transform="matrix(0.1 0 0 0.1 -50 -50)"><rect fill="none" fill-rule="evenodd" height="200" id="elem5" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" vector-effect="non-scaling-stroke" width="300" x="600" y="500"></rect></g></svg>"##,
+        },
+        TestCase {
+            desc: "Fixed mapping modes flip the y-axis in the transform",
+            map_mode: MapMode::MM_LOMETRIC,
+            logical_coordinates: true,
+            expected: r##"<svg viewBox="0 -200 400 200" xmlns="http://www.w3.org/2000/svg"><g transform="matrix(1 0 0 -1 -500 500)"><rect fill="none" fill-rule="evenodd" height="200" id="elem5" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="300" x="600" y="500"></rect></g></svg>"##,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let player = if case.logical_coordinates {
+            SVGPlayer::new().logical_coordinates()
+        } else {
+            SVGPlayer::new()
+        };
+        let player = set_map_mode(player, case.map_mode)
+            .set_window_origin(2, META_SETWINDOWORG {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 500,
+                x: 500,
+            })
+            .and_then(|player| {
+                player.set_window_ext(3, META_SETWINDOWEXT {
+                    record_size: RecordSize::from_raw(0),
+                    record_function: 0,
+                    y: 1000,
+                    x: 1000,
+                })
+            })
+            .and_then(|player| {
+                player.set_viewport_ext(4, META_SETVIEWPORTEXT {
+                    record_size: RecordSize::from_raw(0),
+                    record_function: 0,
+                    y: 100,
+                    x: 100,
+                })
+            })
+            .expect("mapping failed");
+
+        assert_eq!(
+            rectangle(player),
+            case.expected,
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
+
+#[test]
+fn logical_coordinates_keep_text_upright() {
+    let svg = set_map_mode(
+        SVGPlayer::new().logical_coordinates(),
+        MapMode::MM_LOMETRIC,
+    )
+    .text_out(2, META_TEXTOUT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        string_length: 1,
+        string: b"A".to_vec(),
+        y_start: -100,
+        x_start: 100,
+    })
+    .and_then(Player::generate)
+    .expect("rendering failed");
+    let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    assert!(
+        svg_str.contains(r#"<g transform="matrix(1 0 0 -1 0 0)"><g id="elem2" transform="matrix(1 0 0 -1 0 -200)"><text "#),
+        "text is not mirrored back around its reference point: {svg_str}",
+    );
+}
+
+#[test]
+fn current_position_is_kept_in_logical_units() {
+    let svg = SVGPlayer::new()
+        .move_to(1, META_MOVETO {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 10,
+            x: 10,
+        })
+        .and_then(|player| {
+            player.set_window_origin(2, META_SETWINDOWORG {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 5,
+                x: 5,
+            })
+        })
+        .and_then(|player| {
+            player.line_to(3, META_LINETO {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 20,
+                x: 20,
+            })
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg = String::from_utf8(svg).expect("SVG output is not UTF-8");
+
+    // The window origin set after META_MOVETO moves the start of the line
+    // along with its end.
+    assert!(svg.contains(r#"d="M 5 5 L 15 15""#), "{svg}");
+}
+
+#[test]
+fn logical_coordinates_keep_blending_elements_out_of_clip_groups() {
+    let player = set_map_mode(
+        SVGPlayer::new().logical_coordinates(),
+        MapMode::MM_ANISOTROPIC,
+    )
+    .set_window_ext(2, META_SETWINDOWEXT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 1000,
+        x: 1000,
+    })
+    .and_then(|player| {
+        player.set_viewport_ext(3, META_SETVIEWPORTEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+    })
+    .and_then(|player| {
+        player.intersect_clip_rect(4, META_INTERSECTCLIPRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom: 800,
+            right: 800,
+            top: 0,
+            left: 0,
+        })
+    })
+    .and_then(|player| {
+        player.set_raster_operation(4, META_SETROP2 {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            draw_mode: BinaryRasterOperation::R2_XORPEN,
+            reserved: None,
+        })
+    })
+    .expect("mapping failed");
+    let svg = rectangle(player);
+
+    // A clip-path on the group would isolate the blending rectangle from
+    // the drawing underneath, so the rectangle is clipped by itself, with
+    // the transform of the group undone.
+    assert!(
+        svg.contains(r#"<clipPath id="defs1" transform="matrix(10 0 0 10 0 0)"><rect clip-path="url(#defs0)" height="65535" width="65535" x="-32768" y="-32768"></rect></clipPath>"#),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r#"<g transform="matrix(0.1 0 0 0.1 0 0)"><rect clip-path="url(#defs1)" "#),
+        "{svg}"
+    );
+}
@@ -0,0 +1,5 @@
+mod gdi_state;
+mod logical_coordinates;
+mod save_dc;
+mod set_rop2;
+mod set_text_justification;
@@ -0,0 +1,79 @@
+use wmf_core::{
+    converter::{Player, SVGPlayer},
+    parser::{
+        META_EXCLUDECLIPRECT, META_RECTANGLE, META_RESTOREDC, META_SAVEDC,
+        META_SETWINDOWEXT, RecordSize,
+    },
+};
+
+fn rectangle(left: i16) -> META_RECTANGLE {
+    META_RECTANGLE {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        bottom_rect: 20,
+        right_rect: left + 10,
+        top_rect: 10,
+        left_rect: left,
+    }
+}
+
+fn rect_svg(id: &str, x: i16) -> String {
+    format!(
+        r##"<rect fill="none" fill-rule="evenodd" height="10" id="{id}" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="10" x="{x}" y="10"></rect>"##,
+    )
+}
+
+#[test]
+fn meta_savedc_svg_groups_scope() {
+    let svg = SVGPlayer::new()
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .and_then(|player| player.rectangle(1, rectangle(0)))
+        .and_then(|player| {
+            player.save_device_context(2, META_SAVEDC {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+            })
+        })
+        .and_then(|player| {
+            player.exclude_clip_rect(3, META_EXCLUDECLIPRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                bottom: 15,
+                right: 45,
+                top: 10,
+                left: 20,
+            })
+        })
+        .and_then(|player| player.rectangle(4, rectangle(20)))
+        .and_then(|player| player.rectangle(5, rectangle(40)))
+        .and_then(|player| {
+            player.restore_device_context(6, META_RESTOREDC {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                n_saved_dc: -1,
+            })
+        })
+        .and_then(|player| player.rectangle(7, rectangle(60)))
+        .and_then(Player::generate)
+        .expect("rendering failed");
+    let svg_str = String::from_utf8(svg).expect("SVG output is not UTF-8");
+    let elements = &svg_str[svg_str.find("</defs>").expect("defs is missing")
+        + 7..svg_str.len() - 6];
+
+    // The clip path is shared by the group and reverts with RESTOREDC.
+    assert_eq!(
+        elements,
+        format!(
+            r#"{}<g><g clip-path="url(#defs0)">{}{}</g></g>{}"#,
+            rect_svg("elem1", 0),
+            rect_svg("elem4", 20),
+            rect_svg("elem5", 40),
+            rect_svg("elem7", 60),
+        ),
+    );
+}
@@ -0,0 +1,281 @@
+use std::sync::{Arc, Mutex};
+
+use wmf_core::{
+    converter::{Diagnostic, DiagnosticKind, PlayError, Player, SVGPlayer},
+    parser::{
+        BinaryRasterOperation, ColorRef, LogBrush, META_CREATEBRUSHINDIRECT,
+        META_CREATEREGION, META_FILLREGION, META_FRAMEREGION, META_RECTANGLE,
+        META_SETROP2, META_SETWINDOWEXT, MetafileHeader, RecordSize,
+        RecordType, Rect, Region,
+    },
+};
+
+use crate::fixtures::header;
+
+fn draw_rectangle(
+    draw_mode: BinaryRasterOperation,
+    diagnostics: &Arc<Mutex<Vec<Diagnostic>>>,
+) -> String {
+    let sink = Arc::clone(diagnostics);
+    let player = SVGPlayer::new()
+        .diagnostic_sink(move |d| sink.lock().unwrap().push(d))
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .expect("set_window_ext failed")
+        .set_raster_operation(1, META_SETROP2 {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            draw_mode,
+            reserved: None,
+        })
+        .expect("set_raster_operation failed")
+        .rectangle(2, META_RECTANGLE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            bottom_rect: 50,
+            right_rect: 50,
+            top_rect: 10,
+            left_rect: 10,
+        })
+        .expect("rectangle failed");
+
+    let svg = player.generate().expect("SVG generation failed");
+
+    String::from_utf8(svg).expect("SVG output is not UTF-8")
+}
+
+#[test]
+fn meta_setrop2_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        draw_mode: BinaryRasterOperation,
+        expected_rect: &'static str,
+        approximated: bool,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "R2_COPYPEN draws the pen as is",
+            draw_mode: BinaryRasterOperation::R2_COPYPEN,
+            expected_rect: r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem2" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="40" x="10" y="10"></rect>"##,
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_NOP leaves the destination unchanged",
+            draw_mode: BinaryRasterOperation::R2_NOP,
+            expected_rect: "",
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_WHITE forces the pen to white",
+            draw_mode: BinaryRasterOperation::R2_WHITE,
+            expected_rect: r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem2" stroke="#FFFFFF" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="40" x="10" y="10"></rect>"##,
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_NOT inverts the destination with a white difference",
+            draw_mode: BinaryRasterOperation::R2_NOT,
+            expected_rect: r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem2" stroke="#FFFFFF" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" style="mix-blend-mode: difference;" width="40" x="10" y="10"></rect>"##,
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_NOTXORPEN uses the inverted pen with a difference",
+            draw_mode: BinaryRasterOperation::R2_NOTXORPEN,
+            expected_rect: r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem2" stroke="#FFFFFF" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" style="mix-blend-mode: difference;" width="40" x="10" y="10"></rect>"##,
+            approximated: false,
+        },
+        TestCase {
+            desc: "R2_MERGEPEN is approximated with screen blending",
+            draw_mode: BinaryRasterOperation::R2_MERGEPEN,
+            expected_rect: r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem2" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" style="mix-blend-mode: screen;" width="40" x="10" y="10"></rect>"##,
+            approximated: true,
+        },
+        TestCase {
+            desc: "R2_NOTMERGEPEN falls back to the pen color",
+            draw_mode: BinaryRasterOperation::R2_NOTMERGEPEN,
+            expected_rect: r##"<rect fill="none" fill-rule="evenodd" height="40" id="elem2" stroke="#000000" stroke-dasharray="none" stroke-linecap="butt" stroke-linejoin="miter" stroke-opacity="1.00" stroke-width="1" width="40" x="10" y="10"></rect>"##,
+            approximated: true,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let svg = draw_rectangle(case.draw_mode, &diagnostics);
+
+        assert_eq!(
+            svg,
+            format!(
+                r#"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">{}</svg>"#,
+                case.expected_rect,
+            ),
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+
+        let diagnostics = diagnostics.lock().unwrap();
+
+        if case.approximated {
+            assert_eq!(diagnostics.len(), 1, "case {i}: {}", case.desc);
+            assert_eq!(diagnostics[0].record_number, 2);
+            assert_eq!(diagnostics[0].record_type, RecordType::META_RECTANGLE);
+            assert_eq!(diagnostics[0].kind, DiagnosticKind::Approximated);
+        } else {
+            assert!(diagnostics.is_empty(), "case {i}: {}", case.desc);
+        }
+    }
+}
+
+/// Play META_SETROP2 and a region drawn with a red brush by `draw`.
+fn draw_region(
+    draw_mode: BinaryRasterOperation,
+    draw: impl FnOnce(SVGPlayer) -> Result<SVGPlayer, PlayError>,
+    diagnostics: &Arc<Mutex<Vec<Diagnostic>>>,
+) -> String {
+    let sink = Arc::clone(diagnostics);
+    let header = MetafileHeader::StartsWithHeader(header(2));
+    let region = Region {
+        next_in_chain: 0,
+        object_type: 6,
+        object_count: 0,
+        size: 0,
+        scan_count: 0,
+        max_scan: 0,
+        bounding_rectangle: Rect { left: 10, top: 10, right: 50, bottom: 50 },
+        a_scans: vec![],
+    };
+    let svg = SVGPlayer::new()
+        .diagnostic_sink(move |d| sink.lock().unwrap().push(d))
+        .header(0, header)
+        .and_then(|player| {
+            player.set_window_ext(0, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+        })
+        .and_then(|player| {
+            player.create_region(1, META_CREATEREGION {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                region,
+            })
+        })
+        .and_then(|player| {
+            player.create_brush_indirect(2, META_CREATEBRUSHINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                log_brush: LogBrush::Solid {
+                    color_ref: ColorRef {
+                        red: 0xFF,
+                        green: 0,
+                        blue: 0,
+                        reserved: 0,
+                    },
+                },
+            })
+        })
+        .and_then(|player| {
+            player.set_raster_operation(3, META_SETROP2 {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                draw_mode,
+                reserved: None,
+            })
+        })
+        .and_then(draw)
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    String::from_utf8(svg).expect("SVG output is not UTF-8")
+}
+
+#[test]
+fn meta_setrop2_svg_region_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        draw_mode: BinaryRasterOperation,
+        frame: bool,
+        expected: &'static str,
+        record_type: Option<RecordType>,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "R2_WHITE forces the brush of META_FILLREGION to white",
+            draw_mode: BinaryRasterOperation::R2_WHITE,
+            frame: false,
+            expected: r##"<path d="M 10 10 L 50 10 L 50 50 L 10 50 Z" fill="#FFFFFF" id="elem4" stroke="none"></path>"##,
+            record_type: None,
+        },
+        TestCase {
+            desc: "R2_NOP leaves the destination of META_FILLREGION",
+            draw_mode: BinaryRasterOperation::R2_NOP,
+            frame: false,
+            expected: "",
+            record_type: None,
+        },
+        TestCase {
+            desc: "R2_MERGEPEN blends the frame of META_FRAMEREGION",
+            draw_mode: BinaryRasterOperation::R2_MERGEPEN,
+            frame: true,
+            expected: r##"<rect fill="none" height="40" id="elem4" stroke="#FF0000" stroke-width="2" style="mix-blend-mode: screen;" width="40" x="10" y="10"></rect>"##,
+            record_type: Some(RecordType::META_FRAMEREGION),
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let svg = draw_region(
+            case.draw_mode,
+            |player| {
+                if case.frame {
+                    player.frame_region(4, META_FRAMEREGION {
+                        record_size: RecordSize::from_raw(0),
+                        record_function: 0,
+                        region: 0,
+                        brush: 1,
+                        height: 2,
+                        width: 2,
+                    })
+                } else {
+                    player.fill_region(4, META_FILLREGION {
+                        record_size: RecordSize::from_raw(0),
+                        record_function: 0,
+                        region: 0,
+                        brush: 1,
+                    })
+                }
+            },
+            &diagnostics,
+        );
+
+        assert_eq!(
+            svg,
+            format!(
+                r#"<svg viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">{}</svg>"#,
+                case.expected,
+            ),
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+
+        let reported: Vec<_> = diagnostics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|d| (d.record_type, d.kind))
+            .collect();
+        let expected: Vec<_> = case
+            .record_type
+            .map(|record_type| (record_type, DiagnosticKind::Approximated))
+            .into_iter()
+            .collect();
+
+        assert_eq!(reported, expected, "case {i}: {}", case.desc);
+    }
+}
@@ -0,0 +1,144 @@
+use wmf_core::{
+    converter::{PlayError, Player, SVGPlayer},
+    parser::{
+        META_EXTTEXTOUT, META_SETTEXTCHAREXTRA, META_SETTEXTJUSTIFICATION,
+        META_SETWINDOWEXT, RecordSize,
+    },
+};
+
+/// Play META_SETTEXTCHAREXTRA and META_SETTEXTJUSTIFICATION, then draw
+/// `text` at (10, 20).
+fn justify(
+    text: &str,
+    char_extra: u16,
+    (break_count, break_extra): (u16, u16),
+    dx: &[i16],
+) -> Result<Vec<u8>, PlayError> {
+    SVGPlayer::new()
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 100,
+        })
+        .and_then(|player| {
+            player.set_text_char_extra(1, META_SETTEXTCHAREXTRA {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                char_extra,
+            })
+        })
+        .and_then(|player| {
+            player.set_text_justification(2, META_SETTEXTJUSTIFICATION {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                break_count,
+                break_extra,
+            })
+        })
+        .and_then(|player| {
+            player.ext_text_out(3, META_EXTTEXTOUT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 20,
+                x: 10,
+                string_length: i16::try_from(text.len()).unwrap(),
+                fw_opts: std::collections::BTreeSet::new(),
+                rectangle: None,
+                string: text.as_bytes().to_vec(),
+                dx: dx.to_vec(),
+            })
+        })
+        .and_then(Player::generate)
+}
+
+#[test]
+fn meta_settextjustification_svg_table_test() {
+    struct TestCase {
+        desc: &'static str,
+        text: &'static str,
+        char_extra: u16,
+        break_count: u16,
+        break_extra: u16,
+        dx: Vec<i16>,
+        expected: &'static str,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "Without extra the text is drawn as a single run",
+            text: "a b c",
+            char_extra: 0,
+            break_count: 0,
+            break_extra: 0,
+            dx: vec![],
+            expected: r##"<text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem3" text-anchor="start" x="10" y="30">a b c</text>"##,
+        },
+        TestCase {
+            desc: "The remainder of the break extra goes to the first breaks",
+            text: "a b c",
+            char_extra: 0,
+            break_count: 2,
+            break_extra: 5,
+            dx: vec![],
+            expected: r##"<text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem3" text-anchor="start" x="10" y="30"><tspan>a</tspan><tspan> </tspan><tspan dx="3">b</tspan><tspan> </tspan><tspan dx="2">c</tspan></text>"##,
+        },
+        TestCase {
+            desc: "Character extra is added after every glyph",
+            text: "a b c",
+            char_extra: 2,
+            break_count: 0,
+            break_extra: 0,
+            dx: vec![],
+            expected: r##"<text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem3" text-anchor="start" x="10" y="30"><tspan>a</tspan><tspan dx="2"> </tspan><tspan dx="2">b</tspan><tspan dx="2"> </tspan><tspan dx="2">c</tspan></text>"##,
+        },
+        TestCase {
+            desc: "Extra is added to the advances of the Dx array",
+            text: "a b c",
+            char_extra: 1,
+            break_count: 2,
+            break_extra: 5,
+            dx: vec![10; 5],
+            expected: r##"<text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem3" text-anchor="start" x="10" y="30"><tspan>a</tspan><tspan x="21"> </tspan><tspan x="35">b</tspan><tspan x="46"> </tspan><tspan x="59">c</tspan></text>"##,
+        },
+        TestCase {
+            desc: "The remainder is spread one unit each over the first breaks",
+            text: "a b c d",
+            char_extra: 0,
+            break_count: 3,
+            break_extra: 8,
+            dx: vec![],
+            expected: r##"<text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem3" text-anchor="start" x="10" y="30"><tspan>a</tspan><tspan> </tspan><tspan dx="3">b</tspan><tspan> </tspan><tspan dx="3">c</tspan><tspan> </tspan><tspan dx="2">d</tspan></text>"##,
+        },
+        TestCase {
+            desc: "A negative break extra narrows the breaks",
+            text: "a b c",
+            char_extra: 0,
+            break_count: 2,
+            break_extra: (-5_i16).cast_unsigned(),
+            dx: vec![],
+            expected: r##"<text fill="#000000" font-family="&apos;System&apos;,&apos;System&apos;" font-size="12" font-weight="normal" id="elem3" text-anchor="start" x="10" y="30"><tspan>a</tspan><tspan> </tspan><tspan dx="-3">b</tspan><tspan> </tspan><tspan dx="-2">c</tspan></text>"##,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let result = justify(
+            case.text,
+            case.char_extra,
+            (case.break_count, case.break_extra),
+            &case.dx,
+        );
+
+        assert!(result.is_ok(), "case {i}: {}: Rendering failed", case.desc);
+
+        let svg_str = String::from_utf8(result.unwrap())
+            .expect("SVG output is not UTF-8");
+        let body = &svg_str[svg_str.find('>').unwrap() + 1..svg_str.len() - 6];
+
+        assert_eq!(
+            body, case.expected,
+            "case {i}: {}: SVG output does not match expected",
+            case.desc,
+        );
+    }
+}
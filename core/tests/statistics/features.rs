@@ -0,0 +1,185 @@
+use wmf_core::{
+    converter::{Player, StatisticsPlayer},
+    parser::{
+        BinaryRasterOperation, CharacterSet, ClipPrecision, FamilyFont, Font,
+        FontQuality, META_CREATEFONTINDIRECT, META_PATBLT, META_SETMAPMODE,
+        META_SETROP2, MapMode, OutPrecision, PitchAndFamily, PitchFont,
+        RecordSize, TernaryRasterOperation,
+    },
+};
+
+fn font(charset: CharacterSet) -> META_CREATEFONTINDIRECT {
+    META_CREATEFONTINDIRECT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        font: Font {
+            height: 20,
+            width: 0,
+            escapement: 0,
+            orientation: 0,
+            weight: 400,
+            italic: false,
+            underline: false,
+            strike_out: false,
+            charset,
+            out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+            clip_precision: ClipPrecision::CLIP_DEFAULT_PRECIS,
+            quality: FontQuality::DEFAULT_QUALITY,
+            pitch_and_family: PitchAndFamily {
+                family: FamilyFont::FF_DONTCARE,
+                pitch: PitchFont::DEFAULT_PITCH,
+            },
+            facename: "Arial".to_owned(),
+            fallback_facename: vec![],
+        },
+    }
+}
+
+fn player() -> StatisticsPlayer {
+    StatisticsPlayer::new()
+        .set_map_mode(0, META_SETMAPMODE {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            map_mode: MapMode::MM_ANISOTROPIC,
+        })
+        .and_then(|player| {
+            player.set_raster_operation(1, META_SETROP2 {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                draw_mode: BinaryRasterOperation::R2_XORPEN,
+                reserved: None,
+            })
+        })
+        .and_then(|player| {
+            player.pat_blt(2, META_PATBLT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                raster_operation: TernaryRasterOperation::PATCOPY,
+                height: 10,
+                width: 10,
+                y_left: 0,
+                x_left: 0,
+            })
+        })
+        .and_then(|player| {
+            player.create_font_indirect(3, font(CharacterSet::SHIFTJIS_CHARSET))
+        })
+        .and_then(|player| {
+            player.create_font_indirect(4, font(CharacterSet::ANSI_CHARSET))
+        })
+        .expect("playing failed")
+}
+
+#[test]
+fn statistics_collects_map_modes_raster_operations_and_charsets() {
+    let player = player();
+    let statistics = player.statistics();
+
+    assert_eq!(statistics.map_modes.iter().collect::<Vec<_>>(), vec![
+        &MapMode::MM_ANISOTROPIC
+    ],);
+    assert_eq!(
+        statistics.binary_raster_operations.iter().collect::<Vec<_>>(),
+        vec![&BinaryRasterOperation::R2_XORPEN],
+    );
+    assert_eq!(
+        statistics.ternary_raster_operations.iter().collect::<Vec<_>>(),
+        vec![&TernaryRasterOperation::PATCOPY],
+    );
+    assert_eq!(statistics.charsets.iter().collect::<Vec<_>>(), vec![
+        &CharacterSet::ANSI_CHARSET,
+        &CharacterSet::SHIFTJIS_CHARSET
+    ],);
+}
+
+#[test]
+fn statistics_report_lists_features() {
+    let output = player().generate().expect("generation failed");
+    let report = String::from_utf8_lossy(&output);
+
+    assert!(report.contains("  META_PATBLT: 1\n"), "{report}");
+    assert!(report.contains("objects: 2 used of 0 declared\n"), "{report}");
+    assert!(report.contains("map modes: MM_ANISOTROPIC\n"), "{report}");
+    assert!(
+        report.contains("charsets: ANSI_CHARSET SHIFTJIS_CHARSET\n"),
+        "{report}"
+    );
+}
+
+#[cfg(all(feature = "std", feature = "svg"))]
+#[test]
+fn analyze_reports_records_svg_skips() {
+    use wmf_core::{
+        converter::{AnalyzeOptions, DiagnosticKind},
+        parser::RecordType,
+    };
+
+    let mut buffer = vec![];
+    // META_HEADER of 21 words with no objects and records up to 9 words.
+    for word in [1_u16, 9, 0x0300, 21, 0, 0, 9, 0, 0] {
+        buffer.extend_from_slice(&word.to_le_bytes());
+    }
+    // META_PATBLT with SRCCOPY, which needs a source bitmap.
+    buffer.extend_from_slice(&9_u32.to_le_bytes());
+    buffer.extend_from_slice(&0x061D_u16.to_le_bytes());
+    buffer.extend_from_slice(&0x00CC_0020_u32.to_le_bytes());
+    for word in [10_u16, 10, 0, 0] {
+        buffer.extend_from_slice(&word.to_le_bytes());
+    }
+    // META_EOF
+    buffer.extend_from_slice(&3_u32.to_le_bytes());
+    buffer.extend_from_slice(&0_u16.to_le_bytes());
+
+    for (svg_diagnostics, expected) in [
+        (false, vec![]),
+        (true, vec![(RecordType::META_PATBLT, DiagnosticKind::Skipped)]),
+    ] {
+        let statistics =
+            wmf_core::converter::analyze(&buffer, AnalyzeOptions {
+                svg_diagnostics,
+            })
+            .expect("analysis failed");
+
+        assert_eq!(statistics.records.get(&RecordType::META_PATBLT), Some(&1));
+        assert_eq!(
+            statistics
+                .svg_diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.record_type, diagnostic.kind))
+                .collect::<Vec<_>>(),
+            expected,
+            "svg_diagnostics: {svg_diagnostics}",
+        );
+    }
+}
+
+#[cfg(all(feature = "std", feature = "svg"))]
+#[test]
+fn analyze_keeps_statistics_when_svg_fails() {
+    use wmf_core::{converter::AnalyzeOptions, parser::RecordType};
+
+    let mut buffer = vec![];
+    // META_HEADER of 11 words with no objects and records up to 4 words.
+    for word in [1_u16, 9, 0x0300, 11, 0, 0, 4, 0, 0] {
+        buffer.extend_from_slice(&word.to_le_bytes());
+    }
+    // META_SELECTOBJECT of an object that was never created.
+    buffer.extend_from_slice(&4_u32.to_le_bytes());
+    buffer.extend_from_slice(&0x012D_u16.to_le_bytes());
+    buffer.extend_from_slice(&3_u16.to_le_bytes());
+    // META_EOF
+    buffer.extend_from_slice(&3_u32.to_le_bytes());
+    buffer.extend_from_slice(&0_u16.to_le_bytes());
+
+    let statistics = wmf_core::converter::analyze(&buffer, AnalyzeOptions {
+        svg_diagnostics: true,
+    })
+    .expect("analysis failed");
+
+    assert_eq!(
+        statistics.records.get(&RecordType::META_SELECTOBJECT),
+        Some(&1)
+    );
+    assert!(statistics.svg_error.is_some(), "{statistics}");
+    assert!(statistics.to_string().contains("svg error: "), "{statistics}");
+}
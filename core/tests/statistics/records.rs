@@ -0,0 +1,101 @@
+use wmf_core::{
+    converter::{Player, StatisticsPlayer},
+    parser::{
+        ColorRef, LogBrush, META_CREATEBRUSHINDIRECT, META_DELETEOBJECT,
+        META_EOF, META_ESCAPE, META_LINETO, MetafileEscapes, MetafileHeader,
+        RecordSize, RecordType,
+    },
+};
+
+use crate::fixtures::header;
+
+fn player(number_of_objects: u16) -> StatisticsPlayer {
+    StatisticsPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(number_of_objects)))
+        .expect("playing failed")
+}
+
+fn create_brush(
+    player: StatisticsPlayer,
+    record_number: usize,
+) -> StatisticsPlayer {
+    player
+        .create_brush_indirect(record_number, META_CREATEBRUSHINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            log_brush: LogBrush::Solid { color_ref: ColorRef::black() },
+        })
+        .expect("playing failed")
+}
+
+fn delete_object(
+    player: StatisticsPlayer,
+    record_number: usize,
+    object_index: u16,
+) -> StatisticsPlayer {
+    player
+        .delete_object(record_number, META_DELETEOBJECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            object_index,
+        })
+        .expect("playing failed")
+}
+
+#[test]
+fn statistics_counts_records_and_escapes() {
+    let line_to = META_LINETO {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 10,
+        x: 10,
+    };
+    let player = player(0)
+        .line_to(1, line_to.clone())
+        .and_then(|player| player.line_to(2, line_to))
+        .and_then(|player| {
+            player.escape(3, META_ESCAPE::POSTSCRIPT_DATA {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                byte_count: 0,
+                data: vec![],
+            })
+        })
+        .and_then(|player| {
+            player.eof(4, META_EOF {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+            })
+        })
+        .expect("playing failed");
+    let statistics = player.statistics();
+
+    assert_eq!(statistics.records.iter().collect::<Vec<_>>(), vec![
+        (&RecordType::META_EOF, &1),
+        (&RecordType::META_LINETO, &2),
+        (&RecordType::META_ESCAPE, &1),
+    ],);
+    assert_eq!(statistics.escapes.iter().collect::<Vec<_>>(), vec![(
+        &MetafileEscapes::POSTSCRIPT_DATA,
+        &1
+    )],);
+}
+
+#[test]
+fn statistics_tracks_high_water_mark_of_object_table() {
+    let mut player = player(2);
+
+    player = create_brush(player, 1);
+    player = create_brush(player, 2);
+    player = delete_object(player, 3, 0);
+    // Reuses the slot freed above, so two objects still exist at most.
+    player = create_brush(player, 4);
+
+    assert_eq!(player.statistics().number_of_objects, 2);
+    assert_eq!(player.statistics().objects_high_water_mark, 2);
+
+    // Exceeds the size declared by the header.
+    player = create_brush(player, 5);
+
+    assert_eq!(player.statistics().objects_high_water_mark, 3);
+}
@@ -0,0 +1,76 @@
+use wmf_core::{
+    converter::{Player, TextExtractor},
+    parser::{
+        META_MOVETO, META_SETTEXTALIGN, META_SETWINDOWEXT, META_TEXTOUT,
+        RecordSize, TextAlignmentMode,
+    },
+};
+
+fn text_out(x: i16, y: i16, string: &str) -> META_TEXTOUT {
+    META_TEXTOUT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        string_length: i16::try_from(string.len()).unwrap(),
+        string: string.as_bytes().to_vec(),
+        y_start: y,
+        x_start: x,
+    }
+}
+
+fn extractor() -> TextExtractor {
+    TextExtractor::new()
+        .set_window_ext(0, META_SETWINDOWEXT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            y: 100,
+            x: 200,
+        })
+        .expect("rendering failed")
+}
+
+#[test]
+fn text_lines_join_runs_sharing_a_baseline() {
+    // "Wor" is 22 units wide in 12 units of Helvetica, so "ld" follows it
+    // without a gap, while "World" is well apart from "Hello".
+    let extractor = extractor()
+        .text_out(1, text_out(10, 50, "Next"))
+        .and_then(|player| player.text_out(2, text_out(82, 21, "ld")))
+        .and_then(|player| player.text_out(3, text_out(10, 20, "Hello")))
+        .and_then(|player| player.text_out(4, text_out(60, 22, "Wor")))
+        .expect("rendering failed");
+    let lines = extractor.lines();
+
+    assert_eq!(lines.len(), 2, "{lines:?}");
+    assert_eq!(lines[0].text(), "Hello World");
+    assert!((lines[0].baseline - 29.6).abs() < 1e-3, "{lines:?}");
+    assert_eq!(lines[1].text(), "Next");
+
+    let text = extractor.generate().expect("text generation failed");
+
+    assert_eq!(String::from_utf8_lossy(&text), "Hello World\nNext\n");
+}
+
+#[test]
+fn text_at_the_current_position_follows_the_previous_run() {
+    let text = extractor()
+        .set_text_align(1, META_SETTEXTALIGN {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            text_alignment_mode: TextAlignmentMode::TA_UPDATECP as u16,
+            reserved: None,
+        })
+        .and_then(|player| {
+            player.move_to(2, META_MOVETO {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 20,
+                x: 10,
+            })
+        })
+        .and_then(|player| player.text_out(3, text_out(0, 0, "Wor")))
+        .and_then(|player| player.text_out(4, text_out(0, 0, "ld")))
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    assert_eq!(String::from_utf8_lossy(&text), "World\n");
+}
@@ -0,0 +1,2 @@
+mod lines;
+mod runs;
@@ -0,0 +1,153 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+
+use wmf_core::{
+    converter::{Diagnostic, DiagnosticKind, Player, TextExtractor},
+    parser::{
+        CharacterSet, ClipPrecision, ColorRef, FamilyFont, Font, FontQuality,
+        META_CREATEFONTINDIRECT, META_EXTTEXTOUT, META_SELECTOBJECT,
+        META_SETTEXTCOLOR, META_SETWINDOWEXT, MetafileHeader, OutPrecision,
+        PitchAndFamily, PitchFont, RecordSize,
+    },
+};
+
+use crate::fixtures::header;
+
+fn font() -> Font {
+    Font {
+        height: 20,
+        width: 0,
+        escapement: 0,
+        orientation: 0,
+        weight: 400,
+        italic: false,
+        underline: false,
+        strike_out: false,
+        charset: CharacterSet::SHIFTJIS_CHARSET,
+        out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+        clip_precision: ClipPrecision::CLIP_DEFAULT_PRECIS,
+        quality: FontQuality::DEFAULT_QUALITY,
+        pitch_and_family: PitchAndFamily {
+            family: FamilyFont::FF_DONTCARE,
+            pitch: PitchFont::DEFAULT_PITCH,
+        },
+        facename: "MS Mincho".to_owned(),
+        fallback_facename: vec![],
+    }
+}
+
+fn ext_text_out(string: &[u8], dx: Vec<i16>) -> META_EXTTEXTOUT {
+    META_EXTTEXTOUT {
+        record_size: RecordSize::from_raw(0),
+        record_function: 0,
+        y: 20,
+        x: 10,
+        string_length: i16::try_from(string.len()).unwrap(),
+        fw_opts: BTreeSet::new(),
+        rectangle: None,
+        string: string.to_vec(),
+        dx,
+    }
+}
+
+fn extractor() -> TextExtractor {
+    TextExtractor::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 100,
+            })
+        })
+        .and_then(|player| {
+            player.create_font_indirect(2, META_CREATEFONTINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                font: font(),
+            })
+        })
+        .and_then(|player| {
+            player.select_object(3, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|player| {
+            player.set_text_color(4, META_SETTEXTCOLOR {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                color_ref: ColorRef {
+                    red: 0xFF,
+                    green: 0,
+                    blue: 0,
+                    reserved: 0,
+                },
+            })
+        })
+        .expect("rendering failed")
+}
+
+#[test]
+fn text_runs_are_decoded_in_the_charset_of_the_font() {
+    // "日本" in Shift_JIS.
+    let extractor = extractor()
+        .ext_text_out(5, ext_text_out(&[0x93, 0xFA, 0x96, 0x7B], vec![]))
+        .expect("rendering failed");
+    let [run] = extractor.runs() else {
+        panic!("expected a single run: {:?}", extractor.runs());
+    };
+
+    assert_eq!(run.record_number, 5);
+    assert_eq!(run.text, "日本");
+    assert_eq!(run.font.facename, "MS Mincho");
+    assert_eq!((run.color.red, run.color.green, run.color.blue), (0xFF, 0, 0));
+    // TA_TOP puts the baseline an ascent of 0.8em below the reference
+    // point, and wide characters advance by an em each.
+    assert_eq!((run.x, run.y), (10.0, 36.0));
+    assert_eq!((run.width, run.size), (40.0, 20.0));
+}
+
+#[test]
+fn text_run_width_is_the_sum_of_dx() {
+    let extractor = extractor()
+        .ext_text_out(5, ext_text_out(b"AB", vec![15, 25]))
+        .expect("rendering failed");
+    let [run] = extractor.runs() else {
+        panic!("expected a single run: {:?}", extractor.runs());
+    };
+
+    assert_eq!(run.text, "AB");
+    assert_eq!((run.width, run.size), (40.0, 20.0));
+}
+
+#[test]
+fn blank_text_has_no_run() {
+    let text = extractor()
+        .ext_text_out(5, ext_text_out(b"  ", vec![]))
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    assert_eq!(text, b"");
+}
+
+#[test]
+fn undecodable_text_is_skipped() {
+    let diagnostics: Arc<Mutex<Vec<Diagnostic>>> = Arc::default();
+    let sink = Arc::clone(&diagnostics);
+    // A Shift_JIS lead byte without its trail byte.
+    let extractor = extractor()
+        .diagnostic_sink(move |d| sink.lock().unwrap().push(d))
+        .ext_text_out(5, ext_text_out(&[0x41, 0x93], vec![]))
+        .expect("rendering failed");
+    let diagnostics = diagnostics.lock().unwrap();
+
+    assert!(extractor.runs().is_empty(), "{:?}", extractor.runs());
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].record_number, 5);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::Skipped);
+}
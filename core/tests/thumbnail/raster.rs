@@ -0,0 +1,177 @@
+use wmf_core::{
+    converter::{Player, Thumbnail, ThumbnailPlayer, render_thumbnail},
+    parser::{
+        BinaryRasterOperation, ColorRef, LogBrush, META_CREATEBRUSHINDIRECT,
+        META_PATBLT, META_SELECTOBJECT, META_SETROP2, MetafileHeader,
+        RecordSize, RectL, TernaryRasterOperation,
+    },
+};
+
+use crate::fixtures::{header, rectangle};
+
+const WHITE: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+fn player() -> ThumbnailPlayer {
+    player_over(&RectL { left: 0, top: 0, right: 100, bottom: 100 })
+}
+
+fn player_over(area: &RectL) -> ThumbnailPlayer {
+    ThumbnailPlayer::new(area, 100, 100)
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .expect("rendering failed")
+}
+
+fn red_brush(player: ThumbnailPlayer) -> ThumbnailPlayer {
+    player
+        .create_brush_indirect(1, META_CREATEBRUSHINDIRECT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            log_brush: LogBrush::Solid {
+                color_ref: ColorRef {
+                    red: 255,
+                    green: 0,
+                    blue: 0,
+                    reserved: 0,
+                },
+            },
+        })
+        .and_then(|player| {
+            player.select_object(2, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .expect("rendering failed")
+}
+
+fn pixel(thumbnail: &Thumbnail, x: usize, y: usize) -> [u8; 4] {
+    let at = (y * thumbnail.width() as usize + x) * 4;
+    let rgba = &thumbnail.rgba()[at..at + 4];
+
+    [rgba[0], rgba[1], rgba[2], rgba[3]]
+}
+
+#[test]
+fn thumbnail_fills_inside_the_pen() {
+    let player = red_brush(player())
+        .rectangle(3, rectangle())
+        .expect("rendering failed");
+    let thumbnail = player.thumbnail();
+
+    assert_eq!((thumbnail.width(), thumbnail.height()), (100, 100));
+    assert_eq!(pixel(&thumbnail, 30, 30), [0xFF, 0, 0, 0xFF]);
+    assert_eq!(pixel(&thumbnail, 70, 70), WHITE);
+    // The cosmetic pen is a pixel wide and centred on the edge, so it
+    // covers half of the pixels on either side.
+    assert_eq!(pixel(&thumbnail, 30, 10), [0x80, 0, 0, 0xFF]);
+    assert_eq!(pixel(&thumbnail, 30, 9), [0x80, 0x80, 0x80, 0xFF]);
+}
+
+#[test]
+fn thumbnail_combines_colours_by_the_raster_operation() {
+    let player = player()
+        .pat_blt(1, META_PATBLT {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            raster_operation: TernaryRasterOperation::DSTINVERT,
+            height: 20,
+            width: 20,
+            y_left: 0,
+            x_left: 0,
+        })
+        .expect("rendering failed");
+    let thumbnail = player.thumbnail();
+
+    assert_eq!(pixel(&thumbnail, 10, 10), [0, 0, 0, 0xFF]);
+    assert_eq!(pixel(&thumbnail, 30, 30), WHITE);
+}
+
+#[test]
+fn thumbnail_draws_nothing_with_r2_nop() {
+    let player = red_brush(player())
+        .set_raster_operation(3, META_SETROP2 {
+            record_size: RecordSize::from_raw(0),
+            record_function: 0,
+            draw_mode: BinaryRasterOperation::R2_NOP,
+            reserved: None,
+        })
+        .and_then(|player| player.rectangle(4, rectangle()))
+        .expect("rendering failed");
+    let thumbnail = player.thumbnail();
+
+    assert!(thumbnail.rgba().chunks(4).all(|rgba| rgba == WHITE));
+}
+
+#[test]
+fn thumbnail_is_written_as_png() {
+    let png = player().generate().expect("PNG generation failed");
+
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0DIHDR"));
+    assert_eq!(png[16..24], [0, 0, 0, 100, 0, 0, 0, 100]);
+    assert!(png.ends_with(b"IEND\xAE\x42\x60\x82"));
+}
+
+#[test]
+fn thumbnail_scales_a_saturated_area() {
+    // The widest extent a metafile reports spans more than `i32` holds.
+    let area = RectL {
+        left: i32::MIN,
+        top: i32::MIN,
+        right: i32::MAX,
+        bottom: i32::MAX,
+    };
+    let player = red_brush(player_over(&area))
+        .rectangle(3, rectangle())
+        .expect("rendering failed");
+    let thumbnail = player.thumbnail();
+
+    assert_eq!((thumbnail.width(), thumbnail.height()), (100, 100));
+    assert_eq!(pixel(&thumbnail, 0, 0), WHITE);
+}
+
+/// Placeable metafile of `width` by `height` logical units that paints
+/// nothing.
+fn placeable(width: i16, height: i16) -> Vec<u8> {
+    let mut data = vec![];
+
+    data.extend_from_slice(&0x9AC6_CDD7_u32.to_le_bytes());
+    data.extend_from_slice(&0_u16.to_le_bytes());
+    for value in [0, 0, width, height] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&1440_u16.to_le_bytes());
+    data.extend_from_slice(&0_u32.to_le_bytes());
+
+    let checksum = data.chunks(2).fold(0, |checksum, word| {
+        checksum ^ u16::from_le_bytes([word[0], word[1]])
+    });
+
+    data.extend_from_slice(&checksum.to_le_bytes());
+    // META_HEADER
+    for value in [1_u16, 9, 0x0300, 12, 0, 0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&3_u32.to_le_bytes());
+    data.extend_from_slice(&0_u16.to_le_bytes());
+    // META_EOF
+    data.extend_from_slice(&3_u32.to_le_bytes());
+    data.extend_from_slice(&0_u16.to_le_bytes());
+
+    data
+}
+
+#[test]
+fn render_thumbnail_keeps_the_aspect_ratio() {
+    let thumbnail =
+        render_thumbnail(&placeable(1024, 768), 64, 64).expect("failed");
+
+    assert_eq!((thumbnail.width(), thumbnail.height()), (64, 48));
+    assert_eq!(thumbnail.rgba().len(), 64 * 48 * 4);
+    assert!(thumbnail.rgba().chunks(4).all(|rgba| rgba == WHITE));
+}
+
+#[test]
+fn render_thumbnail_rejects_an_empty_size() {
+    assert!(render_thumbnail(&placeable(1024, 768), 0, 64).is_err());
+}
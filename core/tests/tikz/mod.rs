@@ -0,0 +1,2 @@
+mod picture;
+mod text;
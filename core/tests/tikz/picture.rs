@@ -0,0 +1,135 @@
+use std::sync::{Arc, Mutex};
+
+use wmf_core::{
+    converter::{Diagnostic, DiagnosticKind, Player, TikzPlayer},
+    parser::{
+        BinaryRasterOperation, META_PLACEABLE, MetafileHeader, RecordType, Rect,
+    },
+};
+
+use crate::fixtures::{self, header, rectangle};
+
+fn draw(
+    draw_mode: BinaryRasterOperation,
+    diagnostics: &Arc<Mutex<Vec<Diagnostic>>>,
+) -> String {
+    let sink = Arc::clone(diagnostics);
+    let tikz = fixtures::draw(
+        TikzPlayer::new()
+            .diagnostic_sink(move |d| sink.lock().unwrap().push(d)),
+        draw_mode,
+    );
+
+    String::from_utf8(tikz).expect("TikZ output is not UTF-8")
+}
+
+#[test]
+fn tikz_drawing_records_are_paths_in_points() {
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let tikz = draw(BinaryRasterOperation::R2_COPYPEN, &diagnostics);
+
+    assert!(tikz.contains("\\begin{tikzpicture}[x=1pt,y=-1pt]\n"), "{tikz}");
+    // The window extent at 96 DPI.
+    assert!(
+        tikz.contains(
+            "\\useasboundingbox (0,0) -- (75,0) -- (75,75) -- (0,75) -- \
+             cycle;\n"
+        ),
+        "{tikz}"
+    );
+    // The default pen is a black cosmetic pen and there is no brush.
+    assert!(
+        tikz.contains(
+            "\\draw[draw={rgb,255:red,0;green,0;blue,0},line \
+             width=0.75pt,line cap=butt,line join=miter] (7.5,7.5) -- \
+             (37.5,7.5) -- (37.5,37.5) -- (7.5,37.5) -- cycle;\n"
+        ),
+        "{tikz}"
+    );
+    assert!(tikz.ends_with("\\end{tikzpicture}\n"), "{tikz}");
+    assert!(diagnostics.lock().unwrap().is_empty());
+}
+
+#[test]
+fn tikz_bounding_box_follows_placeable_header() {
+    let placeable = META_PLACEABLE {
+        key: 0x9AC6_CDD7,
+        hwmf: 0,
+        bounding_box: Rect { left: 0, top: 0, right: 2010, bottom: 1000 },
+        inch: 1440,
+        reserved: 0,
+        checksum: [0, 0],
+    };
+    let tikz = TikzPlayer::new()
+        .header(0, MetafileHeader::StartsWithPlaceable(placeable, header(0)))
+        .and_then(|player| player.rectangle(1, rectangle()))
+        .expect("rendering failed")
+        .generate()
+        .expect("TikZ generation failed");
+    let tikz = String::from_utf8(tikz).expect("TikZ output is not UTF-8");
+
+    // 100.5 by 50 points at 1440 units per inch.
+    assert!(
+        tikz.contains(
+            "\\useasboundingbox (0,0) -- (100.5,0) -- (100.5,50) -- (0,50) -- \
+             cycle;\n"
+        ),
+        "{tikz}"
+    );
+    assert!(tikz.contains("(0.5,0.5) -- (2.5,0.5)"), "{tikz}");
+}
+
+#[test]
+fn tikz_raster_operations_are_approximated() {
+    struct TestCase {
+        desc: &'static str,
+        draw_mode: BinaryRasterOperation,
+        expected: Option<&'static str>,
+        kind: DiagnosticKind,
+    }
+
+    let cases = [
+        TestCase {
+            desc: "R2_XORPEN is drawn with the pen colour",
+            draw_mode: BinaryRasterOperation::R2_XORPEN,
+            expected: Some("draw={rgb,255:red,0;green,0;blue,0}"),
+            kind: DiagnosticKind::Approximated,
+        },
+        TestCase {
+            desc: "R2_NOTXORPEN is drawn with the inverted pen colour",
+            draw_mode: BinaryRasterOperation::R2_NOTXORPEN,
+            expected: Some("draw={rgb,255:red,255;green,255;blue,255}"),
+            kind: DiagnosticKind::Approximated,
+        },
+        TestCase {
+            desc: "R2_NOT needs the picture and is skipped",
+            draw_mode: BinaryRasterOperation::R2_NOT,
+            expected: None,
+            kind: DiagnosticKind::Skipped,
+        },
+    ];
+
+    for (i, case) in cases.iter().enumerate() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let tikz = draw(case.draw_mode, &diagnostics);
+        let diagnostics = diagnostics.lock().unwrap();
+
+        match case.expected {
+            Some(expected) => {
+                assert!(tikz.contains(expected), "case {i}: {}", case.desc);
+            }
+            None => {
+                assert!(!tikz.contains("\\draw"), "case {i}: {}", case.desc);
+            }
+        }
+
+        assert!(
+            diagnostics
+                .iter()
+                .any(|d| d.record_type == RecordType::META_RECTANGLE
+                    && d.kind == case.kind),
+            "case {i}: {}: {diagnostics:?}",
+            case.desc,
+        );
+    }
+}
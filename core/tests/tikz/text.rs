@@ -0,0 +1,105 @@
+use wmf_core::{
+    converter::{Player, TikzPlayer},
+    parser::{
+        CharacterSet, ClipPrecision, FamilyFont, Font, FontQuality,
+        META_CREATEFONTINDIRECT, META_SELECTOBJECT, META_SETTEXTALIGN,
+        META_SETWINDOWEXT, META_TEXTOUT, MetafileHeader, OutPrecision,
+        PitchAndFamily, PitchFont, RecordSize,
+    },
+};
+
+use crate::fixtures::header;
+
+fn text(text_alignment_mode: u16, string: &str) -> String {
+    let tikz = TikzPlayer::new()
+        .header(0, MetafileHeader::StartsWithHeader(header(1)))
+        .and_then(|player| {
+            player.set_window_ext(1, META_SETWINDOWEXT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                y: 100,
+                x: 200,
+            })
+        })
+        .and_then(|player| {
+            player.create_font_indirect(2, META_CREATEFONTINDIRECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                font: Font {
+                    height: -20,
+                    width: 0,
+                    escapement: 900,
+                    orientation: 900,
+                    weight: 700,
+                    italic: false,
+                    underline: false,
+                    strike_out: false,
+                    charset: CharacterSet::ANSI_CHARSET,
+                    out_precision: OutPrecision::OUT_DEFAULT_PRECIS,
+                    clip_precision: ClipPrecision::CLIP_DEFAULT_PRECIS,
+                    quality: FontQuality::DEFAULT_QUALITY,
+                    pitch_and_family: PitchAndFamily {
+                        family: FamilyFont::FF_DONTCARE,
+                        pitch: PitchFont::DEFAULT_PITCH,
+                    },
+                    facename: "Times New Roman".to_owned(),
+                    fallback_facename: vec![],
+                },
+            })
+        })
+        .and_then(|player| {
+            player.select_object(3, META_SELECTOBJECT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                object_index: 0,
+            })
+        })
+        .and_then(|player| {
+            player.set_text_align(4, META_SETTEXTALIGN {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                text_alignment_mode,
+                reserved: None,
+            })
+        })
+        .and_then(|player| {
+            player.text_out(5, META_TEXTOUT {
+                record_size: RecordSize::from_raw(0),
+                record_function: 0,
+                string_length: i16::try_from(string.len()).unwrap(),
+                string: string.as_bytes().to_vec(),
+                y_start: 40,
+                x_start: 20,
+            })
+        })
+        .and_then(Player::generate)
+        .expect("rendering failed");
+
+    String::from_utf8(tikz).expect("TikZ output is not UTF-8")
+}
+
+#[test]
+fn tikz_text_is_a_node_in_the_closest_document_font() {
+    // TA_BASELINE | TA_CENTER.
+    let tikz = text(0x001E, "50% & more");
+
+    assert!(
+        tikz.contains(
+            "\\node[anchor=base,inner \
+             sep=0pt,font={\\fontsize{15}{18}\\selectfont\\rmfamily\\\
+             bfseries},text={rgb,255:red,0;green,0;blue,0},rotate=90] at \
+             (15,30) {50\\% \\& more};\n"
+        ),
+        "{tikz}"
+    );
+}
+
+#[test]
+fn tikz_text_is_placed_from_the_top_by_default() {
+    // TA_TOP | TA_LEFT, with the baseline 0.8em below the reference point
+    // along the rotated text.
+    let tikz = text(0x0000, "A");
+
+    assert!(tikz.contains("\\node[anchor=base west,"), "{tikz}");
+    assert!(tikz.contains("] at (27,30) {A};\n"), "{tikz}");
+}
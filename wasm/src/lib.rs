@@ -2,7 +2,10 @@
 
 extern crate alloc;
 
-use alloc::string::{String, ToString};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use wasm_bindgen::prelude::*;
 
@@ -47,6 +50,82 @@ pub fn convert_wmf_to_svg(buf: &[u8]) -> Result<String, JsValue> {
     Ok(String::from_utf8_lossy(&output).to_string())
 }
 
+/// Converts WMF binary data to HTML canvas drawing commands.
+///
+/// # Arguments
+///
+/// - `buf` - Byte array of a WMF file
+///
+/// # Returns
+///
+/// - Body of a JavaScript function drawing on a `CanvasRenderingContext2D`
+///   named `ctx`; it resizes `ctx.canvas` to the metafile at 96 DPI
+/// - On failure, returns a JsValue containing error details
+///
+/// # Example
+///
+/// ```js
+/// import { convertWmf2Canvas } from "wmf-wasm";
+///
+/// const body = convertWmf2Canvas(wmfBytes);
+/// const ctx = document.querySelector("canvas").getContext("2d");
+///
+/// new Function("ctx", body)(ctx);
+/// ```
+#[wasm_bindgen(js_name = convertWmf2Canvas)]
+pub fn convert_wmf_to_canvas(buf: &[u8]) -> Result<String, JsValue> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    set_log_level("info");
+
+    let player = wmf_core::converter::CanvasPlayer::new();
+    let converter = wmf_core::converter::WMFConverter::new(buf, player);
+    let output =
+        converter.run().map_err(|err| JsValue::from(err.to_string()))?;
+
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Renders WMF binary data as a PNG thumbnail.
+///
+/// # Arguments
+///
+/// - `buf` - Byte array of a WMF file
+/// - `max_width` - Largest width of the thumbnail in pixels
+/// - `max_height` - Largest height of the thumbnail in pixels
+///
+/// # Returns
+///
+/// - PNG file of the metafile scaled to fit the size, keeping its aspect ratio
+/// - On failure, returns a JsValue containing error details
+///
+/// # Example
+///
+/// ```js
+/// import { renderThumbnail } from "wmf-wasm";
+///
+/// const png = renderThumbnail(wmfBytes, 128, 128);
+/// const url = URL.createObjectURL(new Blob([png], { type: "image/png" }));
+/// ```
+#[wasm_bindgen(js_name = renderThumbnail)]
+pub fn render_thumbnail(
+    buf: &[u8],
+    max_width: u32,
+    max_height: u32,
+) -> Result<Vec<u8>, JsValue> {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+
+    set_log_level("info");
+
+    let thumbnail =
+        wmf_core::converter::render_thumbnail(buf, max_width, max_height)
+            .map_err(|err| JsValue::from(err.to_string()))?;
+
+    Ok(thumbnail.png())
+}
+
 /// Sets the log level (only when the `tracing` feature is enabled).
 ///
 /// # Arguments